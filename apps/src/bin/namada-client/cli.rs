@@ -39,15 +39,35 @@ pub async fn main() -> Result<()> {
                 Sub::TxRevealPk(TxRevealPk(args)) => {
                     tx::submit_reveal_pk(ctx, args).await;
                 }
+                Sub::TxSubmit(TxSubmit(args)) => {
+                    tx::submit_signed_tx(ctx, args).await;
+                }
+                Sub::TxTransferToEthereum(TxTransferToEthereum(args)) => {
+                    tx::submit_transfer_to_ethereum(ctx, args).await;
+                }
                 Sub::Bond(Bond(args)) => {
                     tx::submit_bond(ctx, args).await;
                 }
                 Sub::Unbond(Unbond(args)) => {
                     tx::submit_unbond(ctx, args).await;
                 }
+                Sub::Redelegate(Redelegate(args)) => {
+                    tx::submit_redelegate(ctx, args).await;
+                }
                 Sub::Withdraw(Withdraw(args)) => {
                     tx::submit_withdraw(ctx, args).await;
                 }
+                Sub::TxUnjailValidator(TxUnjailValidator(args)) => {
+                    tx::submit_unjail_validator(ctx, args).await;
+                }
+                Sub::TxChangeValidatorMetaData(TxChangeValidatorMetaData(
+                    args,
+                )) => {
+                    tx::submit_change_validator_metadata(ctx, args).await;
+                }
+                Sub::TxChangeConsensusKey(TxChangeConsensusKey(args)) => {
+                    tx::submit_change_consensus_key(ctx, args).await;
+                }
                 // Ledger queries
                 Sub::QueryEpoch(QueryEpoch(args)) => {
                     rpc::query_epoch(args).await;
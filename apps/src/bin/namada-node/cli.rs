@@ -1,8 +1,10 @@
 //! Namada node CLI.
 
 use eyre::{Context, Result};
+use namada::types::key::RefTo;
 use namada_apps::cli::{self, cmds};
-use namada_apps::node::ledger;
+use namada_apps::node::ledger::{self, tendermint_node};
+use namada_apps::node::signer::ConsensusSigner;
 
 pub fn main() -> Result<()> {
     let (cmd, mut ctx) = cli::namada_node_cli()?;
@@ -19,6 +21,34 @@ pub fn main() -> Result<()> {
                 ledger::reset(ctx.config.ledger)
                     .wrap_err("Failed to reset Namada node")?;
             }
+            cmds::Ledger::Rollback(_) => {
+                ledger::rollback(ctx.config.ledger)
+                    .wrap_err("Failed to rollback the Namada node")?;
+            }
+            cmds::Ledger::SetConsensusKey(cmds::LedgerSetConsensusKey(
+                args,
+            )) => {
+                if let Some(address) =
+                    &ctx.config.ledger.tendermint.priv_validator_address
+                {
+                    eprintln!(
+                        "The node uses the remote signer at {}. Restart the \
+                         signer with the new consensus key instead.",
+                        address
+                    );
+                    cli::safe_exit(1)
+                }
+                let consensus_key = ctx.get_cached(&args.consensus_key);
+                tendermint_node::write_validator_key(
+                    ctx.config.ledger.tendermint_dir(),
+                    &consensus_key,
+                );
+                println!(
+                    "The consensus key {} has been written to Tendermint's \
+                     `priv_validator_key.json`.",
+                    consensus_key.ref_to()
+                );
+            }
         },
         cmds::NamadaNode::Config(sub) => match sub {
             cmds::Config::Gen(cmds::ConfigGen) => {
@@ -40,6 +70,27 @@ pub fn main() -> Result<()> {
                 );
             }
         },
+        cmds::NamadaNode::Signer(cmds::Signer(args)) => {
+            let consensus_key = match args.consensus_key.as_ref() {
+                Some(key) => ctx.get_cached(key),
+                None => ctx
+                    .wallet
+                    .find_validator_consensus_key()
+                    .wrap_err("Failed to find the validator consensus key")?,
+            };
+            let state_path = args.state_path.unwrap_or_else(|| {
+                ctx.config.ledger.chain_dir().join("signer_state.json")
+            });
+            let mut signer = ConsensusSigner::new(
+                &consensus_key,
+                ctx.config.ledger.chain_id.clone(),
+                state_path,
+            )
+            .wrap_err("Failed to start the signer")?;
+            signer
+                .run(&args.address)
+                .wrap_err("Failed to run the signer")?;
+        }
     }
     Ok(())
 }
@@ -3,16 +3,22 @@
 use std::fs::File;
 use std::io::{self, Write};
 
+use bip39::Mnemonic;
 use borsh::BorshSerialize;
 use color_eyre::eyre::Result;
+use data_encoding::HEXLOWER;
 use itertools::sorted;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use namada::types::key::*;
 use namada::types::masp::{MaspValue, PaymentAddress};
+use namada::types::transaction::hash_tx;
 use namada_apps::cli;
 use namada_apps::cli::{args, cmds, Context};
+use namada_apps::client::signing::hardware::{self, TcpTransport};
+use namada_apps::client::signing::{self, OfflineSignature, UnsignedTx};
 use namada_apps::client::tx::find_valid_diversifier;
-use namada_apps::wallet::{DecryptionError, FindKeyError};
+use namada_apps::wallet::derivation::{self, DerivationPath, WalletSeed};
+use namada_apps::wallet::{DecryptionError, FindKeyError, HardwareKey, Wallet};
 use rand_core::OsRng;
 
 pub fn main() -> Result<()> {
@@ -22,6 +28,12 @@ pub fn main() -> Result<()> {
             cmds::WalletKey::Gen(cmds::KeyGen(args)) => {
                 key_and_address_gen(ctx, args)
             }
+            cmds::WalletKey::Restore(cmds::KeyRestore(args)) => {
+                key_and_address_restore(ctx, args)
+            }
+            cmds::WalletKey::AddHardware(cmds::KeyAddHardware(args)) => {
+                key_add_hardware(ctx, args)
+            }
             cmds::WalletKey::Find(cmds::KeyFind(args)) => key_find(ctx, args),
             cmds::WalletKey::List(cmds::KeyList(args)) => key_list(ctx, args),
             cmds::WalletKey::Export(cmds::Export(args)) => {
@@ -44,6 +56,9 @@ pub fn main() -> Result<()> {
             cmds::WalletMasp::GenSpendKey(cmds::MaspGenSpendKey(args)) => {
                 spending_key_gen(ctx, args)
             }
+            cmds::WalletMasp::RestoreSpendKey(cmds::MaspRestoreSpendKey(
+                args,
+            )) => spending_key_restore(ctx, args),
             cmds::WalletMasp::GenPayAddr(cmds::MaspGenPayAddr(args)) => {
                 payment_address_gen(ctx, args)
             }
@@ -60,10 +75,51 @@ pub fn main() -> Result<()> {
                 address_key_find(ctx, args)
             }
         },
+        cmds::NamadaWallet::Sign(cmds::WalletSign(args)) => sign_tx(ctx, args),
     }
     Ok(())
 }
 
+/// Sign a transaction dumped with `--dump-tx`
+fn sign_tx(
+    mut ctx: Context,
+    args::SignTx {
+        tx_path,
+        signing_key,
+        multisig_index,
+        output_path,
+    }: args::SignTx,
+) {
+    let unsigned: UnsignedTx = signing::read_from_file(&tx_path);
+    println!("Code hash: {}", hash_tx(&unsigned.tx.code));
+    println!(
+        "Data: {}",
+        unsigned
+            .tx
+            .data
+            .as_ref()
+            .map(|data| HEXLOWER.encode(data))
+            .unwrap_or_default()
+    );
+    println!("Timestamp: {}", unsigned.tx.timestamp.to_rfc3339());
+    println!(
+        "Fee: {} {}, gas limit: {}",
+        unsigned.fee.amount,
+        unsigned.fee.token,
+        u64::from(&unsigned.gas_limit)
+    );
+    println!("Epoch: {}", unsigned.epoch);
+
+    let keypair = ctx.get_cached(&signing_key);
+    let signature = OfflineSignature::new(&unsigned, &keypair, multisig_index);
+    let output_path = output_path.unwrap_or_else(|| {
+        let pkh = PublicKeyHash::from(&signature.public_key);
+        format!("{}.{}.sig", tx_path.to_string_lossy(), pkh).into()
+    });
+    signing::write_to_file(&output_path, &signature);
+    println!("Signature written to {}", output_path.to_string_lossy());
+}
+
 /// Find shielded address or key
 fn address_key_find(
     ctx: Context,
@@ -137,6 +193,10 @@ fn spending_keys_list(
             }
             // Always print the corresponding viewing key
             writeln!(w, "    Viewing Key: {}", key).unwrap();
+            if let Some(path) = wallet.find_spending_key_derivation_path(&alias)
+            {
+                writeln!(w, "    Derivation path: {}", path).unwrap();
+            }
             // A subset of viewing keys will have corresponding spending keys.
             // Print those too if they are available and requested.
             if unsafe_show_secret {
@@ -195,11 +255,34 @@ fn spending_key_gen(
     args::MaspSpendKeyGen {
         alias,
         unsafe_dont_encrypt,
+        hd_path,
+        bip39_passphrase,
     }: args::MaspSpendKeyGen,
 ) {
     let mut wallet = ctx.wallet;
     let alias = alias.to_lowercase();
-    let (alias, _key) = wallet.gen_spending_key(alias, unsafe_dont_encrypt);
+    let (alias, _key) = match hd_path {
+        Some(hd_path) => {
+            let path = parse_hd_path(
+                Some(hd_path),
+                DerivationPath::default_for_shielded(),
+            );
+            let seed =
+                wallet_seed(&mut wallet, unsafe_dont_encrypt, bip39_passphrase);
+            wallet
+                .derive_spending_key(
+                    alias,
+                    unsafe_dont_encrypt,
+                    seed.as_bytes(),
+                    path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    cli::safe_exit(1)
+                })
+        }
+        None => wallet.gen_spending_key(alias, unsafe_dont_encrypt),
+    };
     wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
     println!(
         "Successfully added a spending key with alias: \"{}\"",
@@ -207,6 +290,35 @@ fn spending_key_gen(
     );
 }
 
+/// Restore a spending key from a mnemonic code.
+fn spending_key_restore(
+    ctx: Context,
+    args::MaspSpendKeyRestore {
+        alias,
+        unsafe_dont_encrypt,
+        hd_path,
+        bip39_passphrase,
+    }: args::MaspSpendKeyRestore,
+) {
+    let mut wallet = ctx.wallet;
+    let alias = alias.to_lowercase();
+    let path = parse_hd_path(hd_path, DerivationPath::default_for_shielded());
+    let mnemonic = read_mnemonic();
+    let passphrase = read_bip39_passphrase(bip39_passphrase, false);
+    let seed = WalletSeed::new(&mnemonic, &passphrase);
+    let (alias, _key) = wallet
+        .derive_spending_key(alias, unsafe_dont_encrypt, seed.as_bytes(), path)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            cli::safe_exit(1)
+        });
+    wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+    println!(
+        "Successfully restored a spending key with alias: \"{}\"",
+        alias
+    );
+}
+
 /// Generate a shielded payment address from the given key.
 fn payment_address_gen(
     mut ctx: Context,
@@ -303,10 +415,34 @@ fn key_and_address_gen(
         scheme,
         alias,
         unsafe_dont_encrypt,
+        hd_path,
+        bip39_passphrase,
     }: args::KeyAndAddressGen,
 ) {
     let mut wallet = ctx.wallet;
-    let (alias, _key) = wallet.gen_key(scheme, alias, unsafe_dont_encrypt);
+    let (alias, _key) = match hd_path {
+        Some(hd_path) => {
+            let path = parse_hd_path(
+                Some(hd_path),
+                DerivationPath::default_for_transparent_scheme(scheme),
+            );
+            let seed =
+                wallet_seed(&mut wallet, unsafe_dont_encrypt, bip39_passphrase);
+            wallet
+                .derive_key(
+                    scheme,
+                    alias,
+                    unsafe_dont_encrypt,
+                    seed.as_bytes(),
+                    path,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    cli::safe_exit(1)
+                })
+        }
+        None => wallet.gen_key(scheme, alias, unsafe_dont_encrypt),
+    };
     wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
     println!(
         "Successfully added a key and an address with alias: \"{}\"",
@@ -314,6 +450,151 @@ fn key_and_address_gen(
     );
 }
 
+/// Restore a keypair from a mnemonic code and derive implicit address from it
+/// and store them in the wallet.
+fn key_and_address_restore(
+    ctx: Context,
+    args::KeyAndAddressRestore {
+        scheme,
+        alias,
+        unsafe_dont_encrypt,
+        hd_path,
+        bip39_passphrase,
+    }: args::KeyAndAddressRestore,
+) {
+    let mut wallet = ctx.wallet;
+    let path = parse_hd_path(
+        hd_path,
+        DerivationPath::default_for_transparent_scheme(scheme),
+    );
+    let mnemonic = read_mnemonic();
+    let passphrase = read_bip39_passphrase(bip39_passphrase, false);
+    let seed = WalletSeed::new(&mnemonic, &passphrase);
+    let (alias, _key) = wallet
+        .derive_key(scheme, alias, unsafe_dont_encrypt, seed.as_bytes(), path)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            cli::safe_exit(1)
+        });
+    wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+    println!(
+        "Successfully restored a key and an address with alias: \"{}\"",
+        alias
+    );
+}
+
+/// Add a key held by a hardware wallet and its implicit address to the
+/// wallet.
+fn key_add_hardware(
+    ctx: Context,
+    args::KeyAddHardware { alias, hd_path }: args::KeyAddHardware,
+) {
+    let mut wallet = ctx.wallet;
+    let derivation_path = parse_hd_path(
+        hd_path,
+        DerivationPath::default_for_transparent_scheme(SchemeType::Ed25519),
+    );
+    let public_key = TcpTransport::connect_default()
+        .and_then(|mut transport| {
+            hardware::get_public_key(&mut transport, &derivation_path)
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            cli::safe_exit(1)
+        });
+    println!("Public key: {}", public_key);
+    let key = HardwareKey {
+        public_key,
+        derivation_path,
+    };
+    let alias = wallet
+        .add_hardware_key(alias.to_lowercase(), key)
+        .unwrap_or_else(|| {
+            eprintln!("Hardware key not added");
+            cli::safe_exit(1);
+        });
+    wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+    println!(
+        "Successfully added a hardware key and an address with alias: \"{}\"",
+        alias
+    );
+}
+
+/// Parse a derivation path argument, where "default" or no argument stand for
+/// the given default path.
+fn parse_hd_path(
+    hd_path: Option<String>,
+    default: DerivationPath,
+) -> DerivationPath {
+    match hd_path {
+        Some(hd_path) if hd_path != "default" => hd_path
+            .parse()
+            .unwrap_or_else(|err: derivation::DerivationError| {
+                eprintln!("{}", err);
+                cli::safe_exit(1)
+            }),
+        _ => default,
+    }
+}
+
+/// Prompt for a mnemonic code.
+fn read_mnemonic() -> Mnemonic {
+    let phrase =
+        rpassword::read_password_from_tty(Some("Enter your mnemonic code: "))
+            .unwrap_or_default();
+    derivation::parse_mnemonic(&phrase).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        cli::safe_exit(1)
+    })
+}
+
+/// Prompt for the passphrase protecting a mnemonic code if requested, or use
+/// an empty passphrase. A new passphrase has to be confirmed.
+fn read_bip39_passphrase(requested: bool, confirm: bool) -> String {
+    if !requested {
+        return String::new();
+    }
+    let read = |prompt| {
+        rpassword::read_password_from_tty(Some(prompt)).unwrap_or_default()
+    };
+    let passphrase = read("Enter your BIP39 passphrase: ");
+    if confirm
+        && read("To confirm, please enter the same passphrase once more: ")
+            != passphrase
+    {
+        eprintln!("Your two inputs do not match!");
+        cli::safe_exit(1)
+    }
+    passphrase
+}
+
+/// Get the seed of the wallet's mnemonic code to derive a key from. If the
+/// wallet has none yet, a new mnemonic code is generated, which the user has
+/// to write down.
+fn wallet_seed(
+    wallet: &mut Wallet,
+    unsafe_dont_encrypt: bool,
+    bip39_passphrase: bool,
+) -> WalletSeed {
+    let (seed, mnemonic) = wallet
+        .get_or_gen_seed(unsafe_dont_encrypt, || {
+            read_bip39_passphrase(bip39_passphrase, true)
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            cli::safe_exit(1)
+        });
+    if let Some(mnemonic) = mnemonic {
+        println!(
+            "Write down the following mnemonic code and keep it safe, it's \
+             the only way to restore the keys derived in this wallet if it is \
+             lost:"
+        );
+        println!("{}", mnemonic.phrase());
+    }
+    seed
+}
+
 /// Find a keypair in the wallet store.
 fn key_find(
     ctx: Context,
@@ -366,7 +647,8 @@ fn key_list(
 ) {
     let wallet = ctx.wallet;
     let known_keys = wallet.get_keys();
-    if known_keys.is_empty() {
+    let hardware_keys = wallet.get_hardware_keys();
+    if known_keys.is_empty() && hardware_keys.is_empty() {
         println!(
             "No known keys. Try `key gen --alias my-key` to generate a new \
              key."
@@ -385,6 +667,9 @@ fn key_list(
             if let Some(pkh) = pkh {
                 writeln!(w, "    Public key hash: {}", pkh).unwrap();
             }
+            if let Some(path) = wallet.find_key_derivation_path(&alias) {
+                writeln!(w, "    Derivation path: {}", path).unwrap();
+            }
             match stored_keypair.get(decrypt, None) {
                 Ok(keypair) => {
                     writeln!(w, "    Public key: {}", keypair.ref_to())
@@ -402,6 +687,12 @@ fn key_list(
                 }
             }
         }
+        for (alias, key) in hardware_keys {
+            writeln!(w, "  Alias \"{}\" (hardware):", alias).unwrap();
+            writeln!(w, "    Public key: {}", key.public_key).unwrap();
+            writeln!(w, "    Derivation path: {}", key.derivation_path)
+                .unwrap();
+        }
     }
 }
 
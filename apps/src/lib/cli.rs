@@ -103,17 +103,21 @@ pub mod cmds {
     pub enum NamadaNode {
         Ledger(Ledger),
         Config(Config),
+        Signer(Signer),
     }
 
     impl Cmd for NamadaNode {
         fn add_sub(app: App) -> App {
-            app.subcommand(Ledger::def()).subcommand(Config::def())
+            app.subcommand(Ledger::def())
+                .subcommand(Config::def())
+                .subcommand(Signer::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let ledger = SubCmd::parse(matches).map(Self::Ledger);
             let config = SubCmd::parse(matches).map(Self::Config);
-            ledger.or(config)
+            let signer = SubCmd::parse(matches).map(Self::Signer);
+            ledger.or(config).or(signer)
         }
     }
     impl SubCmd for NamadaNode {
@@ -157,6 +161,8 @@ pub mod cmds {
                 .subcommand(TxUpdateVp::def().display_order(1))
                 .subcommand(TxInitAccount::def().display_order(1))
                 .subcommand(TxRevealPk::def().display_order(1))
+                .subcommand(TxSubmit::def().display_order(1))
+                .subcommand(TxTransferToEthereum::def().display_order(1))
                 // Proposal transactions
                 .subcommand(TxInitProposal::def().display_order(1))
                 .subcommand(TxVoteProposal::def().display_order(1))
@@ -164,7 +170,11 @@ pub mod cmds {
                 .subcommand(TxInitValidator::def().display_order(2))
                 .subcommand(Bond::def().display_order(2))
                 .subcommand(Unbond::def().display_order(2))
+                .subcommand(Redelegate::def().display_order(2))
                 .subcommand(Withdraw::def().display_order(2))
+                .subcommand(TxUnjailValidator::def().display_order(2))
+                .subcommand(TxChangeValidatorMetaData::def().display_order(2))
+                .subcommand(TxChangeConsensusKey::def().display_order(2))
                 // Queries
                 .subcommand(QueryEpoch::def().display_order(3))
                 .subcommand(QueryTransfers::def().display_order(3))
@@ -193,13 +203,23 @@ pub mod cmds {
             let tx_init_validator =
                 Self::parse_with_ctx(matches, TxInitValidator);
             let tx_reveal_pk = Self::parse_with_ctx(matches, TxRevealPk);
+            let tx_submit = Self::parse_with_ctx(matches, TxSubmit);
+            let tx_transfer_to_ethereum =
+                Self::parse_with_ctx(matches, TxTransferToEthereum);
             let tx_init_proposal =
                 Self::parse_with_ctx(matches, TxInitProposal);
             let tx_vote_proposal =
                 Self::parse_with_ctx(matches, TxVoteProposal);
             let bond = Self::parse_with_ctx(matches, Bond);
             let unbond = Self::parse_with_ctx(matches, Unbond);
+            let redelegate = Self::parse_with_ctx(matches, Redelegate);
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
+            let tx_unjail_validator =
+                Self::parse_with_ctx(matches, TxUnjailValidator);
+            let tx_change_validator_metadata =
+                Self::parse_with_ctx(matches, TxChangeValidatorMetaData);
+            let tx_change_consensus_key =
+                Self::parse_with_ctx(matches, TxChangeConsensusKey);
             let query_epoch = Self::parse_with_ctx(matches, QueryEpoch);
             let query_transfers = Self::parse_with_ctx(matches, QueryTransfers);
             let query_conversions =
@@ -224,12 +244,18 @@ pub mod cmds {
                 .or(tx_update_vp)
                 .or(tx_init_account)
                 .or(tx_reveal_pk)
+                .or(tx_submit)
+                .or(tx_transfer_to_ethereum)
                 .or(tx_init_proposal)
                 .or(tx_vote_proposal)
                 .or(tx_init_validator)
                 .or(bond)
                 .or(unbond)
+                .or(redelegate)
                 .or(withdraw)
+                .or(tx_unjail_validator)
+                .or(tx_change_validator_metadata)
+                .or(tx_change_consensus_key)
                 .or(query_epoch)
                 .or(query_transfers)
                 .or(query_conversions)
@@ -289,9 +315,15 @@ pub mod cmds {
         TxInitProposal(TxInitProposal),
         TxVoteProposal(TxVoteProposal),
         TxRevealPk(TxRevealPk),
+        TxSubmit(TxSubmit),
+        TxTransferToEthereum(TxTransferToEthereum),
         Bond(Bond),
         Unbond(Unbond),
+        Redelegate(Redelegate),
         Withdraw(Withdraw),
+        TxUnjailValidator(TxUnjailValidator),
+        TxChangeValidatorMetaData(TxChangeValidatorMetaData),
+        TxChangeConsensusKey(TxChangeConsensusKey),
         QueryEpoch(QueryEpoch),
         QueryTransfers(QueryTransfers),
         QueryConversions(QueryConversions),
@@ -316,6 +348,8 @@ pub mod cmds {
         Address(WalletAddress),
         /// MASP key, address management commands
         Masp(WalletMasp),
+        /// Sign a dumped transaction
+        Sign(WalletSign),
     }
 
     impl Cmd for NamadaWallet {
@@ -323,13 +357,15 @@ pub mod cmds {
             app.subcommand(WalletKey::def())
                 .subcommand(WalletAddress::def())
                 .subcommand(WalletMasp::def())
+                .subcommand(WalletSign::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             let key = SubCmd::parse(matches).map(Self::Key);
             let address = SubCmd::parse(matches).map(Self::Address);
             let masp = SubCmd::parse(matches).map(Self::Masp);
-            key.or(address).or(masp)
+            let sign = SubCmd::parse(matches).map(Self::Sign);
+            key.or(address).or(masp).or(sign)
         }
     }
 
@@ -355,6 +391,8 @@ pub mod cmds {
     #[allow(clippy::large_enum_variant)]
     pub enum WalletKey {
         Gen(KeyGen),
+        Restore(KeyRestore),
+        AddHardware(KeyAddHardware),
         Find(KeyFind),
         List(KeyList),
         Export(Export),
@@ -366,10 +404,17 @@ pub mod cmds {
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let generate = SubCmd::parse(matches).map(Self::Gen);
+                let restore = SubCmd::parse(matches).map(Self::Restore);
+                let hardware = SubCmd::parse(matches).map(Self::AddHardware);
                 let lookup = SubCmd::parse(matches).map(Self::Find);
                 let list = SubCmd::parse(matches).map(Self::List);
                 let export = SubCmd::parse(matches).map(Self::Export);
-                generate.or(lookup).or(list).or(export)
+                generate
+                    .or(restore)
+                    .or(hardware)
+                    .or(lookup)
+                    .or(list)
+                    .or(export)
             })
         }
 
@@ -381,6 +426,8 @@ pub mod cmds {
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .subcommand(KeyGen::def())
+                .subcommand(KeyRestore::def())
+                .subcommand(KeyAddHardware::def())
                 .subcommand(KeyFind::def())
                 .subcommand(KeyList::def())
                 .subcommand(Export::def())
@@ -411,6 +458,57 @@ pub mod cmds {
         }
     }
 
+    /// Restore a keypair and its implicit address from a mnemonic code
+    #[derive(Clone, Debug)]
+    pub struct KeyRestore(pub args::KeyAndAddressRestore);
+
+    impl SubCmd for KeyRestore {
+        const CMD: &'static str = "restore";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::KeyAndAddressRestore::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Restores a keypair from the given mnemonic code and \
+                     derivation path and derives the implicit address from \
+                     its public key. The address will be stored with the same \
+                     alias.",
+                )
+                .add_args::<args::KeyAndAddressRestore>()
+        }
+    }
+
+    /// Add a key held by a hardware wallet and its implicit address
+    #[derive(Clone, Debug)]
+    pub struct KeyAddHardware(pub args::KeyAddHardware);
+
+    impl SubCmd for KeyAddHardware {
+        const CMD: &'static str = "add-hardware";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::KeyAddHardware::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Adds the public key derived by a hardware wallet at the \
+                     given path and its implicit address. Transactions signed \
+                     with it are signed on the device, which is reached at \
+                     the address set in the NAMADA_HARDWARE_WALLET env var, \
+                     127.0.0.1:9999 by default.",
+                )
+                .add_args::<args::KeyAddHardware>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct KeyFind(pub args::KeyFind);
 
@@ -468,11 +566,37 @@ pub mod cmds {
         }
     }
 
+    /// Sign a transaction dumped with `--dump-tx`
+    #[derive(Clone, Debug)]
+    pub struct WalletSign(pub args::SignTx);
+
+    impl SubCmd for WalletSign {
+        const CMD: &'static str = "sign";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::SignTx::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Sign a transaction dumped with `--dump-tx` and write the \
+                     signature to a file, to be attached to the transaction \
+                     with `client submit`. This doesn't need a connection to \
+                     a ledger node.",
+                )
+                .add_args::<args::SignTx>()
+        }
+    }
+
     #[allow(clippy::large_enum_variant)]
     #[derive(Clone, Debug)]
     pub enum WalletMasp {
         GenPayAddr(MaspGenPayAddr),
         GenSpendKey(MaspGenSpendKey),
+        RestoreSpendKey(MaspRestoreSpendKey),
         AddAddrKey(MaspAddAddrKey),
         ListPayAddrs(MaspListPayAddrs),
         ListKeys(MaspListKeys),
@@ -486,11 +610,18 @@ pub mod cmds {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let genpa = SubCmd::parse(matches).map(Self::GenPayAddr);
                 let gensk = SubCmd::parse(matches).map(Self::GenSpendKey);
+                let restsk = SubCmd::parse(matches).map(Self::RestoreSpendKey);
                 let addak = SubCmd::parse(matches).map(Self::AddAddrKey);
                 let listpa = SubCmd::parse(matches).map(Self::ListPayAddrs);
                 let listsk = SubCmd::parse(matches).map(Self::ListKeys);
                 let findak = SubCmd::parse(matches).map(Self::FindAddrKey);
-                gensk.or(genpa).or(addak).or(listpa).or(listsk).or(findak)
+                gensk
+                    .or(restsk)
+                    .or(genpa)
+                    .or(addak)
+                    .or(listpa)
+                    .or(listsk)
+                    .or(findak)
             })
         }
 
@@ -503,6 +634,7 @@ pub mod cmds {
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .subcommand(MaspGenSpendKey::def())
+                .subcommand(MaspRestoreSpendKey::def())
                 .subcommand(MaspGenPayAddr::def())
                 .subcommand(MaspAddAddrKey::def())
                 .subcommand(MaspListPayAddrs::def())
@@ -605,11 +737,37 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Generates a random spending key")
+                .about(
+                    "Generates a random spending key or derives it from the \
+                     wallet's mnemonic code",
+                )
                 .add_args::<args::MaspSpendKeyGen>()
         }
     }
 
+    /// Restore a spending key from a mnemonic code
+    #[derive(Clone, Debug)]
+    pub struct MaspRestoreSpendKey(pub args::MaspSpendKeyRestore);
+
+    impl SubCmd for MaspRestoreSpendKey {
+        const CMD: &'static str = "restore-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                MaspRestoreSpendKey(args::MaspSpendKeyRestore::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Restores a spending key from the given mnemonic code and \
+                     derivation path",
+                )
+                .add_args::<args::MaspSpendKeyRestore>()
+        }
+    }
+
     /// Generate a payment address from a viewing key or payment address
     #[derive(Clone, Debug)]
     pub struct MaspGenPayAddr(pub args::MaspPayAddrGen);
@@ -755,6 +913,8 @@ pub mod cmds {
     pub enum Ledger {
         Run(LedgerRun),
         Reset(LedgerReset),
+        Rollback(LedgerRollback),
+        SetConsensusKey(LedgerSetConsensusKey),
     }
 
     impl SubCmd for Ledger {
@@ -764,7 +924,12 @@ pub mod cmds {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let run = SubCmd::parse(matches).map(Self::Run);
                 let reset = SubCmd::parse(matches).map(Self::Reset);
+                let rollback = SubCmd::parse(matches).map(Self::Rollback);
+                let set_consensus_key =
+                    SubCmd::parse(matches).map(Self::SetConsensusKey);
                 run.or(reset)
+                    .or(rollback)
+                    .or(set_consensus_key)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun)))
             })
@@ -778,6 +943,8 @@ pub mod cmds {
                 )
                 .subcommand(LedgerRun::def())
                 .subcommand(LedgerReset::def())
+                .subcommand(LedgerRollback::def())
+                .subcommand(LedgerSetConsensusKey::def())
         }
     }
 
@@ -814,6 +981,49 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerRollback;
+
+    impl SubCmd for LedgerRollback {
+        const CMD: &'static str = "rollback";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Roll Namada ledger node's state back to the previous \
+                 committed block height. Only a single height can be rolled \
+                 back.",
+            )
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerSetConsensusKey(pub args::LedgerSetConsensusKey);
+
+    impl SubCmd for LedgerSetConsensusKey {
+        const CMD: &'static str = "set-consensus-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                Self(args::LedgerSetConsensusKey::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Write a consensus key from the wallet to Tendermint's \
+                     `priv_validator_key.json`, to sign with a rotated \
+                     consensus key once it's active. The node must not be \
+                     running.",
+                )
+                .add_args::<args::LedgerSetConsensusKey>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Config {
         Gen(ConfigGen),
@@ -852,6 +1062,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Signer(pub args::Signer);
+
+    impl SubCmd for Signer {
+        const CMD: &'static str = "signer";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Signer(args::Signer::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Serve the signing requests of a Tendermint node with a \
+                     validator consensus key from the wallet.",
+                )
+                .add_args::<args::Signer>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryResult(pub args::QueryResult);
 
@@ -977,6 +1209,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxTransferToEthereum(pub args::TxTransferToEthereum);
+
+    impl SubCmd for TxTransferToEthereum {
+        const CMD: &'static str = "transfer-to-ethereum";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxTransferToEthereum(args::TxTransferToEthereum::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to transfer tokens to Ethereum \
+                     over the bridge.",
+                )
+                .add_args::<args::TxTransferToEthereum>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxIbcTransfer(pub args::TxIbcTransfer);
 
@@ -1100,6 +1354,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Redelegate(pub args::Redelegate);
+
+    impl SubCmd for Redelegate {
+        const CMD: &'static str = "redelegate";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Redelegate(args::Redelegate::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Redelegate tokens from one validator to another without \
+                     unbonding them.",
+                )
+                .add_args::<args::Redelegate>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Withdraw(pub args::Withdraw);
 
@@ -1119,6 +1395,75 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxUnjailValidator(pub args::TxUnjailValidator);
+
+    impl SubCmd for TxUnjailValidator {
+        const CMD: &'static str = "unjail-validator";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxUnjailValidator(args::TxUnjailValidator::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to unjail a validator that has \
+                     been jailed for missing too many blocks.",
+                )
+                .add_args::<args::TxUnjailValidator>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxChangeValidatorMetaData(pub args::TxChangeValidatorMetaData);
+
+    impl SubCmd for TxChangeValidatorMetaData {
+        const CMD: &'static str = "change-metadata";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxChangeValidatorMetaData(
+                    args::TxChangeValidatorMetaData::parse(matches),
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to change a validator's \
+                     metadata.",
+                )
+                .add_args::<args::TxChangeValidatorMetaData>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxChangeConsensusKey(pub args::TxChangeConsensusKey);
+
+    impl SubCmd for TxChangeConsensusKey {
+        const CMD: &'static str = "change-consensus-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxChangeConsensusKey(args::TxChangeConsensusKey::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to change a validator's \
+                     consensus key. The new key takes effect at the pipeline \
+                     offset.",
+                )
+                .add_args::<args::TxChangeConsensusKey>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryEpoch(pub args::Query);
 
@@ -1386,6 +1731,34 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxSubmit(pub args::SubmitTx);
+
+    impl SubCmd for TxSubmit {
+        const CMD: &'static str = "submit";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxSubmit(args::SubmitTx::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Attach the signatures made with `wallet sign` to a \
+                     transaction dumped with `--dump-tx` and submit it. The \
+                     wrapper transaction is signed with the key given by \
+                     `--signing-key` or `--signer`, which pays the fee \
+                     recorded in the dumped transaction.",
+                )
+                .add_args::<args::SubmitTx>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Utils {
         JoinNetwork(JoinNetwork),
@@ -1515,11 +1888,13 @@ pub mod args {
     use namada::ibc::core::ics24_host::identifier::{ChannelId, PortId};
     use namada::types::address::Address;
     use namada::types::chain::{ChainId, ChainIdPrefix};
+    use namada::types::ethereum_events::EthAddress;
     use namada::types::governance::ProposalVote;
     use namada::types::key::*;
     use namada::types::masp::MaspValue;
     use namada::types::storage::{self, Epoch};
     use namada::types::token;
+    use namada::types::transaction::pos::ValidatorMetaData;
     use namada::types::transaction::GasLimit;
     use rust_decimal::Decimal;
 
@@ -1546,6 +1921,7 @@ pub mod args {
             Err(_) => config::DEFAULT_BASE_DIR.into(),
         }),
     );
+    const BIP39_PASSPHRASE: ArgFlag = flag("bip39-passphrase");
     const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
     const CHAIN_ID: Arg<ChainId> = arg("chain-id");
     const CHAIN_ID_OPT: ArgOpt<ChainId> = CHAIN_ID.opt();
@@ -1554,16 +1930,24 @@ pub mod args {
     const CODE_PATH: Arg<PathBuf> = arg("code-path");
     const CODE_PATH_OPT: ArgOpt<PathBuf> = CODE_PATH.opt();
     const COMMISSION_RATE: Arg<Decimal> = arg("commission-rate");
+    const CONSENSUS_KEY: Arg<WalletKeypair> = arg("consensus-key");
     const CONSENSUS_TIMEOUT_COMMIT: ArgDefault<Timeout> = arg_default(
         "consensus-timeout-commit",
         DefaultFn(|| Timeout::from_str("1s").unwrap()),
     );
+    const CONTACT: ArgOpt<String> = arg_opt("contact");
     const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
     const DATA_PATH: Arg<PathBuf> = arg("data-path");
     const DECRYPT: ArgFlag = flag("decrypt");
+    const DESCRIPTION: ArgOpt<String> = arg_opt("description");
+    const DESTINATION_VALIDATOR: Arg<WalletAddress> =
+        arg("destination-validator");
     const DONT_ARCHIVE: ArgFlag = flag("dont-archive");
     const DRY_RUN_TX: ArgFlag = flag("dry-run");
+    const DUMP_TX: ArgOpt<PathBuf> = arg_opt("dump-tx");
     const EPOCH: ArgOpt<Epoch> = arg_opt("epoch");
+    const ERC20: Arg<EthAddress> = arg("erc20");
+    const ETH_RECEIVER: Arg<EthAddress> = arg("receiver");
     const FORCE: ArgFlag = flag("force");
     const DONT_PREFETCH_WASM: ArgFlag = flag("dont-prefetch-wasm");
     const GAS_AMOUNT: ArgDefault<token::Amount> =
@@ -1574,6 +1958,7 @@ pub mod args {
         arg_default_from_ctx("gas-token", DefaultFn(|| "NAM".into()));
     const GENESIS_PATH: Arg<PathBuf> = arg("genesis-path");
     const GENESIS_VALIDATOR: ArgOpt<String> = arg("genesis-validator").opt();
+    const HD_PATH: ArgOpt<String> = arg_opt("hd-path");
     const LEDGER_ADDRESS_ABOUT: &str =
         "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
          scheme is not supplied, it is assumed to be TCP.";
@@ -1589,8 +1974,11 @@ pub mod args {
     const MAX_COMMISSION_RATE_CHANGE: Arg<Decimal> =
         arg("max-commission-rate-change");
     const MODE: ArgOpt<String> = arg_opt("mode");
+    const MULTISIG_INDEX: ArgOpt<u8> = arg_opt("multisig-index");
+    const NAME: ArgOpt<String> = arg_opt("name");
     const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
+    const OUTPUT_PATH: ArgOpt<PathBuf> = arg_opt("output-path");
     const OWNER: ArgOpt<WalletAddress> = arg_opt("owner");
     const PIN: ArgFlag = flag("pin");
     const PORT_ID: ArgDefault<PortId> = arg_default(
@@ -1600,7 +1988,10 @@ pub mod args {
     const PROPOSAL_OFFLINE: ArgFlag = flag("offline");
     const PROTOCOL_KEY: ArgOpt<WalletPublicKey> = arg_opt("protocol-key");
     const PRE_GENESIS_PATH: ArgOpt<PathBuf> = arg_opt("pre-genesis-path");
+    const PRIV_VALIDATOR_ADDRESS: Arg<TendermintAddress> =
+        arg("priv-validator-address");
     const PUBLIC_KEY: Arg<WalletPublicKey> = arg("public-key");
+    const PUBLIC_KEYS: ArgMulti<WalletPublicKey> = PUBLIC_KEY.multi();
     const PROPOSAL_ID: Arg<u64> = arg("proposal-id");
     const PROPOSAL_ID_OPT: ArgOpt<u64> = arg_opt("proposal-id");
     const PROPOSAL_VOTE: Arg<ProposalVote> = arg("vote");
@@ -1610,13 +2001,17 @@ pub mod args {
     const RECEIVER: Arg<String> = arg("receiver");
     const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
+    const SIGNATURES: ArgMulti<PathBuf> = arg_multi("signature");
     const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
     const SIGNING_KEY_OPT: ArgOpt<WalletKeypair> = SIGNING_KEY.opt();
     const SIGNING_KEY: Arg<WalletKeypair> = arg("signing-key");
     const SOURCE: Arg<WalletAddress> = arg("source");
     const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
+    const SOURCE_VALIDATOR: Arg<WalletAddress> = arg("source-validator");
+    const STATE_PATH: ArgOpt<PathBuf> = arg_opt("state-path");
     const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
     const SUB_PREFIX: ArgOpt<String> = arg_opt("sub-prefix");
+    const THRESHOLD: ArgOpt<u8> = arg_opt("threshold");
     const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
     const TOKEN_OPT: ArgOpt<WalletAddress> = TOKEN.opt();
@@ -1624,6 +2019,7 @@ pub mod args {
     const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
     const TX_HASH: Arg<String> = arg("tx-hash");
+    const TX_PATH: Arg<PathBuf> = arg("tx-path");
     const UNSAFE_DONT_ENCRYPT: ArgFlag = flag("unsafe-dont-encrypt");
     const UNSAFE_SHOW_SECRET: ArgFlag = flag("unsafe-show-secret");
     const VALIDATOR: Arg<WalletAddress> = arg("validator");
@@ -1637,6 +2033,7 @@ pub mod args {
     const VIEWING_KEY: Arg<WalletViewingKey> = arg("key");
     const WASM_CHECKSUMS_PATH: Arg<PathBuf> = arg("wasm-checksums-path");
     const WASM_DIR: ArgOpt<PathBuf> = arg_opt("wasm-dir");
+    const WEBSITE: ArgOpt<String> = arg_opt("website");
 
     /// Global command arguments
     #[derive(Clone, Debug)]
@@ -1815,6 +2212,56 @@ pub mod args {
         }
     }
 
+    /// Transfer to Ethereum transaction arguments
+    #[derive(Clone, Debug)]
+    pub struct TxTransferToEthereum {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Transfer source address
+        pub source: WalletAddress,
+        /// The ERC20 contract of the transferred token
+        pub asset: EthAddress,
+        /// The Ethereum address receiving the tokens
+        pub receiver: EthAddress,
+        /// Transferred token amount
+        pub amount: token::Amount,
+    }
+
+    impl Args for TxTransferToEthereum {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let asset = ERC20.parse(matches);
+            let receiver = ETH_RECEIVER.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                source,
+                asset,
+                receiver,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address. The source's key is used to \
+                     produce the signature.",
+                ))
+                .arg(ERC20.def().about(
+                    "The address of the ERC20 contract of the transferred \
+                     token on Ethereum.",
+                ))
+                .arg(
+                    ETH_RECEIVER
+                        .def()
+                        .about("The Ethereum address receiving the tokens."),
+                )
+                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+        }
+    }
+
     /// IBC transfer transaction arguments
     #[derive(Clone, Debug)]
     pub struct TxIbcTransfer {
@@ -1898,8 +2345,10 @@ pub mod args {
         pub source: WalletAddress,
         /// Path to the VP WASM code file for the new account
         pub vp_code_path: Option<PathBuf>,
-        /// Public key for the new account
-        pub public_key: WalletPublicKey,
+        /// Public keys for the new account
+        pub public_keys: Vec<WalletPublicKey>,
+        /// The number of signatures required by a multisignature account
+        pub threshold: Option<u8>,
     }
 
     impl Args for TxInitAccount {
@@ -1907,12 +2356,14 @@ pub mod args {
             let tx = Tx::parse(matches);
             let source = SOURCE.parse(matches);
             let vp_code_path = CODE_PATH_OPT.parse(matches);
-            let public_key = PUBLIC_KEY.parse(matches);
+            let public_keys = PUBLIC_KEYS.parse(matches);
+            let threshold = THRESHOLD.parse(matches);
             Self {
                 tx,
                 source,
                 vp_code_path,
-                public_key,
+                public_keys,
+                threshold,
             }
         }
 
@@ -1926,9 +2377,15 @@ pub mod args {
                      for the new account. Uses the default user VP if none \
                      specified.",
                 ))
-                .arg(PUBLIC_KEY.def().about(
+                .arg(PUBLIC_KEYS.def().required(true).about(
                     "A public key to be used for the new account in \
-                     hexadecimal encoding.",
+                     hexadecimal encoding. Multiple keys can be given to \
+                     create a multisignature account.",
+                ))
+                .arg(THRESHOLD.def().about(
+                    "The number of signatures required to authorize a \
+                     transaction of a multisignature account. Defaults to the \
+                     number of public keys.",
                 ))
         }
     }
@@ -1944,6 +2401,7 @@ pub mod args {
         pub protocol_key: Option<WalletPublicKey>,
         pub commission_rate: Decimal,
         pub max_commission_rate_change: Decimal,
+        pub metadata: ValidatorMetaData,
         pub validator_vp_code_path: Option<PathBuf>,
         pub unsafe_dont_encrypt: bool,
     }
@@ -1959,6 +2417,7 @@ pub mod args {
             let commission_rate = COMMISSION_RATE.parse(matches);
             let max_commission_rate_change =
                 MAX_COMMISSION_RATE_CHANGE.parse(matches);
+            let metadata = ValidatorMetaData::parse(matches);
             let validator_vp_code_path = VALIDATOR_CODE_PATH.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
             Self {
@@ -1970,6 +2429,7 @@ pub mod args {
                 protocol_key,
                 commission_rate,
                 max_commission_rate_change,
+                metadata,
                 validator_vp_code_path,
                 unsafe_dont_encrypt,
             }
@@ -2007,6 +2467,7 @@ pub mod args {
                      Expressed as a decimal between 0 and 1. This is a \
                      required parameter.",
                 ))
+                .add_args::<ValidatorMetaData>()
                 .arg(VALIDATOR_CODE_PATH.def().about(
                     "The path to the validity predicate WASM code to be used \
                      for the validator account. Uses the default validator VP \
@@ -2139,34 +2600,82 @@ pub mod args {
         }
     }
 
+    /// Redelegation arguments
     #[derive(Clone, Debug)]
-    pub struct InitProposal {
+    pub struct Redelegate {
         /// Common tx arguments
         pub tx: Tx,
-        /// The proposal file path
-        pub proposal_data: PathBuf,
-        /// Flag if proposal should be run offline
-        pub offline: bool,
+        /// Owner of the redelegated delegation
+        pub owner: WalletAddress,
+        /// Validator address from which the tokens are redelegated
+        pub src_validator: WalletAddress,
+        /// Validator address to which the tokens are redelegated
+        pub dest_validator: WalletAddress,
+        /// Amount of tokens to redelegate
+        pub amount: token::Amount,
     }
 
-    impl Args for InitProposal {
+    impl Args for Redelegate {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let proposal_data = DATA_PATH.parse(matches);
-            let offline = PROPOSAL_OFFLINE.parse(matches);
-
+            let owner = SOURCE.parse(matches);
+            let src_validator = SOURCE_VALIDATOR.parse(matches);
+            let dest_validator = DESTINATION_VALIDATOR.parse(matches);
+            let amount = AMOUNT.parse(matches);
             Self {
                 tx,
-                proposal_data,
-                offline,
+                owner,
+                src_validator,
+                dest_validator,
+                amount,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
-                .arg(DATA_PATH.def().about(
-                    "The data path file (json) that describes the proposal.",
-                ))
+                .arg(SOURCE.def().about("The owner of the delegation."))
+                .arg(
+                    SOURCE_VALIDATOR.def().about(
+                        "The address of the validator to redelegate from.",
+                    ),
+                )
+                .arg(
+                    DESTINATION_VALIDATOR.def().about(
+                        "The address of the validator to redelegate to.",
+                    ),
+                )
+                .arg(AMOUNT.def().about("Amount of tokens to redelegate."))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct InitProposal {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The proposal file path
+        pub proposal_data: PathBuf,
+        /// Flag if proposal should be run offline
+        pub offline: bool,
+    }
+
+    impl Args for InitProposal {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let proposal_data = DATA_PATH.parse(matches);
+            let offline = PROPOSAL_OFFLINE.parse(matches);
+
+            Self {
+                tx,
+                proposal_data,
+                offline,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(DATA_PATH.def().about(
+                    "The data path file (json) that describes the proposal.",
+                ))
                 .arg(
                     PROPOSAL_OFFLINE
                         .def()
@@ -2217,11 +2726,10 @@ pub mod args {
                             DATA_PATH_OPT.name,
                         ]),
                 )
-                .arg(
-                    PROPOSAL_VOTE
-                        .def()
-                        .about("The vote for the proposal. Either yay or nay."),
-                )
+                .arg(PROPOSAL_VOTE.def().about(
+                    "The vote for the proposal. One of yay, nay, abstain or \
+                     veto.",
+                ))
                 .arg(
                     PROPOSAL_OFFLINE
                         .def()
@@ -2262,6 +2770,44 @@ pub mod args {
         }
     }
 
+    /// Submit a dumped transaction arguments
+    #[derive(Clone, Debug)]
+    pub struct SubmitTx {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the transaction dumped with `--dump-tx`
+        pub tx_path: PathBuf,
+        /// Paths to the signatures made with `wallet sign`
+        pub signatures: Vec<PathBuf>,
+    }
+
+    impl Args for SubmitTx {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let tx_path = TX_PATH.parse(matches);
+            let signatures = SIGNATURES.parse(matches);
+            Self {
+                tx,
+                tx_path,
+                signatures,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(
+                    TX_PATH
+                        .def()
+                        .about("The path to the transaction to submit."),
+                )
+                .arg(SIGNATURES.def().required(true).about(
+                    "The path to a signature of the transaction. Give one \
+                     signature for an account with a single key or the \
+                     signatures of a multisignature account.",
+                ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProposal {
         /// Common query args
@@ -2294,6 +2840,8 @@ pub mod args {
         pub offline: bool,
         /// The folder containing the proposal and votes
         pub proposal_folder: Option<PathBuf>,
+        /// The address whose key signs the offline tally report, if any
+        pub signer: Option<WalletAddress>,
     }
 
     impl Args for QueryProposalResult {
@@ -2302,12 +2850,14 @@ pub mod args {
             let proposal_id = PROPOSAL_ID_OPT.parse(matches);
             let offline = PROPOSAL_OFFLINE.parse(matches);
             let proposal_folder = DATA_PATH_OPT.parse(matches);
+            let signer = SIGNER.parse(matches);
 
             Self {
                 query,
                 proposal_id,
                 offline,
                 proposal_folder,
+                signer,
             }
         }
 
@@ -2332,6 +2882,15 @@ pub mod args {
                         )
                         .conflicts_with(PROPOSAL_ID.name),
                 )
+                .arg(
+                    SIGNER
+                        .def()
+                        .about(
+                            "Sign the offline tally report with the key of \
+                             this address and write it to the data folder.",
+                        )
+                        .requires(PROPOSAL_OFFLINE.name),
+                )
         }
     }
 
@@ -2388,6 +2947,134 @@ pub mod args {
         }
     }
 
+    impl Args for ValidatorMetaData {
+        fn parse(matches: &ArgMatches) -> Self {
+            let name = NAME.parse(matches);
+            let description = DESCRIPTION.parse(matches);
+            let website = WEBSITE.parse(matches);
+            let contact = CONTACT.parse(matches);
+            Self {
+                name,
+                description,
+                website,
+                contact,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(NAME.def().about("The validator's name."))
+                .arg(DESCRIPTION.def().about("A description of the validator."))
+                .arg(WEBSITE.def().about("The validator's website."))
+                .arg(
+                    CONTACT
+                        .def()
+                        .about("The validator's contact, e.g. an e-mail."),
+                )
+        }
+    }
+
+    /// Validator metadata change arguments
+    #[derive(Clone, Debug)]
+    pub struct TxChangeValidatorMetaData {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the validator whose metadata to change
+        pub validator: WalletAddress,
+        /// Metadata fields to change. Fields that are not given are left
+        /// unchanged and fields given as an empty string are removed.
+        pub metadata: ValidatorMetaData,
+    }
+
+    impl Args for TxChangeValidatorMetaData {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let metadata = ValidatorMetaData::parse(matches);
+            Self {
+                tx,
+                validator,
+                metadata,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(VALIDATOR.def().about(
+                    "The address of the validator whose metadata to change.",
+                ))
+                .add_args::<ValidatorMetaData>()
+        }
+    }
+
+    /// Validator consensus key change arguments
+    #[derive(Clone, Debug)]
+    pub struct TxChangeConsensusKey {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the validator whose consensus key to change
+        pub validator: WalletAddress,
+        /// The new consensus key. A new one is generated if none given.
+        pub consensus_key: Option<WalletKeypair>,
+        /// Don't encrypt the generated consensus key
+        pub unsafe_dont_encrypt: bool,
+    }
+
+    impl Args for TxChangeConsensusKey {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let consensus_key = VALIDATOR_CONSENSUS_KEY.parse(matches);
+            let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            Self {
+                tx,
+                validator,
+                consensus_key,
+                unsafe_dont_encrypt,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(VALIDATOR.def().about(
+                    "The address of the validator whose consensus key to \
+                     change.",
+                ))
+                .arg(VALIDATOR_CONSENSUS_KEY.def().about(
+                    "The new consensus key for the validator. A new one will \
+                     be generated if none given.",
+                ))
+                .arg(UNSAFE_DONT_ENCRYPT.def().about(
+                    "UNSAFE: Do not encrypt the generated keypair. Do not use \
+                     this for keys used in a live network.",
+                ))
+        }
+    }
+
+    /// Validator unjailing arguments
+    #[derive(Clone, Debug)]
+    pub struct TxUnjailValidator {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the jailed validator
+        pub validator: WalletAddress,
+    }
+
+    impl Args for TxUnjailValidator {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self { tx, validator }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>().arg(
+                VALIDATOR
+                    .def()
+                    .about("The address of the jailed validator to unjail."),
+            )
+        }
+    }
+
     /// Query asset conversions
     #[derive(Clone, Debug)]
     pub struct QueryConversions {
@@ -2729,6 +3416,9 @@ pub mod args {
         pub signing_key: Option<WalletKeypair>,
         /// Sign the tx with the keypair of the public key of the given address
         pub signer: Option<WalletAddress>,
+        /// Write the unsigned tx to the given file instead of signing and
+        /// submitting it
+        pub dump_tx: Option<PathBuf>,
     }
 
     impl Tx {
@@ -2749,6 +3439,7 @@ pub mod args {
                     .as_ref()
                     .map(|sk| ctx.get_cached(sk)),
                 signer: self.signer.as_ref().map(|signer| ctx.get(signer)),
+                dump_tx: self.dump_tx.clone(),
             }
         }
     }
@@ -2802,6 +3493,11 @@ pub mod args {
                     )
                     .conflicts_with(SIGNING_KEY_OPT.name),
             )
+            .arg(DUMP_TX.def().about(
+                "Write the unsigned transaction to the given file instead of \
+                 signing and submitting it. The transaction can then be \
+                 signed with `wallet sign` and submitted with `client submit`.",
+            ))
         }
 
         fn parse(matches: &ArgMatches) -> Self {
@@ -2816,6 +3512,7 @@ pub mod args {
 
             let signing_key = SIGNING_KEY_OPT.parse(matches);
             let signer = SIGNER.parse(matches);
+            let dump_tx = DUMP_TX.parse(matches);
             Self {
                 dry_run,
                 force,
@@ -2827,6 +3524,7 @@ pub mod args {
                 gas_limit,
                 signing_key,
                 signer,
+                dump_tx,
             }
         }
     }
@@ -2897,15 +3595,23 @@ pub mod args {
         pub alias: String,
         /// Don't encrypt the keypair
         pub unsafe_dont_encrypt: bool,
+        /// Derivation path of the key from the wallet's mnemonic code
+        pub hd_path: Option<String>,
+        /// Protect a new mnemonic code with a passphrase
+        pub bip39_passphrase: bool,
     }
 
     impl Args for MaspSpendKeyGen {
         fn parse(matches: &ArgMatches) -> Self {
             let alias = ALIAS.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let hd_path = HD_PATH.parse(matches);
+            let bip39_passphrase = BIP39_PASSPHRASE.parse(matches);
             Self {
                 alias,
                 unsafe_dont_encrypt,
+                hd_path,
+                bip39_passphrase,
             }
         }
 
@@ -2919,6 +3625,65 @@ pub mod args {
                 "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
                  used in a live network.",
             ))
+            .arg(HD_PATH.def().about(
+                "Derive the spending key from the wallet's mnemonic code at \
+                 this ZIP32 path, e.g. \"m/32'/877'/0'\", or \"default\" \
+                 for this default path. If the wallet has no mnemonic code \
+                 yet, a new one is generated and printed, and can be used to \
+                 restore the keys derived from it. If none provided, a random \
+                 key is generated.",
+            ))
+            .arg(BIP39_PASSPHRASE.def().about(
+                "Prompt for a passphrase protecting the wallet's mnemonic \
+                 code, if a new one is generated.",
+            ))
+        }
+    }
+
+    /// MASP restore spending key arguments
+    #[derive(Clone, Debug)]
+    pub struct MaspSpendKeyRestore {
+        /// Key alias
+        pub alias: String,
+        /// Don't encrypt the keypair
+        pub unsafe_dont_encrypt: bool,
+        /// Derivation path of the key
+        pub hd_path: Option<String>,
+        /// The mnemonic code is protected with a passphrase
+        pub bip39_passphrase: bool,
+    }
+
+    impl Args for MaspSpendKeyRestore {
+        fn parse(matches: &ArgMatches) -> Self {
+            let alias = ALIAS.parse(matches);
+            let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let hd_path = HD_PATH.parse(matches);
+            let bip39_passphrase = BIP39_PASSPHRASE.parse(matches);
+            Self {
+                alias,
+                unsafe_dont_encrypt,
+                hd_path,
+                bip39_passphrase,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                ALIAS
+                    .def()
+                    .about("An alias to be associated with the spending key."),
+            )
+            .arg(UNSAFE_DONT_ENCRYPT.def().about(
+                "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
+                 used in a live network.",
+            ))
+            .arg(HD_PATH.def().about(
+                "The ZIP32 path to derive the spending key at. If none \
+                 provided, the default path \"m/32'/877'/0'\" is used.",
+            ))
+            .arg(BIP39_PASSPHRASE.def().about(
+                "Prompt for the passphrase protecting the mnemonic code.",
+            ))
         }
     }
 
@@ -2968,6 +3733,10 @@ pub mod args {
         pub alias: Option<String>,
         /// Don't encrypt the keypair
         pub unsafe_dont_encrypt: bool,
+        /// Derivation path of the key from the wallet's mnemonic code
+        pub hd_path: Option<String>,
+        /// Protect a new mnemonic code with a passphrase
+        pub bip39_passphrase: bool,
     }
 
     impl Args for KeyAndAddressGen {
@@ -2975,10 +3744,14 @@ pub mod args {
             let scheme = SCHEME.parse(matches);
             let alias = ALIAS_OPT.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let hd_path = HD_PATH.parse(matches);
+            let bip39_passphrase = BIP39_PASSPHRASE.parse(matches);
             Self {
                 scheme,
                 alias,
                 unsafe_dont_encrypt,
+                hd_path,
+                bip39_passphrase,
             }
         }
 
@@ -2996,6 +3769,101 @@ pub mod args {
                 "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
                  used in a live network.",
             ))
+            .arg(HD_PATH.def().about(
+                "Derive the key from the wallet's mnemonic code at this BIP44 \
+                 path, e.g. \"m/44'/877'/0'/0'/0'\", or \"default\" for \
+                 the default path of the key scheme. If the wallet has no \
+                 mnemonic code yet, a new one is generated and printed, and \
+                 can be used to restore the keys derived from it. If none \
+                 provided, a random key is generated.",
+            ))
+            .arg(BIP39_PASSPHRASE.def().about(
+                "Prompt for a passphrase protecting the wallet's mnemonic \
+                 code, if a new one is generated.",
+            ))
+        }
+    }
+
+    /// Wallet restore key and implicit address arguments
+    #[derive(Clone, Debug)]
+    pub struct KeyAndAddressRestore {
+        /// Scheme type
+        pub scheme: SchemeType,
+        /// Key alias
+        pub alias: Option<String>,
+        /// Don't encrypt the keypair
+        pub unsafe_dont_encrypt: bool,
+        /// Derivation path of the key
+        pub hd_path: Option<String>,
+        /// The mnemonic code is protected with a passphrase
+        pub bip39_passphrase: bool,
+    }
+
+    impl Args for KeyAndAddressRestore {
+        fn parse(matches: &ArgMatches) -> Self {
+            let scheme = SCHEME.parse(matches);
+            let alias = ALIAS_OPT.parse(matches);
+            let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let hd_path = HD_PATH.parse(matches);
+            let bip39_passphrase = BIP39_PASSPHRASE.parse(matches);
+            Self {
+                scheme,
+                alias,
+                unsafe_dont_encrypt,
+                hd_path,
+                bip39_passphrase,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(SCHEME.def().about(
+                "The type of key that should be restored. Argument must be \
+                 either ed25519 or secp256k1. If none provided, the default \
+                 key scheme is ed25519.",
+            ))
+            .arg(ALIAS_OPT.def().about(
+                "The key and address alias. If none provided, the alias will \
+                 be the public key hash.",
+            ))
+            .arg(UNSAFE_DONT_ENCRYPT.def().about(
+                "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
+                 used in a live network.",
+            ))
+            .arg(HD_PATH.def().about(
+                "The BIP44 path to derive the key at. If none provided, the \
+                 default path of the key scheme is used, which is \
+                 \"m/44'/877'/0'/0'/0'\" for ed25519 and \
+                 \"m/44'/877'/0'/0/0\" for secp256k1.",
+            ))
+            .arg(BIP39_PASSPHRASE.def().about(
+                "Prompt for the passphrase protecting the mnemonic code.",
+            ))
+        }
+    }
+
+    /// Wallet add hardware wallet key arguments
+    #[derive(Clone, Debug)]
+    pub struct KeyAddHardware {
+        /// Key alias
+        pub alias: String,
+        /// Derivation path of the key on the device
+        pub hd_path: Option<String>,
+    }
+
+    impl Args for KeyAddHardware {
+        fn parse(matches: &ArgMatches) -> Self {
+            let alias = ALIAS.parse(matches);
+            let hd_path = HD_PATH.parse(matches);
+            Self { alias, hd_path }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(ALIAS.def().about("The key and address alias."))
+                .arg(HD_PATH.def().about(
+                    "The BIP44 path the device derives the key at. If none \
+                     provided, the default path \"m/44'/877'/0'/0'/0'\" is \
+                     used.",
+                ))
         }
     }
 
@@ -3152,6 +4020,51 @@ pub mod args {
         }
     }
 
+    /// Wallet transaction signing arguments
+    #[derive(Clone, Debug)]
+    pub struct SignTx {
+        /// Path to the transaction dumped with `--dump-tx`
+        pub tx_path: PathBuf,
+        /// The key to sign the transaction with
+        pub signing_key: WalletKeypair,
+        /// The index of the key in a multisignature account
+        pub multisig_index: Option<u8>,
+        /// Where to write the signature
+        pub output_path: Option<PathBuf>,
+    }
+
+    impl Args for SignTx {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx_path = TX_PATH.parse(matches);
+            let signing_key = SIGNING_KEY.parse(matches);
+            let multisig_index = MULTISIG_INDEX.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                tx_path,
+                signing_key,
+                multisig_index,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(TX_PATH.def().about("The path to the transaction to sign."))
+                .arg(SIGNING_KEY.def().about(
+                    "Sign the transaction with the key for the given public \
+                     key, public key hash or alias from your wallet.",
+                ))
+                .arg(MULTISIG_INDEX.def().about(
+                    "The index of the signing key in the multisignature \
+                     account the transaction is signed for.",
+                ))
+                .arg(OUTPUT_PATH.def().about(
+                    "The path of the file to write the signature to. Defaults \
+                     to the transaction's path suffixed with the signing \
+                     key's hash.",
+                ))
+        }
+    }
+
     /// Wallet address lookup arguments
     #[derive(Clone, Debug)]
     pub struct AddressOrAliasFind {
@@ -3397,6 +4310,69 @@ pub mod args {
                 ))
         }
     }
+
+    /// Ledger set consensus key arguments
+    #[derive(Clone, Debug)]
+    pub struct LedgerSetConsensusKey {
+        /// The consensus key to sign with
+        pub consensus_key: WalletKeypair,
+    }
+
+    impl Args for LedgerSetConsensusKey {
+        fn parse(matches: &ArgMatches) -> Self {
+            let consensus_key = CONSENSUS_KEY.parse(matches);
+            Self { consensus_key }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(CONSENSUS_KEY.def().about(
+                "The consensus key to sign with, given by its public key, \
+                 public key hash or alias in the wallet.",
+            ))
+        }
+    }
+
+    /// Remote signer arguments
+    #[derive(Clone, Debug)]
+    pub struct Signer {
+        /// The address the Tendermint node listens on for the signer
+        pub address: TendermintAddress,
+        /// The consensus key to sign with
+        pub consensus_key: Option<WalletKeypair>,
+        /// Where to persist the last signature
+        pub state_path: Option<PathBuf>,
+    }
+
+    impl Args for Signer {
+        fn parse(matches: &ArgMatches) -> Self {
+            let address = PRIV_VALIDATOR_ADDRESS.parse(matches);
+            let consensus_key = VALIDATOR_CONSENSUS_KEY.parse(matches);
+            let state_path = STATE_PATH.parse(matches);
+            Self {
+                address,
+                consensus_key,
+                state_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(PRIV_VALIDATOR_ADDRESS.def().about(
+                "The `unix://` address of the socket the Tendermint node \
+                 listens on for the signer, set as `priv_validator_address` \
+                 in the node's config.",
+            ))
+            .arg(VALIDATOR_CONSENSUS_KEY.def().about(
+                "The consensus key to sign with, given by its public key, \
+                 public key hash or alias in the wallet. Defaults to the \
+                 consensus key of the validator in the wallet.",
+            ))
+            .arg(STATE_PATH.def().about(
+                "The path of the file where the height, round and step of the \
+                 last signature are kept to protect against double-signing. \
+                 Defaults to `signer_state.json` in the chain's directory.",
+            ))
+        }
+    }
 }
 
 pub fn namada_cli() -> (cmds::Namada, String) {
@@ -4,14 +4,10 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
-use std::fs::File;
 use std::io::{self, Write};
 use std::iter::Iterator;
 use std::str::FromStr;
 
-use async_std::fs;
-use async_std::path::PathBuf;
-use async_std::prelude::*;
 use borsh::{BorshDeserialize, BorshSerialize};
 use data_encoding::HEXLOWER;
 use eyre::{eyre, Context as EyreContext};
@@ -22,10 +18,14 @@ use masp_primitives::primitives::ViewingKey;
 use masp_primitives::sapling::Node;
 use masp_primitives::transaction::components::Amount;
 use masp_primitives::zip32::ExtendedFullViewingKey;
+use namada::ledger::dkg::storage as dkg_storage;
 use namada::ledger::events::Event;
 use namada::ledger::governance::parameters::GovParams;
 use namada::ledger::governance::storage as gov_storage;
-use namada::ledger::native_vp::governance::utils::Votes;
+use namada::ledger::native_vp::governance::utils::TallyThresholds;
+use namada::ledger::offline_governance::{
+    self, OfflineVotingData, StakeSnapshot,
+};
 use namada::ledger::parameters::{storage as param_storage, EpochDuration};
 use namada::ledger::pos::types::{decimal_mult_u64, WeightedValidator};
 use namada::ledger::pos::{
@@ -36,10 +36,10 @@ use namada::ledger::storage::ConversionState;
 use namada::proto::{SignedTxData, Tx};
 use namada::types::address::{masp, tokens, Address};
 use namada::types::governance::{
-    OfflineProposal, OfflineVote, ProposalResult, ProposalVote, TallyResult,
-    VotePower,
+    FundsDestination, ProposalStatus, StorageProposal,
 };
 use namada::types::hash::Hash;
+use namada::types::key::multisig::MultisigKeys;
 use namada::types::key::*;
 use namada::types::masp::{BalanceOwner, ExtendedViewingKey, PaymentAddress};
 use namada::types::storage::{
@@ -47,14 +47,15 @@ use namada::types::storage::{
 };
 use namada::types::token::{balance_key, Transfer};
 use namada::types::transaction::{
-    process_tx, AffineCurve, DecryptedTx, EllipticCurve, PairingEngine, TxType,
-    WrapperTx,
+    process_tx, AffineCurve, DecryptedTx, EllipticCurve, EncryptionKey,
+    PairingEngine, TxType, WrapperTx,
 };
 use namada::types::{address, storage, token};
 use rust_decimal::Decimal;
 use tokio::time::{Duration, Instant};
 
 use crate::cli::{self, args, Context};
+use crate::client::signing::find_keypair;
 use crate::client::tendermint_rpc_types::TxResponse;
 use crate::client::tx::{
     Conversions, PinnedBalanceError, TransactionDelta, TransferDelta,
@@ -126,6 +127,17 @@ pub async fn query_epoch(args: args::Query) -> Epoch {
     epoch
 }
 
+/// Query the key that the txs of the given epoch are encrypted with. The
+/// bootstrap key is used when no key was generated for the epoch.
+pub async fn query_encryption_key(
+    client: &HttpClient,
+    epoch: Epoch,
+) -> EncryptionKey {
+    query_storage_value(client, &dkg_storage::encryption_key_key(epoch))
+        .await
+        .unwrap_or_default()
+}
+
 /// Query the last committed block
 pub async fn query_block(
     args: args::Query,
@@ -386,6 +398,8 @@ fn extract_payload(
 ) {
     match process_tx(tx) {
         Ok(TxType::Wrapper(wrapper_tx)) => {
+            // Only the wrappers of the epochs without a key generated by the
+            // DKG, which use the bootstrap key, can be decrypted here
             let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
             extract_payload(
                 Tx::from(match wrapper_tx.decrypt(privkey) {
@@ -736,107 +750,92 @@ fn print_balances(
 pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
     async fn print_proposal(
         client: &HttpClient,
-        id: u64,
+        proposal: StorageProposal,
         current_epoch: Epoch,
         details: bool,
-    ) -> Option<()> {
-        let author_key = gov_storage::get_author_key(id);
-        let start_epoch_key = gov_storage::get_voting_start_epoch_key(id);
-        let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
-
-        let author =
-            query_storage_value::<Address>(client, &author_key).await?;
-        let start_epoch =
-            query_storage_value::<Epoch>(client, &start_epoch_key).await?;
-        let end_epoch =
-            query_storage_value::<Epoch>(client, &end_epoch_key).await?;
-
+    ) {
+        let status = proposal.status(current_epoch);
+        println!("Proposal: {}", proposal.id);
+        if details {
+            println!("{:4}Type: {}", "", proposal.proposal_type);
+        }
+        println!("{:4}Author: {}", "", proposal.author);
         if details {
-            let content_key = gov_storage::get_content_key(id);
-            let grace_epoch_key = gov_storage::get_grace_epoch_key(id);
-            let content = query_storage_value::<HashMap<String, String>>(
-                client,
-                &content_key,
-            )
-            .await?;
-            let grace_epoch =
-                query_storage_value::<Epoch>(client, &grace_epoch_key).await?;
-
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
             println!("{:4}Content:", "");
-            for (key, value) in &content {
+            for (key, value) in &proposal.content {
                 println!("{:8}{}: {}", "", key, value);
             }
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
-            println!("{:4}Grace Epoch: {}", "", grace_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                let votes = get_proposal_votes(client, start_epoch, id).await;
-                let partial_proposal_result =
-                    compute_tally(client, start_epoch, votes).await;
+        }
+        println!("{:4}Start Epoch: {}", "", proposal.voting_start_epoch);
+        println!("{:4}End Epoch: {}", "", proposal.voting_end_epoch);
+        if details {
+            println!("{:4}Grace Epoch: {}", "", proposal.grace_epoch);
+        }
+        if details && status != ProposalStatus::Pending {
+            let proposal_result = unwrap_client_response(
+                RPC.vp().gov().tally(client, &proposal.id).await,
+            )
+            .expect("The tally of a started proposal should be available");
+            if status == ProposalStatus::OnGoing {
                 println!(
                     "{:4}Yay votes: {}",
-                    "", partial_proposal_result.total_yay_power
+                    "", proposal_result.total_yay_power
                 );
                 println!(
                     "{:4}Nay votes: {}",
-                    "", partial_proposal_result.total_nay_power
+                    "", proposal_result.total_nay_power
+                );
+                println!(
+                    "{:4}Abstain votes: {}",
+                    "", proposal_result.total_abstain_power
+                );
+                println!(
+                    "{:4}Veto votes: {}",
+                    "", proposal_result.total_veto_power
                 );
-                println!("{:4}Status: on-going", "");
+                println!("{:4}Status: {}", "", status);
             } else {
-                let votes = get_proposal_votes(client, start_epoch, id).await;
-                let proposal_result =
-                    compute_tally(client, start_epoch, votes).await;
-                println!("{:4}Status: done", "");
+                println!("{:4}Status: {}", "", status);
                 println!("{:4}Result: {}", "", proposal_result);
             }
         } else {
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                println!("{:4}Status: on-going", "");
-            } else {
-                println!("{:4}Status: done", "");
-            }
+            println!("{:4}Status: {}", "", status);
         }
-
-        Some(())
     }
 
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
     let current_epoch = query_epoch(args.query.clone()).await;
     match args.proposal_id {
         Some(id) => {
-            if print_proposal(&client, id, current_epoch, true)
-                .await
-                .is_none()
-            {
-                eprintln!("No valid proposal was found with id {}", id)
+            match unwrap_client_response(
+                RPC.vp().gov().proposal(&client, &id).await,
+            ) {
+                Some(proposal) => {
+                    print_proposal(&client, proposal, current_epoch, true).await
+                }
+                None => {
+                    eprintln!("No valid proposal was found with id {}", id)
+                }
             }
         }
         None => {
-            let last_proposal_id_key = gov_storage::get_counter_key();
-            let last_proposal_id =
-                query_storage_value::<u64>(&client, &last_proposal_id_key)
-                    .await
-                    .unwrap();
-
-            for id in 0..last_proposal_id {
-                if print_proposal(&client, id, current_epoch, false)
-                    .await
-                    .is_none()
-                {
-                    eprintln!("No valid proposal was found with id {}", id)
-                };
+            // The proposals are queried one page at a time
+            let mut offset = 0;
+            loop {
+                let proposals = unwrap_client_response(
+                    RPC.vp()
+                        .gov()
+                        .proposals(&client, &None, &Some(offset), &None)
+                        .await,
+                );
+                if proposals.is_empty() {
+                    break;
+                }
+                offset += proposals.len() as u64;
+                for proposal in proposals {
+                    print_proposal(&client, proposal, current_epoch, false)
+                        .await;
+                }
             }
         }
     }
@@ -1153,7 +1152,7 @@ pub async fn get_token_balance(
 }
 
 pub async fn query_proposal_result(
-    _ctx: Context,
+    mut ctx: Context,
     args: args::QueryProposalResult,
 ) {
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
@@ -1161,17 +1160,17 @@ pub async fn query_proposal_result(
 
     match args.proposal_id {
         Some(id) => {
-            let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
-            let end_epoch =
-                query_storage_value::<Epoch>(&client, &end_epoch_key).await;
-
-            match end_epoch {
-                Some(end_epoch) => {
-                    if current_epoch > end_epoch {
-                        let votes =
-                            get_proposal_votes(&client, end_epoch, id).await;
-                        let proposal_result =
-                            compute_tally(&client, end_epoch, votes).await;
+            let proposal = unwrap_client_response(
+                RPC.vp().gov().proposal(&client, &id).await,
+            );
+
+            match proposal {
+                Some(proposal) => {
+                    if proposal.status(current_epoch) == ProposalStatus::Done {
+                        let proposal_result = unwrap_client_response(
+                            RPC.vp().gov().tally(&client, &id).await,
+                        )
+                        .expect("The tally of an ended proposal should exist");
                         println!("Proposal: {}", id);
                         println!("{:4}Result: {}", "", proposal_result);
                     } else {
@@ -1189,85 +1188,71 @@ pub async fn query_proposal_result(
             if args.offline {
                 match args.proposal_folder {
                     Some(path) => {
-                        let mut dir = fs::read_dir(&path)
-                            .await
-                            .expect("Should be able to read the directory.");
-                        let mut files = HashSet::new();
-                        let mut is_proposal_present = false;
-
-                        while let Some(entry) = dir.next().await {
-                            match entry {
-                                Ok(entry) => match entry.file_type().await {
-                                    Ok(entry_stat) => {
-                                        if entry_stat.is_file() {
-                                            if entry.file_name().eq(&"proposal")
-                                            {
-                                                is_proposal_present = true
-                                            } else if entry
-                                                .file_name()
-                                                .to_string_lossy()
-                                                .starts_with("proposal-vote-")
-                                            {
-                                                // Folder may contain other
-                                                // files than just the proposal
-                                                // and the votes
-                                                files.insert(entry.path());
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!(
-                                            "Can't read entry type: {}.",
-                                            e
-                                        );
-                                        cli::safe_exit(1)
-                                    }
-                                },
-                                Err(e) => {
-                                    eprintln!("Can't read entry: {}.", e);
-                                    cli::safe_exit(1)
-                                }
-                            }
-                        }
-
-                        if !is_proposal_present {
-                            eprintln!(
-                                "The folder must contain the offline proposal \
-                                 in a file named \"proposal\""
-                            );
+                        let data = OfflineVotingData::load(&path)
+                            .unwrap_or_else(|err| {
+                                eprintln!("{}", err);
+                                cli::safe_exit(1)
+                            });
+                        let snapshot =
+                            get_offline_stake_snapshot(&client, &data).await;
+                        let gov_params =
+                            get_governance_parameters(&client).await;
+                        let report = offline_governance::tally(
+                            &data,
+                            &snapshot,
+                            TallyThresholds::from(&gov_params),
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("{}", err);
                             cli::safe_exit(1)
-                        }
+                        });
 
-                        let file = File::open(path.join("proposal"))
-                            .expect("Proposal file must exist.");
-                        let proposal: OfflineProposal =
-                            serde_json::from_reader(file).expect(
-                                "JSON was not well-formatted for proposal.",
+                        for (voter, rejection) in &report.rejected {
+                            println!(
+                                "{:4}Vote from {} not counted: {:?}",
+                                "", voter, rejection
                             );
-
-                        let public_key = get_public_key(
-                            &proposal.address,
-                            args.query.ledger_address.clone(),
-                        )
-                        .await
-                        .expect("Public key should exist.");
-
-                        if !proposal.check_signature(&public_key) {
-                            eprintln!("Bad proposal signature.");
-                            cli::safe_exit(1)
                         }
+                        println!("{:4}Result: {}", "", report.result);
+                        println!(
+                            "{:4}Report hash: {}",
+                            "",
+                            report.compute_hash()
+                        );
 
-                        let votes = get_proposal_offline_votes(
-                            &client,
-                            proposal.clone(),
-                            files,
-                        )
-                        .await;
-                        let proposal_result =
-                            compute_tally(&client, proposal.tally_epoch, votes)
-                                .await;
-
-                        println!("{:4}Result: {}", "", proposal_result);
+                        if let Some(signer) = args.signer {
+                            let signer = ctx.get(&signer);
+                            let signing_key = find_keypair(
+                                &mut ctx.wallet,
+                                &signer,
+                                args.query.ledger_address.clone(),
+                            )
+                            .await;
+                            let signed_report =
+                                report.sign(signer.clone(), &signing_key);
+                            let report_filename =
+                                path.join(format!("tally-report-{}", signer));
+                            let report_bytes = signed_report
+                                .try_to_vec()
+                                .expect("Encoding tally report shouldn't fail");
+                            match std::fs::write(&report_filename, report_bytes)
+                            {
+                                Ok(_) => {
+                                    println!(
+                                        "Signed tally report created: {}.",
+                                        report_filename.to_string_lossy()
+                                    );
+                                }
+                                Err(e) => {
+                                    eprintln!(
+                                        "Error while creating tally report \
+                                         file: {}.",
+                                        e
+                                    );
+                                    cli::safe_exit(1)
+                                }
+                            }
+                        }
                     }
                     None => {
                         eprintln!(
@@ -1349,6 +1334,22 @@ pub async fn query_protocol_parameters(
         "{:4}Light client attack minimum slash rate: {}",
         "", pos_params.light_client_attack_min_slash_rate
     );
+    println!(
+        "{:4}Cubic slashing window length: {}",
+        "", pos_params.cubic_slashing_window_length
+    );
+    println!(
+        "{:4}Liveness window length: {}",
+        "", pos_params.liveness_window_len
+    );
+    println!(
+        "{:4}Liveness max. missed blocks rate: {}",
+        "", pos_params.liveness_max_missed_rate
+    );
+    println!(
+        "{:4}Liveness slash rate: {}",
+        "", pos_params.liveness_slash_rate
+    );
     println!(
         "{:4}Max. validator slots: {}",
         "", pos_params.max_validator_slots
@@ -1754,7 +1755,12 @@ pub async fn query_bonded_stake(ctx: Context, args: args::QueryBondedStake) {
                         validator.encode(),
                         if is_active { "active" } else { "inactive" },
                         bonded_stake,
-                    )
+                    );
+                    if let Some(metadata) =
+                        query_validator_metadata(&client, &validator).await
+                    {
+                        print_validator_metadata(&metadata);
+                    }
                 }
                 None => {
                     println!("No bonded stake found for {}", validator.encode())
@@ -1768,10 +1774,12 @@ pub async fn query_bonded_stake(ctx: Context, args: args::QueryBondedStake) {
 
             writeln!(w, "Active validators:").unwrap();
             for active in &validator_set.active {
+                let name = query_validator_name(&client, &active.address).await;
                 writeln!(
                     w,
-                    "  {}: {}",
+                    "  {}{}: {}",
                     active.address.encode(),
+                    name,
                     active.bonded_stake
                 )
                 .unwrap();
@@ -1779,10 +1787,13 @@ pub async fn query_bonded_stake(ctx: Context, args: args::QueryBondedStake) {
             if !validator_set.inactive.is_empty() {
                 writeln!(w, "Inactive validators:").unwrap();
                 for inactive in &validator_set.inactive {
+                    let name =
+                        query_validator_name(&client, &inactive.address).await;
                     writeln!(
                         w,
-                        "  {}: {}",
+                        "  {}{}: {}",
                         inactive.address.encode(),
+                        name,
                         inactive.bonded_stake
                     )
                     .unwrap();
@@ -1952,6 +1963,17 @@ pub async fn get_public_key(
     query_storage_value(&client, &key).await
 }
 
+/// Get the public keys and signature threshold of a multisignature account
+/// stored in its storage sub-space
+pub async fn get_multisig_keys(
+    address: &Address,
+    ledger_address: TendermintAddress,
+) -> Option<MultisigKeys> {
+    let client = HttpClient::new(ledger_address).unwrap();
+    let key = multisig_key(address);
+    query_storage_value(&client, &key).await
+}
+
 /// Check if the given address is a known validator.
 pub async fn is_validator(
     address: &Address,
@@ -1961,6 +1983,43 @@ pub async fn is_validator(
     unwrap_client_response(RPC.vp().pos().is_validator(&client, address).await)
 }
 
+/// Query the metadata of a validator, if any has been set.
+pub async fn query_validator_metadata(
+    client: &HttpClient,
+    validator: &Address,
+) -> Option<pos::ValidatorMetaData> {
+    unwrap_client_response(
+        RPC.vp().pos().validator_metadata(client, validator).await,
+    )
+}
+
+/// Query the name of a validator formatted for display next to its address,
+/// or an empty string if the validator has no name.
+async fn query_validator_name(
+    client: &HttpClient,
+    validator: &Address,
+) -> String {
+    query_validator_metadata(client, validator)
+        .await
+        .and_then(|metadata| metadata.name)
+        .map(|name| format!(" ({})", name))
+        .unwrap_or_default()
+}
+
+fn print_validator_metadata(metadata: &pos::ValidatorMetaData) {
+    let fields = [
+        ("Name", &metadata.name),
+        ("Description", &metadata.description),
+        ("Website", &metadata.website),
+        ("Contact", &metadata.contact),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            println!("  {}: {}", label, value);
+        }
+    }
+}
+
 /// Check if a given address is a known delegator
 pub async fn is_delegator(
     address: &Address,
@@ -2501,255 +2560,62 @@ pub async fn query_result(_ctx: Context, args: args::QueryResult) {
     }
 }
 
-pub async fn get_proposal_votes(
-    client: &HttpClient,
-    epoch: Epoch,
-    proposal_id: u64,
-) -> Votes {
-    let validators = get_all_validators(client, epoch).await;
-
-    let vote_prefix_key =
-        gov_storage::get_proposal_vote_prefix_key(proposal_id);
-    let vote_iter =
-        query_storage_prefix::<ProposalVote>(client, &vote_prefix_key).await;
-
-    let mut yay_validators: HashMap<Address, VotePower> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-
-    if let Some(vote_iter) = vote_iter {
-        for (key, vote) in vote_iter {
-            let voter_address = gov_storage::get_voter_address(&key)
-                .expect("Vote key should contain the voting address.")
-                .clone();
-            if vote.is_yay() && validators.contains(&voter_address) {
-                let amount: VotePower =
-                    get_validator_stake(client, epoch, &voter_address)
-                        .await
-                        .into();
-                yay_validators.insert(voter_address, amount);
-            } else if !validators.contains(&voter_address) {
-                let validator_address =
-                    gov_storage::get_vote_delegation_address(&key)
-                        .expect(
-                            "Vote key should contain the delegation address.",
-                        )
-                        .clone();
-                let delegator_token_amount = get_bond_amount_at(
-                    client,
-                    &voter_address,
-                    &validator_address,
-                    epoch,
-                )
-                .await;
-                if let Some(amount) = delegator_token_amount {
-                    if vote.is_yay() {
-                        let entry =
-                            yay_delegators.entry(voter_address).or_default();
-                        entry
-                            .insert(validator_address, VotePower::from(amount));
-                    } else {
-                        let entry =
-                            nay_delegators.entry(voter_address).or_default();
-                        entry
-                            .insert(validator_address, VotePower::from(amount));
-                    }
-                }
-            }
-        }
-    }
-
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    }
-}
-
-pub async fn get_proposal_offline_votes(
+/// Get the public keys and the stake at the proposal's tally epoch of all the
+/// addresses taking part in an offline vote
+pub async fn get_offline_stake_snapshot(
     client: &HttpClient,
-    proposal: OfflineProposal,
-    files: HashSet<PathBuf>,
-) -> Votes {
-    let validators = get_all_validators(client, proposal.tally_epoch).await;
-
-    let proposal_hash = proposal.compute_hash();
-
-    let mut yay_validators: HashMap<Address, VotePower> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-
-    for path in files {
-        let file = File::open(&path).expect("Proposal file must exist.");
-        let proposal_vote: OfflineVote = serde_json::from_reader(file)
-            .expect("JSON was not well-formatted for offline vote.");
-
-        let key = pk_key(&proposal_vote.address);
-        let public_key = query_storage_value(client, &key)
-            .await
-            .expect("Public key should exist.");
+    data: &OfflineVotingData,
+) -> StakeSnapshot {
+    let epoch = data.proposal.tally_epoch;
+    let mut snapshot = StakeSnapshot {
+        epoch,
+        total_stake: get_total_staked_tokens(client, epoch).await.into(),
+        ..Default::default()
+    };
 
-        if !proposal_vote.proposal_hash.eq(&proposal_hash)
-            || !proposal_vote.check_signature(&public_key)
+    let validators = unwrap_client_response(
+        RPC.vp()
+            .pos()
+            .validator_addresses(client, &Some(epoch))
+            .await,
+    );
+    for address in data.addresses() {
+        if let Some(public_key) =
+            query_storage_value(client, &pk_key(&address)).await
         {
-            continue;
+            snapshot.public_keys.insert(address.clone(), public_key);
         }
 
-        if proposal_vote.vote.is_yay()
-            && validators.contains(&proposal_vote.address)
-        {
-            let amount: VotePower = get_validator_stake(
-                client,
-                proposal.tally_epoch,
-                &proposal_vote.address,
-            )
-            .await
-            .into();
-            yay_validators.insert(proposal_vote.address, amount);
-        } else if is_delegator_at(
-            client,
-            &proposal_vote.address,
-            proposal.tally_epoch,
-        )
-        .await
-        {
-            let key = pos::bonds_for_source_prefix(&proposal_vote.address);
-            let bonds_iter =
-                query_storage_prefix::<pos::Bonds>(client, &key).await;
-            if let Some(bonds) = bonds_iter {
-                for (key, epoched_bonds) in bonds {
-                    // Look-up slashes for the validator in this key and
-                    // apply them if any
-                    let validator = pos::get_validator_address_from_bond(&key)
-                        .expect(
-                            "Delegation key should contain validator address.",
-                        );
-                    let slashes_key = pos::validator_slashes_key(&validator);
-                    let slashes = query_storage_value::<pos::Slashes>(
-                        client,
-                        &slashes_key,
-                    )
-                    .await
-                    .unwrap_or_default();
-                    let mut delegated_amount: token::Amount = 0.into();
-                    let bond = epoched_bonds
-                        .get(proposal.tally_epoch)
-                        .expect("Delegation bond should be defined.");
-                    let mut to_deduct = bond.neg_deltas;
-                    for (start_epoch, &(mut delta)) in
-                        bond.pos_deltas.iter().sorted()
-                    {
-                        // deduct bond's neg_deltas
-                        if to_deduct > delta {
-                            to_deduct -= delta;
-                            // If the whole bond was deducted, continue to
-                            // the next one
-                            continue;
-                        } else {
-                            delta -= to_deduct;
-                            to_deduct = token::Amount::default();
-                        }
-
-                        delta = apply_slashes(
-                            &slashes,
-                            delta,
-                            *start_epoch,
-                            None,
-                            None,
-                        );
-                        delegated_amount += delta;
-                    }
-
-                    let validator_address =
-                        pos::get_validator_address_from_bond(&key).expect(
-                            "Delegation key should contain validator address.",
-                        );
-                    if proposal_vote.vote.is_yay() {
-                        let entry = yay_delegators
-                            .entry(proposal_vote.address.clone())
-                            .or_default();
-                        entry.insert(
-                            validator_address,
-                            VotePower::from(delegated_amount),
-                        );
-                    } else {
-                        let entry = nay_delegators
-                            .entry(proposal_vote.address.clone())
-                            .or_default();
-                        entry.insert(
-                            validator_address,
-                            VotePower::from(delegated_amount),
-                        );
-                    }
-                }
-            }
+        if validators.contains(&address) {
+            let stake = get_validator_stake(client, epoch, &address).await;
+            snapshot.validators.insert(address, stake.into());
+            continue;
         }
-    }
 
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    }
-}
-
-// Compute the result of a proposal
-pub async fn compute_tally(
-    client: &HttpClient,
-    epoch: Epoch,
-    votes: Votes,
-) -> ProposalResult {
-    let total_staked_tokens: VotePower =
-        get_total_staked_tokens(client, epoch).await.into();
-
-    let Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    } = votes;
-
-    let mut total_yay_staked_tokens = VotePower::from(0_u64);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_staked_tokens += amount;
-    }
-
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (_, vote_map) in yay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if !yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens += vote_power;
+        let delegatees = unwrap_client_response(
+            RPC.vp()
+                .pos()
+                .delegations(client, &address, &Some(epoch))
+                .await,
+        );
+        let mut delegations = HashMap::new();
+        for validator in delegatees {
+            let amount = unwrap_client_response(
+                RPC.vp()
+                    .pos()
+                    .bond_amount(client, &address, &validator, &Some(epoch))
+                    .await,
+            );
+            if amount != token::Amount::default() {
+                delegations.insert(validator, amount.into());
             }
         }
-    }
-
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (_, vote_map) in nay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens -= vote_power;
-            }
+        if !delegations.is_empty() {
+            snapshot.delegations.insert(address, delegations);
         }
     }
 
-    if total_yay_staked_tokens >= (total_staked_tokens / 3) * 2 {
-        ProposalResult {
-            result: TallyResult::Passed,
-            total_voting_power: total_staked_tokens,
-            total_yay_power: total_yay_staked_tokens,
-            total_nay_power: 0,
-        }
-    } else {
-        ProposalResult {
-            result: TallyResult::Rejected,
-            total_voting_power: total_staked_tokens,
-            total_yay_power: total_yay_staked_tokens,
-            total_nay_power: 0,
-        }
-    }
+    snapshot
 }
 
 pub async fn get_bond_amount_at(
@@ -2842,7 +2708,9 @@ pub async fn get_delegators_delegation(
     client: &HttpClient,
     address: &Address,
 ) -> HashSet<Address> {
-    unwrap_client_response(RPC.vp().pos().delegations(client, address).await)
+    unwrap_client_response(
+        RPC.vp().pos().delegations(client, address, &None).await,
+    )
 }
 
 pub async fn get_governance_parameters(client: &HttpClient) -> GovParams {
@@ -2877,6 +2745,36 @@ pub async fn get_governance_parameters(client: &HttpClient) -> GovParams {
         .await
         .expect("Parameter should be definied.");
 
+    let key = gov_storage::get_quorum_key();
+    let quorum = query_storage_value::<Decimal>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    let key = gov_storage::get_pass_threshold_key();
+    let pass_threshold = query_storage_value::<Decimal>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    let key = gov_storage::get_veto_threshold_key();
+    let veto_threshold = query_storage_value::<Decimal>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    let key = gov_storage::get_rejected_funds_key();
+    let rejected_funds = query_storage_value::<FundsDestination>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    let key = gov_storage::get_vetoed_funds_key();
+    let vetoed_funds = query_storage_value::<FundsDestination>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    let key = gov_storage::get_failed_funds_key();
+    let failed_funds = query_storage_value::<FundsDestination>(client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
     GovParams {
         min_proposal_fund: u64::from(min_proposal_fund),
         max_proposal_code_size,
@@ -2884,6 +2782,12 @@ pub async fn get_governance_parameters(client: &HttpClient) -> GovParams {
         max_proposal_period,
         max_proposal_content_size,
         min_proposal_grace_epochs,
+        quorum,
+        pass_threshold,
+        veto_threshold,
+        rejected_funds,
+        vetoed_funds,
+        failed_funds,
     }
 }
 
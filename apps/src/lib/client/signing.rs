@@ -1,19 +1,147 @@
 //! Helpers for making digital signatures using cryptographic keys from the
-//! wallet.
+//! wallet or held by hardware wallets.
 
-use borsh::BorshSerialize;
-use namada::proto::Tx;
+pub mod hardware;
+
+use std::fs;
+use std::path::Path;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada::proto::{SigningTx, Tx, TxSignature};
 use namada::types::address::{Address, ImplicitAddress};
 use namada::types::key::*;
 use namada::types::storage::Epoch;
-use namada::types::transaction::{hash_tx, Fee, WrapperTx};
+use namada::types::transaction::{hash_tx, Fee, GasLimit, WrapperTx};
+use thiserror::Error;
 
+use self::hardware::{DeviceError, HardwareSigner};
 use super::rpc;
 use crate::cli::context::{WalletAddress, WalletKeypair};
 use crate::cli::{self, args, Context};
 use crate::client::tendermint_rpc_types::TxBroadcastData;
 use crate::facade::tendermint_config::net::Address as TendermintAddress;
-use crate::wallet::Wallet;
+use crate::facade::tendermint_rpc::HttpClient;
+use crate::wallet::{HardwareKey, Wallet};
+
+/// A signing backend, holding the secret key of a public key and making
+/// signatures with it
+pub trait Signer: Send {
+    /// The public key of the secret key used to sign
+    fn public_key(&self) -> common::PublicKey;
+
+    /// Sign the hash of a tx
+    fn sign_tx(
+        &mut self,
+        tx: &SigningTx,
+    ) -> Result<common::Signature, SignerError>;
+}
+
+#[derive(Error, Debug)]
+pub enum SignerError {
+    #[error("{0}")]
+    Hardware(#[from] DeviceError),
+}
+
+/// The software backend, signing with a keypair loaded from the wallet
+impl Signer for common::SecretKey {
+    fn public_key(&self) -> common::PublicKey {
+        self.ref_to()
+    }
+
+    fn sign_tx(
+        &mut self,
+        tx: &SigningTx,
+    ) -> Result<common::Signature, SignerError> {
+        Ok(common::SigScheme::sign(self, tx.hash()))
+    }
+}
+
+/// Look-up the public key of an established address from the ledger. Exits if
+/// it's not found.
+async fn lookup_public_key(
+    addr: &Address,
+    ledger_address: TendermintAddress,
+) -> common::PublicKey {
+    println!(
+        "Looking-up public key of {} from the ledger...",
+        addr.encode()
+    );
+    rpc::get_public_key(addr, ledger_address)
+        .await
+        .unwrap_or_else(|| {
+            eprintln!("No public key found for the address {}", addr.encode());
+            cli::safe_exit(1);
+        })
+}
+
+/// Load the keypair of the given public key from the wallet. Exits if the key
+/// cannot be found or loaded.
+fn find_keypair_by_pk(
+    wallet: &mut Wallet,
+    public_key: &common::PublicKey,
+) -> common::SecretKey {
+    wallet.find_key_by_pk(public_key).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to load the keypair from the wallet for public key {}. \
+             Failed with: {}",
+            public_key, err
+        );
+        cli::safe_exit(1)
+    })
+}
+
+/// Connect to the hardware wallet holding the given key. Exits on failure.
+fn connect_hardware_signer(key: &HardwareKey) -> Box<dyn Signer> {
+    match HardwareSigner::connect(key) {
+        Ok(signer) => Box::new(signer),
+        Err(err) => {
+            eprintln!(
+                "Unable to use the hardware wallet for public key {}. Failed \
+                 with: {}",
+                key.public_key, err
+            );
+            cli::safe_exit(1)
+        }
+    }
+}
+
+/// Find the signer of the given public key: the hardware wallet holding it if
+/// the wallet knows of one, otherwise its keypair loaded from the wallet.
+/// Exits if the key cannot be found or used.
+fn find_signer_by_pk(
+    wallet: &mut Wallet,
+    public_key: &common::PublicKey,
+) -> Box<dyn Signer> {
+    if let Some(key) = wallet.find_hardware_key_by_pk(public_key) {
+        return connect_hardware_signer(key);
+    }
+    Box::new(find_keypair_by_pk(wallet, public_key))
+}
+
+/// Find the public key for the given address and the signer for it, like
+/// [`find_keypair`] but also using the keys held by hardware wallets. Exits
+/// if the key cannot be found or used.
+pub async fn find_signer(
+    wallet: &mut Wallet,
+    addr: &Address,
+    ledger_address: TendermintAddress,
+) -> Box<dyn Signer> {
+    match addr {
+        Address::Established(_) => {
+            let public_key = lookup_public_key(addr, ledger_address).await;
+            find_signer_by_pk(wallet, &public_key)
+        }
+        Address::Implicit(ImplicitAddress(pkh)) => {
+            if let Some(key) = wallet.find_hardware_key_by_pkh(pkh) {
+                return connect_hardware_signer(key);
+            }
+            Box::new(find_keypair(wallet, addr, ledger_address).await)
+        }
+        Address::Internal(_) => {
+            Box::new(find_keypair(wallet, addr, ledger_address).await)
+        }
+    }
+}
 
 /// Find the public key for the given address and try to load the keypair
 /// for it from the wallet. Panics if the key cannot be found or loaded.
@@ -24,27 +152,8 @@ pub async fn find_keypair(
 ) -> common::SecretKey {
     match addr {
         Address::Established(_) => {
-            println!(
-                "Looking-up public key of {} from the ledger...",
-                addr.encode()
-            );
-            let public_key = rpc::get_public_key(addr, ledger_address)
-                .await
-                .unwrap_or_else(|| {
-                    eprintln!(
-                        "No public key found for the address {}",
-                        addr.encode()
-                    );
-                    cli::safe_exit(1);
-                });
-            wallet.find_key_by_pk(&public_key).unwrap_or_else(|err| {
-                eprintln!(
-                    "Unable to load the keypair from the wallet for public \
-                     key {}. Failed with: {}",
-                    public_key, err
-                );
-                cli::safe_exit(1)
-            })
+            let public_key = lookup_public_key(addr, ledger_address).await;
+            find_keypair_by_pk(wallet, &public_key)
         }
         Address::Implicit(ImplicitAddress(pkh)) => {
             wallet.find_key_by_pkh(pkh).unwrap_or_else(|err| {
@@ -67,6 +176,72 @@ pub async fn find_keypair(
     }
 }
 
+/// Find the signers of a multisignature account, from the wallet or the
+/// hardware wallets it knows of. Returns the signers along with the indices
+/// of their public keys in the account, up to the account's threshold, or
+/// `None` if the address is not a multisignature account. Exits if the
+/// wallet doesn't hold enough of the account's keys to meet its threshold.
+pub async fn find_multisig_signers(
+    wallet: &mut Wallet,
+    addr: &Address,
+    ledger_address: TendermintAddress,
+) -> Option<Vec<(u8, Box<dyn Signer>)>> {
+    if !matches!(addr, Address::Established(_)) {
+        return None;
+    }
+    let keys = rpc::get_multisig_keys(addr, ledger_address).await?;
+    let signers: Vec<_> = keys
+        .public_keys
+        .iter()
+        .enumerate()
+        .filter_map(|(index, public_key)| {
+            let signer: Box<dyn Signer> =
+                match wallet.find_hardware_key_by_pk(public_key) {
+                    Some(key) => connect_hardware_signer(key),
+                    None => Box::new(wallet.find_key_by_pk(public_key).ok()?),
+                };
+            Some((index as u8, signer))
+        })
+        .take(usize::from(keys.threshold))
+        .collect();
+    if signers.len() < usize::from(keys.threshold) {
+        eprintln!(
+            "Only {} of the {} keys required to sign for the multisignature \
+             account {} were found in the wallet.",
+            signers.len(),
+            keys.threshold,
+            addr.encode()
+        );
+        cli::safe_exit(1)
+    }
+    Some(signers)
+}
+
+/// Sign the hash of a tx with the given signer. Exits on failure.
+fn sign_tx_or_exit(
+    signer: &mut dyn Signer,
+    tx: &SigningTx,
+) -> common::Signature {
+    signer.sign_tx(tx).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to sign the transaction with public key {}. Failed with: \
+             {}",
+            signer.public_key(),
+            err
+        );
+        cli::safe_exit(1)
+    })
+}
+
+/// Attach the given signature(s) of the hash of a tx to it
+fn attach_signature(tx: Tx, sig: TxSignature) -> Tx {
+    let code = tx.code.clone();
+    SigningTx::from(tx)
+        .attach_signature(sig)
+        .expand(code)
+        .expect("Tx code must match its hash")
+}
+
 /// Carries types that can be directly/indirectly used to sign a transaction.
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
@@ -82,14 +257,14 @@ pub enum TxSigningKey {
 }
 
 /// Given CLI arguments and some defaults, determine the rightful transaction
-/// signer. Return the given signing key or public key of the given signer if
-/// possible. If no explicit signer given, use the `default`. If no `default`
-/// is given, panics.
+/// signer. Return the given signing key or the signer of the given address if
+/// possible, which may be a hardware wallet. If no explicit signer given, use
+/// the `default`. If no `default` is given, panics.
 pub async fn tx_signer(
     ctx: &mut Context,
     args: &args::Tx,
     mut default: TxSigningKey,
-) -> common::SecretKey {
+) -> Box<dyn Signer> {
     // Override the default signing key source if possible
     if let Some(signing_key) = &args.signing_key {
         default = TxSigningKey::WalletKeypair(signing_key.clone());
@@ -99,11 +274,11 @@ pub async fn tx_signer(
     // Now actually fetch the signing key and apply it
     match default {
         TxSigningKey::WalletKeypair(signing_key) => {
-            ctx.get_cached(&signing_key)
+            Box::new(ctx.get_cached(&signing_key))
         }
         TxSigningKey::WalletAddress(signer) => {
             let signer = ctx.get(&signer);
-            let signing_key = find_keypair(
+            let signing_key = find_signer(
                 &mut ctx.wallet,
                 &signer,
                 args.ledger_address.clone(),
@@ -112,7 +287,7 @@ pub async fn tx_signer(
             // Check if the signer is implicit account that needs to reveal its
             // PK first
             if matches!(signer, Address::Implicit(_)) {
-                let pk: common::PublicKey = signing_key.ref_to();
+                let pk = signing_key.public_key();
                 super::tx::reveal_pk_if_needed(ctx, &pk, args).await;
             }
             signing_key
@@ -121,7 +296,7 @@ pub async fn tx_signer(
             // Check if the signing key needs to reveal its PK first
             let pk: common::PublicKey = signing_key.ref_to();
             super::tx::reveal_pk_if_needed(ctx, &pk, args).await;
-            signing_key
+            Box::new(signing_key)
         }
         TxSigningKey::None => {
             panic!(
@@ -140,14 +315,54 @@ pub async fn tx_signer(
 /// hashes needed for monitoring the tx on chain.
 ///
 /// If it is a dry run, it is not put in a wrapper, but returned as is.
+///
+/// When the signer is a multisignature account, the tx is signed with the
+/// account's keys found in the wallet and the wrapper is signed with the
+/// first of them.
+///
+/// Keys held by hardware wallets sign on the device.
 pub async fn sign_tx(
     mut ctx: Context,
     tx: Tx,
     args: &args::Tx,
     default: TxSigningKey,
 ) -> (Context, TxBroadcastData) {
-    let keypair = tx_signer(&mut ctx, args, default).await;
-    let tx = tx.sign(&keypair);
+    let multisig_signer = match (&args.signing_key, &args.signer, &default) {
+        (None, Some(signer), _)
+        | (None, None, TxSigningKey::WalletAddress(signer)) => {
+            Some(ctx.get(signer))
+        }
+        _ => None,
+    };
+    let multisig_signers = match multisig_signer {
+        Some(signer) => {
+            find_multisig_signers(
+                &mut ctx.wallet,
+                &signer,
+                args.ledger_address.clone(),
+            )
+            .await
+        }
+        None => None,
+    };
+    let signing_tx = SigningTx::from(tx.clone());
+    let (tx, mut signer) = match multisig_signers {
+        Some(mut signers) => {
+            let sigs = signers
+                .iter_mut()
+                .map(|(index, signer)| {
+                    (*index, sign_tx_or_exit(signer.as_mut(), &signing_tx))
+                })
+                .collect();
+            let (_index, signer) = signers.swap_remove(0);
+            (attach_signature(tx, TxSignature::Multi(sigs)), signer)
+        }
+        None => {
+            let mut signer = tx_signer(&mut ctx, args, default).await;
+            let sig = sign_tx_or_exit(signer.as_mut(), &signing_tx);
+            (attach_signature(tx, TxSignature::Single(sig)), signer)
+        }
+    };
 
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.ledger_address.clone(),
@@ -156,7 +371,7 @@ pub async fn sign_tx(
     let broadcast_data = if args.dry_run {
         TxBroadcastData::DryRun(tx)
     } else {
-        sign_wrapper(&ctx, args, epoch, tx, &keypair).await
+        sign_wrapper(&ctx, args, epoch, tx, signer.as_mut()).await
     };
     (ctx, broadcast_data)
 }
@@ -169,33 +384,258 @@ pub async fn sign_wrapper(
     args: &args::Tx,
     epoch: Epoch,
     tx: Tx,
-    keypair: &common::SecretKey,
+    signer: &mut dyn Signer,
 ) -> TxBroadcastData {
-    let tx = {
-        WrapperTx::new(
-            Fee {
-                amount: args.fee_amount,
-                token: ctx.get(&args.fee_token),
-            },
-            keypair,
-            epoch,
-            args.gas_limit.clone(),
-            tx,
-            // TODO: Actually use the fetched encryption key
-            Default::default(),
-        )
+    let fee = Fee {
+        amount: args.fee_amount,
+        token: ctx.get(&args.fee_token),
     };
+    wrap_and_sign(
+        args.ledger_address.clone(),
+        fee,
+        args.gas_limit.clone(),
+        epoch,
+        tx,
+        signer,
+    )
+    .await
+}
+
+/// Create a wrapper tx with the given fee and gas limit from a normal tx and
+/// sign it. Get the hash of the wrapper and its payload which is needed for
+/// monitoring its progress on chain.
+pub async fn wrap_and_sign(
+    ledger_address: TendermintAddress,
+    fee: Fee,
+    gas_limit: GasLimit,
+    epoch: Epoch,
+    tx: Tx,
+    signer: &mut dyn Signer,
+) -> TxBroadcastData {
+    let client = HttpClient::new(ledger_address).unwrap();
+    let encryption_key = rpc::query_encryption_key(&client, epoch).await;
+    let tx = WrapperTx::new_with_pk(
+        fee,
+        signer.public_key(),
+        epoch,
+        gas_limit,
+        tx,
+        encryption_key,
+    );
 
     // We use this to determine when the wrapper tx makes it on-chain
     let wrapper_hash = hash_tx(&tx.try_to_vec().unwrap()).to_string();
     // We use this to determine when the decrypted inner tx makes it
     // on-chain
     let decrypted_hash = tx.tx_hash.to_string();
+    let tx = tx.to_unsigned_tx();
+    let sig = sign_tx_or_exit(signer, &SigningTx::from(tx.clone()));
     TxBroadcastData::Wrapper {
-        tx: tx
-            .sign(keypair)
-            .expect("Wrapper tx signing keypair should be correct"),
+        tx: attach_signature(tx, TxSignature::Single(sig)),
         wrapper_hash,
         decrypted_hash,
     }
 }
+
+/// A transaction dumped with `--dump-tx` instead of being signed and
+/// submitted. It can be signed separately, e.g. on an offline machine, with
+/// `wallet sign` and then submitted with `client submit`.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UnsignedTx {
+    /// The inner tx, without a signature
+    pub tx: Tx,
+    /// The fee to be paid by the wrapper tx
+    pub fee: Fee,
+    /// The max amount of gas used to process the tx
+    pub gas_limit: GasLimit,
+    /// The epoch in which the tx was dumped. The wrapper tx is built in the
+    /// epoch in which the tx gets submitted.
+    pub epoch: Epoch,
+}
+
+impl UnsignedTx {
+    /// The hash of the inner tx that is signed. It commits to the hash of the
+    /// tx code, the tx data and the timestamp.
+    pub fn signing_hash(&self) -> [u8; 32] {
+        SigningTx::from(self.tx.clone()).hash()
+    }
+
+    /// Attach the given signatures to the inner tx. A single signature
+    /// without an index signs for an account with a single public key,
+    /// otherwise all the signatures must have the index of their public key
+    /// in a multisignature account. Every signature is checked against the
+    /// public key it was made with.
+    pub fn attach_signatures(
+        &self,
+        signatures: Vec<OfflineSignature>,
+    ) -> Result<Tx, String> {
+        let signing_hash = self.signing_hash();
+        for signature in &signatures {
+            common::SigScheme::verify_signature_raw(
+                &signature.public_key,
+                &signing_hash,
+                &signature.signature,
+            )
+            .map_err(|err| {
+                format!(
+                    "Invalid signature for public key {}: {}",
+                    signature.public_key, err
+                )
+            })?;
+        }
+        let sig = match &signatures[..] {
+            [] => return Err("No signatures given".to_string()),
+            [OfflineSignature {
+                index: None,
+                signature,
+                ..
+            }] => TxSignature::Single(signature.clone()),
+            _ => TxSignature::Multi(
+                signatures
+                    .into_iter()
+                    .map(|signature| match signature.index {
+                        Some(index) => Ok((index, signature.signature)),
+                        None => Err(format!(
+                            "Missing the multisignature index of public key {}",
+                            signature.public_key
+                        )),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        Ok(attach_signature(self.tx.clone(), sig))
+    }
+}
+
+/// A signature of an [`UnsignedTx`] made with `wallet sign`
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct OfflineSignature {
+    /// The public key of the keypair that made the signature
+    pub public_key: common::PublicKey,
+    /// The index of the public key in a multisignature account, if the tx
+    /// is signed on behalf of one
+    pub index: Option<u8>,
+    /// The signature of the inner tx
+    pub signature: common::Signature,
+}
+
+impl OfflineSignature {
+    /// Sign the inner tx of the given unsigned tx
+    pub fn new(
+        unsigned: &UnsignedTx,
+        keypair: &common::SecretKey,
+        index: Option<u8>,
+    ) -> Self {
+        Self {
+            public_key: keypair.ref_to(),
+            index,
+            signature: common::SigScheme::sign(
+                keypair,
+                unsigned.signing_hash(),
+            ),
+        }
+    }
+}
+
+/// Write a Borsh encoded value to the given file. Exits on failure.
+pub fn write_to_file(path: impl AsRef<Path>, value: &impl BorshSerialize) {
+    let path = path.as_ref();
+    let bytes = value.try_to_vec().expect("Encoding shouldn't fail");
+    if let Err(err) = fs::write(path, bytes) {
+        eprintln!("Failed to write to {}: {}", path.to_string_lossy(), err);
+        cli::safe_exit(1)
+    }
+}
+
+/// Read a Borsh encoded value from the given file. Exits on failure.
+pub fn read_from_file<T: BorshDeserialize>(path: impl AsRef<Path>) -> T {
+    let path = path.as_ref();
+    fs::read(path)
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| {
+            T::try_from_slice(&bytes).map_err(|err| err.to_string())
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {}", path.to_string_lossy(), err);
+            cli::safe_exit(1)
+        })
+}
+
+/// Dump the given tx to a file to be signed separately instead of signing and
+/// submitting it. The wrapper's fee, gas limit and epoch are taken from the
+/// tx arguments and the current epoch.
+pub async fn dump_tx(ctx: &Context, args: &args::Tx, tx: Tx, path: &Path) {
+    let epoch = rpc::query_epoch(args::Query {
+        ledger_address: args.ledger_address.clone(),
+    })
+    .await;
+    let unsigned = UnsignedTx {
+        tx,
+        fee: Fee {
+            amount: args.fee_amount,
+            token: ctx.get(&args.fee_token),
+        },
+        gas_limit: args.gas_limit.clone(),
+        epoch,
+    };
+    write_to_file(path, &unsigned);
+    println!(
+        "Unsigned transaction written to {}. Code hash: {}, signing hash: {}",
+        path.to_string_lossy(),
+        hash_tx(&unsigned.tx.code),
+        namada::types::hash::Hash(unsigned.signing_hash()),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use namada::types::address;
+    use namada::types::time::DateTimeUtc;
+
+    use super::*;
+
+    #[test]
+    fn test_attach_offline_signatures() {
+        let unsigned = UnsignedTx {
+            tx: Tx {
+                code: vec![1, 2, 3],
+                data: Some(vec![4, 5, 6]),
+                timestamp: DateTimeUtc::now(),
+            },
+            fee: Fee {
+                amount: 0.into(),
+                token: address::nam(),
+            },
+            gas_limit: 0.into(),
+            epoch: Epoch(0),
+        };
+        let keypair_1 = testing::keypair_1();
+        let keypair_2 = testing::keypair_2();
+
+        // A single signature without an index
+        let single = OfflineSignature::new(&unsigned, &keypair_1, None);
+        let tx = unsigned.attach_signatures(vec![single.clone()]).unwrap();
+        tx.verify_sig(&keypair_1.ref_to(), &single.signature)
+            .expect("The signature should be valid");
+
+        // Indexed signatures of a multisignature account
+        let sigs = vec![
+            OfflineSignature::new(&unsigned, &keypair_1, Some(0)),
+            OfflineSignature::new(&unsigned, &keypair_2, Some(1)),
+        ];
+        unsigned.attach_signatures(sigs).unwrap();
+
+        // Multiple signatures must all be indexed
+        let sigs = vec![
+            single,
+            OfflineSignature::new(&unsigned, &keypair_2, Some(1)),
+        ];
+        assert!(unsigned.attach_signatures(sigs).is_err());
+
+        // A signature of another tx is rejected
+        let mut other_tx = unsigned.clone();
+        other_tx.tx.data = Some(vec![7, 8, 9]);
+        let other = OfflineSignature::new(&other_tx, &keypair_1, None);
+        assert!(unsigned.attach_signatures(vec![other]).is_err());
+    }
+}
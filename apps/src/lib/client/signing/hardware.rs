@@ -0,0 +1,439 @@
+//! A signing backend for hardware wallets. The secret keys never leave the
+//! device, which is sent APDU commands to get the public key derived at a
+//! given path and to sign txs with its secret key. Txs are sent in full, so
+//! that the device shows what it signs and computes the signed hash itself.
+//!
+//! Devices are reached over TCP in the framing used by the Speculos emulator
+//! and by proxies to USB devices: a command is prefixed with its length as a
+//! big-endian `u32` and an answer is its data length as a big-endian `u32`,
+//! followed by the data and the status word.
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+use borsh::BorshDeserialize;
+use namada::proto::SigningTx;
+use namada::types::key::*;
+use thiserror::Error;
+
+use super::{Signer, SignerError};
+use crate::wallet::derivation::DerivationPath;
+use crate::wallet::HardwareKey;
+
+/// Env. var to set the `host:port` address of the hardware wallet
+pub const ENV_VAR_HARDWARE_WALLET: &str = "NAMADA_HARDWARE_WALLET";
+
+/// The address used when [`ENV_VAR_HARDWARE_WALLET`] is not set, which is
+/// the default APDU port of Speculos
+pub const DEFAULT_DEVICE_ADDRESS: &str = "127.0.0.1:9999";
+
+/// The class of the APDU commands of the Namada app
+pub const CLA: u8 = 0x57;
+/// Get the public key derived at the path in the command data. Answers with
+/// the Borsh encoded `common::PublicKey`.
+pub const INS_GET_PUBLIC_KEY: u8 = 0x01;
+/// Sign the hash of a tx, once it's approved on the device. The first command
+/// has the path in its data, followed by commands with the chunks of the
+/// encoded [`SigningTx`]. The last one is answered with the Borsh encoded
+/// `common::Signature`.
+pub const INS_SIGN_TX: u8 = 0x02;
+
+/// `P1` of the first command of a multi-part instruction
+pub const P1_FIRST: u8 = 0x00;
+/// `P1` of the next commands of a multi-part instruction
+pub const P1_NEXT: u8 = 0x01;
+/// `P1` of the last command of a multi-part instruction
+pub const P1_LAST: u8 = 0x02;
+
+/// The max length of the data of an APDU command
+pub const MAX_COMMAND_DATA_LEN: usize = 255;
+/// The max length of the data of an answer. Any valid answer is much
+/// shorter, this bounds how much memory a faulty device can make us allocate.
+pub const MAX_ANSWER_LEN: u32 = 1024;
+
+/// The status word of a successful command
+pub const SW_OK: u16 = 0x9000;
+/// The status word of a command rejected by the user on the device
+pub const SW_REJECTED: u16 = 0x6986;
+
+#[derive(Error, Debug)]
+pub enum DeviceError {
+    #[error("Failed to communicate with the hardware wallet: {0}")]
+    Transport(io::Error),
+    #[error("The request was rejected on the hardware wallet")]
+    Rejected,
+    #[error("The hardware wallet failed with status {0:#06x}")]
+    Status(u16),
+    #[error("Invalid answer from the hardware wallet: {0}")]
+    InvalidAnswer(String),
+    #[error("The answer of {0} bytes from the hardware wallet is too long")]
+    AnswerTooLong(u32),
+    #[error(
+        "The hardware wallet derives the public key {0} instead of the \
+         expected one, check that the right device is connected"
+    )]
+    UnexpectedKey(common::PublicKey),
+}
+
+/// An APDU command to the Namada app
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApduCommand {
+    /// The instruction
+    pub ins: u8,
+    /// The first parameter of the instruction
+    pub p1: u8,
+    /// The second parameter of the instruction
+    pub p2: u8,
+    /// The command data, of at most 255 bytes
+    pub data: Vec<u8>,
+}
+
+impl ApduCommand {
+    /// Encode the command as `CLA INS P1 P2 Lc data`
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![CLA, self.ins, self.p1, self.p2];
+        bytes.push(
+            u8::try_from(self.data.len())
+                .expect("APDU command data cannot exceed 255 bytes"),
+        );
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Decode a command encoded with [`ApduCommand::encode`]
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [CLA, ins, p1, p2, len, data @ ..]
+                if usize::from(*len) == data.len() =>
+            {
+                Some(Self {
+                    ins: *ins,
+                    p1: *p1,
+                    p2: *p2,
+                    data: data.to_vec(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The answer to an APDU command
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApduAnswer {
+    /// The answer data
+    pub data: Vec<u8>,
+    /// The status word
+    pub status: u16,
+}
+
+/// A way to exchange APDU commands with a device
+pub trait ApduTransport: Send {
+    /// Send a command and wait for its answer
+    fn exchange(
+        &mut self,
+        command: &ApduCommand,
+    ) -> Result<ApduAnswer, DeviceError>;
+}
+
+/// A device reached over TCP
+#[derive(Debug)]
+pub struct TcpTransport(TcpStream);
+
+impl TcpTransport {
+    /// Connect to the device at the given `host:port` address
+    pub fn connect(address: &str) -> Result<Self, DeviceError> {
+        TcpStream::connect(address)
+            .map(Self)
+            .map_err(DeviceError::Transport)
+    }
+
+    /// Connect to the device at the address set with
+    /// [`ENV_VAR_HARDWARE_WALLET`] or at [`DEFAULT_DEVICE_ADDRESS`]
+    pub fn connect_default() -> Result<Self, DeviceError> {
+        let address = env::var(ENV_VAR_HARDWARE_WALLET)
+            .unwrap_or_else(|_| DEFAULT_DEVICE_ADDRESS.to_string());
+        Self::connect(&address)
+    }
+}
+
+impl ApduTransport for TcpTransport {
+    fn exchange(
+        &mut self,
+        command: &ApduCommand,
+    ) -> Result<ApduAnswer, DeviceError> {
+        let command = command.encode();
+        let mut request = (command.len() as u32).to_be_bytes().to_vec();
+        request.extend(command);
+        self.0.write_all(&request).map_err(DeviceError::Transport)?;
+
+        let mut len = [0; 4];
+        self.0
+            .read_exact(&mut len)
+            .map_err(DeviceError::Transport)?;
+        let len = u32::from_be_bytes(len);
+        if len > MAX_ANSWER_LEN {
+            return Err(DeviceError::AnswerTooLong(len));
+        }
+        let mut data = vec![0; len as usize];
+        self.0
+            .read_exact(&mut data)
+            .map_err(DeviceError::Transport)?;
+        let mut status = [0; 2];
+        self.0
+            .read_exact(&mut status)
+            .map_err(DeviceError::Transport)?;
+        Ok(ApduAnswer {
+            data,
+            status: u16::from_be_bytes(status),
+        })
+    }
+}
+
+/// Encode a derivation path as the number of its indices followed by each
+/// index as a big-endian `u32`
+pub fn encode_path(path: &DerivationPath) -> Vec<u8> {
+    let indices: Vec<u32> = path.indices().collect();
+    let len =
+        u8::try_from(indices.len()).expect("The derivation path is too long");
+    let mut bytes = vec![len];
+    for index in indices {
+        bytes.extend_from_slice(&index.to_be_bytes());
+    }
+    bytes
+}
+
+/// Send a command and get the data of its answer if it succeeded
+fn exchange_ok(
+    transport: &mut dyn ApduTransport,
+    command: &ApduCommand,
+) -> Result<Vec<u8>, DeviceError> {
+    let answer = transport.exchange(command)?;
+    match answer.status {
+        SW_OK => Ok(answer.data),
+        SW_REJECTED => Err(DeviceError::Rejected),
+        status => Err(DeviceError::Status(status)),
+    }
+}
+
+/// Get the public key the device derives at the given path
+pub fn get_public_key(
+    transport: &mut dyn ApduTransport,
+    path: &DerivationPath,
+) -> Result<common::PublicKey, DeviceError> {
+    let command = ApduCommand {
+        ins: INS_GET_PUBLIC_KEY,
+        p1: 0,
+        p2: 0,
+        data: encode_path(path),
+    };
+    let data = exchange_ok(transport, &command)?;
+    common::PublicKey::try_from_slice(&data)
+        .map_err(|err| DeviceError::InvalidAnswer(err.to_string()))
+}
+
+/// Signs with a key held by a hardware wallet
+pub struct HardwareSigner {
+    transport: Box<dyn ApduTransport>,
+    path: DerivationPath,
+    public_key: common::PublicKey,
+}
+
+impl HardwareSigner {
+    /// Use the key the device derives at the given path
+    pub fn new(
+        mut transport: Box<dyn ApduTransport>,
+        path: DerivationPath,
+    ) -> Result<Self, DeviceError> {
+        let public_key = get_public_key(transport.as_mut(), &path)?;
+        Ok(Self {
+            transport,
+            path,
+            public_key,
+        })
+    }
+
+    /// Connect to the default device and check that it holds the given key
+    pub fn connect(key: &HardwareKey) -> Result<Self, DeviceError> {
+        let transport = Box::new(TcpTransport::connect_default()?);
+        let signer = Self::new(transport, key.derivation_path.clone())?;
+        if signer.public_key != key.public_key {
+            return Err(DeviceError::UnexpectedKey(signer.public_key));
+        }
+        Ok(signer)
+    }
+}
+
+impl Signer for HardwareSigner {
+    fn public_key(&self) -> common::PublicKey {
+        self.public_key.clone()
+    }
+
+    fn sign_tx(
+        &mut self,
+        tx: &SigningTx,
+    ) -> Result<common::Signature, SignerError> {
+        let transport = self.transport.as_mut();
+        let command = ApduCommand {
+            ins: INS_SIGN_TX,
+            p1: P1_FIRST,
+            p2: 0,
+            data: encode_path(&self.path),
+        };
+        exchange_ok(transport, &command)?;
+
+        println!(
+            "Please review and approve the signature on your hardware wallet."
+        );
+        let bytes = tx.to_bytes();
+        let mut chunks = bytes.chunks(MAX_COMMAND_DATA_LEN).peekable();
+        let mut data = vec![];
+        while let Some(chunk) = chunks.next() {
+            let command = ApduCommand {
+                ins: INS_SIGN_TX,
+                p1: if chunks.peek().is_some() {
+                    P1_NEXT
+                } else {
+                    P1_LAST
+                },
+                p2: 0,
+                data: chunk.to_vec(),
+            };
+            data = exchange_ok(transport, &command)?;
+        }
+        let signature = common::Signature::try_from_slice(&data)
+            .map_err(|err| DeviceError::InvalidAnswer(err.to_string()))?;
+        // Don't trust the device to sign what it's been asked to
+        common::SigScheme::verify_signature_raw(
+            &self.public_key,
+            &tx.hash(),
+            &signature,
+        )
+        .map_err(|err| DeviceError::InvalidAnswer(err.to_string()))?;
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+    use std::str::FromStr;
+    use std::thread;
+
+    use borsh::BorshSerialize;
+    use namada::proto::generated::types;
+    use namada::proto::Tx;
+    use namada::types::transaction::hash_tx;
+    use prost::Message;
+
+    use super::*;
+
+    /// Emulate a device holding the given key at any path over TCP, like
+    /// Speculos does for the Namada app. Txs without data are rejected as if
+    /// by the user. Returns the address to connect to.
+    fn spawn_emulator(keypair: common::SecretKey) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut message = vec![];
+            loop {
+                let mut len = [0; 4];
+                if stream.read_exact(&mut len).is_err() {
+                    return;
+                }
+                let mut command = vec![0; u32::from_be_bytes(len) as usize];
+                stream.read_exact(&mut command).unwrap();
+                let command = ApduCommand::decode(&command).unwrap();
+                let (data, status) = match (command.ins, command.p1) {
+                    (INS_GET_PUBLIC_KEY, _) => {
+                        (keypair.ref_to().try_to_vec().unwrap(), SW_OK)
+                    }
+                    (INS_SIGN_TX, P1_FIRST) => {
+                        message.clear();
+                        (vec![], SW_OK)
+                    }
+                    (INS_SIGN_TX, P1_NEXT) => {
+                        message.extend(command.data);
+                        (vec![], SW_OK)
+                    }
+                    (INS_SIGN_TX, P1_LAST) => {
+                        message.extend(command.data);
+                        let tx = types::Tx::decode(&message[..]).unwrap();
+                        if tx.data.is_none() {
+                            (vec![], SW_REJECTED)
+                        } else {
+                            let hash = hash_tx(&message).0;
+                            let sig = common::SigScheme::sign(&keypair, hash);
+                            (sig.try_to_vec().unwrap(), SW_OK)
+                        }
+                    }
+                    _ => (vec![], 0x6d00),
+                };
+                let mut answer = (data.len() as u32).to_be_bytes().to_vec();
+                answer.extend(data);
+                answer.extend(status.to_be_bytes());
+                stream.write_all(&answer).unwrap();
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_hardware_signer() {
+        let keypair = testing::keypair_1();
+        let address = spawn_emulator(keypair.clone());
+        let transport = Box::new(TcpTransport::connect(&address).unwrap());
+        let path = DerivationPath::from_str("m/44'/877'/0'/0'/0'").unwrap();
+        let mut signer = HardwareSigner::new(transport, path).unwrap();
+        assert_eq!(signer.public_key(), keypair.ref_to());
+
+        // The device makes the same signature as the software backend, for a
+        // tx sent in more than one chunk
+        let tx = SigningTx::from(Tx::new(vec![1; 32], Some(vec![2; 1000])));
+        assert!(tx.to_bytes().len() > 2 * MAX_COMMAND_DATA_LEN);
+        let signature = signer.sign_tx(&tx).unwrap();
+        assert_eq!(signature, common::SigScheme::sign(&keypair, tx.hash()));
+
+        let tx = SigningTx::from(Tx::new(vec![1; 32], None));
+        assert!(matches!(
+            signer.sign_tx(&tx),
+            Err(SignerError::Hardware(DeviceError::Rejected))
+        ));
+    }
+
+    #[test]
+    fn test_answer_too_long() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut len = [0; 4];
+            stream.read_exact(&mut len).unwrap();
+            let mut command = vec![0; u32::from_be_bytes(len) as usize];
+            stream.read_exact(&mut command).unwrap();
+            stream.write_all(&u32::MAX.to_be_bytes()).unwrap();
+        });
+        let mut transport = TcpTransport::connect(&address).unwrap();
+        let path = DerivationPath::from_str("m/44'/877'/0'/0'/0'").unwrap();
+        assert!(matches!(
+            get_public_key(&mut transport, &path),
+            Err(DeviceError::AnswerTooLong(u32::MAX))
+        ));
+    }
+
+    #[test]
+    fn test_apdu_command_encoding() {
+        let command = ApduCommand {
+            ins: INS_SIGN_TX,
+            p1: P1_LAST,
+            p2: 0,
+            data: vec![1, 2, 3],
+        };
+        let bytes = command.encode();
+        assert_eq!(bytes, vec![CLA, INS_SIGN_TX, P1_LAST, 0, 3, 1, 2, 3]);
+        assert_eq!(ApduCommand::decode(&bytes), Some(command));
+        assert_eq!(ApduCommand::decode(&bytes[..7]), None);
+    }
+}
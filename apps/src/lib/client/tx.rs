@@ -37,13 +37,18 @@ use namada::ibc::tx_msg::Msg;
 use namada::ibc::Height as IbcHeight;
 use namada::ibc_proto::cosmos::base::v1beta1::Coin;
 use namada::ledger::governance::storage as gov_storage;
-use namada::ledger::masp;
-use namada::ledger::pos::{BondId, Bonds, CommissionRates, Unbonds};
+use namada::ledger::pos::{
+    BondId, Bonds, CommissionRates, PosParams, Unbonds, ValidatorState,
+    ValidatorStates,
+};
+use namada::ledger::{eth_bridge, masp};
 use namada::proto::Tx;
 use namada::types::address::{masp, masp_tx_key, Address};
+use namada::types::ethereum_events::{EthAddress, TransferToEthereum};
 use namada::types::governance::{
-    OfflineProposal, OfflineVote, Proposal, ProposalVote,
+    OfflineProposal, OfflineVote, Proposal, ProposalType, ProposalVote,
 };
+use namada::types::key::multisig::MultisigKeys;
 use namada::types::key::*;
 use namada::types::masp::{PaymentAddress, TransferTarget};
 use namada::types::storage::{
@@ -69,7 +74,10 @@ use super::types::ShieldedTransferContext;
 use crate::cli::context::WalletAddress;
 use crate::cli::{args, safe_exit, Context};
 use crate::client::rpc::{query_conversion, query_storage_value};
-use crate::client::signing::{find_keypair, sign_tx, tx_signer, TxSigningKey};
+use crate::client::signing::{
+    self, find_keypair, find_signer, sign_tx, tx_signer, Signer, TxSigningKey,
+    UnsignedTx,
+};
 use crate::client::tendermint_rpc_types::{TxBroadcastData, TxResponse};
 use crate::client::types::ParsedTxTransferArgs;
 use crate::facade::tendermint_config::net::Address as TendermintAddress;
@@ -86,11 +94,16 @@ const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 const TX_UPDATE_VP_WASM: &str = "tx_update_vp.wasm";
 const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
 const TX_IBC_WASM: &str = "tx_ibc.wasm";
+const TX_TRANSFER_TO_ETHEREUM_WASM: &str = "tx_transfer_to_ethereum.wasm";
 const VP_USER_WASM: &str = "vp_user.wasm";
 const TX_BOND_WASM: &str = "tx_bond.wasm";
 const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
 const TX_CHANGE_COMMISSION_WASM: &str = "tx_change_validator_commission.wasm";
+const TX_UNJAIL_VALIDATOR_WASM: &str = "tx_unjail_validator.wasm";
+const TX_REDELEGATE_WASM: &str = "tx_redelegate.wasm";
+const TX_CHANGE_METADATA_WASM: &str = "tx_change_validator_metadata.wasm";
+const TX_CHANGE_CONSENSUS_KEY_WASM: &str = "tx_change_consensus_key.wasm";
 
 /// Timeout for requests to the `/accepted` and `/applied`
 /// ABCI query endpoints.
@@ -167,7 +180,30 @@ pub async fn submit_update_vp(ctx: Context, args: args::TxUpdateVp) {
 }
 
 pub async fn submit_init_account(mut ctx: Context, args: args::TxInitAccount) {
-    let public_key = ctx.get_cached(&args.public_key);
+    let public_keys: Vec<common::PublicKey> = args
+        .public_keys
+        .iter()
+        .map(|public_key| ctx.get_cached(public_key))
+        .collect();
+    let threshold = args
+        .threshold
+        .unwrap_or_else(|| public_keys.len().try_into().unwrap_or(u8::MAX));
+    if public_keys.len() > 1 {
+        let keys = MultisigKeys {
+            public_keys: public_keys.clone(),
+            threshold,
+        };
+        if !keys.is_valid() {
+            eprintln!(
+                "The public keys of a multisignature account must be unique, \
+                 there can be at most 256 of them and the threshold must be \
+                 between 1 and the number of keys."
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
     let vp_code = args
         .vp_code_path
         .map(|path| ctx.read_wasm(path))
@@ -182,7 +218,8 @@ pub async fn submit_init_account(mut ctx: Context, args: args::TxInitAccount) {
 
     let tx_code = ctx.read_wasm(TX_INIT_ACCOUNT_WASM);
     let data = InitAccount {
-        public_key,
+        public_keys,
+        threshold,
         vp_code,
     };
     let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
@@ -205,6 +242,7 @@ pub async fn submit_init_validator(
         protocol_key,
         commission_rate,
         max_commission_rate_change,
+        metadata,
         validator_vp_code_path,
         unsafe_dont_encrypt,
     }: args::TxInitValidator,
@@ -292,6 +330,16 @@ pub async fn submit_init_validator(
             safe_exit(1)
         }
     }
+    if !metadata.is_valid() {
+        eprintln!(
+            "The validator metadata fields must not be empty or longer than \
+             {} bytes",
+            pos::ValidatorMetaData::MAX_FIELD_LEN
+        );
+        if !tx_args.force {
+            safe_exit(1)
+        }
+    }
     // Validate the validator VP code
     if let Err(err) = vm::validate_untrusted_wasm(&validator_vp_code) {
         eprintln!(
@@ -311,6 +359,7 @@ pub async fn submit_init_validator(
         dkg_key,
         commission_rate,
         max_commission_rate_change,
+        metadata,
         validator_vp_code,
     };
     let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
@@ -318,7 +367,9 @@ pub async fn submit_init_validator(
     let (mut ctx, initialized_accounts) =
         process_tx(ctx, &tx_args, tx, TxSigningKey::WalletAddress(source))
             .await;
-    if !tx_args.dry_run {
+    if tx_args.dump_tx.is_some() {
+        println!("Transaction dumped. No addresses have been saved.")
+    } else if !tx_args.dry_run {
         let (validator_address_alias, validator_address) =
             match &initialized_accounts[..] {
                 // There should be 1 account for the validator itself
@@ -370,7 +421,20 @@ pub async fn submit_init_validator(
         ctx.wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
 
         let tendermint_home = ctx.config.ledger.tendermint_dir();
-        tendermint_node::write_validator_key(&tendermint_home, &consensus_key);
+        // A node that uses a remote signer doesn't read the consensus key
+        // from Tendermint's home
+        match &ctx.config.ledger.tendermint.priv_validator_address {
+            Some(address) => println!(
+                "The consensus key has not been written to Tendermint's \
+                 `priv_validator_key.json`, as the node uses the remote \
+                 signer at {}. Run the signer with the consensus key \"{}\".",
+                address, consensus_key_alias
+            ),
+            None => tendermint_node::write_validator_key(
+                &tendermint_home,
+                &consensus_key,
+            ),
+        }
         tendermint_node::write_validator_state(tendermint_home);
 
         println!();
@@ -1583,7 +1647,7 @@ pub async fn submit_transfer(mut ctx: Context, args: args::TxTransfer) {
     // will need to cover the gas fees.
     let chosen_signer = tx_signer(&mut ctx, &args.tx, default_signer.clone())
         .await
-        .ref_to();
+        .public_key();
     let shielded_gas = masp_tx_key().ref_to() == chosen_signer;
     // Determine whether to pin this transaction to a storage key
     let key = match ctx.get(&args.target) {
@@ -1763,6 +1827,64 @@ pub async fn submit_ibc_transfer(ctx: Context, args: args::TxIbcTransfer) {
         .await;
 }
 
+pub async fn submit_transfer_to_ethereum(
+    ctx: Context,
+    args: args::TxTransferToEthereum,
+) {
+    let source = ctx.get(&args.source);
+    // Check that the source address exists on chain
+    let source_exists =
+        rpc::known_address(&source, args.tx.ledger_address.clone()).await;
+    if !source_exists {
+        eprintln!("The source address {} doesn't exist on chain.", source);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    // Check source balance of either the native token or the wrapped ERC20
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    let native_erc20 = rpc::query_storage_value::<EthAddress>(
+        &client,
+        &eth_bridge::storage::native_erc20_key(),
+    )
+    .await;
+    let balance_key = if native_erc20 == Some(args.asset) {
+        token::balance_key(&ctx.native_token, &source)
+    } else {
+        eth_bridge::storage::wrapped_erc20_balance_key(&args.asset, &source)
+    };
+    let balance =
+        rpc::query_storage_value::<token::Amount>(&client, &balance_key)
+            .await
+            .unwrap_or_default();
+    if balance < args.amount {
+        eprintln!(
+            "The balance of the source {} of token {} is lower than the \
+             amount to be transferred. Amount to transfer is {} and the \
+             balance is {}.",
+            source, args.asset, args.amount, balance
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let tx_code = ctx.read_wasm(TX_TRANSFER_TO_ETHEREUM_WASM);
+    let data = TransferToEthereum {
+        amount: args.amount,
+        asset: args.asset,
+        sender: source,
+        receiver: args.receiver,
+    };
+    tracing::debug!("Transfer to Ethereum data {:?}", data);
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(ctx, &args.tx, tx, TxSigningKey::WalletAddress(args.source))
+        .await;
+}
+
 pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
     let file = File::open(&args.proposal_data).expect("File must exist.");
     let proposal: Proposal =
@@ -1829,6 +1951,23 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
         if !args.tx.force {
             safe_exit(1)
         }
+    } else if !proposal.proposal_type.is_valid(proposal.grace_epoch) {
+        eprintln!(
+            "Invalid {} proposal payload: it must declare at least one change \
+             or payment, with parameters in their valid range, non-zero \
+             amounts and payments ending no earlier than the grace epoch",
+            proposal.proposal_type
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    } else if proposal.proposal_type != ProposalType::Default
+        && proposal.proposal_code_path.is_some()
+    {
+        eprintln!("Only default proposals can have a proposal code.");
+        if !args.tx.force {
+            safe_exit(1)
+        }
     }
 
     if args.offline {
@@ -2102,14 +2241,16 @@ pub async fn submit_reveal_pk_aux(
     let tx = Tx::new(tx_code, Some(tx_data));
 
     // submit_tx without signing the inner tx
-    let keypair = if let Some(signing_key) = &args.signing_key {
-        ctx.get_cached(signing_key)
-    } else if let Some(signer) = args.signer.as_ref() {
-        let signer = ctx.get(signer);
-        find_keypair(&mut ctx.wallet, &signer, args.ledger_address.clone())
-            .await
-    } else {
-        find_keypair(&mut ctx.wallet, &addr, args.ledger_address.clone()).await
+    let mut signer: Box<dyn Signer> = match &args.signing_key {
+        Some(signing_key) => Box::new(ctx.get_cached(signing_key)),
+        None => {
+            let signer = match args.signer.as_ref() {
+                Some(signer) => ctx.get(signer),
+                None => addr,
+            };
+            find_signer(&mut ctx.wallet, &signer, args.ledger_address.clone())
+                .await
+        }
     };
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.ledger_address.clone(),
@@ -2118,7 +2259,8 @@ pub async fn submit_reveal_pk_aux(
     let to_broadcast = if args.dry_run {
         TxBroadcastData::DryRun(tx)
     } else {
-        super::signing::sign_wrapper(ctx, args, epoch, tx, &keypair).await
+        super::signing::sign_wrapper(ctx, args, epoch, tx, signer.as_mut())
+            .await
     };
 
     if args.dry_run {
@@ -2374,6 +2516,91 @@ pub async fn submit_unbond(ctx: Context, args: args::Unbond) {
     .await;
 }
 
+pub async fn submit_redelegate(ctx: Context, args: args::Redelegate) {
+    let owner = ctx.get(&args.owner);
+    let src_validator = ctx.get(&args.src_validator);
+    let dest_validator = ctx.get(&args.dest_validator);
+    // Check that the validator addresses exist on chain
+    for validator in [&src_validator, &dest_validator] {
+        let is_validator =
+            rpc::is_validator(validator, args.tx.ledger_address.clone()).await;
+        if !is_validator {
+            eprintln!(
+                "The address {} doesn't belong to any known validator account.",
+                validator
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+    if src_validator == dest_validator {
+        eprintln!(
+            "The source and destination validators must be different, got {} \
+             for both.",
+            src_validator
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    // Check the owner's current bond amount with the source validator
+    let bond_id = BondId {
+        source: owner.clone(),
+        validator: src_validator.clone(),
+    };
+    let bond_key = ledger::pos::bond_key(&bond_id);
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    let bonds = rpc::query_storage_value::<Bonds>(&client, &bond_key).await;
+    match bonds {
+        Some(bonds) => {
+            let mut bond_amount: token::Amount = 0.into();
+            for bond in bonds.iter() {
+                for delta in bond.pos_deltas.values() {
+                    bond_amount += *delta;
+                }
+            }
+            if args.amount > bond_amount {
+                eprintln!(
+                    "The total bonds of the owner {} is lower than the amount \
+                     to be redelegated. Amount to redelegate is {} and the \
+                     total bonds is {}.",
+                    owner, args.amount, bond_amount
+                );
+                if !args.tx.force {
+                    safe_exit(1)
+                }
+            }
+        }
+        None => {
+            eprintln!("No bonds found");
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+
+    let tx_code = ctx.read_wasm(TX_REDELEGATE_WASM);
+    let data = pos::Redelegation {
+        src_validator,
+        dest_validator,
+        owner,
+        amount: args.amount,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let default_signer = args.owner;
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(default_signer),
+    )
+    .await;
+}
+
 pub async fn submit_withdraw(ctx: Context, args: args::Withdraw) {
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.tx.ledger_address.clone(),
@@ -2529,6 +2756,118 @@ pub async fn submit_validator_commission_change(
     .await;
 }
 
+pub async fn submit_change_validator_metadata(
+    ctx: Context,
+    args: args::TxChangeValidatorMetaData,
+) {
+    let tx_code = ctx.read_wasm(TX_CHANGE_METADATA_WASM);
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+
+    let validator = ctx.get(&args.validator);
+    if !rpc::is_validator(&validator, args.tx.ledger_address.clone()).await {
+        eprintln!("The given address {validator} is not a validator.");
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    // Apply the given changes on top of the current metadata. An empty
+    // string removes the field.
+    let mut metadata = rpc::query_validator_metadata(&client, &validator)
+        .await
+        .unwrap_or_default();
+    let update = |field: &mut Option<String>, change: Option<String>| {
+        if let Some(change) = change {
+            *field = if change.is_empty() {
+                None
+            } else {
+                Some(change)
+            };
+        }
+    };
+    update(&mut metadata.name, args.metadata.name);
+    update(&mut metadata.description, args.metadata.description);
+    update(&mut metadata.website, args.metadata.website);
+    update(&mut metadata.contact, args.metadata.contact);
+    if !metadata.is_valid() {
+        eprintln!(
+            "The validator metadata fields must not be longer than {} bytes",
+            pos::ValidatorMetaData::MAX_FIELD_LEN
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let data = pos::MetaDataChange {
+        validator,
+        metadata,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let default_signer = args.validator;
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(default_signer),
+    )
+    .await;
+}
+
+pub async fn submit_unjail_validator(
+    ctx: Context,
+    args: args::TxUnjailValidator,
+) {
+    let epoch = rpc::query_epoch(args::Query {
+        ledger_address: args.tx.ledger_address.clone(),
+    })
+    .await;
+
+    let tx_code = ctx.read_wasm(TX_UNJAIL_VALIDATOR_WASM);
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+
+    let validator = ctx.get(&args.validator);
+    if rpc::is_validator(&validator, args.tx.ledger_address.clone()).await {
+        let state_key = ledger::pos::validator_state_key(&validator);
+        let states =
+            rpc::query_storage_value::<ValidatorStates>(&client, &state_key)
+                .await;
+        match states.as_ref().and_then(|states| states.get(epoch)) {
+            Some(ValidatorState::Jailed) => {}
+            _ => {
+                eprintln!(
+                    "The validator {validator} is not jailed in the current \
+                     epoch {epoch}."
+                );
+                if !args.tx.force {
+                    safe_exit(1)
+                }
+            }
+        }
+    } else {
+        eprintln!("The given address {validator} is not a validator.");
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let data = validator
+        .try_to_vec()
+        .expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let default_signer = args.validator;
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(default_signer),
+    )
+    .await;
+}
+
 /// Submit transaction and wait for result. Returns a list of addresses
 /// initialized in the transaction if any. In dry run, this is always empty.
 async fn process_tx(
@@ -2537,7 +2876,70 @@ async fn process_tx(
     tx: Tx,
     default_signer: TxSigningKey,
 ) -> (Context, Vec<Address>) {
+    if let Some(path) = &args.dump_tx {
+        signing::dump_tx(&ctx, args, tx, path).await;
+        return (ctx, vec![]);
+    }
     let (ctx, to_broadcast) = sign_tx(ctx, tx, args, default_signer).await;
+    broadcast_or_dry_run(ctx, args, to_broadcast).await
+}
+
+/// Submit a tx dumped with `--dump-tx` with the signatures made with `wallet
+/// sign` attached to it. The wrapper is built in the current epoch and signed
+/// with the tx signing key, which must be given with either `--signer` or
+/// `--signing-key`.
+pub async fn submit_signed_tx(mut ctx: Context, args: args::SubmitTx) {
+    let unsigned: UnsignedTx = signing::read_from_file(&args.tx_path);
+    let signatures = args
+        .signatures
+        .iter()
+        .map(signing::read_from_file)
+        .collect();
+    let tx = unsigned
+        .attach_signatures(signatures)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            safe_exit(1)
+        });
+    let to_broadcast = if args.tx.dry_run {
+        TxBroadcastData::DryRun(tx)
+    } else {
+        if args.tx.signer.is_none() && args.tx.signing_key.is_none() {
+            eprintln!(
+                "The wrapper of a signed tx must be signed by its fee payer; \
+                 please specify either --signer or --signing-key."
+            );
+            safe_exit(1)
+        }
+        let mut signer =
+            tx_signer(&mut ctx, &args.tx, TxSigningKey::None).await;
+        // The tx may have been dumped a while ago, so the wrapper is built in
+        // the current epoch
+        let epoch = rpc::query_epoch(args::Query {
+            ledger_address: args.tx.ledger_address.clone(),
+        })
+        .await;
+        signing::wrap_and_sign(
+            args.tx.ledger_address.clone(),
+            unsigned.fee,
+            unsigned.gas_limit,
+            epoch,
+            tx,
+            signer.as_mut(),
+        )
+        .await
+    };
+    let (ctx, initialized_accounts) =
+        broadcast_or_dry_run(ctx, &args.tx, to_broadcast).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
+/// Broadcast a signed tx or dry-run it
+async fn broadcast_or_dry_run(
+    ctx: Context,
+    args: &args::Tx,
+    to_broadcast: TxBroadcastData,
+) -> (Context, Vec<Address>) {
     // NOTE: use this to print the request JSON body:
 
     // let request =
@@ -2768,3 +3170,93 @@ pub async fn submit_tx(
 
     parsed
 }
+
+pub async fn submit_change_consensus_key(
+    mut ctx: Context,
+    args: args::TxChangeConsensusKey,
+) {
+    let epoch = rpc::query_epoch(args::Query {
+        ledger_address: args.tx.ledger_address.clone(),
+    })
+    .await;
+
+    let tx_code = ctx.read_wasm(TX_CHANGE_CONSENSUS_KEY_WASM);
+
+    let validator = ctx.get(&args.validator);
+    if !rpc::is_validator(&validator, args.tx.ledger_address.clone()).await {
+        eprintln!("The given address {validator} is not a validator.");
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let validator_alias = ctx
+        .wallet
+        .find_alias(&validator)
+        .map(|alias| alias.to_string())
+        .unwrap_or_else(|| validator.encode());
+    let consensus_key_alias =
+        format!("{}-consensus-key-{}", validator_alias, epoch.0);
+    let consensus_key = ctx
+        .get_opt_cached(&args.consensus_key)
+        .map(|key| match key {
+            common::SecretKey::Ed25519(_) => key,
+            common::SecretKey::Secp256k1(_) => {
+                eprintln!("Consensus key can only be ed25519");
+                safe_exit(1)
+            }
+        })
+        .unwrap_or_else(|| {
+            println!("Generating new consensus key...");
+            ctx.wallet
+                .gen_key(
+                    // Note that TM only allows ed25519 for consensus key
+                    SchemeType::Ed25519,
+                    Some(consensus_key_alias.clone()),
+                    args.unsafe_dont_encrypt,
+                )
+                .1
+        });
+    ctx.wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+
+    let data = pos::ConsensusKeyChange {
+        validator,
+        consensus_key: consensus_key.ref_to(),
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let default_signer = args.validator.clone();
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(default_signer),
+    )
+    .await;
+
+    if !args.tx.dry_run {
+        let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+        let params = rpc::query_storage_value::<PosParams>(
+            &client,
+            &ledger::pos::params_key(),
+        )
+        .await
+        .expect("PoS parameters should always exist in storage");
+        let pipeline_epoch = epoch + params.pipeline_len;
+        println!(
+            "The new consensus key will become active at epoch \
+             {pipeline_epoch}. Until then, the ledger node must keep signing \
+             with the current key. The new key is stored in the wallet{}.",
+            args.consensus_key
+                .map(|_| String::new())
+                .unwrap_or_else(|| format!(" as \"{consensus_key_alias}\""))
+        );
+        println!(
+            "Once the chain reaches epoch {pipeline_epoch}, stop the node and \
+             write the new key to Tendermint's `priv_validator_key.json` with \
+             `namada node ledger set-consensus-key`, or restart the remote \
+             signer with the new key, if any."
+        );
+    }
+}
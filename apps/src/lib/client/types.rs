@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use async_trait::async_trait;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::primitives::{Diversifier, Note, ViewingKey};
@@ -37,6 +39,9 @@ pub struct ParsedTxArgs {
     pub signing_key: Option<key::common::SecretKey>,
     /// Sign the tx with the keypair of the public key of the given address
     pub signer: Option<Address>,
+    /// Write the unsigned tx to the given file instead of signing and
+    /// submitting it
+    pub dump_tx: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug)]
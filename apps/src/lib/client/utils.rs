@@ -278,11 +278,25 @@ pub async fn join_network(
 
         let tm_home_dir = chain_dir.join("tendermint");
 
-        // Write consensus key to tendermint home
-        tendermint_node::write_validator_key(
-            &tm_home_dir,
-            &pre_genesis_wallet.consensus_key,
-        );
+        // Write consensus key to tendermint home, unless the node is
+        // configured to use a remote signer, which holds the key instead
+        let priv_validator_address = Config::load(&base_dir, &chain_id, None)
+            .ledger
+            .tendermint
+            .priv_validator_address;
+        match priv_validator_address {
+            Some(address) => println!(
+                "The consensus key has not been written to Tendermint's \
+                 `priv_validator_key.json`, as the node uses the remote \
+                 signer at {}. Run the signer with the consensus key from the \
+                 pre-genesis wallet.",
+                address
+            ),
+            None => tendermint_node::write_validator_key(
+                &tm_home_dir,
+                &pre_genesis_wallet.consensus_key,
+            ),
+        }
 
         // Derive the node ID from the node key
         let node_id = id_from_pk(&tendermint_node_key.ref_to());
@@ -495,7 +509,9 @@ pub fn init_network(
                 unsafe_dont_encrypt,
             );
 
-            // Write consensus key for Tendermint
+            // Write consensus key for Tendermint. A validator that uses a
+            // remote signer should have its consensus public key set in the
+            // genesis config instead, so that no key is written here.
             tendermint_node::write_validator_key(&tm_home_dir, &keypair);
 
             keypair.ref_to()
@@ -32,6 +32,7 @@ pub mod genesis_config {
     use namada::ledger::parameters::EpochDuration;
     use namada::ledger::pos::{GenesisValidator, PosParams};
     use namada::types::address::Address;
+    use namada::types::governance::FundsDestination;
     use namada::types::key::dkg_session_keys::DkgPublicKey;
     use namada::types::key::*;
     use namada::types::time::Rfc3339String;
@@ -125,6 +126,11 @@ pub mod genesis_config {
         pub gov_params: GovernanceParamsConfig,
         // Wasm definitions
         pub wasm: HashMap<String, WasmConfig>,
+        // Whether the app hash commits to the metadata of every block, which
+        // is needed to restore the state from a state sync snapshot. It
+        // changes the app hash, so it's disabled when not set, e.g. for the
+        // chains started before it was added.
+        pub commit_block_metadata: Option<bool>,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -147,6 +153,18 @@ pub mod genesis_config {
         // Minimum number of epoch between end and grace epoch
         // XXX: u64 doesn't work with toml-rs!
         pub min_proposal_grace_epochs: u64,
+        // Minimum fraction of the total voting power that has to vote
+        pub quorum: Decimal,
+        // Minimum fraction of yay votes over non-abstaining votes to pass
+        pub pass_threshold: Decimal,
+        // Fraction of veto votes above which the proposal is vetoed
+        pub veto_threshold: Decimal,
+        // Where the funds of a rejected proposal go
+        pub rejected_funds: FundsDestination,
+        // Where the funds of a vetoed proposal go
+        pub vetoed_funds: FundsDestination,
+        // Where the funds of a proposal that failed to execute go
+        pub failed_funds: FundsDestination,
     }
 
     /// Validator pre-genesis configuration can be created with client utils
@@ -277,6 +295,21 @@ pub mod genesis_config {
         // light client attack.
         // XXX: u64 doesn't work with toml-rs!
         pub light_client_attack_min_slash_rate: Decimal,
+        // Number of epochs before and after an infraction epoch within
+        // which the infractions are considered correlated for cubic
+        // slashing.
+        // XXX: u64 doesn't work with toml-rs!
+        pub cubic_slashing_window_length: u64,
+        // Number of the most recent blocks over which the validators'
+        // liveness is tracked.
+        // XXX: u64 doesn't work with toml-rs!
+        pub liveness_window_len: u64,
+        // Maximum fraction of the blocks in the liveness window that a
+        // validator may miss before it's jailed.
+        pub liveness_max_missed_rate: Decimal,
+        // Portion of a validator's stake that should be slashed when it's
+        // jailed for missing too many blocks.
+        pub liveness_slash_rate: Decimal,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -503,6 +536,7 @@ pub mod genesis_config {
             pos_params,
             gov_params,
             wasm,
+            commit_block_metadata,
         } = config;
 
         let native_token = Address::decode(
@@ -592,6 +626,12 @@ pub mod genesis_config {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             max_proposal_period,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            rejected_funds,
+            vetoed_funds,
+            failed_funds,
         } = gov_params;
         let gov_params = GovParams {
             min_proposal_fund,
@@ -600,6 +640,12 @@ pub mod genesis_config {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             max_proposal_period,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            rejected_funds,
+            vetoed_funds,
+            failed_funds,
         };
 
         let PosParamsConfig {
@@ -613,6 +659,10 @@ pub mod genesis_config {
             target_staked_ratio,
             duplicate_vote_min_slash_rate,
             light_client_attack_min_slash_rate,
+            cubic_slashing_window_length,
+            liveness_window_len,
+            liveness_max_missed_rate,
+            liveness_slash_rate,
         } = pos_params;
         let pos_params = PosParams {
             max_validator_slots,
@@ -625,6 +675,10 @@ pub mod genesis_config {
             target_staked_ratio,
             duplicate_vote_min_slash_rate,
             light_client_attack_min_slash_rate,
+            cubic_slashing_window_length,
+            liveness_window_len,
+            liveness_max_missed_rate,
+            liveness_slash_rate,
         };
 
         let mut genesis = Genesis {
@@ -637,6 +691,7 @@ pub mod genesis_config {
             parameters,
             pos_params,
             gov_params,
+            commit_block_metadata: commit_block_metadata.unwrap_or_default(),
         };
         genesis.init();
         genesis
@@ -685,6 +740,8 @@ pub struct Genesis {
     pub parameters: Parameters,
     pub pos_params: PosParams,
     pub gov_params: GovParams,
+    /// Whether the app hash commits to the metadata of every block
+    pub commit_block_metadata: bool,
 }
 
 impl Genesis {
@@ -952,6 +1009,7 @@ pub fn genesis() -> Genesis {
         pos_params: PosParams::default(),
         gov_params: GovParams::default(),
         native_token: address::nam(),
+        commit_block_metadata: true,
     }
 }
 
@@ -91,12 +91,42 @@ pub struct Shell {
     /// When set, will limit the how many block heights in the past can the
     /// storage be queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// When set, a validator node is fed the Ethereum events written into
+    /// this file by a mock Ethereum oracle, for testing.
+    pub ethereum_mock_events_file: Option<PathBuf>,
+    /// When set, a snapshot of the state is taken at every block height that
+    /// is a multiple of this interval and served to the nodes that state sync
+    /// from it. Tendermint's `statesync` must be configured separately to
+    /// restore a node from the snapshots of its peers.
+    pub snapshot_interval: Option<u64>,
+    /// The policy of pruning the data of old block heights from the DB
+    #[serde(default)]
+    pub storage_pruning: StoragePruning,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::tendermint_dir()`] method to read the value.
     tendermint_dir: PathBuf,
 }
 
+/// The policy of pruning the diffs of the account subspaces, the merkle tree
+/// stores and the block results of old heights from the DB. The values read
+/// at a past height depend on the diffs of every height since then, so the
+/// queries are limited to the recent heights that are kept.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum StoragePruning {
+    /// Keep the data of every block height
+    #[default]
+    Archive,
+    /// Keep the data of the last `keep_recent` block heights. At least the
+    /// last 2 heights are kept, so that the last block can be rolled back.
+    KeepRecent { keep_recent: u64 },
+    /// Keep the data of the last `keep_recent` block heights, with the same
+    /// minimum as above, and of every block height that is a multiple of
+    /// `keep_every`
+    KeepEvery { keep_recent: u64, keep_every: u64 },
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tendermint {
     pub rpc_address: SocketAddr,
@@ -118,6 +148,10 @@ pub struct Tendermint {
     pub instrumentation_prometheus: bool,
     pub instrumentation_prometheus_listen_addr: SocketAddr,
     pub instrumentation_namespace: String,
+    /// When set, Tendermint listens on this address for a remote signer,
+    /// such as `namada node signer`, instead of using the consensus key in
+    /// `priv_validator_key.json`
+    pub priv_validator_address: Option<TendermintAddress>,
 }
 
 impl Ledger {
@@ -140,6 +174,9 @@ impl Ledger {
                 tx_wasm_compilation_cache_bytes: None,
                 // Default corresponds to 1 hour of past blocks at 1 block/sec
                 storage_read_past_height_limit: Some(3600),
+                ethereum_mock_events_file: None,
+                snapshot_interval: None,
+                storage_pruning: StoragePruning::default(),
                 db_dir: DB_DIR.into(),
                 tendermint_dir: TENDERMINT_DIR.into(),
             },
@@ -164,6 +201,7 @@ impl Ledger {
                     26661,
                 ),
                 instrumentation_namespace: "namadan_tm".to_string(),
+                priv_validator_address: None,
             },
         }
     }
@@ -0,0 +1,197 @@
+//! Ethereum events observed by a validator node, to be voted on in its vote
+//! extensions. Until the node follows an Ethereum full node, the events can be
+//! fed to it by a mock oracle that reads them from a file.
+
+use std::collections::BTreeSet;
+
+use namada::types::ethereum_events::EthereumEvent;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The receiving end of the Ethereum events channel, with a queue of the
+/// events that have not been applied in the ledger yet.
+#[derive(Debug)]
+pub struct EthereumReceiver {
+    channel: UnboundedReceiver<EthereumEvent>,
+    queue: BTreeSet<EthereumEvent>,
+}
+
+impl EthereumReceiver {
+    /// Create a new receiver with an empty queue
+    pub fn new(channel: UnboundedReceiver<EthereumEvent>) -> Self {
+        Self {
+            channel,
+            queue: BTreeSet::new(),
+        }
+    }
+
+    /// Move the events received on the channel into the queue
+    pub fn fill_queue(&mut self) {
+        while let Ok(event) = self.channel.try_recv() {
+            self.queue.insert(event);
+        }
+    }
+
+    /// Get the queued events, ordered by their nonces
+    pub fn get_events(&self) -> Vec<EthereumEvent> {
+        self.queue.iter().cloned().collect()
+    }
+
+    /// Remove the events that have already been applied in the ledger, i.e.
+    /// the transfers to Namada with a lower nonce than `next_nonce`
+    pub fn remove_applied(&mut self, next_nonce: u64) {
+        self.queue.retain(|event| match event {
+            EthereumEvent::TransfersToNamada { nonce, .. } => {
+                *nonce >= next_nonce
+            }
+        });
+    }
+}
+
+/// A mock oracle feeding the ledger with the Ethereum events written into a
+/// file, for testing without an Ethereum node.
+pub mod mock {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    /// How often the events file is checked for new events
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Loop until an abort signal is received, sending to the ledger the
+    /// events appended to the given file. The file contains one JSON encoded
+    /// [`EthereumEvent`] per line and it doesn't have to exist yet.
+    pub async fn run(
+        events_file: PathBuf,
+        sender: UnboundedSender<EthereumEvent>,
+        abort_recv: tokio::sync::oneshot::Receiver<()>,
+    ) {
+        tracing::info!(
+            "Starting the mock Ethereum oracle, reading events from {}.",
+            events_file.to_string_lossy()
+        );
+        tokio::select! {
+            _ = poll_events_file(&events_file, &sender) => {
+                tracing::info!(
+                    "The ledger has stopped receiving Ethereum events."
+                );
+            },
+            resp_sender = abort_recv => {
+                if let Err(err) = resp_sender {
+                    tracing::error!(
+                        "The mock Ethereum oracle abort sender has \
+                         unexpectedly dropped: {}",
+                        err
+                    );
+                }
+            }
+        }
+        tracing::info!("Shutting down the mock Ethereum oracle...");
+    }
+
+    /// Send the events from the complete lines appended to the file since the
+    /// last check. Returns when the receiver has been dropped.
+    async fn poll_events_file(
+        events_file: &Path,
+        sender: &UnboundedSender<EthereumEvent>,
+    ) {
+        let mut lines_read = 0;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let contents = match tokio::fs::read_to_string(events_file).await {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            let new_lines = contents
+                .split_inclusive('\n')
+                .skip(lines_read)
+                .take_while(|line| line.ends_with('\n'));
+            for line in new_lines {
+                lines_read += 1;
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<EthereumEvent>(line) {
+                    Ok(event) => {
+                        tracing::info!(?event, "Received an Ethereum event");
+                        if sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(
+                            "Invalid Ethereum event {}: {}",
+                            line,
+                            err
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::types::address::testing::established_address_1;
+    use namada::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+    use namada::types::ethereum_events::TransferToNamada;
+
+    use super::*;
+
+    fn transfers_to_namada(nonce: u64) -> EthereumEvent {
+        EthereumEvent::TransfersToNamada {
+            nonce,
+            transfers: vec![TransferToNamada {
+                amount: 100.into(),
+                asset: DAI_ERC20_ETH_ADDRESS,
+                receiver: established_address_1(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_receiver_queue() {
+        let (sender, channel) = tokio::sync::mpsc::unbounded_channel();
+        let mut receiver = EthereumReceiver::new(channel);
+        for nonce in [2, 0, 1, 0] {
+            sender.send(transfers_to_namada(nonce)).unwrap();
+        }
+        assert!(receiver.get_events().is_empty());
+
+        // The events are deduplicated and ordered by their nonces
+        receiver.fill_queue();
+        assert_eq!(
+            receiver.get_events(),
+            vec![
+                transfers_to_namada(0),
+                transfers_to_namada(1),
+                transfers_to_namada(2)
+            ]
+        );
+
+        receiver.remove_applied(2);
+        assert_eq!(receiver.get_events(), vec![transfers_to_namada(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_oracle() {
+        let dir = tempfile::tempdir().unwrap();
+        let events_file = dir.path().join("events.json");
+        let (sender, mut channel) = tokio::sync::mpsc::unbounded_channel();
+        let (_abort_send, abort_recv) = tokio::sync::oneshot::channel();
+        tokio::spawn(mock::run(events_file.clone(), sender, abort_recv));
+
+        let lines: String = [transfers_to_namada(0), transfers_to_namada(1)]
+            .iter()
+            .map(|event| serde_json::to_string(event).unwrap() + "\n")
+            .collect();
+        std::fs::write(&events_file, lines).unwrap();
+        assert_eq!(channel.recv().await, Some(transfers_to_namada(0)));
+        assert_eq!(channel.recv().await, Some(transfers_to_namada(1)));
+    }
+}
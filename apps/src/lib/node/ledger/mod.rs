@@ -1,5 +1,6 @@
 mod abortable;
 mod broadcaster;
+mod ethereum_oracle;
 mod shell;
 mod shims;
 pub mod storage;
@@ -140,16 +141,16 @@ impl Shell {
                 Ok(Response::CheckTx(self.mempool_validate(&tx.tx, r#type)))
             }
             Request::ListSnapshots(_) => {
-                Ok(Response::ListSnapshots(Default::default()))
+                Ok(Response::ListSnapshots(self.list_snapshots()))
             }
-            Request::OfferSnapshot(_) => {
-                Ok(Response::OfferSnapshot(Default::default()))
+            Request::OfferSnapshot(req) => {
+                Ok(Response::OfferSnapshot(self.offer_snapshot(req)))
             }
-            Request::LoadSnapshotChunk(_) => {
-                Ok(Response::LoadSnapshotChunk(Default::default()))
+            Request::LoadSnapshotChunk(req) => {
+                Ok(Response::LoadSnapshotChunk(self.load_snapshot_chunk(req)))
             }
-            Request::ApplySnapshotChunk(_) => {
-                Ok(Response::ApplySnapshotChunk(Default::default()))
+            Request::ApplySnapshotChunk(req) => {
+                Ok(Response::ApplySnapshotChunk(self.apply_snapshot_chunk(req)))
             }
         }
     }
@@ -198,6 +199,11 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
     shell::reset(config)
 }
 
+/// Rolls back the database to the previous committed block height
+pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
+    shell::rollback(config)
+}
+
 /// Runs and monitors a few concurrent tasks.
 ///
 /// This includes:
@@ -205,6 +211,7 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
 ///   - A shell which contains an ABCI server, for talking to the Tendermint
 ///     node.
 ///   - A [`Broadcaster`], for the ledger to submit txs to Tendermint's mempool.
+///   - A mock Ethereum oracle, if configured for a validator node.
 ///
 /// All must be alive for correct functioning.
 async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
@@ -217,23 +224,25 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
     // Start Tendermint node
     let tendermint_node = start_tendermint(&mut spawner, &config);
 
-    // Start ABCI server and broadcaster (the latter only if we are a validator
-    // node)
-    let (abci, broadcaster, shell_handler) = start_abci_broadcaster_shell(
-        &mut spawner,
-        wasm_dir,
-        setup_data,
-        config,
-    );
+    // Start ABCI server, broadcaster and Ethereum oracle (the latter two only
+    // if we are a validator node)
+    let (abci, broadcaster, ethereum_oracle, shell_handler) =
+        start_abci_broadcaster_shell(
+            &mut spawner,
+            wasm_dir,
+            setup_data,
+            config,
+        );
 
     // Wait for interrupt signal or abort message
     let aborted = spawner.wait_for_abort().await.child_terminated();
 
     // Wait for all managed tasks to finish.
-    let res = tokio::try_join!(tendermint_node, abci, broadcaster);
+    let res =
+        tokio::try_join!(tendermint_node, abci, broadcaster, ethereum_oracle);
 
     match res {
-        Ok((tendermint_res, abci_res, _)) => {
+        Ok((tendermint_res, abci_res, _, _)) => {
             // we ignore errors on user-initiated shutdown
             if aborted {
                 if let Err(err) = tendermint_res {
@@ -362,10 +371,11 @@ async fn run_aux_setup(
     }
 }
 
-/// Launches two tasks into the asynchronous runtime:
+/// Launches three tasks into the asynchronous runtime:
 ///
 ///   1. An ABCI server.
 ///   2. A service for broadcasting transactions via an HTTP client.
+///   3. A mock Ethereum oracle, when its events file is configured.
 ///
 /// Lastly, this function executes an ABCI shell on a new OS thread.
 fn start_abci_broadcaster_shell(
@@ -376,6 +386,7 @@ fn start_abci_broadcaster_shell(
 ) -> (
     task::JoinHandle<shell::Result<()>>,
     task::JoinHandle<()>,
+    task::JoinHandle<()>,
     thread::JoinHandle<()>,
 ) {
     let rpc_address = config.tendermint.rpc_address.to_string();
@@ -416,6 +427,36 @@ fn start_abci_broadcaster_shell(
         spawn_dummy_task(())
     };
 
+    // Channel for the Ethereum events observed by a validator
+    let (eth_sender, eth_receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    // Start the mock Ethereum oracle
+    let ethereum_oracle = match (
+        &config.tendermint.tendermint_mode,
+        config.shell.ethereum_mock_events_file.clone(),
+    ) {
+        (TendermintMode::Validator, Some(events_file)) => {
+            let (oracle_abort_send, oracle_abort_recv) =
+                tokio::sync::oneshot::channel::<()>();
+            spawner
+                .spawn_abortable("Ethereum oracle", move |aborter| async move {
+                    ethereum_oracle::mock::run(
+                        events_file,
+                        eth_sender,
+                        oracle_abort_recv,
+                    )
+                    .await;
+                    tracing::info!("Ethereum oracle is no longer running.");
+
+                    drop(aborter);
+                })
+                .with_cleanup(async move {
+                    let _ = oracle_abort_send.send(());
+                })
+        }
+        _ => spawn_dummy_task(()),
+    };
+
     // Setup DB cache, it must outlive the DB instance that's in the shell
     let db_cache =
         rocksdb::Cache::new_lru_cache(db_block_cache_size_bytes as usize)
@@ -432,6 +473,7 @@ fn start_abci_broadcaster_shell(
         config,
         wasm_dir,
         broadcaster_sender,
+        eth_receiver,
         &db_cache,
         vp_wasm_compilation_cache,
         tx_wasm_compilation_cache,
@@ -471,7 +513,7 @@ fn start_abci_broadcaster_shell(
         })
         .expect("Must be able to start a thread for the shell");
 
-    (abci, broadcaster, shell_handler)
+    (abci, broadcaster, ethereum_oracle, shell_handler)
 }
 
 /// Runs the an asynchronous ABCI server with four sub-components for consensus,
@@ -0,0 +1,1081 @@
+//! The ledger's part in the DKG of the tx encryption keys and in the
+//! threshold decryption of the wrapper txs.
+//!
+//! Every node keeps the state of the DKG session of the next epoch, which is
+//! advanced by the DKG protocol txs applied in [`Shell::finalize_block`] and
+//! can be restored from the transcript kept in storage. Validators deal and
+//! aggregate in the session after each block is committed and, once the key of
+//! an epoch has been generated, broadcast their decryption shares of the
+//! wrapper txs of that epoch in the tx queue.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, PrimeField, Zero};
+use ferveo::dkg::pv::PubliclyVerifiableDkg;
+use ferveo::dkg::{DkgState, Params};
+use ferveo_common::{TendermintValidator, ValidatorSet};
+use namada::ledger::dkg::{
+    storage as dkg_storage, DkgTranscript, ShareRange, SECURITY_THRESHOLD,
+    TOTAL_WEIGHT,
+};
+use namada::ledger::pos::into_tm_voting_power;
+use namada::types::hash::Hash;
+use namada::types::key::dkg_session_keys::{
+    dkg_pk_key, DkgKeypair, DkgPublicKey,
+};
+use namada::types::storage::Epoch;
+use namada::types::transaction::encrypted::{
+    share_evaluation_point, DecryptionShare, Fr, G1, G2,
+};
+use namada::types::transaction::protocol::{
+    DkgMessage, ProtocolTxType, TxDecryptionShares,
+};
+use namada::types::transaction::EncryptionKey;
+
+use super::*;
+
+/// The number of epochs for which the transcript of a DKG session is kept
+/// after the session started
+const TRANSCRIPT_EPOCHS: u64 = 3;
+
+/// The number of blocks after which a wrapper tx in the tx queue that is
+/// still waiting for decryption shares is marked un-decryptable, so that the
+/// validators holding the key shares cannot stall the tx queue
+const DECRYPTION_TIMEOUT_BLOCKS: u64 = 20;
+
+/// The projective representation of the first group of the pairing
+type G1Projective = <EllipticCurve as PairingEngine>::G1Projective;
+
+/// The projective representation of the second group of the pairing
+type G2Projective = <EllipticCurve as PairingEngine>::G2Projective;
+
+/// The private key shares of a validator, with their evaluation points
+type KeyShares = Vec<(Fr, G2)>;
+
+/// A validator taking part in a DKG session
+#[derive(Debug, Clone)]
+struct SessionValidator {
+    address: Address,
+    protocol_pk: common::PublicKey,
+    validator: TendermintValidator<EllipticCurve>,
+}
+
+/// The state of the DKG session generating the encryption key of an epoch
+pub(super) struct DkgSession {
+    /// The epoch whose key is generated
+    epoch: Epoch,
+    dkg: PubliclyVerifiableDkg<EllipticCurve>,
+    validators: Vec<SessionValidator>,
+    /// The address and the session keypair of this node, if it is one of the
+    /// session's validators
+    me: Option<(Address, DkgKeypair)>,
+    /// Whether this node has broadcast its deal
+    dealt: bool,
+    /// Whether this node has broadcast the aggregation of the deals
+    aggregated: bool,
+}
+
+impl fmt::Debug for DkgSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DkgSession")
+            .field("epoch", &self.epoch)
+            .field("validators", &self.validators)
+            .field("me", &self.me.as_ref().map(|(address, _)| address))
+            .field("dealt", &self.dealt)
+            .field("aggregated", &self.aggregated)
+            .finish()
+    }
+}
+
+impl DkgSession {
+    /// The indices of the private key shares of each validator
+    fn share_ranges(&self) -> BTreeMap<Address, ShareRange> {
+        self.dkg
+            .validators
+            .iter()
+            .filter_map(|dkg_validator| {
+                self.validators
+                    .iter()
+                    .find(|validator| {
+                        validator.validator.address
+                            == dkg_validator.validator.address
+                    })
+                    .map(|validator| {
+                        (
+                            validator.address.clone(),
+                            ShareRange {
+                                start: dkg_validator.share_start as u32,
+                                end: dkg_validator.share_end as u32,
+                            },
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Recover this node's shares of the private key from the aggregated
+    /// deals. Every deal encrypts its share at index `j` with the session key
+    /// of the share's holder, so the sum of the deals' shares is decrypted by
+    /// the inverse of the holder's secret session key.
+    fn private_key_shares(&self) -> Option<KeyShares> {
+        let (address, keypair) = self.me.as_ref()?;
+        if !matches!(self.dkg.state, DkgState::Success { .. }) {
+            return None;
+        }
+        let range = *self.share_ranges().get(address)?;
+        let keypair: ferveo_common::Keypair<EllipticCurve> = keypair.into();
+        let inverse = keypair.decryption_key.inverse()?;
+        Some(
+            range
+                .indices()
+                .map(|index| {
+                    let share = self.dkg.vss.values().fold(
+                        G2Projective::zero(),
+                        |mut sum, pvss| {
+                            sum.add_assign_mixed(&pvss.shares[index as usize]);
+                            sum
+                        },
+                    );
+                    (
+                        share_evaluation_point(TOTAL_WEIGHT, index),
+                        share
+                            .into_affine()
+                            .mul(inverse.into_repr())
+                            .into_affine(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// The public key shares of the session's key at every index, which are
+    /// the generator of `G1` times the evaluations of the aggregated secret
+    /// polynomial, computed from the deals' commitments to its coefficients
+    fn public_key_shares(&self) -> Option<Vec<G1>> {
+        if !matches!(self.dkg.state, DkgState::Success { .. }) {
+            return None;
+        }
+        let mut coeffs: Vec<G1Projective> = vec![];
+        for pvss in self.dkg.vss.values() {
+            if coeffs.len() < pvss.coeffs.len() {
+                coeffs.resize(pvss.coeffs.len(), G1Projective::zero());
+            }
+            for (sum, coeff) in coeffs.iter_mut().zip(&pvss.coeffs) {
+                sum.add_assign_mixed(coeff);
+            }
+        }
+        Some(
+            (0..TOTAL_WEIGHT)
+                .map(|index| {
+                    let x = share_evaluation_point(TOTAL_WEIGHT, index);
+                    coeffs
+                        .iter()
+                        .rev()
+                        .fold(G1Projective::zero(), |acc, coeff| {
+                            acc.mul(x.into_repr()) + coeff
+                        })
+                        .into_affine()
+                })
+                .collect(),
+        )
+    }
+
+    /// Find the validator with the given protocol key
+    fn validator_by_protocol_pk(
+        &self,
+        pk: &common::PublicKey,
+    ) -> Option<&SessionValidator> {
+        self.validators
+            .iter()
+            .find(|validator| &validator.protocol_pk == pk)
+    }
+}
+
+/// The node's DKG state, which isn't committed to storage
+#[derive(Debug, Default)]
+pub(super) struct DkgNode {
+    /// The session generating the key of the next epoch
+    session: Option<DkgSession>,
+    /// This node's private key shares of the keys of the recent epochs
+    key_shares: BTreeMap<Epoch, KeyShares>,
+    /// The public key shares of the keys of the recent epochs, against
+    /// which the submitted decryption shares are verified
+    public_key_shares: BTreeMap<Epoch, Vec<G1>>,
+    /// The wrapper txs in the tx queue whose decryption shares this node has
+    /// already broadcast
+    shared_txs: HashSet<Hash>,
+}
+
+/// How a wrapper tx in the tx queue can be decrypted
+enum Decryption {
+    /// No key was generated for the wrapper's epoch, so it is encrypted with
+    /// the bootstrap key, whose private key is public
+    Bootstrap,
+    /// Enough valid decryption shares have been submitted
+    Shares(Vec<(Fr, DecryptionShare)>),
+    /// The wrapper must wait for more decryption shares
+    Pending,
+    /// Not enough decryption shares have been submitted within
+    /// [`DECRYPTION_TIMEOUT_BLOCKS`]
+    TimedOut,
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// The active validators of the given epoch that can take part in its
+    /// DKG session
+    fn dkg_validators(&self, epoch: Epoch) -> Vec<SessionValidator> {
+        let pos_params = self.storage.read_pos_params();
+        let validator_set = match self.storage.read_validator_set().get(epoch) {
+            Some(validator_set) => validator_set.clone(),
+            None => {
+                tracing::error!(
+                    "The validator set of epoch {} is not known",
+                    epoch
+                );
+                return vec![];
+            }
+        };
+        validator_set
+            .active
+            .iter()
+            .filter_map(|validator| {
+                let protocol_pk = self.read_storage_key::<common::PublicKey>(
+                    &protocol_pk_key(&validator.address),
+                )?;
+                let dkg_pk = self.read_storage_key::<DkgPublicKey>(
+                    &dkg_pk_key(&validator.address),
+                )?;
+                Some(SessionValidator {
+                    address: validator.address.clone(),
+                    protocol_pk,
+                    validator: TendermintValidator {
+                        power: into_tm_voting_power(
+                            pos_params.tm_votes_per_token,
+                            validator.bonded_stake,
+                        ) as u64,
+                        address: validator.address.to_string(),
+                        public_key: (&dkg_pk).into(),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Set up the DKG session of the given epoch. A node that isn't one of
+    /// its validators follows the session as the first validator with a
+    /// random keypair, which is only used to verify the messages.
+    fn new_dkg_session(&self, epoch: Epoch) -> Option<DkgSession> {
+        let validators = self.dkg_validators(epoch);
+        let me = match &self.mode {
+            ShellMode::Validator { data, .. } => data
+                .keys
+                .dkg_keypair
+                .as_ref()
+                .filter(|_| {
+                    validators
+                        .iter()
+                        .any(|validator| validator.address == data.address)
+                })
+                .map(|keypair| (data.address.clone(), keypair.clone())),
+            _ => None,
+        };
+        let (me_validator, session_keypair) = match &me {
+            Some((address, keypair)) => (
+                validators
+                    .iter()
+                    .find(|validator| &validator.address == address)?
+                    .validator
+                    .clone(),
+                keypair.into(),
+            ),
+            None => (
+                validators.first()?.validator.clone(),
+                ferveo_common::Keypair::new(&mut rand::thread_rng()),
+            ),
+        };
+        let params = Params {
+            tau: epoch.0,
+            security_threshold: SECURITY_THRESHOLD,
+            total_weight: TOTAL_WEIGHT,
+            retry_after: 1,
+        };
+        let dkg = PubliclyVerifiableDkg::new(
+            ValidatorSet::new(
+                validators
+                    .iter()
+                    .map(|validator| validator.validator.clone())
+                    .collect(),
+            ),
+            params,
+            me_validator,
+            session_keypair,
+        )
+        .map_err(|err| {
+            tracing::error!(
+                "Failed to set up the DKG session of epoch {}: {}",
+                epoch,
+                err
+            );
+        })
+        .ok()?;
+        Some(DkgSession {
+            epoch,
+            dkg,
+            validators,
+            me,
+            dealt: false,
+            aggregated: false,
+        })
+    }
+
+    /// Restore the DKG session of the given epoch from its transcript
+    fn restore_dkg_session(&self, epoch: Epoch) -> Option<DkgSession> {
+        let transcript: DkgTranscript =
+            self.read_value(&dkg_storage::transcript_key(epoch))?;
+        let mut session = self.new_dkg_session(epoch)?;
+        for (sender, DkgMessage(msg)) in transcript {
+            if let Some((me, _)) = &session.me {
+                if me == &sender {
+                    match &msg {
+                        ferveo::dkg::pv::Message::Deal(_) => {
+                            session.dealt = true
+                        }
+                        ferveo::dkg::pv::Message::Aggregate(_) => {
+                            session.aggregated = true
+                        }
+                    }
+                }
+            }
+            let validator = session
+                .validators
+                .iter()
+                .find(|validator| validator.address == sender)
+                .map(|validator| validator.validator.clone());
+            if let Some(validator) = validator {
+                if let Err(err) = session.dkg.apply_message(validator, msg) {
+                    tracing::error!(
+                        "Failed to replay a message of the DKG session of \
+                         epoch {}: {}",
+                        epoch,
+                        err
+                    );
+                }
+            }
+        }
+        Some(session)
+    }
+
+    /// Restore the DKG state of the node on start-up
+    pub(super) fn restore_dkg(&mut self) {
+        let (current_epoch, _) = self.storage.get_current_epoch();
+        self.dkg = DkgNode::default();
+        let past_epochs = current_epoch
+            .checked_sub(Epoch(1))
+            .into_iter()
+            .chain([current_epoch]);
+        for epoch in past_epochs {
+            if let Some(session) = self.restore_dkg_session(epoch) {
+                if let Some(shares) = session.private_key_shares() {
+                    self.dkg.key_shares.insert(epoch, shares);
+                }
+                if let Some(shares) = session.public_key_shares() {
+                    self.dkg.public_key_shares.insert(epoch, shares);
+                }
+            }
+        }
+        self.dkg.session = self.restore_dkg_session(current_epoch.next());
+    }
+
+    /// Start the DKG session of the given epoch and drop the shares of the
+    /// keys that are no longer used.
+    pub(super) fn start_dkg_session(&mut self, epoch: Epoch) {
+        if let Some(old_epoch) = epoch.checked_sub(Epoch(TRANSCRIPT_EPOCHS)) {
+            self.delete_value(&dkg_storage::transcript_key(old_epoch));
+        }
+        if let Some(last_epoch) = epoch.checked_sub(Epoch(2)) {
+            self.dkg.key_shares = self.dkg.key_shares.split_off(&last_epoch);
+            self.dkg.public_key_shares =
+                self.dkg.public_key_shares.split_off(&last_epoch);
+        }
+        self.dkg.session = self.new_dkg_session(epoch);
+        match &self.dkg.session {
+            Some(session) => {
+                let share_ranges = session.share_ranges();
+                self.write_value(
+                    &dkg_storage::transcript_key(epoch),
+                    &DkgTranscript::new(),
+                );
+                self.write_value(
+                    &dkg_storage::share_ranges_key(epoch),
+                    &share_ranges,
+                );
+                tracing::info!("Started the DKG session of epoch {}", epoch);
+            }
+            None => tracing::error!(
+                "Couldn't start the DKG session of epoch {}, its txs will be \
+                 encrypted with the bootstrap key",
+                epoch
+            ),
+        }
+        self.write_log.commit_tx();
+    }
+
+    /// Check that a DKG message can be applied to the current session
+    pub(super) fn validate_dkg_message(
+        &self,
+        pk: &common::PublicKey,
+        DkgMessage(msg): &DkgMessage,
+    ) -> std::result::Result<(), String> {
+        let session =
+            self.dkg.session.as_ref().ok_or_else(|| {
+                "There is no DKG session in progress".to_string()
+            })?;
+        let sender = session.validator_by_protocol_pk(pk).ok_or_else(|| {
+            format!(
+                "The sender isn't a validator of the DKG session of epoch {}",
+                session.epoch
+            )
+        })?;
+        session
+            .dkg
+            .verify_message(&sender.validator, msg)
+            .map_err(|err| format!("Invalid DKG message: {}", err))
+    }
+
+    /// Apply a DKG message to the current session and record it in the
+    /// session's transcript. Once the session succeeds, its public key is
+    /// written to storage.
+    pub(super) fn apply_dkg_message(
+        &mut self,
+        pk: &common::PublicKey,
+        msg: DkgMessage,
+    ) -> std::result::Result<(), String> {
+        self.validate_dkg_message(pk, &msg)?;
+        let mut session = self
+            .dkg
+            .session
+            .take()
+            .expect("The DKG session should have been validated");
+        let sender = session
+            .validator_by_protocol_pk(pk)
+            .expect("The sender should have been validated")
+            .clone();
+        let result = session
+            .dkg
+            .apply_message(sender.validator, msg.0.clone())
+            .map_err(|err| format!("Failed to apply a DKG message: {}", err));
+        if result.is_ok() {
+            let transcript_key = dkg_storage::transcript_key(session.epoch);
+            let mut transcript: DkgTranscript =
+                self.read_value(&transcript_key).unwrap_or_default();
+            transcript.push((sender.address, msg));
+            self.write_value(&transcript_key, &transcript);
+            if let DkgState::Success { final_key } = &session.dkg.state {
+                tracing::info!(
+                    "The DKG session of epoch {} generated the encryption key",
+                    session.epoch
+                );
+                self.write_value(
+                    &dkg_storage::encryption_key_key(session.epoch),
+                    &EncryptionKey(*final_key),
+                );
+                if let Some(shares) = session.private_key_shares() {
+                    self.dkg.key_shares.insert(session.epoch, shares);
+                }
+                if let Some(shares) = session.public_key_shares() {
+                    self.dkg.public_key_shares.insert(session.epoch, shares);
+                }
+            }
+            self.write_log.commit_tx();
+        }
+        self.dkg.session = Some(session);
+        result
+    }
+
+    /// Broadcast this validator's next message of the current DKG session, if
+    /// any: its deal while the session is collecting them, then the
+    /// aggregation of the deals once enough have been applied.
+    pub(super) fn advance_dkg_session(&mut self) {
+        let (protocol_keypair, broadcast_sender) = match &self.mode {
+            ShellMode::Validator {
+                data,
+                broadcast_sender,
+                ..
+            } => (&data.keys.protocol_keypair, broadcast_sender),
+            _ => return,
+        };
+        let session = match self.dkg.session.as_mut() {
+            Some(session) if session.me.is_some() => session,
+            _ => return,
+        };
+        let msg = match session.dkg.state {
+            DkgState::Sharing { .. } if !session.dealt => {
+                session.dealt = true;
+                session.dkg.share(&mut rand::thread_rng())
+            }
+            DkgState::Dealt if !session.aggregated => {
+                session.aggregated = true;
+                session.dkg.aggregate()
+            }
+            _ => return,
+        };
+        match msg {
+            Ok(msg) => {
+                let tx = ProtocolTxType::from(msg)
+                    .sign(&protocol_keypair.ref_to(), protocol_keypair);
+                let _ = broadcast_sender.send(tx.to_bytes()).map_err(|err| {
+                    tracing::error!(
+                        "Failed to broadcast a DKG message: {}",
+                        err
+                    );
+                });
+            }
+            Err(err) => tracing::error!(
+                "Failed to create a message of the DKG session of epoch {}: {}",
+                session.epoch,
+                err
+            ),
+        }
+    }
+
+    /// Broadcast this validator's decryption shares of the wrapper txs in the
+    /// tx queue that it hasn't shared yet
+    pub(super) fn broadcast_decryption_shares(&mut self) {
+        let (protocol_keypair, broadcast_sender) = match &self.mode {
+            ShellMode::Validator {
+                data,
+                broadcast_sender,
+                ..
+            } => (&data.keys.protocol_keypair, broadcast_sender),
+            _ => return,
+        };
+        let queued: HashSet<Hash> = self
+            .storage
+            .tx_queue
+            .iter()
+            .map(|wrapper| wrapper.tx_hash.clone())
+            .collect();
+        self.dkg.shared_txs.retain(|hash| queued.contains(hash));
+        let shares: Vec<(Hash, TxDecryptionShares)> = self
+            .storage
+            .tx_queue
+            .iter()
+            .filter(|wrapper| !self.dkg.shared_txs.contains(&wrapper.tx_hash))
+            .filter_map(|wrapper| {
+                let key_shares: Vec<G2> = self
+                    .dkg
+                    .key_shares
+                    .get(&wrapper.epoch)?
+                    .iter()
+                    .map(|(_, key_share)| *key_share)
+                    .collect();
+                Some((
+                    wrapper.tx_hash.clone(),
+                    wrapper.inner_tx.blinded_decryption_shares(&key_shares),
+                ))
+            })
+            .collect();
+        if shares.is_empty() {
+            return;
+        }
+        self.dkg
+            .shared_txs
+            .extend(shares.iter().map(|(hash, _)| hash.clone()));
+        let tx = ProtocolTxType::DecryptionShares(shares)
+            .sign(&protocol_keypair.ref_to(), protocol_keypair);
+        let _ = broadcast_sender.send(tx.to_bytes()).map_err(|err| {
+            tracing::error!("Failed to broadcast decryption shares: {}", err);
+        });
+    }
+
+    /// Find the validator with the given protocol key among the holders of
+    /// the private key shares of the given epoch
+    fn share_holder(
+        &self,
+        epoch: Epoch,
+        pk: &common::PublicKey,
+    ) -> Option<(Address, ShareRange)> {
+        let share_ranges: BTreeMap<Address, ShareRange> =
+            self.read_value(&dkg_storage::share_ranges_key(epoch))?;
+        share_ranges.into_iter().find(|(address, _)| {
+            self.read_storage_key::<common::PublicKey>(&protocol_pk_key(
+                address,
+            ))
+            .as_ref()
+                == Some(pk)
+        })
+    }
+
+    /// Verify the decryption shares of a wrapper tx in the tx queue
+    /// submitted by the validator with the given protocol key against the
+    /// public shares of the key of the wrapper's epoch. Returns the sender's
+    /// address and its decryption shares, one for each of its private key
+    /// shares.
+    fn verify_decryption_shares(
+        &self,
+        pk: &common::PublicKey,
+        hash: &Hash,
+        tx_shares: &TxDecryptionShares,
+    ) -> std::result::Result<(Address, Vec<DecryptionShare>), String> {
+        let wrapper = self
+            .storage
+            .tx_queue
+            .iter()
+            .find(|wrapper| &wrapper.tx_hash == hash)
+            .ok_or_else(|| {
+                format!("The wrapper tx {} is not in the tx queue", hash)
+            })?;
+        let (address, range) =
+            self.share_holder(wrapper.epoch, pk).ok_or_else(|| {
+                format!(
+                    "The sender holds no key shares of epoch {}",
+                    wrapper.epoch
+                )
+            })?;
+        let public_key_shares = self
+            .dkg
+            .public_key_shares
+            .get(&wrapper.epoch)
+            .and_then(|shares| {
+                shares.get(range.start as usize..range.end as usize)
+            })
+            .ok_or_else(|| {
+                format!(
+                    "The public key shares of epoch {} are not known",
+                    wrapper.epoch
+                )
+            })?;
+        let shares = tx_shares
+            .verify(&wrapper.inner_tx, public_key_shares)
+            .ok_or_else(|| {
+                format!("Invalid decryption shares of the wrapper tx {}", hash)
+            })?;
+        Ok((address, shares))
+    }
+
+    /// Check that the decryption shares are of wrapper txs in the tx queue
+    /// and that the sender submitted a valid share for each of its private
+    /// key shares of the wrapper's epoch
+    pub(super) fn validate_decryption_shares(
+        &self,
+        pk: &common::PublicKey,
+        shares: &[(Hash, TxDecryptionShares)],
+    ) -> std::result::Result<(), String> {
+        for (hash, tx_shares) in shares {
+            self.verify_decryption_shares(pk, hash, tx_shares)?;
+        }
+        Ok(())
+    }
+
+    /// Record the decryption shares submitted by a validator, once all of
+    /// them have been verified
+    pub(super) fn apply_decryption_shares(
+        &mut self,
+        pk: &common::PublicKey,
+        shares: Vec<(Hash, TxDecryptionShares)>,
+    ) -> std::result::Result<(), String> {
+        let verified = shares
+            .iter()
+            .map(|(hash, tx_shares)| {
+                let (address, tx_shares) =
+                    self.verify_decryption_shares(pk, hash, tx_shares)?;
+                Ok((hash, address, tx_shares))
+            })
+            .collect::<std::result::Result<Vec<_>, String>>()?;
+        for (hash, address, tx_shares) in verified {
+            let key = dkg_storage::decryption_shares_key(hash);
+            let mut submitted: BTreeMap<Address, Vec<DecryptionShare>> =
+                self.read_value(&key).unwrap_or_default();
+            submitted.insert(address, tx_shares);
+            self.write_value(&key, &submitted);
+        }
+        self.write_log.commit_tx();
+        Ok(())
+    }
+
+    /// Check that a wrapper tx is encrypted with the key of the current
+    /// epoch. The key shares of the older epochs are eventually dropped, so
+    /// the wrappers of those epochs might never be decrypted.
+    pub(super) fn validate_wrapper_epoch(
+        &self,
+        wrapper: &WrapperTx,
+    ) -> std::result::Result<(), String> {
+        let (current_epoch, _gas) = self.storage.get_current_epoch();
+        if wrapper.epoch == current_epoch {
+            Ok(())
+        } else {
+            Err(format!(
+                "The wrapped tx {} is encrypted with the key of epoch {}, but \
+                 the current epoch is {}",
+                wrapper.tx_hash, wrapper.epoch, current_epoch
+            ))
+        }
+    }
+
+    /// Add a wrapper tx to the tx queue, recording the height of the current
+    /// block to time out its decryption
+    pub(super) fn enqueue_wrapper(&mut self, wrapper: WrapperTx) {
+        let height = self.storage.block.height;
+        self.write_value(
+            &dkg_storage::queued_at_key(&wrapper.tx_hash),
+            &height,
+        );
+        self.write_log.commit_tx();
+        self.storage.tx_queue.push(wrapper);
+    }
+
+    /// Delete the decryption shares and the queue height of a wrapper tx
+    /// removed from the queue
+    pub(super) fn forget_decryption_shares(&mut self, wrapper: &WrapperTx) {
+        self.delete_value(&dkg_storage::decryption_shares_key(
+            &wrapper.tx_hash,
+        ));
+        self.delete_value(&dkg_storage::queued_at_key(&wrapper.tx_hash));
+        self.write_log.commit_tx();
+    }
+
+    /// Determine how a wrapper tx in the tx queue can be decrypted
+    fn wrapper_decryption(&self, wrapper: &WrapperTx) -> Decryption {
+        let key: Option<EncryptionKey> =
+            self.read_value(&dkg_storage::encryption_key_key(wrapper.epoch));
+        if key.is_none() {
+            return Decryption::Bootstrap;
+        }
+        let share_ranges: BTreeMap<Address, ShareRange> = self
+            .read_value(&dkg_storage::share_ranges_key(wrapper.epoch))
+            .unwrap_or_default();
+        // Only verified shares are recorded, so any threshold-sized set of
+        // them decrypts the wrapper
+        let submitted: BTreeMap<Address, Vec<DecryptionShare>> = self
+            .read_value(&dkg_storage::decryption_shares_key(&wrapper.tx_hash))
+            .unwrap_or_default();
+        let shares: Vec<(Fr, DecryptionShare)> = submitted
+            .into_iter()
+            .filter_map(|(address, tx_shares)| {
+                let range = share_ranges.get(&address)?;
+                Some(range.indices().zip(tx_shares).map(|(index, share)| {
+                    (share_evaluation_point(TOTAL_WEIGHT, index), share)
+                }))
+            })
+            .flatten()
+            .take(SECURITY_THRESHOLD as usize + 1)
+            .collect();
+        if shares.len() > SECURITY_THRESHOLD as usize {
+            return Decryption::Shares(shares);
+        }
+        // The proposed block is the one after the last committed block
+        let queued_at: BlockHeight = self
+            .read_value(&dkg_storage::queued_at_key(&wrapper.tx_hash))
+            .unwrap_or_default();
+        if self.storage.last_height.0 + 1
+            >= queued_at.0 + DECRYPTION_TIMEOUT_BLOCKS
+        {
+            Decryption::TimedOut
+        } else {
+            Decryption::Pending
+        }
+    }
+
+    /// Whether enough decryption shares of a wrapper tx in the tx queue have
+    /// been submitted to decrypt it or its decryption has timed out
+    pub(super) fn can_decrypt_wrapper(&self, wrapper: &WrapperTx) -> bool {
+        !matches!(self.wrapper_decryption(wrapper), Decryption::Pending)
+    }
+
+    /// Decrypt a wrapper tx in the tx queue, if enough decryption shares of
+    /// it have been submitted. A wrapper whose decryption has timed out is
+    /// un-decryptable.
+    pub(super) fn decrypt_wrapper(
+        &self,
+        wrapper: &WrapperTx,
+    ) -> Option<DecryptedTx> {
+        let decrypted = match self.wrapper_decryption(wrapper) {
+            Decryption::Bootstrap => wrapper.decrypt(
+                <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator(),
+            ),
+            Decryption::Shares(shares) => wrapper.decrypt_with_shares(&shares),
+            Decryption::TimedOut => {
+                return Some(DecryptedTx::Undecryptable(wrapper.clone()));
+            }
+            Decryption::Pending => return None,
+        };
+        Some(match decrypted {
+            Ok(tx) => DecryptedTx::Decrypted(tx),
+            Err(_) => DecryptedTx::Undecryptable(wrapper.clone()),
+        })
+    }
+
+    /// Check that a tx decrypted from a wrapper in the tx queue was marked
+    /// un-decryptable only if it is in fact so
+    pub(super) fn verify_decrypted_correctly(
+        &self,
+        decrypted: &DecryptedTx,
+        wrapper: &WrapperTx,
+    ) -> bool {
+        match decrypted {
+            DecryptedTx::Decrypted(_) => true,
+            DecryptedTx::Undecryptable(_) => matches!(
+                self.decrypt_wrapper(wrapper),
+                Some(DecryptedTx::Undecryptable(_))
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_dkg {
+    use ark_ff::UniformRand;
+    use namada::types::address;
+    use namada::types::transaction::protocol::ProtocolTx;
+    use namada::types::transaction::Fee;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::{gen_keypair, setup};
+
+    /// Test that a DKG session is started for the next epoch at genesis,
+    /// with all the key shares held by the only validator
+    #[test]
+    fn test_dkg_session_started() {
+        let (shell, _) = setup();
+        let session = shell.dkg.session.as_ref().expect("Test failed");
+        assert_eq!(session.epoch, Epoch(1));
+        let share_ranges: BTreeMap<Address, ShareRange> = shell
+            .read_value(&dkg_storage::share_ranges_key(Epoch(1)))
+            .expect("Test failed");
+        assert_eq!(share_ranges.len(), 1);
+        let weight: u32 = share_ranges.values().map(ShareRange::weight).sum();
+        assert_eq!(weight, TOTAL_WEIGHT);
+        let transcript: DkgTranscript = shell
+            .read_value(&dkg_storage::transcript_key(Epoch(1)))
+            .expect("Test failed");
+        assert!(transcript.is_empty());
+    }
+
+    /// Test that the validator deals in the DKG session and that a message
+    /// from a non-validator is rejected
+    #[test]
+    fn test_dkg_deal() {
+        let (mut shell, mut broadcast) = setup();
+        shell.advance_dkg_session();
+        let tx = broadcast.try_recv().expect("Test failed");
+        let tx = Tx::try_from(tx.as_slice()).expect("Test failed");
+        let (pk, msg) = match process_tx(tx).expect("Test failed") {
+            TxType::Protocol(ProtocolTx {
+                pk,
+                tx: ProtocolTxType::DKG(msg),
+            }) => (pk, msg),
+            _ => panic!("Test failed"),
+        };
+        // the deal is only broadcast once
+        shell.advance_dkg_session();
+        assert!(broadcast.try_recv().is_err());
+
+        assert!(shell
+            .validate_dkg_message(&gen_keypair().ref_to(), &msg)
+            .is_err());
+        shell.apply_dkg_message(&pk, msg).expect("Test failed");
+        let transcript: DkgTranscript = shell
+            .read_value(&dkg_storage::transcript_key(Epoch(1)))
+            .expect("Test failed");
+        assert_eq!(transcript.len(), 1);
+    }
+
+    /// Test that a wrapper tx of an epoch without a generated key is
+    /// decrypted with the bootstrap key, while the wrapper of an epoch with a
+    /// key waits for the decryption shares
+    #[test]
+    fn test_wrapper_decryption() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: shell.storage.native_token.clone(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            tx.clone(),
+            Default::default(),
+        );
+        assert!(shell.can_decrypt_wrapper(&wrapper));
+        assert!(matches!(
+            shell.decrypt_wrapper(&wrapper),
+            Some(DecryptedTx::Decrypted(decrypted)) if decrypted == tx
+        ));
+
+        shell.write_value(
+            &dkg_storage::encryption_key_key(Epoch(0)),
+            &EncryptionKey::default(),
+        );
+        assert!(!shell.can_decrypt_wrapper(&wrapper));
+        assert!(shell.decrypt_wrapper(&wrapper).is_none());
+    }
+
+    /// Test that a wrapper tx that is still waiting for decryption shares
+    /// after the timeout is marked un-decryptable
+    #[test]
+    fn test_wrapper_decryption_timeout() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: shell.storage.native_token.clone(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            tx,
+            Default::default(),
+        );
+        shell.write_value(
+            &dkg_storage::encryption_key_key(Epoch(0)),
+            &EncryptionKey::default(),
+        );
+        shell.storage.block.height = BlockHeight(1);
+        shell.enqueue_wrapper(wrapper.clone());
+
+        shell.storage.last_height = BlockHeight(DECRYPTION_TIMEOUT_BLOCKS - 1);
+        assert!(!shell.can_decrypt_wrapper(&wrapper));
+        assert!(shell.decrypt_wrapper(&wrapper).is_none());
+
+        shell.storage.last_height = BlockHeight(DECRYPTION_TIMEOUT_BLOCKS);
+        assert!(shell.can_decrypt_wrapper(&wrapper));
+        let decrypted = shell.decrypt_wrapper(&wrapper).expect("Test failed");
+        assert!(matches!(decrypted, DecryptedTx::Undecryptable(_)));
+        assert!(shell.verify_decrypted_correctly(&decrypted, &wrapper));
+
+        // The queue height is deleted with the wrapper
+        shell.forget_decryption_shares(&wrapper);
+        assert!(shell
+            .read_value::<BlockHeight>(&dkg_storage::queued_at_key(
+                &wrapper.tx_hash
+            ))
+            .is_none());
+    }
+
+    /// Test that invalid decryption shares are rejected and that a wrapper
+    /// tx is decrypted with the valid shares of the other validators
+    #[test]
+    fn test_invalid_decryption_shares() {
+        let (mut shell, _) = setup();
+        let mut rng = rand::thread_rng();
+        let epoch = Epoch(0);
+        // A secret polynomial that any `SECURITY_THRESHOLD + 1` shares
+        // interpolate, whose value at 0 is the private key
+        let coeffs: Vec<Fr> = (0..=SECURITY_THRESHOLD)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let eval = |x: Fr| {
+            coeffs
+                .iter()
+                .rev()
+                .fold(Fr::zero(), |acc, coeff| acc * x + coeff)
+        };
+        let points: Vec<Fr> = (0..TOTAL_WEIGHT)
+            .map(|index| share_evaluation_point(TOTAL_WEIGHT, index))
+            .collect();
+        let public_key_shares: Vec<G1> = points
+            .iter()
+            .map(|x| G1::prime_subgroup_generator().mul(eval(*x)).into_affine())
+            .collect();
+        let key_shares: Vec<G2> = points
+            .iter()
+            .map(|x| G2::prime_subgroup_generator().mul(eval(*x)).into_affine())
+            .collect();
+        let encryption_key = EncryptionKey(
+            G1::prime_subgroup_generator().mul(coeffs[0]).into_affine(),
+        );
+        shell.write_value(
+            &dkg_storage::encryption_key_key(epoch),
+            &encryption_key,
+        );
+        shell.dkg.public_key_shares.insert(epoch, public_key_shares);
+
+        // An honest validator holding enough shares to decrypt and a
+        // dishonest one holding the others
+        let honest = (
+            address::testing::established_address_1(),
+            gen_keypair(),
+            ShareRange {
+                start: 0,
+                end: SECURITY_THRESHOLD + 1,
+            },
+        );
+        let dishonest = (
+            address::testing::established_address_2(),
+            gen_keypair(),
+            ShareRange {
+                start: SECURITY_THRESHOLD + 1,
+                end: TOTAL_WEIGHT,
+            },
+        );
+        let share_ranges: BTreeMap<Address, ShareRange> = [&honest, &dishonest]
+            .into_iter()
+            .map(|(address, _, range)| (address.clone(), *range))
+            .collect();
+        shell.write_value(&dkg_storage::share_ranges_key(epoch), &share_ranges);
+        for (address, keypair, _) in [&honest, &dishonest] {
+            shell.write_value(&protocol_pk_key(address), &keypair.ref_to());
+        }
+
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: shell.storage.native_token.clone(),
+            },
+            &gen_keypair(),
+            epoch,
+            0.into(),
+            tx.clone(),
+            encryption_key,
+        );
+        shell.storage.tx_queue.push(wrapper.clone());
+        let shares_of = |range: std::ops::Range<u32>| {
+            wrapper.inner_tx.blinded_decryption_shares(
+                &key_shares[range.start as usize..range.end as usize],
+            )
+        };
+
+        // The dishonest validator's shares are computed with key shares that
+        // it doesn't hold
+        let weight = dishonest.2.weight();
+        let invalid = vec![(wrapper.tx_hash.clone(), shares_of(0..weight))];
+        assert!(shell
+            .validate_decryption_shares(&dishonest.1.ref_to(), &invalid)
+            .is_err());
+        assert!(shell
+            .apply_decryption_shares(&dishonest.1.ref_to(), invalid)
+            .is_err());
+        assert!(!shell.can_decrypt_wrapper(&wrapper));
+
+        let valid =
+            vec![(wrapper.tx_hash.clone(), shares_of(honest.2.indices()))];
+        shell
+            .apply_decryption_shares(&honest.1.ref_to(), valid)
+            .expect("Test failed");
+        assert!(matches!(
+            shell.decrypt_wrapper(&wrapper),
+            Some(DecryptedTx::Decrypted(decrypted)) if decrypted == tx
+        ));
+    }
+}
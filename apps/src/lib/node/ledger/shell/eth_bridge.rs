@@ -0,0 +1,437 @@
+//! Tallying of the validators' votes on the Ethereum events and application of
+//! the transfers to Namada once they have been attested.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use namada::ledger::eth_bridge::storage as bridge_storage;
+use namada::types::ethereum_events::{
+    EthAddress, EthereumEvent, TransferToNamada,
+};
+use namada::types::hash::Hash;
+use namada::types::storage::Epoch;
+use namada::types::vote_extensions::ethereum_events::VextDigest;
+
+use super::*;
+
+/// The number of epochs after which the tally of an Ethereum event that has
+/// not been attested by enough validators is removed
+const EVENT_TALLY_EXPIRY_EPOCHS: u64 = 2;
+
+/// The votes of the validators on an Ethereum event
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+struct EventTally {
+    /// The event voted on
+    event: EthereumEvent,
+    /// The validators who have voted for the event
+    seen_by: BTreeSet<Address>,
+    /// The epoch in which the event was first voted on
+    first_seen: Epoch,
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Add the votes from the vote extensions to the tallies of the Ethereum
+    /// events. An event is attested once the validators who have voted for
+    /// it, in this block or in an earlier one, hold more than 2/3 of the
+    /// active stake of the current epoch, at which point its tally is
+    /// removed. The attested transfers to Namada are then applied in the
+    /// order of their nonces, so that late votes on an event can't apply it
+    /// again. The tallies of the events that haven't been attested within
+    /// [`EVENT_TALLY_EXPIRY_EPOCHS`] are removed first. Returns the events
+    /// attested in this block.
+    pub(super) fn apply_eth_events_digest(
+        &mut self,
+        digest: &VextDigest,
+    ) -> Vec<EthereumEvent> {
+        self.expire_event_tallies();
+
+        let mut votes: BTreeMap<Hash, (&EthereumEvent, BTreeSet<&Address>)> =
+            BTreeMap::new();
+        for vext in &digest.vexts {
+            for event in &vext.data.ethereum_events {
+                votes
+                    .entry(event.hash())
+                    .or_insert_with(|| (event, BTreeSet::new()))
+                    .1
+                    .insert(&vext.data.validator_addr);
+            }
+        }
+
+        let current_epoch = self.storage.block.epoch;
+        let active_validators = self.get_active_validator_stakes();
+        let total_stake: u64 = active_validators.values().sum();
+        let mut attested = vec![];
+        for (hash, (event, validators)) in votes {
+            let key = bridge_storage::event_tally_key(&hash);
+            let mut tally: EventTally =
+                self.read_value(&key).unwrap_or_else(|| EventTally {
+                    event: event.clone(),
+                    seen_by: BTreeSet::new(),
+                    first_seen: current_epoch,
+                });
+            tally.seen_by.extend(validators.into_iter().cloned());
+            let voting_power: u64 = tally
+                .seen_by
+                .iter()
+                .filter_map(|validator| active_validators.get(validator))
+                .sum();
+            if 3 * u128::from(voting_power) > 2 * u128::from(total_stake) {
+                if self.attest_event(&tally.event) {
+                    attested.push(tally.event);
+                }
+                self.delete_value(&key);
+            } else {
+                self.write_value(&key, &tally);
+            }
+        }
+
+        self.apply_transfers_to_namada();
+        self.write_log.commit_tx();
+        attested
+    }
+
+    /// Remove the tallies of the events that haven't been attested within
+    /// [`EVENT_TALLY_EXPIRY_EPOCHS`] since they were first voted on. Later
+    /// votes on an expired event start a new tally.
+    fn expire_event_tallies(&mut self) {
+        let current_epoch = self.storage.block.epoch;
+        let (tallies, _gas) = self
+            .storage
+            .iter_prefix(&bridge_storage::event_tallies_prefix());
+        let keys: Vec<Key> = tallies
+            .filter_map(|(key, _value, _gas)| Key::parse(key).ok())
+            .collect();
+        for key in keys {
+            let tally = match self.read_value::<EventTally>(&key) {
+                Some(tally) => tally,
+                None => continue,
+            };
+            if tally.first_seen + EVENT_TALLY_EXPIRY_EPOCHS <= current_epoch {
+                tracing::info!(
+                    event = ?tally.event,
+                    "The Ethereum event has not been attested since epoch {}, \
+                     removing its tally",
+                    tally.first_seen
+                );
+                self.delete_value(&key);
+            }
+        }
+    }
+
+    /// Record an event attested by the validators. Returns `false` if another
+    /// event with the same nonce has already been attested or applied.
+    fn attest_event(&mut self, event: &EthereumEvent) -> bool {
+        match event {
+            EthereumEvent::TransfersToNamada { nonce, transfers } => {
+                let next_nonce: u64 =
+                    self.read_value(
+                        &bridge_storage::transfers_to_namada_nonce_key(),
+                    )
+                    .unwrap_or_default();
+                let key =
+                    bridge_storage::attested_transfers_to_namada_key(*nonce);
+                if *nonce < next_nonce
+                    || self.read_value::<Vec<TransferToNamada>>(&key).is_some()
+                {
+                    tracing::error!(
+                        nonce,
+                        "Conflicting transfers to Namada have already been \
+                         attested"
+                    );
+                    return false;
+                }
+                self.write_value(&key, transfers);
+                true
+            }
+        }
+    }
+
+    /// Apply the attested transfers to Namada, starting from the next nonce
+    /// and until a nonce that has not been attested yet. The wrapped ERC20
+    /// tokens are minted to the receivers, while the wrapped native tokens
+    /// are released from the bridge's escrow.
+    fn apply_transfers_to_namada(&mut self) {
+        let nonce_key = bridge_storage::transfers_to_namada_nonce_key();
+        let first_nonce: u64 = self.read_value(&nonce_key).unwrap_or_default();
+        let native_erc20: Option<EthAddress> =
+            self.read_value(&bridge_storage::native_erc20_key());
+        let native_token = self.storage.native_token.clone();
+        let escrow = eth_bridge::vp::ADDRESS;
+
+        let mut nonce = first_nonce;
+        while let Some(transfers) = self.read_value::<Vec<TransferToNamada>>(
+            &bridge_storage::attested_transfers_to_namada_key(nonce),
+        ) {
+            for TransferToNamada {
+                amount,
+                asset,
+                receiver,
+            } in transfers
+            {
+                if Some(asset) == native_erc20 {
+                    let escrowed = self.read_balance(&native_token, &escrow);
+                    if escrowed < amount {
+                        tracing::error!(
+                            nonce,
+                            %receiver,
+                            %amount,
+                            "The escrow holds less native tokens than \
+                             transferred back from Ethereum"
+                        );
+                        continue;
+                    }
+                    self.write_balance(
+                        &native_token,
+                        &escrow,
+                        escrowed - amount,
+                    );
+                    let balance = self.read_balance(&native_token, &receiver);
+                    self.write_balance(
+                        &native_token,
+                        &receiver,
+                        balance + amount,
+                    );
+                } else {
+                    let key = bridge_storage::wrapped_erc20_balance_key(
+                        &asset, &receiver,
+                    );
+                    let balance: token::Amount =
+                        self.read_value(&key).unwrap_or_default();
+                    self.write_value(&key, &(balance + amount));
+                }
+            }
+            tracing::info!(nonce, "Applied the transfers to Namada");
+            nonce += 1;
+        }
+        if nonce != first_nonce {
+            self.write_value(&nonce_key, &nonce);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_eth_bridge {
+    use namada::ledger::pos::namada_proof_of_stake::epoched::Epoched;
+    use namada::ledger::pos::namada_proof_of_stake::types::{
+        ValidatorSet, WeightedValidator,
+    };
+    use namada::types::ethereum_events::testing::{
+        DAI_ERC20_ETH_ADDRESS, WNAM_ERC20_ETH_ADDRESS,
+    };
+    use namada::types::storage::Epoch;
+    use namada::types::vote_extensions::ethereum_events::Vext;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::*;
+
+    /// Set up a shell with four active validators of equal stake. Returns the
+    /// shell and the validators' addresses.
+    fn setup_validators() -> (TestShell, Vec<Address>) {
+        let (mut shell, _) = setup();
+        let validators: Vec<Address> = [
+            address::testing::established_address_1(),
+            address::testing::established_address_2(),
+            address::testing::established_address_3(),
+            address::testing::established_address_4(),
+        ]
+        .into();
+        let active = validators
+            .iter()
+            .map(|address| WeightedValidator {
+                bonded_stake: 100,
+                address: address.clone(),
+            })
+            .collect();
+        let validator_set = Epoched::init_at_genesis(
+            ValidatorSet {
+                active,
+                inactive: BTreeSet::new(),
+            },
+            Epoch(0),
+        );
+        shell.storage.write_validator_set(&validator_set);
+        (shell, validators)
+    }
+
+    /// A digest of the vote extensions of the given validators, all voting for
+    /// the given events. The signatures are not checked in finalize block.
+    fn digest(validators: &[Address], events: &[EthereumEvent]) -> VextDigest {
+        let keypair = gen_keypair();
+        VextDigest {
+            vexts: validators
+                .iter()
+                .map(|validator| {
+                    Vext {
+                        ethereum_events: events.to_vec(),
+                        ..Vext::empty(BlockHeight(1), validator.clone())
+                    }
+                    .sign(&keypair)
+                })
+                .collect(),
+        }
+    }
+
+    fn transfers_to_namada(
+        nonce: u64,
+        asset: EthAddress,
+        receiver: &Address,
+    ) -> EthereumEvent {
+        EthereumEvent::TransfersToNamada {
+            nonce,
+            transfers: vec![TransferToNamada {
+                amount: 100.into(),
+                asset,
+                receiver: receiver.clone(),
+            }],
+        }
+    }
+
+    /// Test that the events are applied only once more than 2/3 of the stake
+    /// has voted for them, possibly across several blocks.
+    #[test]
+    fn test_eth_events_voting_power() {
+        let (mut shell, validators) = setup_validators();
+        let receiver = address::testing::established_address_1();
+        let event = transfers_to_namada(0, DAI_ERC20_ETH_ADDRESS, &receiver);
+        let balance_key = bridge_storage::wrapped_erc20_balance_key(
+            &DAI_ERC20_ETH_ADDRESS,
+            &receiver,
+        );
+
+        // Half of the stake is not enough
+        let attested = shell.apply_eth_events_digest(&digest(
+            &validators[..2],
+            &[event.clone()],
+        ));
+        assert!(attested.is_empty());
+        assert_eq!(shell.read_value::<token::Amount>(&balance_key), None);
+
+        // A vote from a validator who has already voted doesn't count twice
+        let attested = shell.apply_eth_events_digest(&digest(
+            &validators[1..2],
+            &[event.clone()],
+        ));
+        assert!(attested.is_empty());
+
+        // The third validator's vote is added to the votes from the last
+        // block
+        let attested = shell.apply_eth_events_digest(&digest(
+            &validators[2..3],
+            &[event.clone()],
+        ));
+        assert_eq!(attested, vec![event.clone()]);
+        assert_eq!(
+            shell.read_value::<token::Amount>(&balance_key),
+            Some(100.into())
+        );
+        assert_eq!(
+            shell.read_value::<u64>(
+                &bridge_storage::transfers_to_namada_nonce_key()
+            ),
+            Some(1)
+        );
+
+        // The tally is removed once the event is attested
+        let tally_key = bridge_storage::event_tally_key(&event.hash());
+        assert!(shell.read_value::<EventTally>(&tally_key).is_none());
+
+        // Late votes don't apply the event again
+        let attested =
+            shell.apply_eth_events_digest(&digest(&validators[3..], &[event]));
+        assert!(attested.is_empty());
+        assert_eq!(
+            shell.read_value::<token::Amount>(&balance_key),
+            Some(100.into())
+        );
+    }
+
+    /// Test that the tallies of the events that haven't been attested are
+    /// removed once they expire, including those of the late votes on the
+    /// events that have already been attested.
+    #[test]
+    fn test_eth_events_tally_expiry() {
+        let (mut shell, validators) = setup_validators();
+        let receiver = address::testing::established_address_1();
+        let pending = transfers_to_namada(0, DAI_ERC20_ETH_ADDRESS, &receiver);
+        let attested = transfers_to_namada(1, DAI_ERC20_ETH_ADDRESS, &receiver);
+        let pending_key = bridge_storage::event_tally_key(&pending.hash());
+        let attested_key = bridge_storage::event_tally_key(&attested.hash());
+
+        shell.apply_eth_events_digest(&digest(
+            &validators[..2],
+            &[pending.clone()],
+        ));
+        shell
+            .apply_eth_events_digest(&digest(&validators, &[attested.clone()]));
+        shell.apply_eth_events_digest(&digest(&validators[..1], &[attested]));
+        shell
+            .write_log
+            .commit_block(&mut shell.storage)
+            .expect("Test failed");
+
+        // The tallies are kept until they expire
+        shell.storage.block.epoch = Epoch(EVENT_TALLY_EXPIRY_EPOCHS - 1);
+        shell.apply_eth_events_digest(&digest(&[], &[]));
+        assert!(shell.read_value::<EventTally>(&pending_key).is_some());
+        assert!(shell.read_value::<EventTally>(&attested_key).is_some());
+
+        shell.storage.block.epoch = Epoch(EVENT_TALLY_EXPIRY_EPOCHS);
+        shell.apply_eth_events_digest(&digest(&[], &[]));
+        assert!(shell.read_value::<EventTally>(&pending_key).is_none());
+        assert!(shell.read_value::<EventTally>(&attested_key).is_none());
+
+        // A later vote on the expired event starts a new tally
+        shell.apply_eth_events_digest(&digest(&validators[2..3], &[pending]));
+        let tally: EventTally =
+            shell.read_value(&pending_key).expect("Test failed");
+        assert_eq!(tally.seen_by, BTreeSet::from([validators[2].clone()]));
+        assert_eq!(tally.first_seen, Epoch(EVENT_TALLY_EXPIRY_EPOCHS));
+    }
+
+    /// Test that the attested transfers are applied in the order of their
+    /// nonces and that the native tokens are released from the escrow.
+    #[test]
+    fn test_transfers_to_namada_in_order() {
+        let (mut shell, validators) = setup_validators();
+        let native_token = shell.storage.native_token.clone();
+        let receiver = address::testing::established_address_1();
+        let escrow = eth_bridge::vp::ADDRESS;
+        shell.write_value(
+            &bridge_storage::native_erc20_key(),
+            &WNAM_ERC20_ETH_ADDRESS,
+        );
+        shell.write_balance(&native_token, &escrow, 1_000.into());
+        let receiver_balance = shell.read_balance(&native_token, &receiver);
+
+        // The transfers with nonce 1 wait for the ones with nonce 0
+        let second = transfers_to_namada(1, WNAM_ERC20_ETH_ADDRESS, &receiver);
+        let attested = shell
+            .apply_eth_events_digest(&digest(&validators, &[second.clone()]));
+        assert_eq!(attested, vec![second]);
+        assert_eq!(shell.read_balance(&native_token, &escrow), 1_000.into());
+
+        let first = transfers_to_namada(0, WNAM_ERC20_ETH_ADDRESS, &receiver);
+        shell.apply_eth_events_digest(&digest(&validators, &[first]));
+        assert_eq!(shell.read_balance(&native_token, &escrow), 800.into());
+        assert_eq!(
+            shell.read_balance(&native_token, &receiver),
+            receiver_balance + 200.into()
+        );
+        assert_eq!(
+            shell.read_value::<u64>(
+                &bridge_storage::transfers_to_namada_nonce_key()
+            ),
+            Some(2)
+        );
+
+        // A different event with an already applied nonce is ignored
+        let conflicting =
+            transfers_to_namada(0, DAI_ERC20_ETH_ADDRESS, &receiver);
+        let attested =
+            shell.apply_eth_events_digest(&digest(&validators, &[conflicting]));
+        assert!(attested.is_empty());
+    }
+}
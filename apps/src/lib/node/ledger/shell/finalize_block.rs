@@ -1,12 +1,17 @@
 //! Implementation of the `FinalizeBlock` ABCI++ method for the Shell
 
 use namada::ledger::pos::types::into_tm_voting_power;
-use namada::ledger::protocol;
+use namada::ledger::storage::types::encode;
+use namada::ledger::storage::write_log::StorageModification;
+use namada::ledger::{inflation, parameters, protocol};
 use namada::types::storage::{BlockHash, BlockResults, Header};
+use namada::types::transaction::protocol::{ProtocolTx, ProtocolTxType};
 
-use super::governance::execute_governance_proposals;
+use super::governance::{execute_governance_proposals, execute_pgf_payments};
 use super::*;
-use crate::facade::tendermint_proto::abci::Misbehavior as Evidence;
+use crate::facade::tendermint_proto::abci::{
+    Misbehavior as Evidence, VoteInfo,
+};
 use crate::facade::tendermint_proto::crypto::PublicKey as TendermintPublicKey;
 
 impl<D, H> Shell<D, H>
@@ -45,10 +50,13 @@ where
         // begin the next block and check if a new epoch began
         let (height, new_epoch) =
             self.update_state(req.header, req.hash, req.byzantine_validators);
+        let proposer = self.read_block_proposer(&req.proposer_address);
+        self.apply_block_rewards(new_epoch, proposer.as_ref(), req.votes);
 
         if new_epoch {
             let _proposals_result =
                 execute_governance_proposals(self, &mut response)?;
+            execute_pgf_payments(self);
         }
 
         // Tracks the accepted transactions
@@ -120,26 +128,61 @@ where
                 tx_event["gas_used"] = "0".into();
                 response.events.push(tx_event);
                 // if the rejected tx was decrypted, remove it
-                // from the queue of txs to be processed
+                // from the queue of txs to be processed and settle its fee
                 if let TxType::Decrypted(_) = &tx_type {
-                    self.storage.tx_queue.pop();
+                    if let Some(wrapper) = self.storage.tx_queue.pop() {
+                        self.forget_decryption_shares(&wrapper);
+                        self.settle_wrapper_fee(&wrapper, 0, proposer.as_ref());
+                    }
                 }
                 continue;
             }
 
+            // The wrapper of a decrypted tx, whose fee is settled once the
+            // decrypted tx is applied
+            let mut wrapper = None;
+            // Whether the whole fee of the wrapper is charged, regardless of
+            // the gas used by its decrypted tx
+            let mut charge_full_fee = false;
             let mut tx_event = match &tx_type {
-                TxType::Wrapper(_wrapper) => {
-                    self.storage.tx_queue.push(_wrapper.clone());
-                    Event::new_tx_event(&tx_type, height.0)
+                TxType::Wrapper(wrapper) => {
+                    let mut tx_event = Event::new_tx_event(&tx_type, height.0);
+                    // The fee is debited from the fee payer when the wrapper
+                    // is accepted and held with it in the tx queue
+                    if !self.charge_wrapper_fee(wrapper) {
+                        tx_event["code"] = ErrorCodes::InvalidTx.into();
+                        tx_event["info"] = "The fee payer doesn't have \
+                                            sufficient balance to pay the fee"
+                            .into();
+                        tx_event["gas_used"] = "0".into();
+                        response.events.push(tx_event);
+                        continue;
+                    }
+                    self.enqueue_wrapper(wrapper.clone());
+                    tx_event
                 }
                 TxType::Decrypted(inner) => {
                     // We remove the corresponding wrapper tx from the queue
-                    self.storage.tx_queue.pop();
+                    // and enforce its gas limit on the decrypted tx
+                    wrapper = self.storage.tx_queue.pop();
+                    if let Some(wrapper) = &wrapper {
+                        self.forget_decryption_shares(wrapper);
+                    }
+                    if let (Some(wrapper), DecryptedTx::Decrypted(_)) =
+                        (&wrapper, inner)
+                    {
+                        self.gas_meter.set_transaction_gas_limit(u64::from(
+                            &wrapper.gas_limit,
+                        ));
+                    }
                     let mut event = Event::new_tx_event(&tx_type, height.0);
                     if let DecryptedTx::Undecryptable(_) = inner {
                         event["log"] =
                             "Transaction could not be decrypted.".into();
                         event["code"] = ErrorCodes::Undecryptable.into();
+                        // The gas limit of an undecryptable tx cannot be
+                        // checked, so none of its gas is refunded
+                        charge_full_fee = true;
                     }
                     event
                 }
@@ -150,10 +193,70 @@ where
                     );
                     continue;
                 }
+                TxType::Protocol(ProtocolTx {
+                    tx: ProtocolTxType::EthereumEvents(digest),
+                    ..
+                }) => {
+                    let mut tx_event = Event::new_tx_event(&tx_type, height.0);
+                    let attested = self.apply_eth_events_digest(digest);
+                    tracing::info!(
+                        "Applied the votes on the Ethereum events, attested \
+                         events: {:?}",
+                        attested
+                    );
+                    tx_event["code"] = ErrorCodes::Ok.into();
+                    tx_event["info"] =
+                        format!("Attested Ethereum events: {}", attested.len());
+                    tx_event["gas_used"] = "0".into();
+                    self.storage.block.results.accept(tx_index);
+                    response.events.push(tx_event);
+                    continue;
+                }
+                TxType::Protocol(ProtocolTx {
+                    pk,
+                    tx: ProtocolTxType::DKG(msg),
+                }) => {
+                    let mut tx_event = Event::new_tx_event(&tx_type, height.0);
+                    match self.apply_dkg_message(pk, msg.clone()) {
+                        Ok(()) => {
+                            tx_event["code"] = ErrorCodes::Ok.into();
+                            tx_event["info"] = "Applied the DKG message".into();
+                            self.storage.block.results.accept(tx_index);
+                        }
+                        Err(err) => {
+                            tx_event["code"] = ErrorCodes::InvalidTx.into();
+                            tx_event["info"] = err;
+                        }
+                    }
+                    tx_event["gas_used"] = "0".into();
+                    response.events.push(tx_event);
+                    continue;
+                }
+                TxType::Protocol(ProtocolTx {
+                    pk,
+                    tx: ProtocolTxType::DecryptionShares(shares),
+                }) => {
+                    let mut tx_event = Event::new_tx_event(&tx_type, height.0);
+                    match self.apply_decryption_shares(pk, shares.clone()) {
+                        Ok(()) => {
+                            tx_event["code"] = ErrorCodes::Ok.into();
+                            tx_event["info"] =
+                                "Applied the decryption shares".into();
+                            self.storage.block.results.accept(tx_index);
+                        }
+                        Err(err) => {
+                            tx_event["code"] = ErrorCodes::InvalidTx.into();
+                            tx_event["info"] = err;
+                        }
+                    }
+                    tx_event["gas_used"] = "0".into();
+                    response.events.push(tx_event);
+                    continue;
+                }
                 TxType::Protocol(_) => {
                     tracing::error!(
-                        "Internal logic error: FinalizeBlock received a \
-                         TxType::Protocol transaction"
+                        "Internal logic error: FinalizeBlock received an \
+                         unsupported TxType::Protocol transaction"
                     );
                     continue;
                 }
@@ -220,6 +323,18 @@ where
                     }
                     tx_event["gas_used"] = result.gas_used.to_string();
                     tx_event["info"] = result.to_string();
+                    if let Some(wrapper) = &wrapper {
+                        let used_gas = if charge_full_fee {
+                            u64::from(&wrapper.gas_limit)
+                        } else {
+                            result.gas_used
+                        };
+                        self.settle_wrapper_fee(
+                            wrapper,
+                            used_gas,
+                            proposer.as_ref(),
+                        );
+                    }
                 }
                 Err(msg) => {
                     tracing::info!(
@@ -228,12 +343,33 @@ where
                         msg
                     );
                     self.write_log.drop_tx();
-                    tx_event["gas_used"] = self
-                        .gas_meter
-                        .get_current_transaction_gas()
-                        .to_string();
-                    tx_event["info"] = msg.to_string();
+                    let gas_used = self.gas_meter.get_current_transaction_gas();
+                    // Move the gas of the failed tx into the block's gas, so
+                    // that it doesn't count towards the next tx. Like for an
+                    // applied tx, exceeding the block's gas is an error.
+                    let msg = match self.gas_meter.finalize_transaction() {
+                        Ok(_) => msg.to_string(),
+                        Err(gas_err) => format!(
+                            "{}, {}",
+                            msg,
+                            Error::TxApply(protocol::Error::GasError(gas_err))
+                        ),
+                    };
+                    tx_event["gas_used"] = gas_used.to_string();
+                    tx_event["info"] = msg;
                     tx_event["code"] = ErrorCodes::WasmRuntimeError.into();
+                    if let Some(wrapper) = &wrapper {
+                        let used_gas = if charge_full_fee {
+                            u64::from(&wrapper.gas_limit)
+                        } else {
+                            gas_used
+                        };
+                        self.settle_wrapper_fee(
+                            wrapper,
+                            used_gas,
+                            proposer.as_ref(),
+                        );
+                    }
                 }
             }
             response.events.push(tx_event);
@@ -241,6 +377,8 @@ where
 
         if new_epoch {
             self.update_epoch(&mut response);
+            let (current_epoch, _) = self.storage.get_current_epoch();
+            self.start_dkg_session(current_epoch.next());
         }
 
         let _ = self
@@ -255,7 +393,8 @@ where
 
     /// Sets the metadata necessary for a new block, including
     /// the hash, height, validator changes, and evidence of
-    /// byzantine behavior. Applies slashes if necessary.
+    /// byzantine behavior. Queues slashes for the evidence and applies the
+    /// queued slashes on a new epoch.
     /// Returns a bool indicating if a new epoch began and
     /// the height of the new block.
     fn update_state(
@@ -281,13 +420,233 @@ where
 
         let new_epoch = self
             .storage
-            .update_epoch(height, header_time)
+            .update_epoch(height, header_time, |storage| {
+                if let Err(err) = inflation::update_pos_inflation(storage) {
+                    tracing::error!("Failed to update PoS inflation: {}", err);
+                }
+            })
             .expect("Must be able to update epoch");
 
         self.slash();
+        if new_epoch {
+            self.process_slashes();
+        }
         (height, new_epoch)
     }
 
+    /// Accumulate the block rewards of the proposer and the signers of the
+    /// last committed block and record the blocks missed by the validators
+    /// that didn't sign it, jailing those who missed too many. If a new epoch
+    /// began, the rewards accumulated in the last epoch are distributed using
+    /// the PoS inflation updated on the epoch transition. Lastly, the proposer
+    /// of the current block is recorded, so that it can be rewarded in the
+    /// next block.
+    fn apply_block_rewards(
+        &mut self,
+        new_epoch: bool,
+        proposer: Option<&Address>,
+        votes: Vec<VoteInfo>,
+    ) {
+        let (current_epoch, _gas) = self.storage.get_current_epoch();
+        let last_block_epoch = if new_epoch {
+            current_epoch.prev()
+        } else {
+            current_epoch
+        };
+
+        if let Some(last_proposer) = self.storage.read_last_block_proposer() {
+            let mut signers: HashSet<Address> = HashSet::new();
+            let mut non_signers: HashSet<Address> = HashSet::new();
+            for vote in votes {
+                let validator = match vote.validator {
+                    Some(validator) => validator,
+                    None => continue,
+                };
+                let raw_hash = tm_raw_hash_to_string(validator.address);
+                match self.storage.read_validator_address_raw_hash(&raw_hash) {
+                    Some(address) if vote.signed_last_block => {
+                        signers.insert(address);
+                    }
+                    Some(address) => {
+                        non_signers.insert(address);
+                    }
+                    None => tracing::error!(
+                        "Cannot find validator's address from raw hash {}",
+                        raw_hash
+                    ),
+                }
+            }
+            if let Err(err) = self.storage.log_block_rewards(
+                last_block_epoch,
+                &last_proposer,
+                &signers,
+            ) {
+                tracing::error!("Error in logging block rewards: {}", err);
+            }
+
+            // Jail the validators that have missed too many blocks
+            let last_height = self.storage.last_height.0;
+            match self.storage.record_missed_blocks(
+                current_epoch,
+                last_height,
+                &non_signers,
+            ) {
+                Ok(jailed) => {
+                    for validator in jailed {
+                        tracing::info!(
+                            "Jailed validator {} for missing too many blocks \
+                             at height {}",
+                            validator,
+                            last_height
+                        );
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("Error in recording missed blocks: {}", err)
+                }
+            }
+        }
+
+        if new_epoch {
+            // The PoS inflation for the new epoch has been updated in
+            // `Storage::update_epoch`
+            let inflation = match parameters::read(&self.storage) {
+                Ok((params, _gas)) => {
+                    token::Amount::from(params.pos_inflation_amount)
+                }
+                Err(err) => {
+                    tracing::error!("Failed to read PoS inflation: {}", err);
+                    token::Amount::default()
+                }
+            };
+            match self.storage.distribute_rewards(
+                current_epoch,
+                last_block_epoch,
+                inflation,
+            ) {
+                Ok(distributed) => tracing::info!(
+                    "Distributed {} of PoS rewards for epoch {}",
+                    distributed,
+                    last_block_epoch
+                ),
+                Err(err) => {
+                    tracing::error!("Error in distributing rewards: {}", err)
+                }
+            }
+        }
+
+        if let Some(proposer) = proposer {
+            self.storage.write_last_block_proposer(proposer);
+        }
+    }
+
+    /// Find the address of the block proposer from the raw hash of its
+    /// consensus key.
+    fn read_block_proposer(&self, proposer_address: &[u8]) -> Option<Address> {
+        let raw_hash = tm_raw_hash_to_string(proposer_address);
+        let proposer = self.storage.read_validator_address_raw_hash(&raw_hash);
+        if proposer.is_none() && !proposer_address.is_empty() {
+            tracing::error!(
+                "Cannot find the block proposer's address from raw hash {}",
+                raw_hash
+            );
+        }
+        proposer
+    }
+
+    /// Debit the fee of an accepted wrapper tx from its fee payer. Returns
+    /// `false` if the fee payer doesn't have sufficient balance, which may
+    /// happen when it's been spent by previous txs in the block.
+    fn charge_wrapper_fee(&mut self, wrapper: &WrapperTx) -> bool {
+        let fee_payer = wrapper_fee_payer(wrapper);
+        let balance = self.read_balance(&wrapper.fee.token, &fee_payer);
+        if balance < wrapper.fee.amount {
+            return false;
+        }
+        self.write_balance(
+            &wrapper.fee.token,
+            &fee_payer,
+            balance - wrapper.fee.amount,
+        );
+        self.write_log.commit_tx();
+        true
+    }
+
+    /// Settle the fee of a wrapper tx whose decrypted tx used the given
+    /// amount of gas. The part of the fee paying for the unused gas (see
+    /// [`namada::types::transaction::GasLimit::refund_amount`]) is refunded to
+    /// the fee payer and the rest goes to the block proposer.
+    fn settle_wrapper_fee(
+        &mut self,
+        wrapper: &WrapperTx,
+        used_gas: u64,
+        proposer: Option<&Address>,
+    ) {
+        let fee_payer = wrapper_fee_payer(wrapper);
+        let token = &wrapper.fee.token;
+        let gas_limit = u64::from(&wrapper.gas_limit);
+        let refund = if gas_limit == 0 {
+            token::Amount::default()
+        } else {
+            let refund_gas =
+                u64::from(wrapper.gas_limit.refund_amount(used_gas));
+            let fee = u128::from(u64::from(wrapper.fee.amount));
+            // The refunded gas cannot exceed the gas limit, so the refund is
+            // bounded by the fee
+            let refund = fee * u128::from(refund_gas) / u128::from(gas_limit);
+            token::Amount::from(refund as u64)
+        };
+        let reward = wrapper.fee.amount - refund;
+        let fee_receiver = match proposer {
+            Some(proposer) => proposer,
+            None => {
+                tracing::error!(
+                    "Unknown block proposer, refunding the whole fee of the \
+                     wrapper tx {}",
+                    wrapper.tx_hash
+                );
+                &fee_payer
+            }
+        };
+        for (owner, amount) in [(&fee_payer, refund), (fee_receiver, reward)] {
+            if amount != token::Amount::default() {
+                let balance = self.read_balance(token, owner);
+                self.write_balance(token, owner, balance + amount);
+            }
+        }
+        self.write_log.commit_tx();
+    }
+
+    /// Read a token balance, including the changes made by the txs applied
+    /// earlier in the block.
+    pub(super) fn read_balance(
+        &self,
+        token: &Address,
+        owner: &Address,
+    ) -> token::Amount {
+        let key = token::balance_key(token, owner);
+        match self.write_log.read(&key).0 {
+            Some(StorageModification::Write { value }) => {
+                token::Amount::try_from_slice(value).unwrap_or_default()
+            }
+            Some(StorageModification::Delete) => token::Amount::default(),
+            _ => self.get_balance(token, owner),
+        }
+    }
+
+    /// Write a token balance into the write log.
+    pub(super) fn write_balance(
+        &mut self,
+        token: &Address,
+        owner: &Address,
+        balance: token::Amount,
+    ) {
+        let key = token::balance_key(token, owner);
+        self.write_log
+            .write(&key, encode(&balance))
+            .expect("Writing a balance into the write log shouldn't fail");
+    }
+
     /// If a new epoch begins, we update the response to include
     /// changes to the validator sets and consensus parameters
     fn update_epoch(&self, response: &mut shim::response::FinalizeBlock) {
@@ -330,7 +689,21 @@ where
 /// are covered by the e2e tests.
 #[cfg(test)]
 mod test_finalize_block {
+    use std::collections::{BTreeMap, HashMap};
+
+    use data_encoding::HEXUPPER;
+    use namada::ledger::pos::namada_proof_of_stake::epoched::{
+        DynEpochOffset, EpochedDelta,
+    };
+    use namada::ledger::pos::namada_proof_of_stake::types::{
+        cubic_slash_rate, Bond, BondId, MissedBlocks, Redelegation,
+        RewardsAccumulator, Slash, SlashType, ValidatorState,
+    };
+    use namada::types::address::testing::{
+        established_address_1, established_address_2,
+    };
     use namada::types::storage::Epoch;
+    use namada::types::token;
     use namada::types::transaction::{EncryptionKey, Fee};
 
     use super::*;
@@ -339,6 +712,44 @@ mod test_finalize_block {
         FinalizeBlock, ProcessedTx,
     };
 
+    /// Credit the fee payer of the given keypair with native tokens
+    fn fund_fee_payer(
+        shell: &mut TestShell,
+        keypair: &common::SecretKey,
+        amount: token::Amount,
+    ) {
+        let balance_key = token::balance_key(
+            &shell.storage.native_token,
+            &Address::from(&keypair.ref_to()),
+        );
+        shell
+            .storage
+            .write(&balance_key, encode(&amount))
+            .expect("Test failed");
+    }
+
+    /// Get the address of a genesis validator and the raw hash of its
+    /// consensus key, to be used as the block proposer
+    fn genesis_proposer(shell: &TestShell) -> (Address, Vec<u8>) {
+        let validator = shell
+            .storage
+            .read_validator_set()
+            .get(Epoch(0))
+            .and_then(|validator_set| validator_set.active.iter().next())
+            .expect("Test failed")
+            .address
+            .clone();
+        let consensus_key = shell
+            .storage
+            .read_validator_consensus_key(&validator)
+            .and_then(|keys| keys.get(Epoch(0)).cloned())
+            .expect("Test failed");
+        let raw_hash = HEXUPPER
+            .decode(tm_consensus_key_raw_hash(&consensus_key).as_bytes())
+            .expect("Test failed");
+        (validator, raw_hash)
+    }
+
     /// Check that if a wrapper tx was rejected by [`process_proposal`],
     /// check that the correct event is returned. Check that it does
     /// not appear in the queue of txs to be decrypted
@@ -346,6 +757,7 @@ mod test_finalize_block {
     fn test_process_proposal_rejected_wrapper_tx() {
         let (mut shell, _) = setup();
         let keypair = gen_keypair();
+        fund_fee_payer(&mut shell, &keypair, 1_000.into());
         let mut processed_txs = vec![];
         let mut valid_wrappers = vec![];
         // create some wrapper txs
@@ -550,7 +962,7 @@ mod test_finalize_block {
                 },
                 &keypair,
                 Epoch(0),
-                0.into(),
+                1_000_000.into(),
                 raw_tx.clone(),
                 Default::default(),
             );
@@ -642,4 +1054,589 @@ mod test_finalize_block {
         }
         assert_eq!(counter, 2);
     }
+
+    /// Test that the fee of a wrapper tx is debited when the wrapper is
+    /// accepted and that once its decrypted tx is applied, the fee for the
+    /// unused gas is refunded and the rest goes to the block proposer.
+    #[test]
+    fn test_wrapper_fee_settlement() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let fee_payer = Address::from(&keypair.ref_to());
+        let native_token = shell.storage.native_token.clone();
+        fund_fee_payer(&mut shell, &keypair, 1_000.into());
+        let (proposer, proposer_address) = genesis_proposer(&shell);
+        let proposer_balance = shell.read_balance(&native_token, &proposer);
+
+        let mut wasm_path = top_level_directory();
+        wasm_path.push("wasm_for_tests/tx_no_op.wasm");
+        let tx_code = std::fs::read(wasm_path)
+            .expect("Expected a file at given code path");
+        let raw_tx = Tx::new(
+            tx_code,
+            Some(String::from("transaction data").as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 100.into(),
+                token: native_token.clone(),
+            },
+            &keypair,
+            Epoch(0),
+            1_000_000.into(),
+            raw_tx.clone(),
+            Default::default(),
+        );
+        let wrapper_tx = wrapper.sign(&keypair).expect("Test failed");
+
+        // the fee is debited when the wrapper is accepted
+        shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![ProcessedTx {
+                    tx: wrapper_tx.to_bytes(),
+                    result: TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "".into(),
+                    },
+                }],
+                proposer_address: proposer_address.clone(),
+                ..Default::default()
+            })
+            .expect("Test failed");
+        assert_eq!(
+            shell.read_balance(&native_token, &fee_payer),
+            token::Amount::from(900)
+        );
+        assert_eq!(
+            shell.read_balance(&native_token, &proposer),
+            proposer_balance
+        );
+
+        // the fee is settled when the decrypted tx is applied
+        let event = shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![ProcessedTx {
+                    tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(
+                        raw_tx,
+                    )))
+                    .to_bytes(),
+                    result: TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "".into(),
+                    },
+                }],
+                proposer_address,
+                ..Default::default()
+            })
+            .expect("Test failed")
+            .remove(0);
+        let code = event.attributes.get("code").expect("Test failed");
+        assert_eq!(code, &String::from(ErrorCodes::Ok));
+        let refund = shell.read_balance(&native_token, &fee_payer)
+            - token::Amount::from(900);
+        let reward =
+            shell.read_balance(&native_token, &proposer) - proposer_balance;
+        assert!(refund > token::Amount::from(0));
+        assert!(reward > token::Amount::from(0));
+        assert_eq!(refund + reward, token::Amount::from(100));
+    }
+
+    /// Test that a decrypted tx that exceeds the gas limit declared in its
+    /// wrapper is aborted and that no gas is refunded.
+    #[test]
+    fn test_wrapper_gas_limit_exceeded() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let fee_payer = Address::from(&keypair.ref_to());
+        let native_token = shell.storage.native_token.clone();
+        let (proposer, proposer_address) = genesis_proposer(&shell);
+        let proposer_balance = shell.read_balance(&native_token, &proposer);
+
+        let mut wasm_path = top_level_directory();
+        wasm_path.push("wasm_for_tests/tx_no_op.wasm");
+        let tx_code = std::fs::read(wasm_path)
+            .expect("Expected a file at given code path");
+        let raw_tx = Tx::new(
+            tx_code,
+            Some(String::from("transaction data").as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 100.into(),
+                token: native_token.clone(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            raw_tx.clone(),
+            Default::default(),
+        );
+        // the fee has been debited when the wrapper was accepted
+        shell.enqueue_tx(wrapper);
+
+        let event = shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![ProcessedTx {
+                    tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(
+                        raw_tx,
+                    )))
+                    .to_bytes(),
+                    result: TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "".into(),
+                    },
+                }],
+                proposer_address,
+                ..Default::default()
+            })
+            .expect("Test failed")
+            .remove(0);
+        let code = event.attributes.get("code").expect("Test failed");
+        assert_eq!(code, &String::from(ErrorCodes::WasmRuntimeError));
+        assert_eq!(
+            shell.read_balance(&native_token, &fee_payer),
+            token::Amount::from(0)
+        );
+        assert_eq!(
+            shell.read_balance(&native_token, &proposer),
+            proposer_balance + token::Amount::from(100)
+        );
+    }
+
+    /// Test that the whole fee of the wrapper of an undecryptable tx goes to
+    /// the block proposer, as its unused gas is not refunded.
+    #[test]
+    fn test_undecryptable_wrapper_fee_charged() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let fee_payer = Address::from(&keypair.ref_to());
+        let native_token = shell.storage.native_token.clone();
+        let (proposer, proposer_address) = genesis_proposer(&shell);
+        let proposer_balance = shell.read_balance(&native_token, &proposer);
+
+        // not valid tx bytes
+        let tx = "garbage data".as_bytes().to_owned();
+        let inner_tx =
+            namada::types::transaction::encrypted::EncryptedTx::encrypt(
+                &tx,
+                EncryptionKey::default(),
+            );
+        let wrapper = WrapperTx {
+            fee: Fee {
+                amount: 100.into(),
+                token: native_token.clone(),
+            },
+            pk: keypair.ref_to(),
+            epoch: Epoch(0),
+            gas_limit: 1_000_000.into(),
+            inner_tx,
+            tx_hash: hash_tx(&tx),
+        };
+        // the fee has been debited when the wrapper was accepted
+        shell.enqueue_tx(wrapper.clone());
+
+        let event = shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![ProcessedTx {
+                    tx: Tx::from(TxType::Decrypted(
+                        DecryptedTx::Undecryptable(wrapper),
+                    ))
+                    .to_bytes(),
+                    result: TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "".into(),
+                    },
+                }],
+                proposer_address,
+                ..Default::default()
+            })
+            .expect("Test failed")
+            .remove(0);
+        let code = event.attributes.get("code").expect("Test failed");
+        assert_eq!(code, &String::from(ErrorCodes::Undecryptable));
+        assert_eq!(
+            shell.read_balance(&native_token, &fee_payer),
+            token::Amount::from(0)
+        );
+        assert_eq!(
+            shell.read_balance(&native_token, &proposer),
+            proposer_balance + token::Amount::from(100)
+        );
+    }
+
+    /// Accumulate the rewards of a single block for the given validator
+    fn accumulate_rewards(shell: &mut TestShell, validator: &Address) {
+        shell
+            .storage
+            .write_rewards_accumulator(&RewardsAccumulator {
+                num_blocks: 1,
+                validators: BTreeMap::from([(
+                    validator.clone(),
+                    rust_decimal::Decimal::ONE,
+                )]),
+            });
+    }
+
+    /// Get the sum of the validator's bonds and its stake at the given epoch
+    fn bonded_and_stake(
+        shell: &TestShell,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> (token::Amount, token::Change) {
+        let bonded = shell
+            .storage
+            .read_bonds()
+            .into_iter()
+            .filter(|(bond_id, _)| &bond_id.validator == validator)
+            .filter_map(|(_, bonds)| bonds.get(epoch).map(|bond| bond.sum()))
+            .fold(token::Amount::default(), |acc, amount| acc + amount);
+        let stake = shell
+            .storage
+            .read_validator_deltas(validator)
+            .and_then(|deltas| deltas.get(epoch))
+            .unwrap_or_default();
+        (bonded, stake)
+    }
+
+    /// Test that the rewards are credited to the validator's bonds at the
+    /// pipeline offset, minted to the PoS account and that the accumulator
+    /// is reset.
+    #[test]
+    fn test_distribute_rewards() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+        let (validator, _) = genesis_proposer(&shell);
+        let native_token = shell.storage.native_token.clone();
+        let pipeline_epoch = Epoch(params.pipeline_len);
+        let pos_balance = shell.read_balance(&native_token, &pos::ADDRESS);
+        let (bonded_pre, stake_pre) =
+            bonded_and_stake(&shell, &validator, pipeline_epoch);
+        accumulate_rewards(&mut shell, &validator);
+
+        let rewards = token::Amount::from(1_000);
+        let distributed = shell
+            .storage
+            .distribute_rewards(Epoch(0), Epoch(0), rewards)
+            .expect("Test failed");
+        assert!(distributed > token::Amount::default());
+        assert!(distributed <= rewards);
+
+        let (bonded_post, stake_post) =
+            bonded_and_stake(&shell, &validator, pipeline_epoch);
+        assert_eq!(bonded_post, bonded_pre + distributed);
+        assert_eq!(stake_post, stake_pre + token::Change::from(distributed));
+        assert_eq!(
+            shell.read_balance(&native_token, &pos::ADDRESS),
+            pos_balance + distributed
+        );
+        let accumulator = shell.storage.read_rewards_accumulator();
+        assert_eq!(accumulator.num_blocks, 0);
+        assert!(accumulator.validators.is_empty());
+    }
+
+    /// Test that a validator that is being jailed doesn't receive any
+    /// rewards.
+    #[test]
+    fn test_distribute_rewards_skips_jailed_validator() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+        let (validator, _) = genesis_proposer(&shell);
+        let pipeline_epoch = Epoch(params.pipeline_len);
+        let mut state = shell
+            .storage
+            .read_validator_state(&validator)
+            .expect("Test failed");
+        state.set(ValidatorState::Jailed, Epoch(0), &params);
+        shell.storage.write_validator_state(&validator, &state);
+        let bonded_pre = bonded_and_stake(&shell, &validator, pipeline_epoch);
+        accumulate_rewards(&mut shell, &validator);
+
+        let distributed = shell
+            .storage
+            .distribute_rewards(Epoch(0), Epoch(0), token::Amount::from(1_000))
+            .expect("Test failed");
+        assert_eq!(distributed, token::Amount::default());
+        assert_eq!(
+            bonded_and_stake(&shell, &validator, pipeline_epoch),
+            bonded_pre
+        );
+        assert_eq!(shell.storage.read_rewards_accumulator().num_blocks, 0);
+    }
+
+    /// Test that a validator that misses more blocks in the liveness window
+    /// than allowed gets slashed and removed from the validator set at the
+    /// pipeline offset, and that it's skipped while being jailed.
+    #[test]
+    fn test_jail_validator_for_missed_blocks() {
+        let (mut shell, _) = setup();
+        let mut params = shell.storage.read_pos_params();
+        params.liveness_window_len = 4;
+        params.liveness_max_missed_rate = rust_decimal_macros::dec!(0.5);
+        shell.storage.write_pos_params(&params);
+        let (validator, _) = genesis_proposer(&shell);
+        let non_signers = HashSet::from([validator.clone()]);
+        let epoch = Epoch(0);
+        let pipeline_epoch = epoch + params.pipeline_len;
+
+        // Missing the max allowed number of blocks is tolerated
+        for height in 1..=2 {
+            let jailed = shell
+                .storage
+                .record_missed_blocks(epoch, height, &non_signers)
+                .expect("Test failed");
+            assert!(jailed.is_empty());
+        }
+        // Blocks that fall out of the window are forgotten
+        let jailed = shell
+            .storage
+            .record_missed_blocks(epoch, 6, &non_signers)
+            .expect("Test failed");
+        assert!(jailed.is_empty());
+        assert_eq!(
+            shell.storage.read_validator_missed_blocks(&validator),
+            Some(MissedBlocks {
+                window_start: 6,
+                count: 1
+            })
+        );
+
+        for height in 7..=8 {
+            shell
+                .storage
+                .record_missed_blocks(epoch, height, &non_signers)
+                .expect("Test failed");
+        }
+        assert!(shell
+            .storage
+            .read_validator_missed_blocks(&validator)
+            .is_none());
+        assert!(!shell.storage.read_validator_slashes(&validator).is_empty());
+        let state = shell
+            .storage
+            .read_validator_state(&validator)
+            .expect("Test failed");
+        assert_eq!(state.get(epoch), Some(&ValidatorState::Candidate));
+        assert_eq!(state.get(pipeline_epoch), Some(&ValidatorState::Jailed));
+        let validator_set = shell.storage.read_validator_set();
+        let in_set = |epoch: Epoch| {
+            let set = validator_set.get(epoch).expect("Test failed");
+            set.active
+                .iter()
+                .chain(set.inactive.iter())
+                .any(|v| v.address == validator)
+        };
+        assert!(in_set(epoch));
+        assert!(!in_set(pipeline_epoch));
+
+        // A jailed validator doesn't miss any more blocks
+        let jailed = shell
+            .storage
+            .record_missed_blocks(epoch, 9, &non_signers)
+            .expect("Test failed");
+        assert!(jailed.is_empty());
+        assert!(shell
+            .storage
+            .read_validator_missed_blocks(&validator)
+            .is_none());
+    }
+
+    /// Test that a slash is queued until the cubic slashing window around
+    /// its infraction epoch has passed and that it's then applied with the
+    /// rate computed from the slashed stake.
+    #[test]
+    fn test_queued_slash_processed_after_window() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+        let (validator, _) = genesis_proposer(&shell);
+        let window = params.cubic_slashing_window_length;
+
+        shell
+            .storage
+            .slash(
+                &params,
+                Epoch(0),
+                1_u64,
+                SlashType::DuplicateVote,
+                &validator,
+            )
+            .expect("Test failed");
+        assert_eq!(shell.storage.read_slash_queue().len(), 1);
+        assert!(shell.storage.read_validator_slashes(&validator).is_empty());
+
+        // Nothing is processed until the window has passed
+        let applied = shell
+            .storage
+            .process_slashes(Epoch(window))
+            .expect("Test failed");
+        assert!(applied.is_empty());
+        assert_eq!(shell.storage.read_slash_queue().len(), 1);
+
+        let applied = shell
+            .storage
+            .process_slashes(Epoch(window + 1))
+            .expect("Test failed");
+        assert_eq!(applied.len(), 1);
+        assert!(shell.storage.read_slash_queue().is_empty());
+        assert_eq!(shell.storage.read_processed_slashes().len(), 1);
+        let queued = &applied[0];
+        let expected_rate = cubic_slash_rate(
+            params.duplicate_vote_min_slash_rate,
+            rust_decimal::Decimal::from(queued.validator_stake)
+                / rust_decimal::Decimal::from(queued.total_stake),
+        );
+        let slashes = shell.storage.read_validator_slashes(&validator);
+        assert_eq!(slashes.len(), 1);
+        assert_eq!(slashes[0].rate, expected_rate);
+    }
+
+    /// Test that the tokens redelegated away from a validator are slashed for
+    /// its infractions committed while they were still bonded to it.
+    #[test]
+    fn test_slash_redelegated_tokens() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+        let (dest_validator, _) = genesis_proposer(&shell);
+        let src_validator = established_address_1();
+        let bond_id = BondId {
+            source: established_address_2(),
+            validator: dest_validator,
+        };
+        let amount = token::Amount::from(1_000_000);
+        let bond = EpochedDelta::init_at_offset(
+            Bond {
+                pos_deltas: HashMap::from([(
+                    Epoch(params.pipeline_len),
+                    amount,
+                )]),
+                neg_deltas: token::Amount::default(),
+            },
+            Epoch(0),
+            DynEpochOffset::PipelineLen,
+            &params,
+        );
+        shell.storage.write_bond(&bond_id, &bond);
+        let redelegation = Redelegation {
+            src_validator: src_validator.clone(),
+            epoch: Epoch(0),
+            deltas: HashMap::from([(
+                (Epoch(0), Epoch(params.pipeline_len - 1)),
+                amount,
+            )]),
+        };
+        shell
+            .storage
+            .write_redelegations(&bond_id, &vec![redelegation]);
+
+        let slash = |epoch| Slash {
+            epoch,
+            block_height: 1,
+            r#type: SlashType::DuplicateVote,
+            rate: rust_decimal_macros::dec!(0.5),
+        };
+        let bonded_amount = |shell: &TestShell| {
+            shell
+                .storage
+                .read_bond(&bond_id)
+                .and_then(|bond| {
+                    bond.get_at_offset(
+                        Epoch(1),
+                        DynEpochOffset::PipelineLen,
+                        &params,
+                    )
+                })
+                .map(|bond| bond.sum())
+                .unwrap_or_default()
+        };
+
+        // An infraction after the tokens have left the source validator
+        // doesn't affect them
+        shell
+            .storage
+            .slash_redelegations(
+                &params,
+                Epoch(1),
+                &src_validator,
+                &slash(Epoch(params.pipeline_len)),
+            )
+            .expect("Test failed");
+        assert_eq!(bonded_amount(&shell), amount);
+
+        shell
+            .storage
+            .slash_redelegations(
+                &params,
+                Epoch(1),
+                &src_validator,
+                &slash(Epoch(0)),
+            )
+            .expect("Test failed");
+        let expected = token::Amount::from(500_000);
+        assert_eq!(bonded_amount(&shell), expected);
+        let (_, redelegations) = shell
+            .storage
+            .read_redelegations()
+            .into_iter()
+            .find(|(id, _)| id == &bond_id)
+            .expect("Test failed");
+        assert_eq!(redelegations[0].deltas.values().next(), Some(&expected));
+    }
+
+    /// Test that a rotated consensus key replaces the previous one in
+    /// Tendermint's validator set once it takes effect at the pipeline offset.
+    #[test]
+    fn test_consensus_key_rotation_validator_set_update() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+        let (validator, _) = genesis_proposer(&shell);
+        let pipeline_epoch = Epoch(params.pipeline_len);
+        let mut consensus_keys = shell
+            .storage
+            .read_validator_consensus_key(&validator)
+            .expect("Test failed");
+        let old_key = consensus_keys.get(Epoch(0)).cloned().unwrap();
+        let new_key = namada::types::key::testing::keypair_2().ref_to();
+        consensus_keys.set(new_key.clone(), Epoch(0), &params);
+        shell
+            .storage
+            .write_validator_consensus_key(&validator, &consensus_keys);
+        shell
+            .storage
+            .write_validator_address_raw_hash(&validator, &new_key);
+
+        // The key rotation is not visible to Tendermint before the pipeline
+        let mut updates = vec![];
+        shell
+            .storage
+            .validator_set_update(pipeline_epoch - 1, |update| {
+                updates.push(update)
+            });
+        assert!(updates.is_empty());
+
+        let mut updates = vec![];
+        shell
+            .storage
+            .validator_set_update(pipeline_epoch, |update| {
+                updates.push(update)
+            });
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().any(|update| matches!(
+            update,
+            ValidatorSetUpdate::Active(ActiveValidator { consensus_key, .. })
+                if consensus_key == &new_key
+        )));
+        assert!(updates.iter().any(|update| matches!(
+            update,
+            ValidatorSetUpdate::Deactivated(consensus_key)
+                if consensus_key == &old_key
+        )));
+
+        // Evidence signed with either key can be attributed to the validator
+        for key in [old_key, new_key] {
+            let raw_hash = tm_consensus_key_raw_hash(&key);
+            assert_eq!(
+                shell.storage.read_validator_address_raw_hash(raw_hash),
+                Some(validator.clone())
+            );
+        }
+    }
 }
@@ -1,17 +1,25 @@
 use namada::core::ledger::slash_fund::ADDRESS as slash_fund_address;
+use namada::core::ledger::treasury::ADDRESS as treasury_address;
 use namada::ledger::events::EventType;
+use namada::ledger::governance::parameters::{
+    read_dependent_parameters, GovParams,
+};
 use namada::ledger::governance::{
     storage as gov_storage, ADDRESS as gov_address,
 };
 use namada::ledger::native_vp::governance::utils::{
     compute_tally, get_proposal_votes, ProposalEvent,
 };
-use namada::ledger::protocol;
-use namada::ledger::storage::types::encode;
+use namada::ledger::storage::types::{decode, encode};
 use namada::ledger::storage::{DBIter, StorageHasher, DB};
+use namada::ledger::{parameters, protocol};
 use namada::types::address::Address;
-use namada::types::governance::TallyResult;
+use namada::types::governance::{
+    FundsDestination, GovParameter, ParameterChange, PgfPayment, ProposalType,
+    ProtocolParameter, TallyResult, TreasuryTransfer,
+};
 use namada::types::storage::Epoch;
+use namada::types::time::DurationSecs;
 use namada::types::token;
 
 use super::*;
@@ -32,9 +40,21 @@ where
 {
     let mut proposals_result = ProposalsResult::default();
 
+    let default_params = GovParams::default();
+    let rejected_funds = shell
+        .read_storage_key(&gov_storage::get_rejected_funds_key())
+        .unwrap_or(default_params.rejected_funds);
+    let vetoed_funds = shell
+        .read_storage_key(&gov_storage::get_vetoed_funds_key())
+        .unwrap_or(default_params.vetoed_funds);
+    let failed_funds = shell
+        .read_storage_key(&gov_storage::get_failed_funds_key())
+        .unwrap_or(default_params.failed_funds);
+
     for id in std::mem::take(&mut shell.proposal_data) {
         let proposal_funds_key = gov_storage::get_funds_key(id);
         let proposal_end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
+        let proposal_author_key = gov_storage::get_author_key(id);
 
         let funds = shell
             .read_storage_key::<token::Amount>(&proposal_funds_key)
@@ -49,29 +69,31 @@ where
                     "Invalid proposal end_epoch.".to_string(),
                 )
             })?;
+        let proposal_author = shell
+            .read_storage_key::<Address>(&proposal_author_key)
+            .ok_or_else(|| {
+                Error::BadProposal(id, "Invalid proposal author.".to_string())
+            })?;
 
         let votes = get_proposal_votes(&shell.storage, proposal_end_epoch, id);
-        let is_accepted = votes.and_then(|votes| {
-            compute_tally(&shell.storage, proposal_end_epoch, votes)
-        });
-
-        let transfer_address = match is_accepted {
-            Ok(true) => {
-                let proposal_author_key = gov_storage::get_author_key(id);
-                let proposal_author = shell
-                    .read_storage_key::<Address>(&proposal_author_key)
-                    .ok_or_else(|| {
-                        Error::BadProposal(
-                            id,
-                            "Invalid proposal author.".to_string(),
-                        )
-                    })?;
+        let tally_result = votes
+            .and_then(|votes| {
+                compute_tally(&shell.storage, proposal_end_epoch, votes)
+            })
+            .map(|proposal_result| proposal_result.result);
 
+        // where the locked funds go, depending on the proposal outcome
+        let funds_destination = match tally_result {
+            Ok(TallyResult::Passed) => {
+                let proposal_type_key = gov_storage::get_proposal_type_key(id);
+                let proposal_type = shell
+                    .read_storage_key::<ProposalType>(&proposal_type_key)
+                    .unwrap_or_default();
                 let proposal_code_key = gov_storage::get_proposal_code_key(id);
                 let proposal_code =
                     shell.read_storage_key_bytes(&proposal_code_key);
-                match proposal_code {
-                    Some(proposal_code) => {
+                match (proposal_type, proposal_code) {
+                    (ProposalType::Default, Some(proposal_code)) => {
                         let tx = Tx::new(proposal_code, Some(encode(&id)));
                         let tx_type =
                             TxType::Decrypted(DecryptedTx::Decrypted(tx));
@@ -113,7 +135,7 @@ where
                                     response.events.push(proposal_event);
                                     proposals_result.passed.push(id);
 
-                                    proposal_author
+                                    FundsDestination::Author
                                 } else {
                                     shell.write_log.drop_tx();
                                     let proposal_event: Event =
@@ -128,7 +150,7 @@ where
                                     response.events.push(proposal_event);
                                     proposals_result.rejected.push(id);
 
-                                    slash_fund_address
+                                    failed_funds
                                 }
                             }
                             Err(_e) => {
@@ -144,11 +166,11 @@ where
                                 response.events.push(proposal_event);
                                 proposals_result.rejected.push(id);
 
-                                slash_fund_address
+                                failed_funds
                             }
                         }
                     }
-                    None => {
+                    (ProposalType::Default, None) => {
                         let proposal_event: Event = ProposalEvent::new(
                             EventType::Proposal.to_string(),
                             TallyResult::Passed,
@@ -160,11 +182,45 @@ where
                         response.events.push(proposal_event);
                         proposals_result.passed.push(id);
 
-                        proposal_author
+                        FundsDestination::Author
+                    }
+                    (proposal_type, _) => {
+                        let is_executed =
+                            execute_proposal_payload(shell, id, proposal_type);
+                        let proposal_event: Event = ProposalEvent::new(
+                            EventType::Proposal.to_string(),
+                            TallyResult::Passed,
+                            id,
+                            true,
+                            is_executed,
+                        )
+                        .into();
+                        response.events.push(proposal_event);
+                        if is_executed {
+                            proposals_result.passed.push(id);
+                            FundsDestination::Author
+                        } else {
+                            proposals_result.rejected.push(id);
+                            failed_funds
+                        }
                     }
                 }
             }
-            Ok(false) => {
+            Ok(TallyResult::Vetoed) => {
+                let proposal_event: Event = ProposalEvent::new(
+                    EventType::Proposal.to_string(),
+                    TallyResult::Vetoed,
+                    id,
+                    false,
+                    false,
+                )
+                .into();
+                response.events.push(proposal_event);
+                proposals_result.rejected.push(id);
+
+                vetoed_funds
+            }
+            Ok(_) => {
                 let proposal_event: Event = ProposalEvent::new(
                     EventType::Proposal.to_string(),
                     TallyResult::Rejected,
@@ -176,7 +232,7 @@ where
                 response.events.push(proposal_event);
                 proposals_result.rejected.push(id);
 
-                slash_fund_address
+                rejected_funds
             }
             Err(err) => {
                 tracing::error!(
@@ -193,19 +249,576 @@ where
                 .into();
                 response.events.push(proposal_event);
 
-                slash_fund_address
+                failed_funds
             }
         };
 
         let native_token = shell.storage.native_token.clone();
-        // transfer proposal locked funds
-        shell.storage.transfer(
-            &native_token,
+        let transfer_address = match funds_destination {
+            FundsDestination::Author => Some(proposal_author),
+            FundsDestination::SlashFund => Some(slash_fund_address),
+            FundsDestination::Treasury => Some(treasury_address),
+            FundsDestination::Burn => None,
+        };
+        match transfer_address {
+            // transfer proposal locked funds
+            Some(transfer_address) => shell.storage.transfer(
+                &native_token,
+                funds,
+                &gov_address,
+                &transfer_address,
+            ),
+            // burn proposal locked funds
+            None => {
+                let gov_balance_key =
+                    token::balance_key(&native_token, &gov_address);
+                let mut gov_balance = shell
+                    .read_storage_key::<token::Amount>(&gov_balance_key)
+                    .unwrap_or_default();
+                gov_balance.spend(&funds);
+                shell
+                    .storage
+                    .write(&gov_balance_key, encode(&gov_balance))
+                    .expect("Should be able to write to storage.");
+            }
+        }
+    }
+
+    Ok(proposals_result)
+}
+
+/// Execute the payload declared by a typed proposal. Returns `false` when the
+/// payload couldn't be executed, in which case no changes are made.
+fn execute_proposal_payload<D, H>(
+    shell: &mut Shell<D, H>,
+    id: u64,
+    proposal_type: ProposalType,
+) -> bool
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    match proposal_type {
+        ProposalType::Default => true,
+        ProposalType::ParameterChange(changes) => {
+            // The changes must be consistent with the other parameters
+            let errors = read_dependent_parameters(&shell.storage)
+                .expect("Should be able to read from storage.")
+                .validate_changes(&changes);
+            if !errors.is_empty() {
+                tracing::info!(
+                    "The parameter changes of proposal ID {id} are \
+                     inconsistent with the other parameters: {errors:?}"
+                );
+                return false;
+            }
+            // The PoS parameters must be valid with all the changes applied
+            let mut pos_params = shell.storage.read_pos_params();
+            let mut pos_params_changed = false;
+            for change in &changes {
+                if let ParameterChange::Pos(param) = change {
+                    pos_params.apply_change(param);
+                    pos_params_changed = true;
+                }
+            }
+            if pos_params_changed {
+                let errors = pos_params.validate();
+                if !errors.is_empty() {
+                    tracing::info!(
+                        "The parameter changes of proposal ID {id} would make \
+                         the PoS parameters invalid: {errors:?}"
+                    );
+                    return false;
+                }
+            }
+            for change in changes {
+                execute_parameter_change(shell, change);
+            }
+            if pos_params_changed {
+                shell.storage.write_pos_params(&pos_params);
+            }
+            true
+        }
+        ProposalType::TreasurySpend(transfers) => {
+            execute_spend(shell, id, &treasury_address, transfers)
+        }
+        ProposalType::PublicGoodsFunding(payments) => {
+            let pgf_payments_key = gov_storage::get_pgf_payments_key(id);
+            shell
+                .storage
+                .write(&pgf_payments_key, encode(&payments))
+                .expect("Should be able to write to storage.");
+            true
+        }
+        ProposalType::SlashFundSpend(transfers) => {
+            execute_spend(shell, id, &slash_fund_address, transfers)
+        }
+    }
+}
+
+fn execute_parameter_change<D, H>(
+    shell: &mut Shell<D, H>,
+    change: ParameterChange,
+) where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    match change {
+        ParameterChange::Protocol(param) => {
+            let storage = &mut shell.storage;
+            let result = match param {
+                ProtocolParameter::MinNumOfBlocks(value) => {
+                    let (mut epoch_duration, _gas) =
+                        parameters::read_epoch_duration_parameter(storage)
+                            .expect("Should be able to read from storage.");
+                    epoch_duration.min_num_of_blocks = value;
+                    parameters::update_epoch_parameter(storage, &epoch_duration)
+                }
+                ProtocolParameter::MinEpochDuration(value) => {
+                    let (mut epoch_duration, _gas) =
+                        parameters::read_epoch_duration_parameter(storage)
+                            .expect("Should be able to read from storage.");
+                    epoch_duration.min_duration = DurationSecs(value);
+                    parameters::update_epoch_parameter(storage, &epoch_duration)
+                }
+                ProtocolParameter::MaxExpectedTimePerBlock(value) => {
+                    parameters::update_max_expected_time_per_block_parameter(
+                        storage,
+                        &DurationSecs(value),
+                    )
+                }
+                ProtocolParameter::VpWhitelist(value) => {
+                    parameters::update_vp_whitelist_parameter(storage, value)
+                }
+                ProtocolParameter::TxWhitelist(value) => {
+                    parameters::update_tx_whitelist_parameter(storage, value)
+                }
+                ProtocolParameter::EpochsPerYear(value) => {
+                    parameters::update_epochs_per_year_parameter(
+                        storage, &value,
+                    )
+                }
+                ProtocolParameter::PosGainP(value) => {
+                    parameters::update_pos_gain_p_parameter(storage, &value)
+                }
+                ProtocolParameter::PosGainD(value) => {
+                    parameters::update_pos_gain_d_parameter(storage, &value)
+                }
+            };
+            result.expect("Should be able to write to storage.");
+        }
+        ParameterChange::Pos(_) => {
+            // The PoS parameters are validated and written together, once
+            // all the changes of the proposal are applied
+        }
+        ParameterChange::Governance(param) => {
+            let (key, value) = match param {
+                GovParameter::MinProposalFund(value) => (
+                    gov_storage::get_min_proposal_fund_key(),
+                    encode(&token::Amount::whole(value)),
+                ),
+                GovParameter::MaxProposalCodeSize(value) => (
+                    gov_storage::get_max_proposal_code_size_key(),
+                    encode(&value),
+                ),
+                GovParameter::MinProposalPeriod(value) => {
+                    (gov_storage::get_min_proposal_period_key(), encode(&value))
+                }
+                GovParameter::MaxProposalPeriod(value) => {
+                    (gov_storage::get_max_proposal_period_key(), encode(&value))
+                }
+                GovParameter::MaxProposalContentSize(value) => (
+                    gov_storage::get_max_proposal_content_key(),
+                    encode(&value),
+                ),
+                GovParameter::MinProposalGraceEpochs(value) => (
+                    gov_storage::get_min_proposal_grace_epoch_key(),
+                    encode(&value),
+                ),
+                GovParameter::Quorum(value) => {
+                    (gov_storage::get_quorum_key(), encode(&value))
+                }
+                GovParameter::PassThreshold(value) => {
+                    (gov_storage::get_pass_threshold_key(), encode(&value))
+                }
+                GovParameter::VetoThreshold(value) => {
+                    (gov_storage::get_veto_threshold_key(), encode(&value))
+                }
+                GovParameter::RejectedFunds(value) => {
+                    (gov_storage::get_rejected_funds_key(), encode(&value))
+                }
+                GovParameter::VetoedFunds(value) => {
+                    (gov_storage::get_vetoed_funds_key(), encode(&value))
+                }
+                GovParameter::FailedFunds(value) => {
+                    (gov_storage::get_failed_funds_key(), encode(&value))
+                }
+            };
+            shell
+                .storage
+                .write(&key, value)
+                .expect("Should be able to write to storage.");
+        }
+    }
+}
+
+/// Execute all the transfers of a spending proposal from the treasury or the
+/// slash fund, or none of them if the source cannot cover their total
+fn execute_spend<D, H>(
+    shell: &mut Shell<D, H>,
+    id: u64,
+    source: &Address,
+    transfers: Vec<TreasuryTransfer>,
+) -> bool
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let native_token = shell.storage.native_token.clone();
+    let source_balance_key = token::balance_key(&native_token, source);
+    let source_balance = shell
+        .read_storage_key::<token::Amount>(&source_balance_key)
+        .unwrap_or_default();
+    let total = transfers
+        .iter()
+        .fold(token::Amount::default(), |total, transfer| {
+            total + transfer.amount
+        });
+    if total > source_balance {
+        tracing::info!(
+            "The balance {source_balance} of {source} cannot cover the \
+             spending of {total} of proposal ID {id}"
+        );
+        return false;
+    }
+
+    for TreasuryTransfer { target, amount } in transfers {
+        shell
+            .storage
+            .transfer(&native_token, amount, source, &target);
+    }
+    true
+}
+
+/// Pay the recurring public goods funding payments due in the current epoch
+/// from the treasury and remove the payments that have ended
+pub fn execute_pgf_payments<D, H>(shell: &mut Shell<D, H>)
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let (current_epoch, _gas) = shell.storage.get_current_epoch();
+    let native_token = shell.storage.native_token.clone();
+    let treasury_balance_key =
+        token::balance_key(&native_token, &treasury_address);
+
+    let pgf_prefix = gov_storage::get_pgf_prefix();
+    let (pgf_iter, _gas) = shell.storage.iter_prefix(&pgf_prefix);
+    let pgf_payments: Vec<(Key, Vec<PgfPayment>)> = pgf_iter
+        .filter_map(|(key, payments, _gas)| {
+            let key = Key::parse(key).ok()?;
+            let payments = decode(payments).ok()?;
+            Some((key, payments))
+        })
+        .collect();
+
+    for (key, payments) in pgf_payments {
+        let mut remaining_payments = Vec::new();
+        for payment in payments {
+            if payment.end_epoch < current_epoch {
+                continue;
+            }
+            let treasury_balance = shell
+                .read_storage_key::<token::Amount>(&treasury_balance_key)
+                .unwrap_or_default();
+            if payment.amount <= treasury_balance {
+                shell.storage.transfer(
+                    &native_token,
+                    payment.amount,
+                    &treasury_address,
+                    &payment.target,
+                );
+            } else {
+                tracing::info!(
+                    "The treasury balance {treasury_balance} cannot cover the \
+                     public goods funding payment of {} to {}",
+                    payment.amount,
+                    payment.target
+                );
+            }
+            if payment.end_epoch > current_epoch {
+                remaining_payments.push(payment);
+            }
+        }
+
+        if remaining_payments.is_empty() {
+            shell
+                .storage
+                .delete(&key)
+                .expect("Should be able to delete the storage.");
+        } else {
+            shell
+                .storage
+                .write(&key, encode(&remaining_payments))
+                .expect("Should be able to write to storage.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_governance {
+    use namada::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada::types::governance::{PosParameter, ProposalVote};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::{setup, TestShell};
+
+    fn balance(shell: &TestShell, owner: &Address) -> token::Amount {
+        let key = token::balance_key(&shell.storage.native_token, owner);
+        shell.read_storage_key(&key).unwrap_or_default()
+    }
+
+    fn credit(shell: &mut TestShell, owner: &Address, amount: token::Amount) {
+        let key = token::balance_key(&shell.storage.native_token, owner);
+        let balance = balance(shell, owner) + amount;
+        shell
+            .storage
+            .write(&key, encode(&balance))
+            .expect("Test failed");
+    }
+
+    /// Submit a proposal of the given type with its voting period ending at
+    /// the current epoch and queue it for execution. The only genesis
+    /// validator casts the given vote on it, if any.
+    fn init_proposal(
+        shell: &mut TestShell,
+        id: u64,
+        proposal_type: ProposalType,
+        vote: Option<ProposalVote>,
+        funds: token::Amount,
+    ) {
+        let author = established_address_1();
+        let writes = [
+            (gov_storage::get_funds_key(id), encode(&funds)),
+            (gov_storage::get_voting_end_epoch_key(id), encode(&Epoch(0))),
+            (gov_storage::get_author_key(id), encode(&author)),
+            (
+                gov_storage::get_proposal_type_key(id),
+                encode(&proposal_type),
+            ),
+        ];
+        for (key, value) in writes {
+            shell.storage.write(&key, value).expect("Test failed");
+        }
+        credit(shell, &gov_address, funds);
+
+        if let Some(vote) = vote {
+            let validator = shell
+                .storage
+                .read_validator_set()
+                .get(Epoch(0))
+                .and_then(|validator_set| validator_set.active.iter().next())
+                .expect("Test failed")
+                .address
+                .clone();
+            let vote_key = gov_storage::get_vote_proposal_key(
+                id,
+                validator.clone(),
+                validator,
+            );
+            shell
+                .storage
+                .write(&vote_key, encode(&vote))
+                .expect("Test failed");
+        }
+        shell.proposal_data.insert(id);
+    }
+
+    /// Test that the funds of rejected, vetoed and failed proposals are
+    /// moved to the configured destinations and the author of a passed
+    /// proposal is refunded
+    #[test]
+    fn test_proposal_funds_destinations() {
+        let (mut shell, _) = setup();
+        let destinations = [
+            (
+                gov_storage::get_rejected_funds_key(),
+                FundsDestination::Treasury,
+            ),
+            (gov_storage::get_vetoed_funds_key(), FundsDestination::Burn),
+            (
+                gov_storage::get_failed_funds_key(),
+                FundsDestination::SlashFund,
+            ),
+        ];
+        for (key, destination) in destinations {
+            shell
+                .storage
+                .write(&key, encode(&destination))
+                .expect("Test failed");
+        }
+
+        let funds = token::Amount::whole(500);
+        let author = established_address_1();
+        let gov_balance_pre = balance(&shell, &gov_address);
+        let author_balance_pre = balance(&shell, &author);
+        let treasury_balance_pre = balance(&shell, &treasury_address);
+        let slash_fund_balance_pre = balance(&shell, &slash_fund_address);
+
+        // Rejected for the lack of votes
+        init_proposal(&mut shell, 0, ProposalType::Default, None, funds);
+        // Vetoed
+        init_proposal(
+            &mut shell,
+            1,
+            ProposalType::Default,
+            Some(ProposalVote::NoWithVeto),
+            funds,
+        );
+        // Passed, but the slash fund cannot cover the spending
+        let spending = ProposalType::SlashFundSpend(vec![TreasuryTransfer {
+            target: established_address_2(),
+            amount: slash_fund_balance_pre + token::Amount::whole(1),
+        }]);
+        init_proposal(&mut shell, 2, spending, Some(ProposalVote::Yay), funds);
+        // Passed
+        init_proposal(
+            &mut shell,
+            3,
+            ProposalType::Default,
+            Some(ProposalVote::Yay),
             funds,
-            &gov_address,
-            &transfer_address,
         );
+
+        let mut response = shim::response::FinalizeBlock::default();
+        let mut result =
+            execute_governance_proposals(&mut shell, &mut response)
+                .expect("Test failed");
+        result.rejected.sort_unstable();
+        assert_eq!(result.passed, vec![3]);
+        assert_eq!(result.rejected, vec![0, 1, 2]);
+        assert_eq!(response.events.len(), 4);
+
+        assert_eq!(balance(&shell, &author), author_balance_pre + funds);
+        assert_eq!(
+            balance(&shell, &treasury_address),
+            treasury_balance_pre + funds
+        );
+        assert_eq!(
+            balance(&shell, &slash_fund_address),
+            slash_fund_balance_pre + funds
+        );
+        assert_eq!(balance(&shell, &established_address_2()), 0.into());
+        // The funds of the vetoed proposal are burned
+        assert_eq!(balance(&shell, &gov_address), gov_balance_pre);
     }
 
-    Ok(proposals_result)
+    /// Test that the transfers of a slash fund spending proposal are either
+    /// all executed, or none of them if the slash fund cannot cover them
+    #[test]
+    fn test_slash_fund_spend_all_or_nothing() {
+        let (mut shell, _) = setup();
+        let target_1 = established_address_1();
+        let target_2 = established_address_2();
+        let slash_fund_balance = token::Amount::whole(100);
+        let slash_fund_key = token::balance_key(
+            &shell.storage.native_token,
+            &slash_fund_address,
+        );
+        shell
+            .storage
+            .write(&slash_fund_key, encode(&slash_fund_balance))
+            .expect("Test failed");
+        let transfers = |amount_1: u64, amount_2: u64| {
+            ProposalType::SlashFundSpend(vec![
+                TreasuryTransfer {
+                    target: target_1.clone(),
+                    amount: token::Amount::whole(amount_1),
+                },
+                TreasuryTransfer {
+                    target: target_2.clone(),
+                    amount: token::Amount::whole(amount_2),
+                },
+            ])
+        };
+
+        // Each transfer can be covered, but not both of them
+        assert!(!execute_proposal_payload(&mut shell, 0, transfers(60, 60)));
+        assert_eq!(balance(&shell, &slash_fund_address), slash_fund_balance);
+        assert_eq!(balance(&shell, &target_1), 0.into());
+        assert_eq!(balance(&shell, &target_2), 0.into());
+
+        assert!(execute_proposal_payload(&mut shell, 1, transfers(60, 40)));
+        assert_eq!(balance(&shell, &slash_fund_address), 0.into());
+        assert_eq!(balance(&shell, &target_1), token::Amount::whole(60));
+        assert_eq!(balance(&shell, &target_2), token::Amount::whole(40));
+    }
+
+    /// Test that the PoS parameter changes of a proposal are only executed
+    /// if the parameters are valid with all of them applied
+    #[test]
+    fn test_pos_parameter_changes_validated_together() {
+        let (mut shell, _) = setup();
+        let params = shell.storage.read_pos_params();
+
+        // together, the changes exceed the maximum total voting power
+        let changes = ProposalType::ParameterChange(vec![
+            ParameterChange::Pos(PosParameter::TmVotesPerToken(dec!(1.0))),
+            ParameterChange::Pos(PosParameter::MaxValidatorSlots(1_000_000)),
+        ]);
+        assert!(!execute_proposal_payload(&mut shell, 0, changes));
+        let unchanged = shell.storage.read_pos_params();
+        assert_eq!(unchanged.tm_votes_per_token, params.tm_votes_per_token);
+        assert_eq!(unchanged.max_validator_slots, params.max_validator_slots);
+
+        // the max. number of validators can only be raised if the votes per
+        // token are lowered in the same proposal
+        let changes = ProposalType::ParameterChange(vec![
+            ParameterChange::Pos(PosParameter::TmVotesPerToken(dec!(0.0001))),
+            ParameterChange::Pos(PosParameter::MaxValidatorSlots(1_000_000)),
+        ]);
+        assert!(execute_proposal_payload(&mut shell, 1, changes));
+        let changed = shell.storage.read_pos_params();
+        assert_eq!(changed.tm_votes_per_token, dec!(0.0001));
+        assert_eq!(changed.max_validator_slots, 1_000_000);
+    }
+
+    /// Test that the parameter changes of a proposal are only executed if
+    /// they're consistent with the other stored parameters
+    #[test]
+    fn test_dependent_parameter_changes_validated() {
+        let (mut shell, _) = setup();
+        let params =
+            read_dependent_parameters(&shell.storage).expect("Test failed");
+
+        // the min. period would exceed the stored max. period
+        let min_period = params.max_proposal_period + 1;
+        let changes =
+            ProposalType::ParameterChange(vec![ParameterChange::Governance(
+                GovParameter::MinProposalPeriod(min_period),
+            )]);
+        assert!(!execute_proposal_payload(&mut shell, 0, changes));
+        assert_eq!(
+            read_dependent_parameters(&shell.storage).expect("Test failed"),
+            params
+        );
+
+        // the max. period can be raised by the same proposal
+        let changes = ProposalType::ParameterChange(vec![
+            ParameterChange::Governance(GovParameter::MinProposalPeriod(
+                min_period,
+            )),
+            ParameterChange::Governance(GovParameter::MaxProposalPeriod(
+                min_period,
+            )),
+        ]);
+        assert!(execute_proposal_payload(&mut shell, 1, changes));
+        let changed =
+            read_dependent_parameters(&shell.storage).expect("Test failed");
+        assert_eq!(changed.min_proposal_period, min_period);
+        assert_eq!(changed.max_proposal_period, min_period);
+    }
 }
@@ -113,6 +113,12 @@ where
         // Initialize governance parameters
         genesis.gov_params.init_storage(&mut self.storage);
 
+        if genesis.commit_block_metadata {
+            self.storage
+                .enable_block_metadata_commitment()
+                .expect("Enabling the block metadata commitment must not fail");
+        }
+
         // Depends on parameters being initialized
         self.storage
             .init_genesis_epoch(initial_height, genesis_time, &parameters)
@@ -311,6 +317,9 @@ where
             current_epoch,
         );
         ibc::init_genesis_storage(&mut self.storage);
+        // The validators of the next epoch generate its encryption key, while
+        // the txs of the genesis epoch use the bootstrap key
+        self.start_dkg_session(current_epoch.next());
 
         // Set the initial validator set
         for validator in genesis.validators {
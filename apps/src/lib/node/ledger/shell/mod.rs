@@ -5,12 +5,16 @@
 //! and [`Shell::process_proposal`] must be also reverted
 //! (unless we can simply overwrite them in the next block).
 //! More info in <https://github.com/anoma/namada/issues/362>.
+mod dkg;
+mod eth_bridge;
 mod finalize_block;
 mod governance;
 mod init_chain;
 mod prepare_proposal;
 mod process_proposal;
 mod queries;
+mod snapshots;
+mod vote_extensions;
 
 use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
@@ -27,20 +31,23 @@ use namada::ledger::pos::namada_proof_of_stake::types::{
     ActiveValidator, ValidatorSetUpdate,
 };
 use namada::ledger::pos::namada_proof_of_stake::PosBase;
-use namada::ledger::storage::write_log::WriteLog;
+use namada::ledger::storage::types::encode;
+use namada::ledger::storage::write_log::{StorageModification, WriteLog};
 use namada::ledger::storage::{
     DBIter, Sha256Hasher, Storage, StorageHasher, DB,
 };
-use namada::ledger::{ibc, pos, protocol};
+use namada::ledger::{eth_bridge, ibc, pos, protocol};
 use namada::proto::{self, Tx};
 use namada::types::address::{masp, masp_tx_key, Address};
 use namada::types::chain::ChainId;
+use namada::types::ethereum_events::EthereumEvent;
 use namada::types::key::*;
 use namada::types::storage::{BlockHeight, Key, TxIndex};
 use namada::types::time::{DateTimeUtc, TimeZone, Utc};
+use namada::types::transaction::protocol::ProtocolTxType;
 use namada::types::transaction::{
-    hash_tx, process_tx, verify_decrypted_correctly, AffineCurve, DecryptedTx,
-    EllipticCurve, PairingEngine, TxType, WrapperTx,
+    hash_tx, process_tx, AffineCurve, DecryptedTx, EllipticCurve,
+    PairingEngine, TxError, TxType, WrapperTx,
 };
 use namada::types::{address, token};
 use namada::vm::wasm::{TxCache, VpCache};
@@ -48,16 +55,15 @@ use namada::vm::WasmCacheRwAccess;
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
 use thiserror::Error;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use crate::config::{genesis, TendermintMode};
-#[cfg(feature = "abcipp")]
-use crate::facade::tendermint_proto::abci::response_verify_vote_extension::VerifyStatus;
 use crate::facade::tendermint_proto::abci::{
     Misbehavior as Evidence, MisbehaviorType as EvidenceType, ValidatorUpdate,
 };
 use crate::facade::tendermint_proto::crypto::public_key;
 use crate::facade::tower_abci::{request, response};
+use crate::node::ledger::ethereum_oracle::EthereumReceiver;
 use crate::node::ledger::shims::abcipp_shim_types::shim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::response::TxResult;
 use crate::node::ledger::{storage, tendermint_node};
@@ -65,6 +71,11 @@ use crate::node::ledger::{storage, tendermint_node};
 use crate::wallet::ValidatorData;
 use crate::{config, wallet};
 
+/// The minimum number of the most recent block heights whose data is kept when
+/// pruning the DB. The last height is the current state and the one before is
+/// needed to roll back the last block.
+const MIN_KEPT_HEIGHTS: u64 = 2;
+
 fn key_to_tendermint(
     pk: &common::PublicKey,
 ) -> std::result::Result<public_key::Sum, ParsePublicKeyError> {
@@ -82,6 +93,8 @@ fn key_to_tendermint(
 pub enum Error {
     #[error("Error removing the DB data: {0}")]
     RemoveDB(std::io::Error),
+    #[error("Error rolling back the DB: {0}")]
+    RollbackDB(namada::ledger::storage::Error),
     #[error("chain ID mismatch: {0}")]
     ChainId(String),
     #[error("Error decoding a transaction from bytes: {0}")]
@@ -152,12 +165,22 @@ pub fn reset(config: config::Ledger) -> Result<()> {
     Ok(())
 }
 
+/// Roll back the DB to the previous committed block height. Tendermint will
+/// replay the rolled back block to the ledger on the next start.
+pub fn rollback(config: config::Ledger) -> Result<()> {
+    let mut db = storage::PersistentDB::open(config.db_dir(), None);
+    let height = db.rollback().map_err(Error::RollbackDB)?;
+    tracing::info!("Rolled back the DB to block height {}", height);
+    Ok(())
+}
+
 #[derive(Debug)]
 #[allow(dead_code, clippy::large_enum_variant)]
 pub(super) enum ShellMode {
     Validator {
         data: ValidatorData,
         broadcast_sender: UnboundedSender<Vec<u8>>,
+        ethereum_recv: EthereumReceiver,
     },
     Full,
     Seed,
@@ -174,6 +197,17 @@ impl ShellMode {
     }
 }
 
+/// The fee payer of a wrapper tx. If the public key corresponds to the MASP
+/// sentinel transaction key, then the fee payer is effectively the MASP,
+/// otherwise it's derived from the public key.
+fn wrapper_fee_payer(wrapper: &WrapperTx) -> Address {
+    if wrapper.pk != masp_tx_key().ref_to() {
+        wrapper.fee_payer()
+    } else {
+        masp()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum MempoolTxType {
     /// A transaction that has not been validated by this node before
@@ -221,6 +255,19 @@ where
     pub proposal_data: HashSet<u64>,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// The state of the DKG of the tx encryption keys
+    dkg: dkg::DkgNode,
+    /// Taken from config `snapshot_interval`. When set, a snapshot of the
+    /// state is taken at every multiple of this block height interval.
+    snapshot_interval: Option<u64>,
+    /// Taken from config `storage_pruning`
+    storage_pruning: config::StoragePruning,
+    /// Path to the directory with the state sync snapshots
+    snapshots_dir: PathBuf,
+    /// The snapshot being restored by state sync, if any
+    snapshot_restore: Option<snapshots::SnapshotRestore>,
+    /// The thread taking a snapshot in the background, if any
+    snapshot_task: Option<std::thread::JoinHandle<()>>,
 }
 
 impl<D, H> Shell<D, H>
@@ -234,6 +281,7 @@ where
         config: config::Ledger,
         wasm_dir: PathBuf,
         broadcast_sender: UnboundedSender<Vec<u8>>,
+        eth_receiver: UnboundedReceiver<EthereumEvent>,
         db_cache: Option<&D::Cache>,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
@@ -243,8 +291,25 @@ where
         let db_path = config.shell.db_dir(&chain_id);
         let base_dir = config.shell.base_dir;
         let mode = config.tendermint.tendermint_mode;
-        let storage_read_past_height_limit =
-            config.shell.storage_read_past_height_limit;
+        let storage_pruning = config.shell.storage_pruning;
+        // The values at the pruned heights cannot be read anymore
+        let storage_read_past_height_limit = match &storage_pruning {
+            config::StoragePruning::Archive => {
+                config.shell.storage_read_past_height_limit
+            }
+            config::StoragePruning::KeepRecent { keep_recent }
+            | config::StoragePruning::KeepEvery { keep_recent, .. } => {
+                let pruned_limit =
+                    keep_recent.max(MIN_KEPT_HEIGHTS).saturating_sub(1);
+                Some(
+                    config
+                        .shell
+                        .storage_read_past_height_limit
+                        .map_or(pruned_limit, |limit| limit.min(pruned_limit)),
+                )
+            }
+        };
+        let snapshot_interval = config.shell.snapshot_interval;
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Namada should not fail");
@@ -263,6 +328,7 @@ where
             base_dir.join(chain_id.as_str()).join("vp_wasm_cache");
         let tx_wasm_cache_dir =
             base_dir.join(chain_id.as_str()).join("tx_wasm_cache");
+        let snapshots_dir = base_dir.join(chain_id.as_str()).join("snapshots");
         // load in keys and address from wallet if mode is set to `Validator`
         let mode = match mode {
             TendermintMode::Validator => {
@@ -287,6 +353,7 @@ where
                         .map(|data| ShellMode::Validator {
                             data,
                             broadcast_sender,
+                            ethereum_recv: EthereumReceiver::new(eth_receiver),
                         })
                         .expect(
                             "Validator data should have been stored in the \
@@ -305,6 +372,7 @@ where
                             },
                         },
                         broadcast_sender,
+                        ethereum_recv: EthereumReceiver::new(eth_receiver),
                     }
                 }
             }
@@ -312,7 +380,7 @@ where
             TendermintMode::Seed => ShellMode::Seed,
         };
 
-        Self {
+        let mut shell = Self {
             chain_id,
             storage,
             gas_meter: BlockGasMeter::default(),
@@ -333,7 +401,15 @@ where
             proposal_data: HashSet::new(),
             // TODO: config event log params
             event_log: EventLog::default(),
-        }
+            dkg: dkg::DkgNode::default(),
+            snapshot_interval,
+            storage_pruning,
+            snapshots_dir,
+            snapshot_restore: None,
+            snapshot_task: None,
+        };
+        shell.restore_dkg();
+        shell
     }
 
     /// Return a reference to the [`EventLog`].
@@ -410,7 +486,36 @@ where
         }
     }
 
-    /// Apply PoS slashes from the evidence
+    /// Read a value, including the changes made by the txs applied earlier
+    /// in the block.
+    fn read_value<T>(&self, key: &Key) -> Option<T>
+    where
+        T: Clone + BorshDeserialize,
+    {
+        match self.write_log.read(key).0 {
+            Some(StorageModification::Write { value }) => {
+                T::try_from_slice(value).ok()
+            }
+            Some(StorageModification::Delete) => None,
+            _ => self.read_storage_key(key),
+        }
+    }
+
+    /// Write a value into the write log.
+    fn write_value(&mut self, key: &Key, value: &impl BorshSerialize) {
+        self.write_log
+            .write(key, encode(value))
+            .expect("Writing a value into the write log shouldn't fail");
+    }
+
+    /// Delete a value in the write log.
+    fn delete_value(&mut self, key: &Key) {
+        self.write_log
+            .delete(key)
+            .expect("Deleting a value in the write log shouldn't fail");
+    }
+
+    /// Queue PoS slashes from the evidence
     fn slash(&mut self) {
         if !self.byzantine_validators.is_empty() {
             let byzantine_validators =
@@ -499,7 +604,7 @@ where
                     }
                 };
                 tracing::info!(
-                    "Slashing {} for {} in epoch {}, block height {}",
+                    "Queuing a slash of {} for {} in epoch {}, block height {}",
                     validator,
                     slash_type,
                     evidence_epoch,
@@ -507,7 +612,6 @@ where
                 );
                 if let Err(err) = self.storage.slash(
                     &pos_params,
-                    current_epoch,
                     evidence_epoch,
                     evidence_height,
                     slash_type,
@@ -519,23 +623,23 @@ where
         }
     }
 
-    /// INVARIANT: This method must be stateless.
-    #[cfg(feature = "abcipp")]
-    pub fn extend_vote(
-        &self,
-        _req: request::ExtendVote,
-    ) -> response::ExtendVote {
-        Default::default()
-    }
-
-    /// INVARIANT: This method must be stateless.
-    #[cfg(feature = "abcipp")]
-    pub fn verify_vote_extension(
-        &self,
-        _req: request::VerifyVoteExtension,
-    ) -> response::VerifyVoteExtension {
-        response::VerifyVoteExtension {
-            status: VerifyStatus::Accept as i32,
+    /// Apply the queued slashes whose cubic slashing window has passed. This
+    /// should be called at the beginning of a new epoch.
+    fn process_slashes(&mut self) {
+        let current_epoch = self.storage.block.epoch;
+        match self.storage.process_slashes(current_epoch) {
+            Ok(applied) => {
+                for queued in applied {
+                    tracing::info!(
+                        "Slashed {} for {} in epoch {} with rate {}",
+                        queued.validator,
+                        queued.slash.r#type,
+                        queued.slash.epoch,
+                        queued.slash.rate
+                    );
+                }
+            }
+            Err(err) => tracing::error!("Error in processing slashes: {}", err),
         }
     }
 
@@ -562,25 +666,160 @@ where
             self.storage.last_height,
         );
         response.data = root.0;
+
+        // Queue the Ethereum events observed since the last block, to be
+        // voted on in the next vote extension
+        let next_nonce = self
+            .read_storage_key(
+                &eth_bridge::storage::transfers_to_namada_nonce_key(),
+            )
+            .unwrap_or_default();
+        if let ShellMode::Validator { ethereum_recv, .. } = &mut self.mode {
+            ethereum_recv.fill_queue();
+            ethereum_recv.remove_applied(next_nonce);
+        }
+        self.advance_dkg_session();
+        self.broadcast_decryption_shares();
+        self.take_snapshot();
+        self.prune_storage();
         response
     }
 
+    /// Prune the data of the old block heights from the DB, according to the
+    /// configured policy.
+    fn prune_storage(&mut self) {
+        let (keep_recent, keep_every) = match self.storage_pruning {
+            config::StoragePruning::Archive => return,
+            config::StoragePruning::KeepRecent { keep_recent } => {
+                (keep_recent, None)
+            }
+            config::StoragePruning::KeepEvery {
+                keep_recent,
+                keep_every,
+            } => (keep_recent, Some(keep_every)),
+        };
+        let kept_heights = keep_recent.max(MIN_KEPT_HEIGHTS);
+        let before = match self.storage.last_height.0.checked_sub(kept_heights)
+        {
+            Some(height) => BlockHeight(height + 1),
+            None => return,
+        };
+        if let Err(err) = self.storage.db.prune(before, keep_every) {
+            tracing::error!(
+                "Failed to prune the block heights before {}: {}",
+                before,
+                err
+            );
+        }
+    }
+
     /// Validate a transaction request. On success, the transaction will
     /// included in the mempool and propagated to peers, otherwise it will be
     /// rejected.
+    ///
+    /// Only signed wrapper txs are accepted from users. The ciphertext of a
+    /// wrapper is only validated when the tx is new, as it cannot change, but
+    /// the fee payer's balance is checked again on every recheck, as it may
+    /// have been spent in the meantime. Validators may also submit their DKG
+    /// messages and decryption shares, which are checked against the current
+    /// state of the DKG.
+    ///
+    /// Error codes:
+    ///   0: Ok
+    ///   1: Invalid tx
+    ///   2: Tx is invalidly signed
     pub fn mempool_validate(
         &self,
         tx_bytes: &[u8],
-        r#_type: MempoolTxType,
+        r#type: MempoolTxType,
     ) -> response::CheckTx {
         let mut response = response::CheckTx::default();
-        match Tx::try_from(tx_bytes).map_err(Error::TxDecoding) {
-            Ok(_) => response.log = String::from("Mempool validation passed"),
+        let tx = match Tx::try_from(tx_bytes).map_err(Error::TxDecoding) {
+            Ok(tx) => tx,
             Err(msg) => {
-                response.code = 1;
+                response.code = ErrorCodes::InvalidTx.into();
                 response.log = msg.to_string();
+                return response;
+            }
+        };
+
+        let wrapper = match process_tx(tx) {
+            Ok(TxType::Wrapper(wrapper)) => wrapper,
+            Ok(TxType::Protocol(protocol_tx)) => {
+                let result = match &protocol_tx.tx {
+                    ProtocolTxType::DKG(msg) => {
+                        self.validate_dkg_message(&protocol_tx.pk, msg)
+                    }
+                    ProtocolTxType::DecryptionShares(shares) => {
+                        self.validate_decryption_shares(&protocol_tx.pk, shares)
+                    }
+                    _ => Err(String::from(
+                        "Unsupported protocol tx: only DKG messages and \
+                         decryption shares may be submitted to the mempool",
+                    )),
+                };
+                match result {
+                    Ok(()) => {
+                        response.log = String::from("Mempool validation passed")
+                    }
+                    Err(err) => {
+                        response.code = ErrorCodes::InvalidTx.into();
+                        response.log = err;
+                    }
+                }
+                return response;
+            }
+            Ok(_) => {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = String::from(
+                    "Unsupported tx type: only wrapper txs may be submitted \
+                     to the mempool",
+                );
+                return response;
+            }
+            Err(err @ TxError::Deserialization(_)) => {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = err.to_string();
+                return response;
+            }
+            // This occurs if the wrapper signature is missing or invalid
+            Err(err) => {
+                response.code = ErrorCodes::InvalidSig.into();
+                response.log = err.to_string();
+                return response;
+            }
+        };
+
+        if let Err(err) = self.validate_wrapper_epoch(&wrapper) {
+            response.code = ErrorCodes::InvalidTx.into();
+            response.log = err;
+            return response;
+        }
+
+        if let MempoolTxType::NewTransaction = r#type {
+            if !wrapper.validate_ciphertext() {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = format!(
+                    "The ciphertext of the wrapped tx {} is invalid",
+                    wrapper.tx_hash
+                );
+                return response;
             }
         }
+
+        let fee_payer = wrapper_fee_payer(&wrapper);
+        let balance = self.get_balance(&wrapper.fee.token, &fee_payer);
+        if balance < wrapper.fee.amount {
+            response.code = ErrorCodes::InvalidTx.into();
+            response.log = format!(
+                "The fee payer {} doesn't have sufficient balance of {} to \
+                 pay the fee of {}, it only has {}",
+                fee_payer, wrapper.fee.token, wrapper.fee.amount, balance
+            );
+            return response;
+        }
+
+        response.log = String::from("Mempool validation passed");
         response
     }
 
@@ -722,6 +961,9 @@ mod test_utils {
     /// modifications for testing purposes
     pub(super) struct TestShell {
         pub shell: Shell<MockDB, Sha256Hasher>,
+        /// Sends Ethereum events to the shell, as if observed by the
+        /// validator
+        pub eth_sender: UnboundedSender<EthereumEvent>,
     }
 
     impl Deref for TestShell {
@@ -750,6 +992,8 @@ mod test_utils {
         /// receives any protocol txs sent by the shell.
         pub fn new() -> (Self, UnboundedReceiver<Vec<u8>>) {
             let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+            let (eth_sender, eth_receiver) =
+                tokio::sync::mpsc::unbounded_channel();
             let base_dir = tempdir().unwrap().as_ref().canonicalize().unwrap();
             let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
             let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
@@ -763,11 +1007,13 @@ mod test_utils {
                         ),
                         top_level_directory().join("wasm"),
                         sender,
+                        eth_receiver,
                         None,
                         vp_wasm_compilation_cache,
                         tx_wasm_compilation_cache,
                         address::nam(),
                     ),
+                    eth_sender,
                 },
                 receiver,
             )
@@ -855,6 +1101,8 @@ mod test_utils {
                 },
                 byzantine_validators: vec![],
                 txs: vec![],
+                proposer_address: vec![],
+                votes: vec![],
             }
         }
     }
@@ -866,6 +1114,7 @@ mod test_utils {
         let base_dir = tempdir().unwrap().as_ref().canonicalize().unwrap();
         // we have to use RocksDB for this test
         let (sender, _) = tokio::sync::mpsc::unbounded_channel();
+        let (_, eth_receiver) = tokio::sync::mpsc::unbounded_channel();
         let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
         let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
         let native_token = address::nam();
@@ -877,6 +1126,7 @@ mod test_utils {
             ),
             top_level_directory().join("wasm"),
             sender.clone(),
+            eth_receiver,
             None,
             vp_wasm_compilation_cache,
             tx_wasm_compilation_cache,
@@ -927,6 +1177,7 @@ mod test_utils {
 
         // Drop the shell
         std::mem::drop(shell);
+        let (_, eth_receiver) = tokio::sync::mpsc::unbounded_channel();
 
         // Reboot the shell and check that the queue was restored from DB
         let shell = Shell::<PersistentDB, PersistentStorageHasher>::new(
@@ -937,6 +1188,7 @@ mod test_utils {
             ),
             top_level_directory().join("wasm"),
             sender,
+            eth_receiver,
             None,
             vp_wasm_compilation_cache,
             tx_wasm_compilation_cache,
@@ -945,3 +1197,166 @@ mod test_utils {
         assert!(!shell.storage.tx_queue.is_empty());
     }
 }
+
+/// Test the mempool validation of txs in [`Shell::mempool_validate`].
+#[cfg(test)]
+mod test_mempool_validate {
+    use namada::proto::SignedTxData;
+    use namada::types::storage::Epoch;
+    use namada::types::transaction::Fee;
+
+    use super::test_utils::*;
+    use super::*;
+
+    /// Create a wrapper tx paying the given fee with the native token
+    fn wrapper(
+        shell: &TestShell,
+        keypair: &common::SecretKey,
+        fee: token::Amount,
+    ) -> WrapperTx {
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        WrapperTx::new(
+            Fee {
+                amount: fee,
+                token: shell.storage.native_token.clone(),
+            },
+            keypair,
+            Epoch(0),
+            0.into(),
+            tx,
+            Default::default(),
+        )
+    }
+
+    /// Set the balance of the fee payer of the given keypair
+    fn set_balance(
+        shell: &mut TestShell,
+        keypair: &common::SecretKey,
+        amount: token::Amount,
+    ) {
+        let balance_key = token::balance_key(
+            &shell.storage.native_token,
+            &Address::from(&keypair.ref_to()),
+        );
+        shell
+            .storage
+            .write(&balance_key, amount.try_to_vec().expect("Test failed"))
+            .expect("Test failed");
+    }
+
+    /// Test that txs that cannot be decoded and non-wrapper txs are rejected
+    #[test]
+    fn test_invalid_tx_types() {
+        let (shell, _) = setup();
+
+        let result = shell.mempool_validate(
+            "garbage".as_bytes(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+
+        let raw_tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let result = shell.mempool_validate(
+            &raw_tx.to_bytes(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+
+        let decrypted_tx =
+            Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(raw_tx)));
+        let result = shell.mempool_validate(
+            &decrypted_tx.to_bytes(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+    }
+
+    /// Test that unsigned and invalidly signed wrapper txs are rejected
+    #[test]
+    fn test_invalid_wrapper_sig() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        set_balance(&mut shell, &keypair, 100.into());
+        let wrapper = wrapper(&shell, &keypair, 10.into());
+
+        let unsigned = Tx::from(TxType::Wrapper(wrapper.clone()));
+        let result = shell.mempool_validate(
+            &unsigned.to_bytes(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidSig));
+
+        // tamper with the fee after signing
+        let signed = wrapper.sign(&keypair).expect("Test failed");
+        let mut signed_data = SignedTxData::try_from_slice(
+            signed.data.as_ref().expect("Test failed"),
+        )
+        .expect("Test failed");
+        let mut tampered = wrapper;
+        tampered.fee.amount = 0.into();
+        signed_data.data =
+            Some(TxType::Wrapper(tampered).try_to_vec().expect("Test failed"));
+        let tampered = Tx {
+            code: signed.code,
+            data: Some(signed_data.try_to_vec().expect("Test failed")),
+            timestamp: signed.timestamp,
+        };
+        let result = shell.mempool_validate(
+            &tampered.to_bytes(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidSig));
+    }
+
+    /// Test that a wrapper tx is only accepted if the fee payer has
+    /// sufficient balance, both when it's new and when it's re-checked
+    #[test]
+    fn test_wrapper_fee_balance() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let tx = wrapper(&shell, &keypair, 10.into())
+            .sign(&keypair)
+            .expect("Test failed")
+            .to_bytes();
+
+        let result = shell.mempool_validate(&tx, MempoolTxType::NewTransaction);
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+
+        set_balance(&mut shell, &keypair, 10.into());
+        let result = shell.mempool_validate(&tx, MempoolTxType::NewTransaction);
+        assert_eq!(result.code, u32::from(ErrorCodes::Ok));
+
+        // the fee payer spends its balance before the tx is included
+        set_balance(&mut shell, &keypair, 9.into());
+        let result =
+            shell.mempool_validate(&tx, MempoolTxType::RecheckTransaction);
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+    }
+
+    /// Test that a wrapper tx is rejected once it is no longer encrypted
+    /// with the key of the current epoch
+    #[test]
+    fn test_wrapper_epoch() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        set_balance(&mut shell, &keypair, 10.into());
+        let tx = wrapper(&shell, &keypair, 10.into())
+            .sign(&keypair)
+            .expect("Test failed")
+            .to_bytes();
+
+        let result = shell.mempool_validate(&tx, MempoolTxType::NewTransaction);
+        assert_eq!(result.code, u32::from(ErrorCodes::Ok));
+
+        shell.storage.block.epoch = Epoch(1);
+        let result =
+            shell.mempool_validate(&tx, MempoolTxType::RecheckTransaction);
+        assert_eq!(result.code, u32::from(ErrorCodes::InvalidTx));
+    }
+}
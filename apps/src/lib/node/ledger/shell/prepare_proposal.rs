@@ -2,15 +2,14 @@
 
 use namada::ledger::storage::{DBIter, StorageHasher, DB};
 use namada::proto::Tx;
+use namada::types::transaction::protocol::{ProtocolTx, ProtocolTxType};
 use namada::types::transaction::tx_types::TxType;
-use namada::types::transaction::wrapper::wrapper_tx::PairingEngine;
-use namada::types::transaction::{AffineCurve, DecryptedTx, EllipticCurve};
 
 use super::super::*;
 use crate::facade::tendermint_proto::abci::RequestPrepareProposal;
 #[cfg(feature = "abcipp")]
 use crate::facade::tendermint_proto::abci::{tx_record::TxAction, TxRecord};
-use crate::node::ledger::shell::{process_tx, ShellMode};
+use crate::node::ledger::shell::{process_tx, ShellMode, TxError};
 use crate::node::ledger::shims::abcipp_shim_types::shim::TxBytes;
 
 // TODO: remove this hard-coded value; Tendermint, and thus
@@ -27,10 +26,12 @@ where
 {
     /// Begin a new block.
     ///
-    /// We fill half the block space with new wrapper txs given to us
-    /// from the mempool by tendermint. The rest of the block is filled
-    /// with decryptions of the wrapper txs from the previously
-    /// committed block.
+    /// We fill half the block space with new wrapper txs and the validators'
+    /// DKG messages and decryption shares given to us from the mempool by
+    /// tendermint. The rest of the block is filled with decryptions of the
+    /// wrapper txs at the front of the tx queue that can be decrypted. With
+    /// ABCI++, the block starts with a protocol tx aggregating the Ethereum
+    /// events from the validators' vote extensions.
     ///
     /// INVARIANT: Any changes applied in this method must be reverted if
     /// the proposal is rejected (unless we can simply overwrite
@@ -40,20 +41,17 @@ where
         req: RequestPrepareProposal,
     ) -> response::PrepareProposal {
         let txs = if let ShellMode::Validator { .. } = self.mode {
-            // TODO: This should not be hardcoded
-            let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
-
-            // TODO: Craft the Ethereum state update tx
             // filter in half of the new txs from Tendermint, only keeping
-            // wrappers
+            // wrappers and the validators' DKG protocol txs
             let mut total_proposal_size = 0;
             #[cfg(feature = "abcipp")]
             let mut txs: Vec<TxRecord> = req
                 .txs
                 .into_iter()
                 .map(|tx_bytes| {
-                    if let Ok(Ok(TxType::Wrapper(_))) =
-                        Tx::try_from(tx_bytes.as_slice()).map(process_tx)
+                    if Tx::try_from(tx_bytes.as_slice())
+                        .map(process_tx)
+                        .map_or(false, |tx| self.is_mempool_tx(&tx))
                     {
                         record::keep(tx_bytes)
                     } else {
@@ -77,8 +75,9 @@ where
                 .txs
                 .into_iter()
                 .filter_map(|tx_bytes| {
-                    if let Ok(Ok(TxType::Wrapper(_))) =
-                        Tx::try_from(tx_bytes.as_slice()).map(process_tx)
+                    if Tx::try_from(tx_bytes.as_slice())
+                        .map(process_tx)
+                        .map_or(false, |tx| self.is_mempool_tx(&tx))
                     {
                         Some(tx_bytes)
                     } else {
@@ -96,14 +95,14 @@ where
                 })
                 .collect();
 
-            // decrypt the wrapper txs included in the previous block
-            let decrypted_txs = self.storage.tx_queue.iter().map(|tx| {
-                Tx::from(match tx.decrypt(privkey) {
-                    Ok(tx) => DecryptedTx::Decrypted(tx),
-                    _ => DecryptedTx::Undecryptable(tx.clone()),
-                })
-                .to_bytes()
-            });
+            // decrypt the wrapper txs included in the previous blocks, in
+            // order, until one of them is waiting for decryption shares
+            let decrypted_txs = self
+                .storage
+                .tx_queue
+                .iter()
+                .map_while(|tx| self.decrypt_wrapper(tx))
+                .map(|tx| Tx::from(tx).to_bytes());
             #[cfg(feature = "abcipp")]
             let mut decrypted_txs: Vec<_> =
                 decrypted_txs.map(record::add).collect();
@@ -111,6 +110,13 @@ where
             let mut decrypted_txs: Vec<_> = decrypted_txs.collect();
 
             txs.append(&mut decrypted_txs);
+
+            // the Ethereum events voted on in the vote extensions of the
+            // last block are applied before any other tx
+            #[cfg(feature = "abcipp")]
+            if let Some(tx) = self.build_eth_events_tx(req.local_last_commit) {
+                txs.insert(0, record::add(tx));
+            }
             txs
         } else {
             vec![]
@@ -128,6 +134,21 @@ where
             response::PrepareProposal { txs }
         }
     }
+
+    /// Whether a tx from the mempool may be included in a proposal. The
+    /// wrapper txs must be encrypted with the key of the current epoch.
+    fn is_mempool_tx(&self, tx: &std::result::Result<TxType, TxError>) -> bool {
+        match tx {
+            Ok(TxType::Wrapper(wrapper)) => {
+                self.validate_wrapper_epoch(wrapper).is_ok()
+            }
+            Ok(TxType::Protocol(ProtocolTx {
+                tx: ProtocolTxType::DKG(_) | ProtocolTxType::DecryptionShares(_),
+                ..
+            })) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Functions for creating the appropriate TxRecord given the
@@ -167,7 +188,7 @@ pub(super) mod record {
 mod test_prepare_proposal {
     use borsh::BorshSerialize;
     use namada::types::storage::Epoch;
-    use namada::types::transaction::{Fee, WrapperTx};
+    use namada::types::transaction::{DecryptedTx, Fee, WrapperTx};
 
     use super::*;
     use crate::node::ledger::shell::test_utils::{gen_keypair, TestShell};
@@ -1,6 +1,8 @@
 //! Implementation of the ['VerifyHeader`], [`ProcessProposal`],
 //! and [`RevertProposal`] ABCI++ methods for the Shell
 
+use namada::types::transaction::protocol::{ProtocolTx, ProtocolTxType};
+
 use super::*;
 use crate::facade::tendermint_proto::abci::response_process_proposal::ProposalStatus;
 use crate::facade::tendermint_proto::abci::RequestProcessProposal;
@@ -39,29 +41,37 @@ where
         }
     }
 
-    /// Check all the given txs.
+    /// Check all the given txs. Only the wrapper txs at the front of the tx
+    /// queue that can be decrypted may be included.
     pub fn process_txs(&self, txs: &[Vec<u8>]) -> Vec<TxResult> {
-        let mut tx_queue_iter = self.storage.tx_queue.iter();
+        let mut tx_queue_iter = self
+            .storage
+            .tx_queue
+            .iter()
+            .take_while(|wrapper| self.can_decrypt_wrapper(wrapper));
         txs.iter()
-            .map(|tx_bytes| {
-                self.process_single_tx(tx_bytes, &mut tx_queue_iter)
+            .enumerate()
+            .map(|(tx_index, tx_bytes)| {
+                self.process_single_tx(tx_bytes, tx_index, &mut tx_queue_iter)
             })
             .collect()
     }
 
     /// Checks if the Tx can be deserialized from bytes. Checks the fees and
-    /// signatures of the fee payer for a transaction if it is a wrapper tx.
+    /// signatures of the fee payer for a transaction if it is a wrapper tx
+    /// and that it is encrypted with the key of the current epoch.
     ///
     /// Checks validity of a decrypted tx or that a tx marked un-decryptable
     /// is in fact so. Also checks that decrypted txs were submitted in
-    /// correct order.
+    /// correct order, and that the Ethereum events tx, if any, is the first
+    /// tx of the block (`tx_index` is the position of the tx in the block).
     ///
     /// Error codes:
     ///   0: Ok
     ///   1: Invalid tx
     ///   2: Tx is invalidly signed
     ///   3: Wasm runtime error
-    ///   4: Invalid order of decrypted txs
+    ///   4: Invalid order of decrypted txs or of the Ethereum events tx
     ///   5. More decrypted txs than expected
     ///
     /// INVARIANT: Any changes applied in this method must be reverted if the
@@ -70,6 +80,7 @@ where
     pub(crate) fn process_single_tx<'a>(
         &self,
         tx_bytes: &[u8],
+        tx_index: usize,
         tx_queue_iter: &mut impl Iterator<Item = &'a WrapperTx>,
     ) -> TxResult {
         let tx = match Tx::try_from(tx_bytes) {
@@ -82,9 +93,6 @@ where
                 };
             }
         };
-        // TODO: This should not be hardcoded
-        let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
-
         match process_tx(tx) {
             // This occurs if the wrapper / protocol tx signature is invalid
             Err(err) => TxResult {
@@ -99,6 +107,62 @@ where
                            are not supported"
                         .into(),
                 },
+                TxType::Protocol(ProtocolTx {
+                    tx: ProtocolTxType::EthereumEvents(digest),
+                    ..
+                }) => {
+                    // the events must be applied before any other tx, so
+                    // this also rules out a second Ethereum events tx
+                    if tx_index != 0 {
+                        TxResult {
+                            code: ErrorCodes::InvalidOrder.into(),
+                            info: "The Ethereum events tx must be the first \
+                                   tx of the block"
+                                .into(),
+                        }
+                    } else if self.validate_eth_events_digest(&digest) {
+                        TxResult {
+                            code: ErrorCodes::Ok.into(),
+                            info: "Process proposal accepted this transaction"
+                                .into(),
+                        }
+                    } else {
+                        TxResult {
+                            code: ErrorCodes::InvalidTx.into(),
+                            info: "The Ethereum events tx contains invalid \
+                                   vote extensions"
+                                .into(),
+                        }
+                    }
+                }
+                TxType::Protocol(ProtocolTx {
+                    pk,
+                    tx: ProtocolTxType::DKG(msg),
+                }) => match self.validate_dkg_message(&pk, &msg) {
+                    Ok(()) => TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "Process proposal accepted this transaction"
+                            .into(),
+                    },
+                    Err(err) => TxResult {
+                        code: ErrorCodes::InvalidTx.into(),
+                        info: err,
+                    },
+                },
+                TxType::Protocol(ProtocolTx {
+                    pk,
+                    tx: ProtocolTxType::DecryptionShares(shares),
+                }) => match self.validate_decryption_shares(&pk, &shares) {
+                    Ok(()) => TxResult {
+                        code: ErrorCodes::Ok.into(),
+                        info: "Process proposal accepted this transaction"
+                            .into(),
+                    },
+                    Err(err) => TxResult {
+                        code: ErrorCodes::InvalidTx.into(),
+                        info: err,
+                    },
+                },
                 TxType::Protocol(_) => TxResult {
                     code: ErrorCodes::InvalidTx.into(),
                     info: "Protocol transactions are a fun new feature that \
@@ -115,7 +179,8 @@ where
                                        determined in the previous block"
                                     .into(),
                             }
-                        } else if verify_decrypted_correctly(&tx, privkey) {
+                        } else if self.verify_decrypted_correctly(&tx, wrapper)
+                        {
                             TxResult {
                                 code: ErrorCodes::Ok.into(),
                                 info: "Process Proposal accepted this \
@@ -138,8 +203,14 @@ where
                     },
                 },
                 TxType::Wrapper(tx) => {
-                    // validate the ciphertext via Ferveo
-                    if !tx.validate_ciphertext() {
+                    // check that the tx is encrypted with the key of the
+                    // current epoch and validate the ciphertext via Ferveo
+                    if let Err(err) = self.validate_wrapper_epoch(&tx) {
+                        TxResult {
+                            code: ErrorCodes::InvalidTx.into(),
+                            info: err,
+                        }
+                    } else if !tx.validate_ciphertext() {
                         TxResult {
                             code: ErrorCodes::InvalidTx.into(),
                             info: format!(
@@ -149,15 +220,7 @@ where
                             ),
                         }
                     } else {
-                        // If the public key corresponds to the MASP sentinel
-                        // transaction key, then the fee payer is effectively
-                        // the MASP, otherwise derive
-                        // they payer from public key.
-                        let fee_payer = if tx.pk != masp_tx_key().ref_to() {
-                            tx.fee_payer()
-                        } else {
-                            masp()
-                        };
+                        let fee_payer = wrapper_fee_payer(&tx);
                         // check that the fee payer has sufficient balance
                         let balance =
                             self.get_balance(&tx.fee.token, &fee_payer);
@@ -439,6 +502,49 @@ mod test_process_proposal {
         );
     }
 
+    /// Test that a wrapper tx that is not encrypted with the key of the
+    /// current epoch is rejected by [`process_proposal`]
+    #[test]
+    fn test_wrapper_wrong_epoch() {
+        let (mut shell, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: shell.storage.native_token.clone(),
+            },
+            &keypair,
+            Epoch(1),
+            0.into(),
+            tx,
+            Default::default(),
+        )
+        .sign(&keypair)
+        .expect("Test failed");
+
+        let request = ProcessProposal {
+            txs: vec![wrapper.to_bytes()],
+        };
+        let response = if let [resp] = shell
+            .process_proposal(request)
+            .expect("Test failed")
+            .as_slice()
+        {
+            resp.clone()
+        } else {
+            panic!("Test failed")
+        };
+        assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidTx));
+        assert!(response
+            .result
+            .info
+            .contains("is encrypted with the key of epoch 1"));
+    }
+
     /// Test that if the expected order of decrypted txs is
     /// validated, [`process_proposal`] rejects it
     #[test]
@@ -0,0 +1,880 @@
+//! Implementation of the state sync ABCI methods for the Shell.
+//!
+//! When the `snapshot_interval` is configured, the node exports a snapshot of
+//! its state after committing every block whose height is a multiple of the
+//! interval. The snapshot is taken from a checkpoint of the DB in a background
+//! thread, which streams the block's metadata followed by the key-vals of the
+//! account subspaces to disk. It's served to other nodes in chunks, each of
+//! which is hashed in the snapshot's metadata, so that a fresh node can verify
+//! the chunks as they arrive. The restored chunks are written to a file on
+//! disk. Once it has all of them, the node streams the file to rebuild the
+//! merkle tree from the key-vals of the subspaces and checks that it commits
+//! to the trusted app hash and to the block's metadata, before streaming the
+//! state into its DB.
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use namada::ledger::parameters;
+use namada::ledger::storage::{
+    BlockStateWrite, MerkleTree, MerkleTreeStoresRead,
+};
+use namada::types::address::EstablishedAddressGen;
+use namada::types::hash::Hash;
+use namada::types::internal::TxQueue;
+use namada::types::storage::{BlockHash, BlockResults, Epoch, Epochs};
+use sha2::{Digest, Sha256};
+
+use super::*;
+use crate::facade::tendermint_proto::abci::{
+    response_apply_snapshot_chunk, response_offer_snapshot,
+    RequestApplySnapshotChunk, RequestLoadSnapshotChunk, RequestOfferSnapshot,
+    ResponseApplySnapshotChunk, ResponseListSnapshots,
+    ResponseLoadSnapshotChunk, ResponseOfferSnapshot, Snapshot,
+};
+
+/// The version of the snapshots' encoding
+const SNAPSHOT_FORMAT: u32 = 2;
+
+/// The maximum size of a snapshot chunk in bytes. Tendermint limits the size
+/// of a chunk to 16 MiB.
+const SNAPSHOT_CHUNK_SIZE: usize = 10 << 20;
+
+/// The number of the most recent snapshots kept on disk
+const SNAPSHOTS_TO_KEEP: usize = 2;
+
+/// The name of the directory of the DB checkpoint a snapshot is taken from
+const CHECKPOINT_DIR: &str = "checkpoint";
+
+/// The name of the file the chunks of a snapshot being restored are written to
+const RESTORE_FILE: &str = "restore.snapshot";
+
+/// The metadata of the block at a committed height. In a snapshot, its
+/// encoding is prefixed with its length and followed by the key-vals of the
+/// account subspaces, so that a snapshot can be read as a stream.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SnapshotState {
+    height: BlockHeight,
+    hash: BlockHash,
+    epoch: Epoch,
+    pred_epochs: Epochs,
+    results: BlockResults,
+    next_epoch_min_start_height: BlockHeight,
+    next_epoch_min_start_time: DateTimeUtc,
+    address_gen: EstablishedAddressGen,
+    tx_queue: TxQueue,
+}
+
+/// The metadata of a snapshot written on disk
+#[derive(BorshSerialize, BorshDeserialize)]
+struct SnapshotMetadata {
+    /// The hash of the whole snapshot
+    hash: Hash,
+    /// The hashes of the snapshot's chunks, in order
+    chunk_hashes: Vec<Hash>,
+}
+
+/// A snapshot being restored from the chunks received from other nodes
+#[derive(Debug)]
+pub(super) struct SnapshotRestore {
+    height: BlockHeight,
+    hash: Hash,
+    /// The app hash of the snapshot's height, trusted by Tendermint's light
+    /// client
+    app_hash: Vec<u8>,
+    chunk_hashes: Vec<Hash>,
+    /// The file the chunks are written to, each at its offset
+    file: File,
+    /// Whether each chunk has been received
+    received: Vec<bool>,
+}
+
+/// A writer of a snapshot that hashes it and its chunks as they're written
+struct SnapshotWriter<W> {
+    writer: W,
+    hasher: Sha256,
+    chunk_hasher: Sha256,
+    chunk_len: usize,
+    chunk_hashes: Vec<Hash>,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            hasher: Sha256::new(),
+            chunk_hasher: Sha256::new(),
+            chunk_len: 0,
+            chunk_hashes: vec![],
+        }
+    }
+
+    /// Hash the current chunk and start the next one
+    fn finish_chunk(&mut self) {
+        let chunk_hasher = std::mem::take(&mut self.chunk_hasher);
+        self.chunk_hashes.push(Hash(chunk_hasher.finalize().into()));
+        self.chunk_len = 0;
+    }
+
+    /// Flush the snapshot and return its metadata
+    fn finish(mut self) -> std::io::Result<SnapshotMetadata> {
+        self.writer.flush()?;
+        if self.chunk_len > 0 {
+            self.finish_chunk();
+        }
+        Ok(SnapshotMetadata {
+            hash: Hash(self.hasher.finalize().into()),
+            chunk_hashes: self.chunk_hashes,
+        })
+    }
+}
+
+impl<W: Write> Write for SnapshotWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // A single write doesn't cross the end of a chunk
+        let len = buf.len().min(SNAPSHOT_CHUNK_SIZE - self.chunk_len);
+        let written = self.writer.write(&buf[..len])?;
+        self.hasher.update(&buf[..written]);
+        self.chunk_hasher.update(&buf[..written]);
+        self.chunk_len += written;
+        if self.chunk_len == SNAPSHOT_CHUNK_SIZE {
+            self.finish_chunk();
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// The paths of the state and of the metadata of the snapshot of a height
+fn snapshot_paths(
+    snapshots_dir: &Path,
+    height: BlockHeight,
+) -> (PathBuf, PathBuf) {
+    (
+        snapshots_dir.join(format!("{}.snapshot", height)),
+        snapshots_dir.join(format!("{}.meta", height)),
+    )
+}
+
+/// The heights of the snapshots on disk, in ascending order
+fn snapshot_heights(snapshots_dir: &Path) -> Vec<BlockHeight> {
+    let mut heights: Vec<BlockHeight> = fs::read_dir(snapshots_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()?.to_str()? != "meta" {
+                return None;
+            }
+            path.file_stem()?.to_str()?.parse().ok().map(BlockHeight)
+        })
+        .collect();
+    heights.sort();
+    heights
+}
+
+/// Stream the snapshot of the given block's metadata and of the account
+/// subspaces in the DB checkpoint to disk
+fn write_snapshot<C>(
+    snapshots_dir: &Path,
+    state: &SnapshotState,
+    checkpoint: &C,
+) -> std::io::Result<()>
+where
+    C: for<'iter> DBIter<'iter>,
+{
+    let (state_path, metadata_path) =
+        snapshot_paths(snapshots_dir, state.height);
+    let mut writer =
+        SnapshotWriter::new(BufWriter::new(File::create(state_path)?));
+    state.try_to_vec()?.serialize(&mut writer)?;
+    for (key, value, _gas) in checkpoint.iter_prefix(&Key::default()) {
+        (key, value).serialize(&mut writer)?;
+    }
+    let metadata = writer.finish()?;
+    // The metadata is written last, as it marks the snapshot as complete
+    fs::write(metadata_path, metadata.try_to_vec()?)
+}
+
+/// Read an item of a snapshot, which is prefixed with its Borsh encoded
+/// length. Returns `None` at the end of the snapshot.
+fn read_snapshot_item(
+    reader: &mut impl Read,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    let mut item = Vec::with_capacity(len.min(SNAPSHOT_CHUNK_SIZE));
+    reader.take(len as u64).read_to_end(&mut item)?;
+    if item.len() != len {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some(item))
+}
+
+/// Read the block's metadata at the start of a snapshot
+fn read_snapshot_state(
+    reader: &mut impl Read,
+) -> std::result::Result<SnapshotState, String> {
+    let state = read_snapshot_item(reader)
+        .map_err(|err| format!("Invalid snapshot: {}", err))?
+        .ok_or_else(|| "The snapshot is empty".to_string())?;
+    SnapshotState::try_from_slice(&state)
+        .map_err(|err| format!("Invalid snapshot: {}", err))
+}
+
+/// Read the next key-val of the account subspaces in a snapshot. Returns
+/// `None` at the end of the snapshot.
+fn read_snapshot_key_val(
+    reader: &mut impl Read,
+) -> std::result::Result<Option<(Key, Vec<u8>)>, String> {
+    let invalid = |err: std::io::Error| format!("Invalid snapshot: {}", err);
+    let key = match read_snapshot_item(reader).map_err(invalid)? {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+    let value = read_snapshot_item(reader)
+        .map_err(invalid)?
+        .ok_or_else(|| "Invalid snapshot: a key has no value".to_string())?;
+    let key = String::from_utf8(key)
+        .map_err(|err| format!("Invalid storage key: {}", err))?;
+    let key = Key::parse(key)
+        .map_err(|err| format!("Invalid storage key: {}", err))?;
+    Ok(Some((key, value)))
+}
+
+/// Hash the whole snapshot being restored
+fn hash_snapshot_file(path: &Path) -> std::io::Result<Hash> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let len = reader.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        hasher.update(&buf[..len]);
+    }
+    Ok(Hash(hasher.finalize().into()))
+}
+
+/// Delete the snapshots older than the most recent ones that are kept
+fn prune_snapshots(snapshots_dir: &Path) {
+    let heights = snapshot_heights(snapshots_dir);
+    let outdated = heights.len().saturating_sub(SNAPSHOTS_TO_KEEP);
+    for height in &heights[..outdated] {
+        let (state_path, metadata_path) =
+            snapshot_paths(snapshots_dir, *height);
+        let _ = fs::remove_file(metadata_path);
+        let _ = fs::remove_file(state_path);
+    }
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Take a snapshot of the last committed state in the background, if the
+    /// configured interval has elapsed, and delete the older snapshots. A
+    /// snapshot is skipped if the previous one is still being taken.
+    pub(super) fn take_snapshot(&mut self) {
+        let height = self.storage.last_height;
+        match self.snapshot_interval {
+            Some(interval) if interval > 0 && height.0 % interval == 0 => {}
+            _ => return,
+        }
+        if matches!(&self.snapshot_task, Some(task) if !task.is_finished()) {
+            tracing::info!(
+                "Skipped the snapshot at height {}, as the previous one is \
+                 still being taken",
+                height
+            );
+            return;
+        }
+        let snapshots_dir = self.snapshots_dir.clone();
+        let checkpoint_dir = snapshots_dir.join(CHECKPOINT_DIR);
+        // A checkpoint may be left over if the node was stopped while taking
+        // a snapshot
+        let _ = fs::remove_dir_all(&checkpoint_dir);
+        let checkpoint = fs::create_dir_all(&snapshots_dir)
+            .map_err(|err| err.to_string())
+            .and_then(|()| {
+                self.storage
+                    .db
+                    .checkpoint(&checkpoint_dir)
+                    .map_err(|err| err.to_string())
+            });
+        let checkpoint = match checkpoint {
+            Ok(checkpoint) => checkpoint,
+            Err(err) => {
+                tracing::error!(
+                    "Failed to checkpoint the DB for a snapshot at height {}: \
+                     {}",
+                    height,
+                    err
+                );
+                return;
+            }
+        };
+        let state = SnapshotState {
+            height,
+            hash: self.storage.block.hash.clone(),
+            epoch: self.storage.last_epoch,
+            pred_epochs: self.storage.block.pred_epochs.clone(),
+            results: self.storage.block.results.clone(),
+            next_epoch_min_start_height: self
+                .storage
+                .next_epoch_min_start_height,
+            next_epoch_min_start_time: self.storage.next_epoch_min_start_time,
+            address_gen: self.storage.address_gen.clone(),
+            tx_queue: self.storage.tx_queue.clone(),
+        };
+        self.snapshot_task = Some(std::thread::spawn(move || {
+            let result = write_snapshot(&snapshots_dir, &state, &checkpoint);
+            drop(checkpoint);
+            let _ = fs::remove_dir_all(&checkpoint_dir);
+            match result {
+                Ok(()) => {
+                    tracing::info!("Took a snapshot at height {}", height);
+                    prune_snapshots(&snapshots_dir);
+                }
+                Err(err) => {
+                    let (state_path, _) =
+                        snapshot_paths(&snapshots_dir, height);
+                    let _ = fs::remove_file(state_path);
+                    tracing::error!(
+                        "Failed to take a snapshot at height {}: {}",
+                        height,
+                        err
+                    );
+                }
+            }
+        }));
+    }
+
+    /// List the snapshots available on disk
+    pub fn list_snapshots(&self) -> ResponseListSnapshots {
+        let snapshots = snapshot_heights(&self.snapshots_dir)
+            .into_iter()
+            .filter_map(|height| {
+                let (_, metadata_path) =
+                    snapshot_paths(&self.snapshots_dir, height);
+                let metadata = fs::read(metadata_path).ok()?;
+                let SnapshotMetadata { hash, chunk_hashes } =
+                    SnapshotMetadata::try_from_slice(&metadata).ok()?;
+                Some(Snapshot {
+                    height: height.0,
+                    format: SNAPSHOT_FORMAT,
+                    chunks: chunk_hashes.len() as u32,
+                    hash: hash.0.to_vec(),
+                    metadata: chunk_hashes.try_to_vec().ok()?,
+                })
+            })
+            .collect();
+        ResponseListSnapshots { snapshots }
+    }
+
+    /// Load a chunk of a snapshot available on disk. An empty chunk is
+    /// returned if it's not available.
+    pub fn load_snapshot_chunk(
+        &self,
+        req: RequestLoadSnapshotChunk,
+    ) -> ResponseLoadSnapshotChunk {
+        let mut response = ResponseLoadSnapshotChunk::default();
+        if req.format != SNAPSHOT_FORMAT {
+            return response;
+        }
+        let (state_path, _) =
+            snapshot_paths(&self.snapshots_dir, BlockHeight(req.height));
+        let offset = req.chunk as u64 * SNAPSHOT_CHUNK_SIZE as u64;
+        let chunk = File::open(state_path).and_then(|mut file| {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut chunk = Vec::with_capacity(SNAPSHOT_CHUNK_SIZE);
+            file.take(SNAPSHOT_CHUNK_SIZE as u64)
+                .read_to_end(&mut chunk)?;
+            Ok(chunk)
+        });
+        match chunk {
+            Ok(chunk) => response.chunk = chunk,
+            Err(err) => tracing::error!(
+                "Failed to load the chunk {} of the snapshot at height {}: {}",
+                req.chunk,
+                req.height,
+                err
+            ),
+        }
+        response
+    }
+
+    /// Decide whether to restore the state from a snapshot offered by
+    /// Tendermint. Only a node without any state can be restored.
+    pub fn offer_snapshot(
+        &mut self,
+        req: RequestOfferSnapshot,
+    ) -> ResponseOfferSnapshot {
+        use response_offer_snapshot::Result as OfferResult;
+
+        let result = match req.snapshot {
+            _ if self.storage.last_height.0 != 0 => {
+                tracing::error!(
+                    "A snapshot was offered to a node that already has a state"
+                );
+                OfferResult::Abort
+            }
+            Some(snapshot) if snapshot.format != SNAPSHOT_FORMAT => {
+                OfferResult::RejectFormat
+            }
+            Some(snapshot) => {
+                match (
+                    Hash::try_from(snapshot.hash.as_slice()),
+                    Vec::<Hash>::try_from_slice(&snapshot.metadata),
+                ) {
+                    (Ok(hash), Ok(chunk_hashes))
+                        if chunk_hashes.len() == snapshot.chunks as usize
+                            && !chunk_hashes.is_empty() =>
+                    {
+                        let file = fs::create_dir_all(&self.snapshots_dir)
+                            .and_then(|()| {
+                                File::create(
+                                    self.snapshots_dir.join(RESTORE_FILE),
+                                )
+                            });
+                        match file {
+                            Ok(file) => {
+                                tracing::info!(
+                                    "Restoring the state from the snapshot at \
+                                     height {}",
+                                    snapshot.height
+                                );
+                                self.snapshot_restore = Some(SnapshotRestore {
+                                    height: BlockHeight(snapshot.height),
+                                    hash,
+                                    app_hash: req.app_hash,
+                                    file,
+                                    received: vec![false; chunk_hashes.len()],
+                                    chunk_hashes,
+                                });
+                                OfferResult::Accept
+                            }
+                            Err(err) => {
+                                tracing::error!(
+                                    "Failed to create the file of the \
+                                     snapshot being restored: {}",
+                                    err
+                                );
+                                OfferResult::Abort
+                            }
+                        }
+                    }
+                    _ => OfferResult::Reject,
+                }
+            }
+            None => OfferResult::Reject,
+        };
+        ResponseOfferSnapshot {
+            result: result as i32,
+        }
+    }
+
+    /// Apply a chunk of the snapshot being restored. Every chunk is verified
+    /// against its hash and written to disk. Once all the chunks have been
+    /// received, the state is verified and written to the DB.
+    pub fn apply_snapshot_chunk(
+        &mut self,
+        req: RequestApplySnapshotChunk,
+    ) -> ResponseApplySnapshotChunk {
+        use response_apply_snapshot_chunk::Result as ApplyResult;
+
+        let mut response = ResponseApplySnapshotChunk::default();
+        let restore = match self.snapshot_restore.as_mut() {
+            Some(restore) => restore,
+            None => {
+                response.result = ApplyResult::Abort as i32;
+                return response;
+            }
+        };
+        let index = req.index as usize;
+        match restore.chunk_hashes.get(index) {
+            Some(hash) if hash == &Hash::sha256(&req.chunk) => {
+                let offset = index as u64 * SNAPSHOT_CHUNK_SIZE as u64;
+                let written = restore
+                    .file
+                    .seek(SeekFrom::Start(offset))
+                    .and_then(|_| restore.file.write_all(&req.chunk));
+                if let Err(err) = written {
+                    tracing::error!(
+                        "Failed to write the chunk {} of the snapshot: {}",
+                        index,
+                        err
+                    );
+                    response.result = ApplyResult::Abort as i32;
+                    self.snapshot_restore = None;
+                    return response;
+                }
+                restore.received[index] = true;
+            }
+            Some(_) => {
+                tracing::info!(
+                    "Received an invalid chunk {} of the snapshot from {}",
+                    index,
+                    req.sender
+                );
+                response.result = ApplyResult::Retry as i32;
+                response.refetch_chunks = vec![req.index];
+                response.reject_senders = vec![req.sender];
+                return response;
+            }
+            None => {
+                response.result = ApplyResult::RejectSnapshot as i32;
+                self.snapshot_restore = None;
+                return response;
+            }
+        }
+        if restore.received.iter().any(|received| !received) {
+            response.result = ApplyResult::Accept as i32;
+            return response;
+        }
+
+        let restore = self
+            .snapshot_restore
+            .take()
+            .expect("The snapshot being restored should be set");
+        let path = self.snapshots_dir.join(RESTORE_FILE);
+        let result = restore
+            .file
+            .sync_all()
+            .and_then(|()| hash_snapshot_file(&path))
+            .map_err(|err| err.to_string())
+            .and_then(|hash| {
+                if hash == restore.hash {
+                    Ok(())
+                } else {
+                    Err("The snapshot doesn't match its hash".to_string())
+                }
+            })
+            .and_then(|()| {
+                self.restore_snapshot_state(
+                    restore.height,
+                    &path,
+                    &restore.app_hash,
+                )
+            });
+        let _ = fs::remove_file(&path);
+        match result {
+            Ok(()) => {
+                tracing::info!(
+                    "Restored the state from the snapshot at height {}",
+                    restore.height
+                );
+                response.result = ApplyResult::Accept as i32;
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to restore the snapshot at height {}: {}",
+                    restore.height,
+                    err
+                );
+                response.result = ApplyResult::RejectSnapshot as i32;
+            }
+        }
+        response
+    }
+
+    /// Verify the state of a snapshot against the trusted app hash and write
+    /// it in the DB. The merkle tree is rebuilt from the key-vals of the
+    /// subspaces and its root must match the app hash. The block's metadata
+    /// must then match its hash committed to in the subspaces. The snapshot is
+    /// read from disk twice, to verify it and then to write it, so that it
+    /// never has to be held in memory.
+    fn restore_snapshot_state(
+        &mut self,
+        height: BlockHeight,
+        path: &Path,
+        app_hash: &[u8],
+    ) -> std::result::Result<(), String> {
+        let open = || {
+            File::open(path).map(BufReader::new).map_err(|err| {
+                format!("Failed to read the restored snapshot: {}", err)
+            })
+        };
+        let mut reader = open()?;
+        let state = read_snapshot_state(&mut reader)?;
+        if state.height != height {
+            return Err(format!(
+                "The snapshot's state is at height {}, expected {}",
+                state.height, height
+            ));
+        }
+
+        let metadata_hash_key =
+            parameters::storage::get_block_metadata_hash_key();
+        let mut metadata_hash = None;
+        let mut tree = MerkleTree::<H>::new(MerkleTreeStoresRead::default());
+        while let Some((key, value)) = read_snapshot_key_val(&mut reader)? {
+            tree.update(&key, &value)
+                .map_err(|err| format!("Merkle tree error: {}", err))?;
+            if key == metadata_hash_key {
+                metadata_hash = Some(value);
+            }
+        }
+        if tree.root().0 != app_hash {
+            return Err("The subspaces don't match the app hash".into());
+        }
+
+        let block = BlockStateWrite {
+            merkle_tree_stores: tree.stores(),
+            header: None,
+            hash: &state.hash,
+            height,
+            epoch: state.epoch,
+            pred_epochs: &state.pred_epochs,
+            next_epoch_min_start_height: state.next_epoch_min_start_height,
+            next_epoch_min_start_time: state.next_epoch_min_start_time,
+            address_gen: &state.address_gen,
+            results: &state.results,
+            tx_queue: &state.tx_queue,
+        };
+        // Without the commitment to the metadata, which is only enabled at
+        // genesis, the restored metadata couldn't be trusted
+        let metadata_hash = metadata_hash.ok_or_else(|| {
+            "The chain doesn't commit to the blocks' metadata, so the snapshot \
+             cannot be verified"
+                .to_string()
+        })?;
+        if metadata_hash != block.metadata_hash().0 {
+            return Err("The block's metadata doesn't match the hash \
+                        committed to in the merkle tree"
+                .into());
+        }
+
+        // The verified key-vals are written in batches of about the size of
+        // a chunk. The block is written last, as it marks the state as
+        // restored.
+        let mut reader = open()?;
+        read_snapshot_state(&mut reader)?;
+        let mut batch = D::batch();
+        let mut batch_size = 0;
+        while let Some((key, value)) = read_snapshot_key_val(&mut reader)? {
+            batch_size += value.len();
+            self.storage
+                .db
+                .batch_write_subspace_val(&mut batch, height, &key, &value)
+                .map_err(|err| err.to_string())?;
+            if batch_size >= SNAPSHOT_CHUNK_SIZE {
+                self.storage
+                    .db
+                    .exec_batch(std::mem::replace(&mut batch, D::batch()))
+                    .map_err(|err| err.to_string())?;
+                batch_size = 0;
+            }
+        }
+        self.storage
+            .db
+            .exec_batch(batch)
+            .map_err(|err| err.to_string())?;
+        self.storage
+            .db
+            .write_block(block)
+            .map_err(|err| err.to_string())?;
+        self.storage
+            .load_last_state()
+            .map_err(|err| err.to_string())?;
+        self.restore_dkg();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_snapshots {
+    use super::*;
+    use crate::node::ledger::shell::test_utils::{setup, TestShell};
+    use crate::node::ledger::shims::abcipp_shim_types::shim::request::FinalizeBlock;
+
+    /// Commit a block in the shell and take a snapshot of its state
+    fn commit_block_with_snapshot(shell: &mut TestShell) {
+        shell.snapshot_interval = Some(1);
+        shell
+            .finalize_block(FinalizeBlock {
+                hash: BlockHash([1; 32]),
+                ..Default::default()
+            })
+            .expect("Test failed");
+        shell.commit();
+        shell
+            .snapshot_task
+            .take()
+            .expect("Test failed")
+            .join()
+            .expect("Test failed");
+    }
+
+    /// Test that a snapshot taken by a node restores the same state in a
+    /// fresh node
+    #[test]
+    fn test_snapshot_restore() {
+        let (mut shell, _) = setup();
+        commit_block_with_snapshot(&mut shell);
+        let app_hash = shell.storage.merkle_root().0;
+
+        let snapshots = shell.list_snapshots().snapshots;
+        let snapshot = match snapshots.as_slice() {
+            [snapshot] => snapshot.clone(),
+            _ => panic!("Test failed"),
+        };
+        assert_eq!(snapshot.height, shell.storage.last_height.0);
+
+        let (mut fresh, _) = TestShell::new();
+        let response = fresh.offer_snapshot(RequestOfferSnapshot {
+            snapshot: Some(snapshot.clone()),
+            app_hash: app_hash.clone(),
+        });
+        assert_eq!(
+            response.result,
+            response_offer_snapshot::Result::Accept as i32
+        );
+        for index in 0..snapshot.chunks {
+            let chunk = shell
+                .load_snapshot_chunk(RequestLoadSnapshotChunk {
+                    height: snapshot.height,
+                    format: snapshot.format,
+                    chunk: index,
+                })
+                .chunk;
+            let response =
+                fresh.apply_snapshot_chunk(RequestApplySnapshotChunk {
+                    index,
+                    chunk,
+                    sender: String::new(),
+                });
+            assert_eq!(
+                response.result,
+                response_apply_snapshot_chunk::Result::Accept as i32
+            );
+        }
+        assert_eq!(fresh.storage.last_height, shell.storage.last_height);
+        assert_eq!(fresh.storage.merkle_root().0, app_hash);
+        assert_eq!(fresh.storage.block.hash, shell.storage.block.hash);
+    }
+
+    /// Test that a snapshot is rejected if it doesn't match the trusted app
+    /// hash or if one of its chunks is corrupted
+    #[test]
+    fn test_invalid_snapshot_rejected() {
+        let (mut shell, _) = setup();
+        commit_block_with_snapshot(&mut shell);
+        let snapshot = shell.list_snapshots().snapshots.remove(0);
+        assert_eq!(snapshot.chunks, 1);
+        let chunk = shell
+            .load_snapshot_chunk(RequestLoadSnapshotChunk {
+                height: snapshot.height,
+                format: snapshot.format,
+                chunk: 0,
+            })
+            .chunk;
+
+        // a node with a state cannot be restored
+        let response = shell.offer_snapshot(RequestOfferSnapshot {
+            snapshot: Some(snapshot.clone()),
+            app_hash: shell.storage.merkle_root().0,
+        });
+        assert_eq!(
+            response.result,
+            response_offer_snapshot::Result::Abort as i32
+        );
+
+        let (mut fresh, _) = TestShell::new();
+        fresh.offer_snapshot(RequestOfferSnapshot {
+            snapshot: Some(snapshot.clone()),
+            app_hash: vec![0; 32],
+        });
+        let mut corrupted = chunk.clone();
+        corrupted[0] ^= 1;
+        let response = fresh.apply_snapshot_chunk(RequestApplySnapshotChunk {
+            index: 0,
+            chunk: corrupted,
+            sender: "peer".into(),
+        });
+        assert_eq!(
+            response.result,
+            response_apply_snapshot_chunk::Result::Retry as i32
+        );
+        assert_eq!(response.reject_senders, vec!["peer".to_string()]);
+
+        // the valid chunk completes the snapshot, which doesn't match the
+        // trusted app hash
+        let response = fresh.apply_snapshot_chunk(RequestApplySnapshotChunk {
+            index: 0,
+            chunk,
+            sender: String::new(),
+        });
+        assert_eq!(
+            response.result,
+            response_apply_snapshot_chunk::Result::RejectSnapshot as i32
+        );
+        assert_eq!(fresh.storage.last_height, BlockHeight(0));
+    }
+
+    /// Test that a snapshot is rejected if the block's metadata doesn't match
+    /// its hash committed to in the merkle tree, even if the snapshot's
+    /// hashes match its content
+    #[test]
+    fn test_snapshot_invalid_metadata_rejected() {
+        let (mut shell, _) = setup();
+        commit_block_with_snapshot(&mut shell);
+        let app_hash = shell.storage.merkle_root().0;
+        let snapshot = shell.list_snapshots().snapshots.remove(0);
+        let chunk = shell
+            .load_snapshot_chunk(RequestLoadSnapshotChunk {
+                height: snapshot.height,
+                format: snapshot.format,
+                chunk: 0,
+            })
+            .chunk;
+
+        // tamper with the start height of the next epoch and re-hash the
+        // snapshot
+        let mut subspaces = chunk.as_slice();
+        let mut state =
+            read_snapshot_state(&mut subspaces).expect("Test failed");
+        state.next_epoch_min_start_height = BlockHeight(0);
+        let mut tampered = state
+            .try_to_vec()
+            .and_then(|state| state.try_to_vec())
+            .expect("Test failed");
+        tampered.extend_from_slice(subspaces);
+        let hash = Hash::sha256(&tampered);
+
+        let (mut fresh, _) = TestShell::new();
+        let response = fresh.offer_snapshot(RequestOfferSnapshot {
+            snapshot: Some(Snapshot {
+                hash: hash.0.to_vec(),
+                metadata: vec![hash].try_to_vec().expect("Test failed"),
+                ..snapshot
+            }),
+            app_hash,
+        });
+        assert_eq!(
+            response.result,
+            response_offer_snapshot::Result::Accept as i32
+        );
+        let response = fresh.apply_snapshot_chunk(RequestApplySnapshotChunk {
+            index: 0,
+            chunk: tampered,
+            sender: String::new(),
+        });
+        assert_eq!(
+            response.result,
+            response_apply_snapshot_chunk::Result::RejectSnapshot as i32
+        );
+        assert_eq!(fresh.storage.last_height, BlockHeight(0));
+    }
+}
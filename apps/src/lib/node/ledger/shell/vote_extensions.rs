@@ -0,0 +1,384 @@
+//! Validators' vote extensions with the Ethereum events they have observed,
+//! and the validation of the vote extensions aggregated by the block proposer.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "abcipp")]
+use namada::types::transaction::protocol::ProtocolTxType;
+#[cfg(feature = "abcipp")]
+use namada::types::vote_extensions::ethereum_events::Vext;
+use namada::types::vote_extensions::ethereum_events::{SignedVext, VextDigest};
+
+use super::*;
+#[cfg(feature = "abcipp")]
+use crate::facade::tendermint_proto::abci::response_verify_vote_extension::VerifyStatus;
+#[cfg(feature = "abcipp")]
+use crate::facade::tendermint_proto::abci::ExtendedCommitInfo;
+#[cfg(feature = "abcipp")]
+use crate::node::ledger::shims::abcipp_shim_types::shim::TxBytes;
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Extend the validator's pre-commit vote with the Ethereum events it has
+    /// observed, signed with its protocol key.
+    ///
+    /// INVARIANT: This method must be stateless.
+    #[cfg(feature = "abcipp")]
+    pub fn extend_vote(
+        &self,
+        _req: request::ExtendVote,
+    ) -> response::ExtendVote {
+        match &self.mode {
+            ShellMode::Validator {
+                data,
+                ethereum_recv,
+                ..
+            } => {
+                let vext = Vext {
+                    validator_addr: data.address.clone(),
+                    block_height: self.storage.last_height + 1,
+                    ethereum_events: ethereum_recv.get_events(),
+                }
+                .sign(&data.keys.protocol_keypair);
+                response::ExtendVote {
+                    vote_extension: vext
+                        .try_to_vec()
+                        .expect("Serializing a vote extension shouldn't fail"),
+                }
+            }
+            _ => Default::default(),
+        }
+    }
+
+    /// Check that the vote extension of another validator is signed by that
+    /// validator for the block being voted on.
+    ///
+    /// INVARIANT: This method must be stateless.
+    #[cfg(feature = "abcipp")]
+    pub fn verify_vote_extension(
+        &self,
+        req: request::VerifyVoteExtension,
+    ) -> response::VerifyVoteExtension {
+        let raw_hash = tm_raw_hash_to_string(&req.validator_address);
+        let validator = self.storage.read_validator_address_raw_hash(&raw_hash);
+        let is_valid = match SignedVext::try_from_slice(&req.vote_extension) {
+            Ok(vext) => {
+                Some(&vext.data.validator_addr) == validator.as_ref()
+                    && self.validate_eth_events_vext(
+                        &vext,
+                        self.storage.last_height + 1,
+                        &self.get_active_validator_stakes(),
+                    )
+            }
+            Err(err) => {
+                tracing::debug!("Failed to decode a vote extension: {}", err);
+                false
+            }
+        };
+        response::VerifyVoteExtension {
+            status: if is_valid {
+                VerifyStatus::Accept as i32
+            } else {
+                VerifyStatus::Reject as i32
+            },
+        }
+    }
+
+    /// Build a protocol tx with the valid vote extensions from the commit of
+    /// the last block that contain some Ethereum events. Returns `None` if
+    /// there are no such vote extensions or if this node is not a validator.
+    #[cfg(feature = "abcipp")]
+    pub(super) fn build_eth_events_tx(
+        &self,
+        local_last_commit: Option<ExtendedCommitInfo>,
+    ) -> Option<TxBytes> {
+        let protocol_keypair = match &self.mode {
+            ShellMode::Validator { data, .. } => &data.keys.protocol_keypair,
+            _ => return None,
+        };
+        let active_validators = self.get_active_validator_stakes();
+        let mut vexts: BTreeMap<Address, SignedVext> = BTreeMap::new();
+        for vote in local_last_commit?.votes {
+            let vext = match SignedVext::try_from_slice(&vote.vote_extension) {
+                Ok(vext) => vext,
+                Err(_) => continue,
+            };
+            if !vext.data.ethereum_events.is_empty()
+                && self.validate_eth_events_vext(
+                    &vext,
+                    self.storage.last_height,
+                    &active_validators,
+                )
+            {
+                vexts
+                    .entry(vext.data.validator_addr.clone())
+                    .or_insert(vext);
+            }
+        }
+        if vexts.is_empty() {
+            return None;
+        }
+        let digest = VextDigest {
+            vexts: vexts.into_values().collect(),
+        };
+        let tx = ProtocolTxType::EthereumEvents(digest)
+            .sign(&protocol_keypair.ref_to(), protocol_keypair);
+        Some(tx.to_bytes())
+    }
+
+    /// Check that the vote extensions aggregated by the block proposer were
+    /// signed by distinct active validators in the last block.
+    pub(super) fn validate_eth_events_digest(
+        &self,
+        digest: &VextDigest,
+    ) -> bool {
+        if digest.vexts.is_empty() || digest.validators().is_none() {
+            tracing::debug!(
+                "The vote extensions must be from distinct validators"
+            );
+            return false;
+        }
+        let active_validators = self.get_active_validator_stakes();
+        digest.vexts.iter().all(|vext| {
+            self.validate_eth_events_vext(
+                vext,
+                self.storage.last_height,
+                &active_validators,
+            )
+        })
+    }
+
+    /// Check that a vote extension is for the given block height and that it
+    /// is signed with the protocol key of an active validator.
+    pub(super) fn validate_eth_events_vext(
+        &self,
+        vext: &SignedVext,
+        height: BlockHeight,
+        active_validators: &BTreeMap<Address, u64>,
+    ) -> bool {
+        let validator = &vext.data.validator_addr;
+        if vext.data.block_height != height {
+            tracing::debug!(
+                %validator,
+                vext_height = %vext.data.block_height,
+                %height,
+                "Vote extension for an unexpected block height"
+            );
+            return false;
+        }
+        if !active_validators.contains_key(validator) {
+            tracing::debug!(
+                %validator,
+                "Vote extension from an inactive validator"
+            );
+            return false;
+        }
+        let protocol_pk: Option<common::PublicKey> =
+            self.read_storage_key(&protocol_pk_key(validator));
+        match protocol_pk {
+            Some(protocol_pk) => match vext.verify(&protocol_pk) {
+                Ok(()) => true,
+                Err(err) => {
+                    tracing::debug!(
+                        %validator,
+                        "Invalid vote extension signature: {}",
+                        err
+                    );
+                    false
+                }
+            },
+            None => {
+                tracing::error!(
+                    %validator,
+                    "Cannot find the protocol key of an active validator"
+                );
+                false
+            }
+        }
+    }
+
+    /// Get the bonded stake of each validator in the active validator set of
+    /// the current epoch
+    pub(super) fn get_active_validator_stakes(&self) -> BTreeMap<Address, u64> {
+        let (current_epoch, _gas) = self.storage.get_current_epoch();
+        self.storage
+            .read_validator_set()
+            .get(current_epoch)
+            .map(|validator_set| {
+                validator_set
+                    .active
+                    .iter()
+                    .map(|validator| {
+                        (validator.address.clone(), validator.bonded_stake)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test_vote_extensions {
+    use namada::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+    use namada::types::ethereum_events::{EthereumEvent, TransferToNamada};
+    use namada::types::transaction::protocol::ProtocolTxType;
+    use namada::types::vote_extensions::ethereum_events::Vext;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::*;
+    use crate::node::ledger::shims::abcipp_shim_types::shim::request::ProcessedTx;
+
+    /// Get the address and the protocol key of the shell's validator
+    fn validator_data(shell: &TestShell) -> (Address, common::SecretKey) {
+        match &shell.mode {
+            ShellMode::Validator { data, .. } => {
+                (data.address.clone(), data.keys.protocol_keypair.clone())
+            }
+            _ => panic!("Test failed"),
+        }
+    }
+
+    fn transfers_to_namada(nonce: u64) -> EthereumEvent {
+        EthereumEvent::TransfersToNamada {
+            nonce,
+            transfers: vec![TransferToNamada {
+                amount: 100.into(),
+                asset: DAI_ERC20_ETH_ADDRESS,
+                receiver: address::testing::established_address_1(),
+            }],
+        }
+    }
+
+    /// Test that a vote extension is only valid if signed with the protocol
+    /// key of an active validator for the expected height.
+    #[test]
+    fn test_validate_eth_events_vext() {
+        let (shell, _) = setup();
+        let (validator, protocol_key) = validator_data(&shell);
+        let active_validators = shell.get_active_validator_stakes();
+        let height = shell.storage.last_height;
+        let vext = |validator: &Address| Vext {
+            ethereum_events: vec![transfers_to_namada(0)],
+            ..Vext::empty(height, validator.clone())
+        };
+
+        let valid = vext(&validator).sign(&protocol_key);
+        assert!(shell.validate_eth_events_vext(
+            &valid,
+            height,
+            &active_validators
+        ));
+        assert!(!shell.validate_eth_events_vext(
+            &valid,
+            height + 1,
+            &active_validators
+        ));
+
+        let wrong_key = vext(&validator).sign(&gen_keypair());
+        assert!(!shell.validate_eth_events_vext(
+            &wrong_key,
+            height,
+            &active_validators
+        ));
+
+        let not_validator = vext(&address::testing::established_address_2())
+            .sign(&protocol_key);
+        assert!(!shell.validate_eth_events_vext(
+            &not_validator,
+            height,
+            &active_validators
+        ));
+    }
+
+    /// Test that the proposed protocol tx with the vote extensions is accepted
+    /// only if all the vote extensions are valid and from distinct
+    /// validators.
+    #[test]
+    fn test_process_eth_events_tx() {
+        let (shell, _) = setup();
+        let (validator, protocol_key) = validator_data(&shell);
+        let vext = Vext {
+            ethereum_events: vec![transfers_to_namada(0)],
+            ..Vext::empty(shell.storage.last_height, validator)
+        }
+        .sign(&protocol_key);
+        let process = |vexts: Vec<SignedVext>| {
+            let tx = ProtocolTxType::EthereumEvents(VextDigest { vexts })
+                .sign(&protocol_key.ref_to(), &protocol_key);
+            shell.process_txs(&[tx.to_bytes()]).remove(0).code
+        };
+
+        assert_eq!(process(vec![vext.clone()]), u32::from(ErrorCodes::Ok));
+        assert_eq!(
+            process(vec![vext.clone(), vext]),
+            u32::from(ErrorCodes::InvalidTx)
+        );
+        assert_eq!(process(vec![]), u32::from(ErrorCodes::InvalidTx));
+    }
+
+    /// Test that a block is rejected unless the Ethereum events tx is its
+    /// first tx, which also rejects a second Ethereum events tx.
+    #[test]
+    fn test_eth_events_tx_must_be_first() {
+        let (mut shell, _) = setup();
+        let (validator, protocol_key) = validator_data(&shell);
+        let vext = Vext {
+            ethereum_events: vec![transfers_to_namada(0)],
+            ..Vext::empty(shell.storage.last_height, validator)
+        }
+        .sign(&protocol_key);
+        let tx =
+            ProtocolTxType::EthereumEvents(VextDigest { vexts: vec![vext] })
+                .sign(&protocol_key.ref_to(), &protocol_key)
+                .to_bytes();
+        let raw_tx =
+            Tx::new("wasm_code".as_bytes().to_owned(), None).to_bytes();
+        let codes = |results: Vec<ProcessedTx>| -> Vec<u32> {
+            results.into_iter().map(|res| res.result.code).collect()
+        };
+
+        let results = shell
+            .process_proposal(ProcessProposal {
+                txs: vec![tx.clone(), raw_tx.clone()],
+            })
+            .expect("Test failed");
+        assert_eq!(codes(results)[0], u32::from(ErrorCodes::Ok));
+
+        for txs in [vec![raw_tx, tx.clone()], vec![tx.clone(), tx]] {
+            match shell.process_proposal(ProcessProposal { txs }) {
+                Err(TestError::RejectProposal(results)) => assert_eq!(
+                    codes(results)[1],
+                    u32::from(ErrorCodes::InvalidOrder)
+                ),
+                _ => panic!("Test failed"),
+            }
+        }
+    }
+
+    /// Test that the validator extends its votes with the queued Ethereum
+    /// events and that the vote extension can be verified.
+    #[cfg(feature = "abcipp")]
+    #[test]
+    fn test_extend_vote() {
+        let (mut shell, _) = setup();
+        let (validator, protocol_key) = validator_data(&shell);
+        shell
+            .eth_sender
+            .send(transfers_to_namada(0))
+            .expect("Test failed");
+        if let ShellMode::Validator { ethereum_recv, .. } = &mut shell.mode {
+            ethereum_recv.fill_queue();
+        }
+
+        let vote_extension =
+            shell.extend_vote(Default::default()).vote_extension;
+        let vext =
+            SignedVext::try_from_slice(&vote_extension).expect("Test failed");
+        assert_eq!(vext.data.validator_addr, validator);
+        assert_eq!(vext.data.ethereum_events, vec![transfers_to_namada(0)]);
+        assert!(vext.verify(&protocol_key.ref_to()).is_ok());
+    }
+}
@@ -199,10 +199,10 @@ pub mod shim {
         use namada::types::storage::{BlockHash, Header};
         use namada::types::time::DateTimeUtc;
         #[cfg(not(feature = "abcipp"))]
-        use tendermint_proto::abci::Misbehavior as Evidence;
+        use tendermint_proto::abci::{Misbehavior as Evidence, VoteInfo};
         #[cfg(feature = "abcipp")]
         use tendermint_proto_abcipp::abci::{
-            Misbehavior as Evidence, RequestFinalizeBlock,
+            Misbehavior as Evidence, RequestFinalizeBlock, VoteInfo,
         };
 
         pub struct VerifyHeader;
@@ -221,6 +221,10 @@ pub mod shim {
             pub header: Header,
             pub byzantine_validators: Vec<Evidence>,
             pub txs: Vec<ProcessedTx>,
+            /// Raw hash of the consensus key of the block's proposer
+            pub proposer_address: Vec<u8>,
+            /// The validators' votes on the last committed block
+            pub votes: Vec<VoteInfo>,
         }
 
         #[cfg(feature = "abcipp")]
@@ -238,6 +242,11 @@ pub mod shim {
                     },
                     byzantine_validators: req.byzantine_validators,
                     txs: vec![],
+                    proposer_address: req.proposer_address,
+                    votes: req
+                        .decided_last_commit
+                        .map(|commit| commit.votes)
+                        .unwrap_or_default(),
                 }
             }
         }
@@ -260,6 +269,11 @@ pub mod shim {
                     },
                     byzantine_validators: req.byzantine_validators,
                     txs: vec![],
+                    proposer_address: header.proposer_address,
+                    votes: req
+                        .last_commit_info
+                        .map(|commit| commit.votes)
+                        .unwrap_or_default(),
                 }
             }
         }
@@ -13,6 +13,7 @@
 //! - `pred`: predecessor values of the top-level keys of the same name
 //!   - `next_epoch_min_start_height`
 //!   - `next_epoch_min_start_time`
+//! - `pruned_height`: the last block height whose data has been pruned
 //! - `subspace`: accounts sub-spaces
 //!   - `{address}/{dyn}`: any byte data associated with accounts
 //! - `h`: for each block at height `h`:
@@ -28,8 +29,11 @@
 //!   - `header`: block's header
 
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada::ledger::storage::types::PrefixIterator;
@@ -42,6 +46,7 @@ use namada::types::storage::{
     BlockHeight, BlockResults, Header, Key, KeySeg, KEY_SEGMENT_SEPARATOR,
 };
 use namada::types::time::DateTimeUtc;
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{
     BlockBasedOptions, Direction, FlushOptions, IteratorMode, Options,
     ReadOptions, SliceTransform, WriteBatch, WriteOptions,
@@ -55,9 +60,11 @@ use crate::config::utils::num_of_threads;
 const ENV_VAR_ROCKSDB_COMPACTION_THREADS: &str =
     "NAMADA_ROCKSDB_COMPACTION_THREADS";
 
-/// RocksDB handle
+/// RocksDB handle. The DB is shared with the thread pruning old block
+/// heights in the background, if any, which is flagged as running by the
+/// second field.
 #[derive(Debug)]
-pub struct RocksDB(rocksdb::DB);
+pub struct RocksDB(Arc<rocksdb::DB>, Arc<AtomicBool>);
 
 /// DB Handle for batch writes.
 #[derive(Default)]
@@ -115,7 +122,7 @@ pub fn open(
     // TODO use column families
 
     rocksdb::DB::open_cf_descriptors(&cf_opts, path, vec![])
-        .map(RocksDB)
+        .map(|db| RocksDB(Arc::new(db), Arc::default()))
         .map_err(|e| Error::DBError(e.into_string()))
 }
 
@@ -238,10 +245,195 @@ impl RocksDB {
             .write_opt(batch, &write_opts)
             .map_err(|e| Error::DBError(e.into_string()))
     }
+
+    /// Roll back the state to the previous committed block height. The
+    /// top-level keys are restored from their predecessor values, the
+    /// account subspaces are restored from the diffs of the last height and
+    /// the rest of the last height's data is deleted. Returns the height that
+    /// the state was rolled back to.
+    ///
+    /// The predecessor values are deleted once restored, as those of the
+    /// height before aren't kept, so only a single height can be rolled back.
+    /// The rollback is refused if the previous height has been pruned.
+    pub fn rollback(&mut self) -> Result<BlockHeight> {
+        let height: BlockHeight = match self
+            .0
+            .get("height")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+            None => {
+                return Err(Error::Temporary {
+                    error: "There is no committed block to roll back"
+                        .to_string(),
+                });
+            }
+        };
+        let previous_height = height
+            .0
+            .checked_sub(1)
+            .map(BlockHeight)
+            .ok_or_else(|| Error::Temporary {
+                error: "The genesis block cannot be rolled back".to_string(),
+            })?;
+        // The merkle tree stores of the previous height are needed to load
+        // its state
+        let pruned_height = read_pruned_height(&self.0)?;
+        if previous_height <= pruned_height {
+            return Err(Error::Temporary {
+                error: format!(
+                    "The block height {} has been pruned, the block height {} \
+                     cannot be rolled back",
+                    previous_height, height
+                ),
+            });
+        }
+        let mut batch = WriteBatch::default();
+
+        // Restore the predecessor values of the top-level keys
+        for key in [
+            "next_epoch_min_start_height",
+            "next_epoch_min_start_time",
+            "tx_queue",
+        ] {
+            let pred_key = format!("pred/{}", key);
+            match self
+                .0
+                .get(&pred_key)
+                .map_err(|e| Error::DBError(e.into_string()))?
+            {
+                Some(pred_value) => {
+                    batch.put(key, pred_value);
+                    batch.delete(pred_key);
+                }
+                None => {
+                    return Err(Error::Temporary {
+                        error: format!(
+                            "The predecessor value of {} is missing, the \
+                             block height {} cannot be rolled back",
+                            key, height
+                        ),
+                    });
+                }
+            }
+        }
+
+        // Revert the diffs of the account subspaces and delete the data of
+        // the last height
+        let prefix = format!("{}/", height.raw());
+        let old_diffs_prefix = format!("{}/diffs/old/", height.raw());
+        let new_diffs_prefix = format!("{}/diffs/new/", height.raw());
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_total_order_seek(false);
+        let next_height_prefix = format!("{}/", height.next_height().raw());
+        read_opts.set_iterate_upper_bound(next_height_prefix);
+        let mut restored_keys = HashSet::new();
+        let mut written_keys = Vec::new();
+        for value in self.0.iterator_opt(
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+            read_opts,
+        ) {
+            let (key, bytes) = match value {
+                Ok(data) => data,
+                Err(e) => return Err(Error::DBError(e.into_string())),
+            };
+            let path = String::from_utf8((*key).to_vec()).map_err(|e| {
+                Error::Temporary {
+                    error: format!(
+                        "Cannot convert path from utf8 bytes to string: {}",
+                        e
+                    ),
+                }
+            })?;
+            if let Some(subspace_key) = path.strip_prefix(&old_diffs_prefix) {
+                // The key had this value before the last height
+                batch.put(format!("subspace/{}", subspace_key), bytes);
+                restored_keys.insert(subspace_key.to_owned());
+            } else if let Some(subspace_key) =
+                path.strip_prefix(&new_diffs_prefix)
+            {
+                written_keys.push(subspace_key.to_owned());
+            }
+            batch.delete(path);
+        }
+        // The keys written without an old value didn't exist before
+        for subspace_key in written_keys {
+            if !restored_keys.contains(&subspace_key) {
+                batch.delete(format!("subspace/{}", subspace_key));
+            }
+        }
+
+        batch.delete(format!("results/{}", height.raw()));
+        batch.put("height", types::encode(&previous_height));
+
+        self.exec_batch(batch)?;
+        self.flush(true)?;
+        Ok(previous_height)
+    }
+}
+
+/// Read the last block height whose data has been pruned, if any.
+fn read_pruned_height(db: &rocksdb::DB) -> Result<BlockHeight> {
+    match db
+        .get("pruned_height")
+        .map_err(|e| Error::DBError(e.into_string()))?
+    {
+        Some(bytes) => types::decode(bytes).map_err(Error::CodingError),
+        None => Ok(BlockHeight(0)),
+    }
+}
+
+/// Delete the diffs of the account subspaces, the merkle tree stores and the
+/// results of the block heights from the last pruned height up to, but
+/// excluding, the given height, except for the multiples of `keep_every`.
+/// Every height is deleted in its own batch together with the update of the
+/// last pruned height, so the pruning can be interrupted at any height.
+fn prune_heights(
+    db: &rocksdb::DB,
+    before: BlockHeight,
+    keep_every: Option<u64>,
+) -> Result<()> {
+    let pruned_height = read_pruned_height(db)?;
+    let mut write_opts = WriteOptions::default();
+    write_opts.disable_wal(true);
+    for raw_height in pruned_height.0 + 1..before.0 {
+        let height = BlockHeight(raw_height);
+        let mut batch = WriteBatch::default();
+        let kept = keep_every
+            .filter(|every| *every != 0)
+            .map_or(false, |every| raw_height % every == 0);
+        if !kept {
+            let diffs_prefix = format!("{}/diffs/", height.raw());
+            let mut read_opts = ReadOptions::default();
+            read_opts.set_total_order_seek(true);
+            let mut upper_prefix = diffs_prefix.clone().into_bytes();
+            if let Some(last) = upper_prefix.pop() {
+                upper_prefix.push(last + 1);
+            }
+            read_opts.set_iterate_upper_bound(upper_prefix);
+            for value in db.iterator_opt(
+                IteratorMode::From(diffs_prefix.as_bytes(), Direction::Forward),
+                read_opts,
+            ) {
+                let (key, _) =
+                    value.map_err(|e| Error::DBError(e.into_string()))?;
+                batch.delete(key);
+            }
+            for st in StoreType::iter() {
+                batch.delete(format!("{}/tree/{}/store", height.raw(), st));
+            }
+            batch.delete(format!("results/{}", height.raw()));
+        }
+        batch.put("pruned_height", types::encode(&height));
+        db.write_opt(batch, &write_opts)
+            .map_err(|e| Error::DBError(e.into_string()))?;
+    }
+    Ok(())
 }
 
 impl DB for RocksDB {
     type Cache = rocksdb::Cache;
+    type Checkpoint = RocksDB;
     type WriteBatch = RocksDBWriteBatch;
 
     fn open(
@@ -794,6 +986,49 @@ impl DB for RocksDB {
         Ok(prev_len)
     }
 
+    fn prune(
+        &mut self,
+        before: BlockHeight,
+        keep_every: Option<u64>,
+    ) -> Result<()> {
+        // Skip this height if the pruning of the previous heights is still
+        // running, it will be caught up with the next one
+        if self
+            .1
+            .compare_exchange(
+                false,
+                true,
+                AtomicOrdering::AcqRel,
+                AtomicOrdering::Acquire,
+            )
+            .is_err()
+        {
+            return Ok(());
+        }
+        let db = self.0.clone();
+        let running = self.1.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = prune_heights(&db, before, keep_every) {
+                tracing::error!(
+                    "Failed to prune the block heights before {}: {}",
+                    before,
+                    e
+                );
+            }
+            running.store(false, AtomicOrdering::Release);
+        });
+        Ok(())
+    }
+
+    fn checkpoint(&self, path: impl AsRef<Path>) -> Result<Self::Checkpoint> {
+        // The checkpoint's files are hard links to the DB's files, if they're
+        // on the same filesystem
+        Checkpoint::new(&self.0)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(&path))
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        open(path, None)
+    }
+
     fn batch() -> Self::WriteBatch {
         RocksDBWriteBatch::default()
     }
@@ -1148,4 +1383,217 @@ mod test {
             db.read_subspace_val(&key).expect("read should succeed");
         assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 2]));
     }
+
+    /// Test that rolling back the last block restores the state of the
+    /// previous block.
+    #[test]
+    fn test_rollback() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let updated_key = Key::parse("updated").unwrap();
+        let deleted_key = Key::parse("deleted").unwrap();
+        let created_key = Key::parse("created").unwrap();
+
+        fn write_block(
+            db: &mut RocksDB,
+            height: BlockHeight,
+            merkle_tree: &MerkleTree<Sha256Hasher>,
+            pred_epochs: &Epochs,
+            address_gen: &EstablishedAddressGen,
+        ) {
+            let hash = BlockHash::default();
+            let tx_queue = TxQueue::default();
+            let results = BlockResults::default();
+            db.write_block(BlockStateWrite {
+                merkle_tree_stores: merkle_tree.stores(),
+                header: None,
+                hash: &hash,
+                height,
+                epoch: Epoch::default(),
+                results: &results,
+                pred_epochs,
+                next_epoch_min_start_height: height,
+                next_epoch_min_start_time: DateTimeUtc::now(),
+                address_gen,
+                tx_queue: &tx_queue,
+            })
+            .unwrap();
+        }
+
+        let mut merkle_tree = MerkleTree::<Sha256Hasher>::default();
+        let mut pred_epochs = Epochs::default();
+        let mut batch = RocksDB::batch();
+        let height = BlockHeight(1);
+        db.batch_write_subspace_val(&mut batch, height, &updated_key, [1_u8])
+            .unwrap();
+        db.batch_write_subspace_val(&mut batch, height, &deleted_key, [1_u8])
+            .unwrap();
+        db.exec_batch(batch.0).unwrap();
+        merkle_tree.update(&updated_key, [1_u8]).unwrap();
+        merkle_tree.update(&deleted_key, [1_u8]).unwrap();
+        let first_root = merkle_tree.root();
+        let first_pred_epochs = pred_epochs.clone();
+        let first_address_gen = EstablishedAddressGen::new("first");
+        write_block(
+            &mut db,
+            height,
+            &merkle_tree,
+            &pred_epochs,
+            &first_address_gen,
+        );
+
+        // the first block cannot be rolled back, as there are no predecessor
+        // values
+        assert!(db.rollback().is_err());
+
+        let mut batch = RocksDB::batch();
+        let height = BlockHeight(2);
+        db.batch_write_subspace_val(&mut batch, height, &updated_key, [2_u8])
+            .unwrap();
+        db.batch_delete_subspace_val(&mut batch, height, &deleted_key)
+            .unwrap();
+        db.batch_write_subspace_val(&mut batch, height, &created_key, [2_u8])
+            .unwrap();
+        db.exec_batch(batch.0).unwrap();
+        merkle_tree.update(&updated_key, [2_u8]).unwrap();
+        merkle_tree.delete(&deleted_key).unwrap();
+        merkle_tree.update(&created_key, [2_u8]).unwrap();
+        assert_ne!(merkle_tree.root().0, first_root.0);
+        pred_epochs.new_epoch(height, 1000);
+        assert_ne!(pred_epochs, first_pred_epochs);
+        write_block(
+            &mut db,
+            height,
+            &merkle_tree,
+            &pred_epochs,
+            &EstablishedAddressGen::new("second"),
+        );
+
+        let rolled_back_height = db.rollback().expect("rollback failed");
+        assert_eq!(rolled_back_height, BlockHeight(1));
+
+        let state = db
+            .read_last_block()
+            .expect("Should be able to read last block")
+            .expect("Block should have been written");
+        assert_eq!(state.height, BlockHeight(1));
+        assert_eq!(state.next_epoch_min_start_height, BlockHeight(1));
+        assert_eq!(state.pred_epochs, first_pred_epochs);
+        assert_eq!(state.address_gen, first_address_gen);
+        let restored_tree =
+            MerkleTree::<Sha256Hasher>::new(state.merkle_tree_stores);
+        assert_eq!(restored_tree.root().0, first_root.0);
+        assert_eq!(db.read_subspace_val(&updated_key).unwrap(), Some(vec![1]));
+        assert_eq!(db.read_subspace_val(&deleted_key).unwrap(), Some(vec![1]));
+        assert_eq!(db.read_subspace_val(&created_key).unwrap(), None);
+        assert!(db
+            .read_merkle_tree_stores(BlockHeight(2))
+            .unwrap()
+            .is_none());
+
+        // only a single height can be rolled back
+        assert!(db.rollback().is_err());
+    }
+
+    /// Write the given number of blocks, each with a diff of the same key
+    fn write_blocks(db: &mut RocksDB, num_blocks: u64) -> Key {
+        let key = Key::parse("key").unwrap();
+        for height in 1..=num_blocks {
+            let height = BlockHeight(height);
+            let mut batch = RocksDB::batch();
+            db.batch_write_subspace_val(&mut batch, height, &key, [1_u8])
+                .unwrap();
+            db.exec_batch(batch.0).unwrap();
+
+            let merkle_tree = MerkleTree::<Sha256Hasher>::default();
+            let hash = BlockHash::default();
+            let pred_epochs = Epochs::default();
+            let address_gen = EstablishedAddressGen::new("whatever");
+            let tx_queue = TxQueue::default();
+            let results = BlockResults::default();
+            db.write_block(BlockStateWrite {
+                merkle_tree_stores: merkle_tree.stores(),
+                header: None,
+                hash: &hash,
+                height,
+                epoch: Epoch::default(),
+                results: &results,
+                pred_epochs: &pred_epochs,
+                next_epoch_min_start_height: height,
+                next_epoch_min_start_time: DateTimeUtc::now(),
+                address_gen: &address_gen,
+                tx_queue: &tx_queue,
+            })
+            .unwrap();
+        }
+        key
+    }
+
+    /// Check if the diffs, the merkle tree stores and the results of the
+    /// given height have been pruned
+    fn is_pruned(db: &RocksDB, height: u64) -> bool {
+        let height = BlockHeight(height);
+        let new_diff = db.0.get(format!("{}/diffs/new/key", height.raw()));
+        let stores = db.read_merkle_tree_stores(height);
+        let results = db.0.get(format!("results/{}", height.raw()));
+        match (new_diff.unwrap(), stores.unwrap(), results.unwrap()) {
+            (None, None, None) => true,
+            (Some(_), Some(_), Some(_)) => false,
+            _ => panic!("The height {} is partially pruned", height),
+        }
+    }
+
+    /// Test that pruning deletes the diffs, the merkle tree stores and the
+    /// results of the old heights and that a pruned height cannot be rolled
+    /// back to.
+    #[test]
+    fn test_prune() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let key = write_blocks(&mut db, 4);
+
+        prune_heights(&db.0, BlockHeight(3), None).unwrap();
+        assert!(is_pruned(&db, 1));
+        assert!(is_pruned(&db, 2));
+        assert!(!is_pruned(&db, 3));
+        assert!(!is_pruned(&db, 4));
+
+        // the heights pruned before are skipped
+        prune_heights(&db.0, BlockHeight(4), None).unwrap();
+        assert!(is_pruned(&db, 3));
+        assert!(!is_pruned(&db, 4));
+
+        // the last block cannot be rolled back to a pruned height
+        assert!(db.rollback().is_err());
+
+        // the last state is left intact
+        assert_eq!(db.read_subspace_val(&key).unwrap(), Some(vec![1]));
+        assert_eq!(
+            db.read_last_block().unwrap().unwrap().height,
+            BlockHeight(4)
+        );
+    }
+
+    /// Test that pruning keeps all the data of the multiples of `keep_every`
+    #[test]
+    fn test_prune_keep_every() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        write_blocks(&mut db, 6);
+
+        prune_heights(&db.0, BlockHeight(5), Some(2)).unwrap();
+        assert!(is_pruned(&db, 1));
+        assert!(!is_pruned(&db, 2));
+        assert!(is_pruned(&db, 3));
+        assert!(!is_pruned(&db, 4));
+        assert!(!is_pruned(&db, 5));
+
+        // the kept heights stay once the policy changes
+        prune_heights(&db.0, BlockHeight(6), None).unwrap();
+        assert!(!is_pruned(&db, 2));
+        assert!(!is_pruned(&db, 4));
+        assert!(is_pruned(&db, 5));
+        assert!(!is_pruned(&db, 6));
+    }
 }
@@ -97,8 +97,9 @@ pub async fn run(
     #[cfg(feature = "dev")]
     {
         let consensus_key = crate::wallet::defaults::validator_keypair();
-        // write the validator key file if it didn't already exist
-        if !has_validator_key {
+        // write the validator key file if it didn't already exist and the
+        // node doesn't use a remote signer
+        if !has_validator_key && config.priv_validator_address.is_none() {
             write_validator_key_async(&home_dir, &consensus_key).await;
         }
     }
@@ -336,6 +337,8 @@ async fn update_tendermint_config(
     config.instrumentation.namespace =
         tendermint_config.instrumentation_namespace;
 
+    config.priv_validator_laddr = tendermint_config.priv_validator_address;
+
     #[cfg(feature = "abciplus")]
     {
         config.consensus.timeout_commit =
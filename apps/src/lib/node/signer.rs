@@ -0,0 +1,678 @@
+//! A remote signer for a validator's consensus key, which serves the signing
+//! requests of Tendermint's privval protocol so that the key doesn't have to
+//! be written to `priv_validator_key.json` on the node's host.
+//!
+//! Tendermint listens on its `priv_validator_laddr` and the signer dials in.
+//! Only `unix://` sockets are supported, as Tendermint requires TCP
+//! connections to be authenticated and encrypted with its secret connection
+//! protocol. To keep the key on another host, the node's socket can be
+//! forwarded to it, e.g. with `ssh -L`.
+//!
+//! When the node's `priv_validator_address` is set, the `join-network` and
+//! `init-validator` commands don't write the consensus key to
+//! `priv_validator_key.json`, so the signer must be run with it instead. A
+//! genesis validator set up with `init-network` should have its
+//! `consensus_public_key` set in the genesis config, so that the key isn't
+//! generated and written for it.
+//!
+//! Every message is a protobuf `privval.Message` prefixed with its length as
+//! a varint. The height, round and step of the last signature are persisted
+//! before it's returned, and votes and proposals that could conflict with it
+//! are refused, so that the validator cannot be made to double-sign, even
+//! across restarts of the signer.
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, thread};
+
+use borsh::BorshSerialize;
+use namada::types::chain::ChainId;
+use namada::types::key::*;
+use prost::Message as _;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::facade::tendermint_config::net::Address as TendermintAddress;
+use crate::facade::tendermint_proto::crypto::{public_key, PublicKey};
+use crate::facade::tendermint_proto::google::protobuf::Timestamp;
+use crate::facade::tendermint_proto::privval::message::Sum;
+use crate::facade::tendermint_proto::privval::{
+    Message, PingResponse, PubKeyResponse, RemoteSignerError,
+    SignedProposalResponse, SignedVoteResponse,
+};
+use crate::facade::tendermint_proto::types::{
+    BlockId, CanonicalBlockId, CanonicalPartSetHeader, CanonicalProposal,
+    CanonicalVote, Proposal, SignedMsgType, Vote,
+};
+
+/// The step of a proposal in the consensus round
+pub const STEP_PROPOSAL: u8 = 1;
+/// The step of a prevote in the consensus round
+pub const STEP_PREVOTE: u8 = 2;
+/// The step of a precommit in the consensus round
+pub const STEP_PRECOMMIT: u8 = 3;
+
+/// The maximum length of a message from the node, as in Tendermint
+const MAX_MESSAGE_LEN: usize = 10 * 1024;
+
+/// How long to wait before connecting to the node again
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("The signer only supports unix:// addresses, got {0}")]
+    UnsupportedAddress(TendermintAddress),
+    #[error("Tendermint only supports ed25519 consensus keys")]
+    UnsupportedKey,
+    #[error("Failed to communicate with the node: {0}")]
+    Connection(io::Error),
+    #[error("Failed to decode a message from the node: {0}")]
+    Decode(prost::DecodeError),
+    #[error("The message from the node is too large: {0} bytes")]
+    MessageTooLarge(usize),
+    #[error("Unexpected message from the node: {0:?}")]
+    UnexpectedMessage(Option<Sum>),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Refusing to sign for chain {0}")]
+    WrongChain(String),
+    #[error(
+        "Refusing to sign at height {height}, round {round} and step {step}, \
+         which could be a double-sign"
+    )]
+    DoubleSign { height: i64, round: i32, step: u8 },
+    #[error("Failed to read the signer state file {0}: {1}")]
+    ReadState(PathBuf, io::Error),
+    #[error("Failed to decode the signer state file {0}: {1}")]
+    DecodeState(PathBuf, serde_json::Error),
+    #[error("Failed to write the signer state file {0}: {1}")]
+    WriteState(PathBuf, io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The last signature of the signer
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerState {
+    /// The block height of the last signature
+    pub height: i64,
+    /// The consensus round of the last signature
+    pub round: i32,
+    /// The consensus step of the last signature
+    pub step: u8,
+    /// The bytes signed by the last signature
+    #[serde(default, with = "hex_bytes")]
+    pub sign_bytes: Vec<u8>,
+    /// The last signature
+    #[serde(default, with = "hex_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl SignerState {
+    /// Load the state from the given file. The state is empty if the file
+    /// doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| Error::DecodeState(path.to_owned(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(Error::ReadState(path.to_owned(), err)),
+        }
+    }
+
+    /// Write the state to the given file. The file is replaced atomically,
+    /// so that the last state is never lost.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let write = || {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let tmp_path = path.with_extension("tmp");
+            let mut file = fs::File::create(&tmp_path)?;
+            file.write_all(&serde_json::to_vec_pretty(self)?)?;
+            file.sync_all()?;
+            fs::rename(tmp_path, path)
+        };
+        write().map_err(|err| Error::WriteState(path.to_owned(), err))
+    }
+
+    /// Check that signing at the given height, round and step cannot
+    /// conflict with the last signature. Returns `true` if it's the height,
+    /// round and step of the last signature, in which case only the same
+    /// message may be signed again.
+    pub fn check(&self, height: i64, round: i32, step: u8) -> Result<bool> {
+        let last = (self.height, self.round, self.step);
+        if (height, round, step) > last {
+            Ok(false)
+        } else if (height, round, step) == last && !self.signature.is_empty() {
+            Ok(true)
+        } else {
+            Err(Error::DoubleSign {
+                height,
+                round,
+                step,
+            })
+        }
+    }
+}
+
+/// The canonical form of a consensus message, which is what gets signed
+trait Canonical: prost::Message + Default + Clone + PartialEq {
+    /// The timestamp of the message
+    fn timestamp_mut(&mut self) -> &mut Option<Timestamp>;
+}
+
+impl Canonical for CanonicalVote {
+    fn timestamp_mut(&mut self) -> &mut Option<Timestamp> {
+        &mut self.timestamp
+    }
+}
+
+impl Canonical for CanonicalProposal {
+    fn timestamp_mut(&mut self) -> &mut Option<Timestamp> {
+        &mut self.timestamp
+    }
+}
+
+/// Signs votes and proposals with a validator's consensus key
+pub struct ConsensusSigner {
+    keypair: ed25519::SecretKey,
+    chain_id: ChainId,
+    state: SignerState,
+    state_path: PathBuf,
+}
+
+impl ConsensusSigner {
+    /// Sign for the given chain with the given consensus key. The last
+    /// signature is persisted in the state file at the given path.
+    pub fn new(
+        keypair: &common::SecretKey,
+        chain_id: ChainId,
+        state_path: PathBuf,
+    ) -> Result<Self> {
+        let keypair = keypair
+            .try_to_sk::<ed25519::SecretKey>()
+            .map_err(|_| Error::UnsupportedKey)?;
+        let state = SignerState::load(&state_path)?;
+        Ok(Self {
+            keypair,
+            chain_id,
+            state,
+            state_path,
+        })
+    }
+
+    /// The state of the last signature
+    pub fn state(&self) -> &SignerState {
+        &self.state
+    }
+
+    /// Connect to the node listening on the given address and serve its
+    /// requests. The connection is retried whenever it fails or gets
+    /// closed, so this only returns if the address isn't supported.
+    pub fn run(&mut self, address: &TendermintAddress) -> Result<()> {
+        let path = match address {
+            TendermintAddress::Unix { path } => path,
+            _ => return Err(Error::UnsupportedAddress(address.clone())),
+        };
+        loop {
+            match UnixStream::connect(path) {
+                Ok(mut stream) => {
+                    tracing::info!("Connected to the node at {}", address);
+                    match self.serve(&mut stream) {
+                        Ok(()) => {
+                            tracing::info!("The node closed the connection")
+                        }
+                        Err(err) => tracing::error!("{}", err),
+                    }
+                }
+                Err(err) => {
+                    tracing::debug!(
+                        "Couldn't connect to the node at {}: {}",
+                        address,
+                        err
+                    );
+                }
+            }
+            thread::sleep(RECONNECT_INTERVAL);
+        }
+    }
+
+    /// Serve the requests from a connection to the node until it's closed
+    pub fn serve(&mut self, stream: &mut (impl Read + Write)) -> Result<()> {
+        while let Some(request) = read_message(stream)? {
+            let response = match request.sum {
+                Some(request) => self.handle(request)?,
+                None => return Err(Error::UnexpectedMessage(None)),
+            };
+            write_message(
+                stream,
+                &Message {
+                    sum: Some(response),
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Handle a request from the node. Requests that cannot be signed are
+    /// answered with an error, only unexpected messages fail.
+    pub fn handle(&mut self, request: Sum) -> Result<Sum> {
+        let response = match request {
+            Sum::PubKeyRequest(request) => Sum::PubKeyResponse(
+                match self.check_chain_id(&request.chain_id) {
+                    Ok(()) => PubKeyResponse {
+                        pub_key: Some(self.public_key()),
+                        error: None,
+                    },
+                    Err(err) => PubKeyResponse {
+                        pub_key: None,
+                        error: Some(remote_signer_error(err)),
+                    },
+                },
+            ),
+            Sum::SignVoteRequest(request) => {
+                let vote = request.vote.ok_or_else(|| {
+                    Error::InvalidRequest("Missing vote".to_string())
+                });
+                let result = self
+                    .check_chain_id(&request.chain_id)
+                    .and_then(|()| self.sign_vote(vote?));
+                Sum::SignedVoteResponse(match result {
+                    Ok(vote) => SignedVoteResponse {
+                        vote: Some(vote),
+                        error: None,
+                    },
+                    Err(err) => SignedVoteResponse {
+                        vote: None,
+                        error: Some(remote_signer_error(err)),
+                    },
+                })
+            }
+            Sum::SignProposalRequest(request) => {
+                let proposal = request.proposal.ok_or_else(|| {
+                    Error::InvalidRequest("Missing proposal".to_string())
+                });
+                let result = self
+                    .check_chain_id(&request.chain_id)
+                    .and_then(|()| self.sign_proposal(proposal?));
+                Sum::SignedProposalResponse(match result {
+                    Ok(proposal) => SignedProposalResponse {
+                        proposal: Some(proposal),
+                        error: None,
+                    },
+                    Err(err) => SignedProposalResponse {
+                        proposal: None,
+                        error: Some(remote_signer_error(err)),
+                    },
+                })
+            }
+            Sum::PingRequest(_) => Sum::PingResponse(PingResponse {}),
+            other => return Err(Error::UnexpectedMessage(Some(other))),
+        };
+        Ok(response)
+    }
+
+    /// The consensus public key in Tendermint's encoding
+    fn public_key(&self) -> PublicKey {
+        let pk = self.keypair.ref_to().try_to_vec().unwrap();
+        PublicKey {
+            sum: Some(public_key::Sum::Ed25519(pk)),
+        }
+    }
+
+    fn check_chain_id(&self, chain_id: &str) -> Result<()> {
+        if chain_id == self.chain_id.as_str() {
+            Ok(())
+        } else {
+            Err(Error::WrongChain(chain_id.to_string()))
+        }
+    }
+
+    /// Sign a prevote or a precommit
+    pub fn sign_vote(&mut self, mut vote: Vote) -> Result<Vote> {
+        let step = match SignedMsgType::from_i32(vote.r#type) {
+            Some(SignedMsgType::Prevote) => STEP_PREVOTE,
+            Some(SignedMsgType::Precommit) => STEP_PRECOMMIT,
+            _ => {
+                return Err(Error::InvalidRequest(format!(
+                    "Unexpected vote type {}",
+                    vote.r#type
+                )));
+            }
+        };
+        let canonical = CanonicalVote {
+            r#type: vote.r#type,
+            height: vote.height,
+            round: i64::from(vote.round),
+            block_id: canonical_block_id(vote.block_id.clone()),
+            timestamp: vote.timestamp.clone(),
+            chain_id: self.chain_id.as_str().to_string(),
+        };
+        let (signature, timestamp) =
+            self.sign(vote.height, vote.round, step, canonical)?;
+        vote.signature = signature;
+        vote.timestamp = timestamp;
+        Ok(vote)
+    }
+
+    /// Sign a block proposal
+    pub fn sign_proposal(
+        &mut self,
+        mut proposal: Proposal,
+    ) -> Result<Proposal> {
+        if proposal.r#type != SignedMsgType::Proposal as i32 {
+            return Err(Error::InvalidRequest(format!(
+                "Unexpected proposal type {}",
+                proposal.r#type
+            )));
+        }
+        let canonical = CanonicalProposal {
+            r#type: proposal.r#type,
+            height: proposal.height,
+            round: i64::from(proposal.round),
+            pol_round: i64::from(proposal.pol_round),
+            block_id: canonical_block_id(proposal.block_id.clone()),
+            timestamp: proposal.timestamp.clone(),
+            chain_id: self.chain_id.as_str().to_string(),
+        };
+        let (signature, timestamp) = self.sign(
+            proposal.height,
+            proposal.round,
+            STEP_PROPOSAL,
+            canonical,
+        )?;
+        proposal.signature = signature;
+        proposal.timestamp = timestamp;
+        Ok(proposal)
+    }
+
+    /// Sign a canonical message at the given height, round and step, unless
+    /// it could be a double-sign. Returns the signature with the timestamp
+    /// that the message must have.
+    fn sign<T: Canonical>(
+        &mut self,
+        height: i64,
+        round: i32,
+        step: u8,
+        mut canonical: T,
+    ) -> Result<(Vec<u8>, Option<Timestamp>)> {
+        let sign_bytes = canonical.encode_length_delimited_to_vec();
+        if self.state.check(height, round, step)? {
+            if sign_bytes == self.state.sign_bytes {
+                return Ok((
+                    self.state.signature.clone(),
+                    canonical.timestamp_mut().clone(),
+                ));
+            }
+            // After a restart, the node may ask to sign the last message
+            // again with a new timestamp. The last signature is returned
+            // along with its timestamp.
+            let mut last =
+                T::decode_length_delimited(&self.state.sign_bytes[..])
+                    .map_err(Error::Decode)?;
+            let last_timestamp = last.timestamp_mut().take();
+            *canonical.timestamp_mut() = None;
+            if last == canonical {
+                return Ok((self.state.signature.clone(), last_timestamp));
+            }
+            return Err(Error::DoubleSign {
+                height,
+                round,
+                step,
+            });
+        }
+        let signature = ed25519::SigScheme::sign(&self.keypair, &sign_bytes)
+            .try_to_vec()
+            .unwrap();
+        self.state = SignerState {
+            height,
+            round,
+            step,
+            sign_bytes,
+            signature: signature.clone(),
+        };
+        self.state.save(&self.state_path)?;
+        Ok((signature, canonical.timestamp_mut().clone()))
+    }
+}
+
+/// A block ID in its canonical form. A nil vote's block ID is empty and has
+/// no canonical form.
+fn canonical_block_id(block_id: Option<BlockId>) -> Option<CanonicalBlockId> {
+    let block_id = block_id?;
+    let part_set_header = block_id.part_set_header.unwrap_or_default();
+    if block_id.hash.is_empty()
+        && part_set_header.total == 0
+        && part_set_header.hash.is_empty()
+    {
+        return None;
+    }
+    Some(CanonicalBlockId {
+        hash: block_id.hash,
+        part_set_header: Some(CanonicalPartSetHeader {
+            total: part_set_header.total,
+            hash: part_set_header.hash,
+        }),
+    })
+}
+
+fn remote_signer_error(err: Error) -> RemoteSignerError {
+    tracing::warn!("{}", err);
+    RemoteSignerError {
+        code: 0,
+        description: err.to_string(),
+    }
+}
+
+/// Read a length-delimited message. Returns `None` if the connection was
+/// closed before a new message.
+fn read_message(stream: &mut impl Read) -> Result<Option<Message>> {
+    // The length is a varint of at most 10 bytes
+    let mut delimiter = Vec::with_capacity(10);
+    loop {
+        let mut byte = [0; 1];
+        match stream.read_exact(&mut byte) {
+            Err(err)
+                if err.kind() == io::ErrorKind::UnexpectedEof
+                    && delimiter.is_empty() =>
+            {
+                return Ok(None);
+            }
+            result => result.map_err(Error::Connection)?,
+        }
+        delimiter.push(byte[0]);
+        if byte[0] & 0x80 == 0 || delimiter.len() == 10 {
+            break;
+        }
+    }
+    let len = prost::decode_length_delimiter(&delimiter[..])
+        .map_err(Error::Decode)?;
+    if len > MAX_MESSAGE_LEN {
+        return Err(Error::MessageTooLarge(len));
+    }
+    let mut bytes = vec![0; len];
+    stream.read_exact(&mut bytes).map_err(Error::Connection)?;
+    Message::decode(&bytes[..]).map(Some).map_err(Error::Decode)
+}
+
+/// Write a length-delimited message
+fn write_message(stream: &mut impl Write, message: &Message) -> Result<()> {
+    stream
+        .write_all(&message.encode_length_delimited_to_vec())
+        .map_err(Error::Connection)
+}
+
+/// Serialize bytes as an upper-case hex string
+mod hex_bytes {
+    use data_encoding::HEXUPPER;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&HEXUPPER.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let hex = String::deserialize(deserializer)?;
+        HEXUPPER
+            .decode(hex.as_bytes())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use borsh::BorshDeserialize;
+
+    use super::*;
+    use crate::facade::tendermint_proto::privval::{
+        PingRequest, PubKeyRequest, SignVoteRequest,
+    };
+    use crate::facade::tendermint_proto::types::PartSetHeader;
+
+    fn vote(height: i64, round: i32, block_hash: u8, seconds: i64) -> Vote {
+        Vote {
+            r#type: SignedMsgType::Prevote as i32,
+            height,
+            round,
+            block_id: Some(BlockId {
+                hash: vec![block_hash; 32],
+                part_set_header: Some(PartSetHeader {
+                    total: 1,
+                    hash: vec![block_hash; 32],
+                }),
+            }),
+            timestamp: Some(Timestamp { seconds, nanos: 0 }),
+            validator_address: vec![0; 20],
+            ..Default::default()
+        }
+    }
+
+    fn new_signer(state_path: PathBuf) -> ConsensusSigner {
+        ConsensusSigner::new(
+            &testing::keypair_1(),
+            ChainId::default(),
+            state_path,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_signer_state_check() {
+        let state = SignerState {
+            height: 2,
+            round: 1,
+            step: STEP_PREVOTE,
+            sign_bytes: vec![1],
+            signature: vec![1],
+        };
+        assert!(!state.check(2, 1, STEP_PRECOMMIT).unwrap());
+        assert!(!state.check(2, 2, STEP_PROPOSAL).unwrap());
+        assert!(!state.check(3, 0, STEP_PROPOSAL).unwrap());
+        assert!(state.check(2, 1, STEP_PREVOTE).unwrap());
+        assert!(state.check(2, 1, STEP_PROPOSAL).is_err());
+        assert!(state.check(2, 0, STEP_PRECOMMIT).is_err());
+        assert!(state.check(1, 3, STEP_PRECOMMIT).is_err());
+    }
+
+    #[test]
+    fn test_double_sign_protection() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("signer_state.json");
+        let mut signer = new_signer(state_path.clone());
+
+        let signed = signer.sign_vote(vote(10, 0, 1, 100)).unwrap();
+        let pk = testing::keypair_1().ref_to().try_to_pk().unwrap();
+        let signature =
+            ed25519::Signature::try_from_slice(&signed.signature).unwrap();
+        ed25519::SigScheme::verify_signature_raw(
+            &pk,
+            &signer.state().sign_bytes,
+            &signature,
+        )
+        .unwrap();
+
+        // The same vote is signed again with the same signature, even with a
+        // new timestamp
+        assert_eq!(signer.sign_vote(vote(10, 0, 1, 100)).unwrap(), signed);
+        assert_eq!(signer.sign_vote(vote(10, 0, 1, 200)).unwrap(), signed);
+        // A vote for another block or at a past step is refused
+        assert!(signer.sign_vote(vote(10, 0, 2, 100)).is_err());
+        assert!(signer.sign_vote(vote(9, 5, 1, 100)).is_err());
+
+        // The state is persisted across restarts
+        let mut signer = new_signer(state_path);
+        assert!(signer.sign_vote(vote(10, 0, 2, 100)).is_err());
+        let mut precommit = vote(10, 0, 1, 100);
+        precommit.r#type = SignedMsgType::Precommit as i32;
+        assert!(signer.sign_vote(precommit).is_ok());
+        assert_eq!(signer.state().step, STEP_PRECOMMIT);
+    }
+
+    #[test]
+    fn test_serve_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut signer = new_signer(dir.path().join("signer_state.json"));
+        let (mut node, mut stream) = UnixStream::pair().unwrap();
+        let handle = thread::spawn(move || signer.serve(&mut stream));
+
+        let mut request = |sum| {
+            write_message(&mut node, &Message { sum: Some(sum) }).unwrap();
+            read_message(&mut node).unwrap().unwrap().sum.unwrap()
+        };
+        let pk = testing::keypair_1()
+            .ref_to()
+            .try_to_pk::<ed25519::PublicKey>()
+            .unwrap()
+            .try_to_vec()
+            .unwrap();
+        assert!(matches!(
+            request(Sum::PubKeyRequest(PubKeyRequest {
+                chain_id: ChainId::default().as_str().to_string(),
+            })),
+            Sum::PubKeyResponse(PubKeyResponse {
+                pub_key: Some(PublicKey {
+                    sum: Some(public_key::Sum::Ed25519(key)),
+                }),
+                error: None,
+            }) if key == pk
+        ));
+        assert!(matches!(
+            request(Sum::PingRequest(PingRequest {})),
+            Sum::PingResponse(_)
+        ));
+        assert!(matches!(
+            request(Sum::SignVoteRequest(SignVoteRequest {
+                vote: Some(vote(1, 0, 1, 100)),
+                chain_id: "other-chain".to_string(),
+            })),
+            Sum::SignedVoteResponse(SignedVoteResponse {
+                vote: None,
+                error: Some(_),
+            })
+        ));
+        assert!(matches!(
+            request(Sum::SignVoteRequest(SignVoteRequest {
+                vote: Some(vote(1, 0, 1, 100)),
+                chain_id: ChainId::default().as_str().to_string(),
+            })),
+            Sum::SignedVoteResponse(SignedVoteResponse {
+                vote: Some(_),
+                error: None,
+            })
+        ));
+
+        drop(node);
+        handle.join().unwrap().unwrap();
+    }
+}
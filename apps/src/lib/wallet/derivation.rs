@@ -0,0 +1,399 @@
+//! Hierarchical deterministic keys derived from a BIP39 mnemonic code.
+//!
+//! Transparent keys are derived with SLIP-10 for ed25519 and BIP32 for
+//! secp256k1. Spending keys are derived with ZIP32.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use borsh::{BorshDeserialize, BorshSerialize};
+use data_encoding::HEXLOWER;
+use hmac::{Hmac, Mac, NewMac};
+use masp_primitives::zip32;
+use namada::types::key::*;
+use namada::types::masp::ExtendedSpendingKey;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Sha512;
+use thiserror::Error;
+
+/// The SLIP-44 coin type registered for Namada
+pub const NAMADA_COIN_TYPE: u32 = 877;
+
+/// Child indices from this offset up are hardened
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+#[derive(Error, Debug)]
+pub enum DerivationError {
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+    #[error("Invalid mnemonic code: {0}")]
+    InvalidMnemonic(String),
+    #[error("Ed25519 keys can only be derived at hardened indices")]
+    NonHardenedEd25519,
+    #[error("Only ed25519 and secp256k1 keys can be derived")]
+    UnsupportedScheme,
+    #[error("The derived key is invalid, use another derivation path")]
+    InvalidDerivedKey,
+    #[error("Invalid wallet seed: {0}")]
+    InvalidSeed(String),
+    #[error("The alias \"{0}\" is already used by a key derived at {1}")]
+    AliasHasPath(String, DerivationPath),
+}
+
+/// An index of a child key in a derivation path
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChildIndex {
+    /// A child whose public key can be derived from its parent's public key
+    NonHardened(u32),
+    /// A child that can only be derived from its parent's secret key
+    Hardened(u32),
+}
+
+impl ChildIndex {
+    fn is_hardened(&self) -> bool {
+        matches!(self, ChildIndex::Hardened(_))
+    }
+
+    /// The index encoded as in BIP32, with the hardened flag in the top bit
+    fn to_bits(self) -> u32 {
+        match self {
+            ChildIndex::NonHardened(index) => index,
+            ChildIndex::Hardened(index) => index | HARDENED_OFFSET,
+        }
+    }
+}
+
+impl From<ChildIndex> for zip32::ChildIndex {
+    fn from(index: ChildIndex) -> Self {
+        match index {
+            ChildIndex::NonHardened(index) => {
+                zip32::ChildIndex::NonHardened(index)
+            }
+            ChildIndex::Hardened(index) => zip32::ChildIndex::Hardened(index),
+        }
+    }
+}
+
+/// A derivation path from a master key, written as e.g.
+/// `m/44'/877'/0'/0'/0'`. Hardened indices are marked with `'` or `h`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath(Vec<ChildIndex>);
+
+impl DerivationPath {
+    /// The default path of transparent keys of the given scheme, following
+    /// BIP44. All the indices of ed25519 keys are hardened as SLIP-10
+    /// doesn't support non-hardened derivation for them.
+    pub fn default_for_transparent_scheme(scheme: SchemeType) -> Self {
+        let mut path = vec![
+            ChildIndex::Hardened(44),
+            ChildIndex::Hardened(NAMADA_COIN_TYPE),
+            ChildIndex::Hardened(0),
+        ];
+        match scheme {
+            SchemeType::Secp256k1 => path.extend([
+                ChildIndex::NonHardened(0),
+                ChildIndex::NonHardened(0),
+            ]),
+            _ => {
+                path.extend([ChildIndex::Hardened(0), ChildIndex::Hardened(0)])
+            }
+        }
+        Self(path)
+    }
+
+    /// The default path of spending keys, following ZIP32
+    pub fn default_for_shielded() -> Self {
+        Self(vec![
+            ChildIndex::Hardened(32),
+            ChildIndex::Hardened(NAMADA_COIN_TYPE),
+            ChildIndex::Hardened(0),
+        ])
+    }
+
+    /// Check that every index in the path is hardened
+    pub fn is_hardened(&self) -> bool {
+        self.0.iter().all(ChildIndex::is_hardened)
+    }
+
+    /// The indices of the path encoded as in BIP32, with the hardened flag in
+    /// the top bit
+    pub fn indices(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().map(|index| index.to_bits())
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            match index {
+                ChildIndex::NonHardened(index) => write!(f, "/{}", index)?,
+                ChildIndex::Hardened(index) => write!(f, "/{}'", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = DerivationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DerivationError::InvalidPath(s.to_string());
+        let mut segments = s.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid());
+        }
+        segments
+            .map(|segment| {
+                let (index, hardened) = match segment
+                    .strip_suffix('\'')
+                    .or_else(|| segment.strip_suffix('h'))
+                {
+                    Some(index) => (index, true),
+                    None => (segment, false),
+                };
+                let index: u32 = index.parse().map_err(|_| invalid())?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid());
+                }
+                Ok(if hardened {
+                    ChildIndex::Hardened(index)
+                } else {
+                    ChildIndex::NonHardened(index)
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Serialize for DerivationPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DerivationPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let path = String::deserialize(deserializer)?;
+        Self::from_str(&path).map_err(D::Error::custom)
+    }
+}
+
+/// The seed of the mnemonic code of a wallet, which all the keys generated
+/// with a derivation path are derived from
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct WalletSeed(Vec<u8>);
+
+impl WalletSeed {
+    /// Get the seed of a mnemonic code, protected by the given passphrase
+    /// which may be empty
+    pub fn new(mnemonic: &Mnemonic, passphrase: &str) -> Self {
+        Self(mnemonic_seed(mnemonic, passphrase).as_bytes().to_vec())
+    }
+
+    /// The bytes of the seed
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for WalletSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+impl FromStr for WalletSeed {
+    type Err = DerivationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HEXLOWER
+            .decode(s.as_bytes())
+            .map(Self)
+            .map_err(|err| DerivationError::InvalidSeed(err.to_string()))
+    }
+}
+
+/// Generate a new random 24 words mnemonic code
+pub fn gen_mnemonic() -> Mnemonic {
+    Mnemonic::new(MnemonicType::Words24, Language::English)
+}
+
+/// Parse a mnemonic code from its English words
+pub fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, DerivationError> {
+    Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|err| DerivationError::InvalidMnemonic(err.to_string()))
+}
+
+/// Get the seed of a mnemonic code, protected by the given passphrase which
+/// may be empty
+pub fn mnemonic_seed(mnemonic: &Mnemonic, passphrase: &str) -> Seed {
+    Seed::new(mnemonic, passphrase)
+}
+
+/// Derive a keypair of the given scheme from a seed at the given path
+pub fn derive_secret_key(
+    scheme: SchemeType,
+    seed: &[u8],
+    path: &DerivationPath,
+) -> Result<common::SecretKey, DerivationError> {
+    match scheme {
+        SchemeType::Ed25519 => {
+            if !path.is_hardened() {
+                return Err(DerivationError::NonHardenedEd25519);
+            }
+            let (mut key, mut chain_code) = hmac_sha512(b"ed25519 seed", seed);
+            for index in &path.0 {
+                let mut data = vec![0];
+                data.extend_from_slice(&key);
+                data.extend_from_slice(&index.to_bits().to_be_bytes());
+                (key, chain_code) = hmac_sha512(&chain_code, &data);
+            }
+            let sk = ed25519::SecretKey::try_from_slice(&key)
+                .map_err(|_| DerivationError::InvalidDerivedKey)?;
+            Ok(common::SecretKey::Ed25519(sk))
+        }
+        SchemeType::Secp256k1 => {
+            let (key, mut chain_code) = hmac_sha512(b"Bitcoin seed", seed);
+            let mut sk = secp256k1_secret_key(&key)?;
+            for index in &path.0 {
+                let mut data = if index.is_hardened() {
+                    let mut data = vec![0];
+                    data.extend_from_slice(&sk.0.serialize());
+                    data
+                } else {
+                    let pk: secp256k1::PublicKey = sk.ref_to();
+                    pk.0.serialize_compressed().to_vec()
+                };
+                data.extend_from_slice(&index.to_bits().to_be_bytes());
+                let (tweak, child_chain_code) = hmac_sha512(&chain_code, &data);
+                let tweak = secp256k1_secret_key(&tweak)?;
+                sk.0.tweak_add_assign(&tweak.0)
+                    .map_err(|_| DerivationError::InvalidDerivedKey)?;
+                chain_code = child_chain_code;
+            }
+            Ok(common::SecretKey::Secp256k1(sk))
+        }
+        SchemeType::Common => Err(DerivationError::UnsupportedScheme),
+    }
+}
+
+/// Derive a spending key from a seed at the given path
+pub fn derive_spending_key(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> ExtendedSpendingKey {
+    let master = zip32::ExtendedSpendingKey::master(seed);
+    let path: Vec<zip32::ChildIndex> =
+        path.0.iter().copied().map(Into::into).collect();
+    zip32::ExtendedSpendingKey::from_path(&master, &path).into()
+}
+
+/// Split the HMAC-SHA512 of the data into a key and a chain code
+fn hmac_sha512(key: &[u8], data: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key)
+        .expect("HMAC can take a key of any size");
+    mac.update(data);
+    let bytes = mac.finalize().into_bytes();
+    let mut key = [0; 32];
+    let mut chain_code = [0; 32];
+    key.copy_from_slice(&bytes[..32]);
+    chain_code.copy_from_slice(&bytes[32..]);
+    (key, chain_code)
+}
+
+/// Parse a secp256k1 secret key, which must be a non-zero scalar smaller than
+/// the curve order
+fn secp256k1_secret_key(
+    bytes: &[u8; 32],
+) -> Result<secp256k1::SecretKey, DerivationError> {
+    secp256k1::SecretKey::try_from_slice(bytes)
+        .map_err(|_| DerivationError::InvalidDerivedKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The seed of the first test vector of BIP32 and SLIP-10
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn secret_key_hex(sk: common::SecretKey) -> String {
+        let bytes = sk.try_to_vec().unwrap();
+        // Skip the scheme's tag
+        HEXLOWER.encode(&bytes[1..])
+    }
+
+    #[test]
+    fn test_derivation_path_encoding() {
+        let path = DerivationPath::from_str("m/44'/877h/0'/0/1").unwrap();
+        assert_eq!(path.to_string(), "m/44'/877'/0'/0/1");
+        assert!(!path.is_hardened());
+        assert_eq!(
+            DerivationPath::default_for_transparent_scheme(SchemeType::Ed25519)
+                .to_string(),
+            "m/44'/877'/0'/0'/0'"
+        );
+        assert!(DerivationPath::from_str("44'/0'").is_err());
+        assert!(DerivationPath::from_str("m/2147483648").is_err());
+        assert!(DerivationPath::from_str("m/a'").is_err());
+    }
+
+    #[test]
+    fn test_derive_ed25519_key() {
+        let seed = HEXLOWER.decode(SEED.as_bytes()).unwrap();
+        let path = DerivationPath::from_str("m/0'").unwrap();
+        let sk = derive_secret_key(SchemeType::Ed25519, &seed, &path).unwrap();
+        assert_eq!(
+            secret_key_hex(sk),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        let path = DerivationPath::from_str("m/0").unwrap();
+        assert!(derive_secret_key(SchemeType::Ed25519, &seed, &path).is_err());
+    }
+
+    #[test]
+    fn test_derive_secp256k1_key() {
+        let seed = HEXLOWER.decode(SEED.as_bytes()).unwrap();
+        let path = DerivationPath::from_str("m/0'/1").unwrap();
+        let sk =
+            derive_secret_key(SchemeType::Secp256k1, &seed, &path).unwrap();
+        assert_eq!(
+            secret_key_hex(sk),
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368"
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_restore() {
+        let mnemonic = gen_mnemonic();
+        let restored = parse_mnemonic(mnemonic.phrase()).unwrap();
+        let seed = mnemonic_seed(&mnemonic, "passphrase");
+        let restored_seed = mnemonic_seed(&restored, "passphrase");
+        let path = DerivationPath::default_for_shielded();
+        assert_eq!(
+            derive_spending_key(seed.as_bytes(), &path).to_string(),
+            derive_spending_key(restored_seed.as_bytes(), &path).to_string(),
+        );
+        assert!(parse_mnemonic("not a mnemonic").is_err());
+    }
+
+    #[test]
+    fn test_wallet_seed_encoding() {
+        let seed = WalletSeed::new(&gen_mnemonic(), "");
+        let decoded = WalletSeed::from_str(&seed.to_string()).unwrap();
+        assert_eq!(decoded.as_bytes(), seed.as_bytes());
+        assert!(WalletSeed::from_str("not hex").is_err());
+    }
+}
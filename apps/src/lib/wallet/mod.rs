@@ -1,5 +1,6 @@
 mod alias;
 pub mod defaults;
+pub mod derivation;
 mod keys;
 pub mod pre_genesis;
 mod store;
@@ -10,6 +11,7 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::{env, fs};
 
+use bip39::Mnemonic;
 use borsh::{BorshDeserialize, BorshSerialize};
 use masp_primitives::zip32::ExtendedFullViewingKey;
 use namada::types::address::Address;
@@ -21,9 +23,10 @@ pub use store::wallet_file;
 use thiserror::Error;
 
 use self::alias::Alias;
+use self::derivation::{DerivationError, DerivationPath, WalletSeed};
 pub use self::keys::{DecryptionError, StoredKeypair};
 use self::store::Store;
-pub use self::store::{ValidatorData, ValidatorKeys};
+pub use self::store::{HardwareKey, ValidatorData, ValidatorKeys};
 use crate::cli;
 use crate::config::genesis::genesis_config::GenesisConfig;
 
@@ -158,6 +161,86 @@ impl Wallet {
         (alias.into(), key)
     }
 
+    /// Derive a keypair from the seed of a mnemonic code at the given path
+    /// and insert it into the store along with its implicit address, similarly
+    /// to [`Wallet::gen_key`]. The path is stored with the key so that it can
+    /// be restored from the same mnemonic code.
+    pub fn derive_key(
+        &mut self,
+        scheme: SchemeType,
+        alias: Option<String>,
+        unsafe_dont_encrypt: bool,
+        seed: &[u8],
+        path: DerivationPath,
+    ) -> Result<(String, common::SecretKey), DerivationError> {
+        let sk = derivation::derive_secret_key(scheme, seed, &path)?;
+        let password = read_and_confirm_pwd(unsafe_dont_encrypt);
+        let (alias, key) =
+            self.store.insert_derived_key(sk, path, alias, password)?;
+        // Cache the newly added key
+        self.decrypted_key_cache.insert(alias.clone(), key.clone());
+        Ok((alias.into(), key))
+    }
+
+    /// Derive a spending key from the seed of a mnemonic code at the given
+    /// path similarly to [`Wallet::derive_key`].
+    pub fn derive_spending_key(
+        &mut self,
+        alias: String,
+        unsafe_dont_encrypt: bool,
+        seed: &[u8],
+        path: DerivationPath,
+    ) -> Result<(String, ExtendedSpendingKey), DerivationError> {
+        let key = derivation::derive_spending_key(seed, &path);
+        let password = Self::new_password_prompt(unsafe_dont_encrypt);
+        let (alias, key) = self
+            .store
+            .insert_derived_spending_key(key, path, alias, password)?;
+        // Cache the newly added key
+        self.decrypted_spendkey_cache.insert(alias.clone(), key);
+        Ok((alias.into(), key))
+    }
+
+    /// Get the seed of the wallet's mnemonic code that the keys generated
+    /// with a derivation path are derived from. If the seed is encrypted, will
+    /// prompt for its password from stdin. If the wallet has no seed yet, a
+    /// new mnemonic code is generated, protected by the BIP39 passphrase, and
+    /// its seed is stored, encrypted unless asked not to. The new mnemonic
+    /// code is then returned too, so that it can be written down.
+    pub fn get_or_gen_seed(
+        &mut self,
+        unsafe_dont_encrypt: bool,
+        bip39_passphrase: impl FnOnce() -> String,
+    ) -> Result<(WalletSeed, Option<Mnemonic>), DecryptionError> {
+        if let Some(seed) = self.store.get_seed() {
+            return Ok((seed.get(true, None)?, None));
+        }
+        let mnemonic = derivation::gen_mnemonic();
+        let seed = WalletSeed::new(&mnemonic, &bip39_passphrase());
+        println!("The wallet's seed is going to be stored.");
+        let password = read_and_confirm_pwd(unsafe_dont_encrypt);
+        self.store.set_seed(seed.clone(), password);
+        Ok((seed, Some(mnemonic)))
+    }
+
+    /// Find the derivation path of a keypair derived from a mnemonic code by
+    /// its alias.
+    pub fn find_key_derivation_path(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<&DerivationPath> {
+        self.store.find_key_derivation_path(alias)
+    }
+
+    /// Find the derivation path of a spending key derived from a mnemonic code
+    /// by its alias.
+    pub fn find_spending_key_derivation_path(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<&DerivationPath> {
+        self.store.find_spending_key_derivation_path(alias)
+    }
+
     /// Generate keypair
     /// for signing protocol txs and for the DKG (which will also be stored)
     /// A protocol keypair may be optionally provided, indicating that
@@ -235,6 +318,19 @@ impl Wallet {
         )
     }
 
+    /// Find the consensus key of the validator in the wallet by its default
+    /// alias. If the key is encrypted, will prompt for password from stdin.
+    pub fn find_validator_consensus_key(
+        &mut self,
+    ) -> Result<common::SecretKey, FindKeyError> {
+        let alias = self
+            .get_validator_data()
+            .and_then(|data| self.find_alias(&data.address))
+            .map(alias::validator_consensus_key)
+            .ok_or(FindKeyError::KeyNotFound)?;
+        self.find_key(alias.to_string())
+    }
+
     pub fn find_spending_key(
         &mut self,
         alias: impl AsRef<str>,
@@ -332,6 +428,43 @@ impl Wallet {
         )
     }
 
+    /// Find a key held by a hardware wallet by its public key hash.
+    pub fn find_hardware_key_by_pkh(
+        &self,
+        pkh: &PublicKeyHash,
+    ) -> Option<&HardwareKey> {
+        self.store.find_hardware_key_by_pkh(pkh)
+    }
+
+    /// Find a key held by a hardware wallet by its public key.
+    pub fn find_hardware_key_by_pk(
+        &self,
+        pk: &common::PublicKey,
+    ) -> Option<&HardwareKey> {
+        self.store.find_hardware_key_by_pkh(&pk.into())
+    }
+
+    /// Add a key held by a hardware wallet and its implicit address with the
+    /// given alias. Returns the alias the key has been added with, if any.
+    pub fn add_hardware_key(
+        &mut self,
+        alias: String,
+        key: HardwareKey,
+    ) -> Option<String> {
+        self.store
+            .insert_hardware_key(alias.into(), key)
+            .map(Into::into)
+    }
+
+    /// Get all known keys held by hardware wallets by their alias.
+    pub fn get_hardware_keys(&self) -> HashMap<String, HardwareKey> {
+        self.store
+            .get_hardware_keys()
+            .iter()
+            .map(|(alias, key)| (alias.into(), key.clone()))
+            .collect()
+    }
+
     /// Decrypt stored key, if it's not stored un-encrypted.
     /// If a given storage key needs to be decrypted, prompt for password from
     /// stdin and if successfully decrypted, store it in a cache.
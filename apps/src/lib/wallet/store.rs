@@ -21,6 +21,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::alias::{self, Alias};
+use super::derivation::{DerivationError, DerivationPath, WalletSeed};
 use super::keys::StoredKeypair;
 use super::pre_genesis;
 use crate::cli;
@@ -43,6 +44,16 @@ impl ValidatorKeys {
     }
 }
 
+/// A key held by a hardware wallet. Only its public key is known to the
+/// wallet, the device signs with the secret key derived at the path.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HardwareKey {
+    /// The public key of the key held by the device
+    pub public_key: common::PublicKey,
+    /// The path the device derives the key at
+    pub derivation_path: DerivationPath,
+}
+
 /// Special data associated with a validator
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ValidatorData {
@@ -54,6 +65,10 @@ pub struct ValidatorData {
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Store {
+    /// The seed of the wallet's mnemonic code, which the keys generated with a
+    /// derivation path are derived from
+    #[serde(default)]
+    seed: Option<StoredKeypair<WalletSeed>>,
     /// Known viewing keys
     view_keys: HashMap<Alias, ExtendedViewingKey>,
     /// Known spending keys
@@ -67,6 +82,17 @@ pub struct Store {
     /// Known mappings of public key hashes to their aliases in the `keys`
     /// field. Used for look-up by a public key.
     pkhs: HashMap<PublicKeyHash, Alias>,
+    /// Derivation paths of the keypairs derived from a mnemonic code, by
+    /// their alias
+    #[serde(default)]
+    key_derivation_paths: HashMap<Alias, DerivationPath>,
+    /// Derivation paths of the spending keys derived from a mnemonic code, by
+    /// their alias
+    #[serde(default)]
+    spending_key_derivation_paths: HashMap<Alias, DerivationPath>,
+    /// Keys held by hardware wallets
+    #[serde(default)]
+    hardware_keys: HashMap<Alias, HardwareKey>,
     /// Special keys if the wallet belongs to a validator
     pub(crate) validator_data: Option<ValidatorData>,
 }
@@ -248,6 +274,50 @@ impl Store {
         self.keys.get(alias)
     }
 
+    /// Find the derivation path of a keypair by its alias, if it has been
+    /// derived from a mnemonic code.
+    pub fn find_key_derivation_path(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<&DerivationPath> {
+        self.key_derivation_paths.get(&alias.into())
+    }
+
+    /// Find the derivation path of a spending key by its alias, if it has
+    /// been derived from a mnemonic code.
+    pub fn find_spending_key_derivation_path(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<&DerivationPath> {
+        self.spending_key_derivation_paths.get(&alias.into())
+    }
+
+    /// Get the stored seed of the wallet's mnemonic code, if any.
+    pub fn get_seed(&self) -> Option<&StoredKeypair<WalletSeed>> {
+        self.seed.as_ref()
+    }
+
+    /// Store the seed of the wallet's mnemonic code. If no password is
+    /// provided, the seed will be stored raw without encryption.
+    pub fn set_seed(&mut self, seed: WalletSeed, password: Option<String>) {
+        self.seed = Some(StoredKeypair::new(seed, password).0);
+    }
+
+    /// Find a key held by a hardware wallet by its public key hash.
+    pub fn find_hardware_key_by_pkh(
+        &self,
+        pkh: &PublicKeyHash,
+    ) -> Option<&HardwareKey> {
+        self.hardware_keys
+            .values()
+            .find(|key| &PublicKeyHash::from(&key.public_key) == pkh)
+    }
+
+    /// Get all known keys held by hardware wallets by their alias.
+    pub fn get_hardware_keys(&self) -> &HashMap<Alias, HardwareKey> {
+        &self.hardware_keys
+    }
+
     /// Find the stored alias for a public key hash.
     pub fn find_alias_by_pkh(&self, pkh: &PublicKeyHash) -> Option<Alias> {
         self.pkhs.get(pkh).cloned()
@@ -330,7 +400,34 @@ impl Store {
         alias: Option<String>,
         password: Option<String>,
     ) -> (Alias, common::SecretKey) {
-        let sk = gen_sk(scheme);
+        self.insert_new_key(gen_sk(scheme), alias, password)
+    }
+
+    /// Insert a keypair derived from a mnemonic code similarly to how it's
+    /// done for generated keypairs and remember its derivation path. An alias
+    /// that is already used by a derived key is rejected.
+    pub fn insert_derived_key(
+        &mut self,
+        sk: common::SecretKey,
+        path: DerivationPath,
+        alias: Option<String>,
+        password: Option<String>,
+    ) -> Result<(Alias, common::SecretKey), DerivationError> {
+        let pkh = PublicKeyHash::from(&sk.ref_to());
+        let alias: Alias = alias.unwrap_or_else(|| pkh.into()).into();
+        self.check_no_derivation_path(&alias)?;
+        let (alias, sk) = self.insert_new_key(sk, Some(alias.into()), password);
+        self.key_derivation_paths.insert(alias.clone(), path);
+        Ok((alias, sk))
+    }
+
+    /// Insert a new keypair and its implicit address with the given alias
+    fn insert_new_key(
+        &mut self,
+        sk: common::SecretKey,
+        alias: Option<String>,
+        password: Option<String>,
+    ) -> (Alias, common::SecretKey) {
         let pkh: PublicKeyHash = PublicKeyHash::from(&sk.ref_to());
         let (keypair_to_store, raw_keypair) = StoredKeypair::new(sk, password);
         let address = Address::Implicit(ImplicitAddress(pkh.clone()));
@@ -355,7 +452,56 @@ impl Store {
         alias: String,
         password: Option<String>,
     ) -> (Alias, ExtendedSpendingKey) {
-        let spendkey = Self::generate_spending_key();
+        self.insert_new_spending_key(
+            Self::generate_spending_key(),
+            alias,
+            password,
+        )
+    }
+
+    /// Insert a spending key derived from a mnemonic code similarly to how
+    /// it's done for generated spending keys and remember its derivation
+    /// path. An alias that is already used by a derived key is rejected.
+    pub fn insert_derived_spending_key(
+        &mut self,
+        spendkey: ExtendedSpendingKey,
+        path: DerivationPath,
+        alias: String,
+        password: Option<String>,
+    ) -> Result<(Alias, ExtendedSpendingKey), DerivationError> {
+        self.check_no_derivation_path(&Alias::from(alias.clone()))?;
+        let (alias, spendkey) =
+            self.insert_new_spending_key(spendkey, alias, password);
+        self.spending_key_derivation_paths
+            .insert(alias.clone(), path);
+        Ok((alias, spendkey))
+    }
+
+    /// Check that no key derived from a mnemonic code uses the given alias
+    fn check_no_derivation_path(
+        &self,
+        alias: &Alias,
+    ) -> Result<(), DerivationError> {
+        match self
+            .key_derivation_paths
+            .get(alias)
+            .or_else(|| self.spending_key_derivation_paths.get(alias))
+        {
+            Some(path) => Err(DerivationError::AliasHasPath(
+                alias.to_string(),
+                path.clone(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Insert a new spending key and its viewing key with the given alias
+    fn insert_new_spending_key(
+        &mut self,
+        spendkey: ExtendedSpendingKey,
+        alias: String,
+        password: Option<String>,
+    ) -> (Alias, ExtendedSpendingKey) {
         let viewkey = ExtendedFullViewingKey::from(&spendkey.into()).into();
         let (spendkey_to_store, _raw_spendkey) =
             StoredKeypair::new(spendkey, password);
@@ -484,6 +630,34 @@ impl Store {
         Some(alias)
     }
 
+    /// Insert a key held by a hardware wallet along with its implicit address
+    /// with the given alias, similarly to how it's done for keypairs.
+    pub fn insert_hardware_key(
+        &mut self,
+        alias: Alias,
+        key: HardwareKey,
+    ) -> Option<Alias> {
+        if alias.is_empty() {
+            eprintln!("Empty alias given.");
+            return None;
+        }
+        if self.contains_alias(&alias) {
+            match show_overwrite_confirmation(&alias, "a hardware key") {
+                ConfirmationResponse::Replace => {}
+                ConfirmationResponse::Reselect(new_alias) => {
+                    return self.insert_hardware_key(new_alias, key);
+                }
+                ConfirmationResponse::Skip => return None,
+            }
+        }
+        self.remove_alias(&alias);
+        let address =
+            Address::Implicit(ImplicitAddress((&key.public_key).into()));
+        self.addresses.insert(alias.clone(), address);
+        self.hardware_keys.insert(alias.clone(), key);
+        Some(alias)
+    }
+
     /// Insert viewing keys similarly to how it's done for keypairs
     pub fn insert_viewing_key(
         &mut self,
@@ -514,6 +688,7 @@ impl Store {
             || self.view_keys.contains_key(alias)
             || self.spend_keys.contains_key(alias)
             || self.keys.contains_key(alias)
+            || self.hardware_keys.contains_key(alias)
             || self.addresses.contains_left(alias)
     }
 
@@ -525,6 +700,9 @@ impl Store {
         self.keys.remove(alias);
         self.addresses.remove_by_left(alias);
         self.pkhs.retain(|_key, val| val != alias);
+        self.key_derivation_paths.remove(alias);
+        self.spending_key_derivation_paths.remove(alias);
+        self.hardware_keys.remove(alias);
     }
 
     /// Insert payment addresses similarly to how it's done for keypairs
@@ -551,16 +729,18 @@ impl Store {
         Some(alias)
     }
 
-    /// Helper function to restore keypair given alias-keypair mapping and the
-    /// pkhs-alias mapping.
+    /// Helper function to restore keypair given alias-keypair mapping, the
+    /// pkhs-alias mapping and the keypair's derivation path.
     fn restore_keypair(
         &mut self,
         alias: Alias,
         key: Option<StoredKeypair<common::SecretKey>>,
         pkh: Option<PublicKeyHash>,
+        path: Option<DerivationPath>,
     ) {
         key.map(|x| self.keys.insert(alias.clone(), x));
         pkh.map(|x| self.pkhs.insert(x, alias.clone()));
+        path.map(|x| self.key_derivation_paths.insert(alias.clone(), x));
     }
 
     /// Insert a new address with the given alias. If the alias is already used,
@@ -578,6 +758,7 @@ impl Store {
         // Addresses and keypairs can share aliases, so first remove any keys
         // sharing the same namesake before checking if alias has been used.
         let counterpart_key = self.keys.remove(&alias);
+        let counterpart_path = self.key_derivation_paths.remove(&alias);
         let mut counterpart_pkh = None;
         self.pkhs.retain(|k, v| {
             if v == &alias {
@@ -597,6 +778,7 @@ impl Store {
                         alias,
                         counterpart_key,
                         counterpart_pkh,
+                        counterpart_path,
                     );
                     return self.insert_address(new_alias, address);
                 }
@@ -607,6 +789,7 @@ impl Store {
                         alias,
                         counterpart_key,
                         counterpart_pkh,
+                        counterpart_path,
                     );
                     return None;
                 }
@@ -616,7 +799,12 @@ impl Store {
         self.addresses.insert(alias.clone(), address);
         // Since it is intended for the inserted address to share its namesake
         // with the pre-existing keypair
-        self.restore_keypair(alias.clone(), counterpart_key, counterpart_pkh);
+        self.restore_keypair(
+            alias.clone(),
+            counterpart_key,
+            counterpart_pkh,
+            counterpart_path,
+        );
         Some(alias)
     }
 
@@ -785,4 +973,79 @@ mod test_wallet {
         let data = store.encode();
         let _ = Store::decode(data).expect("Test failed");
     }
+
+    #[test]
+    fn test_derivation_paths() {
+        use crate::wallet::derivation;
+
+        let mut store = Store::new();
+        let seed = WalletSeed::new(&derivation::gen_mnemonic(), "");
+        store.set_seed(seed.clone(), None);
+        let key_path =
+            DerivationPath::default_for_transparent_scheme(SchemeType::Ed25519);
+        let sk = derivation::derive_secret_key(
+            SchemeType::Ed25519,
+            seed.as_bytes(),
+            &key_path,
+        )
+        .expect("Test failed");
+        store
+            .insert_derived_key(sk, key_path.clone(), Some("key".into()), None)
+            .expect("Test failed");
+        let spending_key_path = DerivationPath::default_for_shielded();
+        let spendkey = derivation::derive_spending_key(
+            seed.as_bytes(),
+            &spending_key_path,
+        );
+        store
+            .insert_derived_spending_key(
+                spendkey,
+                spending_key_path.clone(),
+                "spendkey".into(),
+                None,
+            )
+            .expect("Test failed");
+
+        // The paths of keypairs and spending keys are kept apart
+        assert_eq!(store.find_key_derivation_path("key"), Some(&key_path));
+        assert_eq!(store.find_spending_key_derivation_path("key"), None);
+        assert_eq!(
+            store.find_spending_key_derivation_path("spendkey"),
+            Some(&spending_key_path)
+        );
+        assert_eq!(store.find_key_derivation_path("spendkey"), None);
+
+        // An alias that already has a path is rejected for either kind
+        assert!(matches!(
+            store.insert_derived_spending_key(
+                spendkey,
+                spending_key_path,
+                "key".into(),
+                None,
+            ),
+            Err(DerivationError::AliasHasPath(_, path)) if path == key_path
+        ));
+        let sk = derivation::derive_secret_key(
+            SchemeType::Ed25519,
+            seed.as_bytes(),
+            &key_path,
+        )
+        .expect("Test failed");
+        assert!(store
+            .insert_derived_key(sk, key_path, Some("spendkey".into()), None)
+            .is_err());
+
+        // The seed and the paths are persisted
+        let store = Store::decode(store.encode()).expect("Test failed");
+        let stored_seed = store
+            .get_seed()
+            .expect("Test failed")
+            .get(false, None)
+            .expect("Test failed");
+        assert_eq!(stored_seed.as_bytes(), seed.as_bytes());
+        assert!(store.find_key_derivation_path("key").is_some());
+        assert!(store
+            .find_spending_key_derivation_path("spendkey")
+            .is_some());
+    }
 }
@@ -0,0 +1,66 @@
+//! The distributed key generation (DKG) of the key that the txs of each epoch
+//! are encrypted with.
+//!
+//! During every epoch, the validators of the next epoch run a session of
+//! Ferveo's publicly verifiable DKG by submitting protocol txs. Each validator
+//! deals shares of a random secret to the others and once enough of them have
+//! dealt, the deals are aggregated into the public key of the next epoch.
+//! Every validator is left with shares of the matching private key in
+//! proportion to its voting power, with which it computes decryption shares
+//! of the wrapper txs in the tx queue. A wrapper tx can be decrypted once
+//! more than [`SECURITY_THRESHOLD`] of the shares of its epoch's key have
+//! been submitted.
+
+pub mod storage;
+
+use std::ops::Range;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// The number of private key shares dealt in a DKG session, which are split
+/// between the validators in proportion to their voting power
+pub const TOTAL_WEIGHT: u32 = 64;
+
+/// The number of private key shares that an adversary may hold without
+/// being able to decrypt the txs. Any larger number of shares suffices to
+/// decrypt them.
+pub const SECURITY_THRESHOLD: u32 = TOTAL_WEIGHT * 2 / 3;
+
+/// The indices of the private key shares held by a validator
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct ShareRange {
+    /// The index of the first share
+    pub start: u32,
+    /// The index after the last share
+    pub end: u32,
+}
+
+impl ShareRange {
+    /// The number of shares in the range
+    pub fn weight(&self) -> u32 {
+        self.end - self.start
+    }
+
+    /// Iterate over the indices of the shares
+    pub fn indices(&self) -> Range<u32> {
+        self.start..self.end
+    }
+}
+
+/// The messages of a DKG session applied in the ledger, in order and with
+/// the validators who sent them, from which the state of the session can be
+/// restored
+#[cfg(feature = "ferveo-tpke")]
+pub type DkgTranscript = Vec<(
+    crate::types::address::Address,
+    crate::types::transaction::protocol::DkgMessage,
+)>;
@@ -0,0 +1,103 @@
+//! Storage layout of the DKG.
+//!
+//! All the keys live under the PoS address, whose VP rejects the changes
+//! that txs make to them, as they may only be written by the protocol:
+//! - `#PoS/dkg/transcript/<epoch>`: the messages of the DKG session of an epoch
+//! - `#PoS/dkg/encryption_key/<epoch>`: the public key that the txs of an epoch
+//!   are encrypted with
+//! - `#PoS/dkg/share_ranges/<epoch>`: the indices of the private key shares of
+//!   each validator of an epoch
+//! - `#PoS/dkg/decryption_shares/<tx_hash>`: the verified decryption shares of
+//!   a wrapper tx in the tx queue submitted by each validator
+//! - `#PoS/dkg/queued_at/<tx_hash>`: the height of the block that added a
+//!   wrapper tx to the tx queue
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::hash::Hash;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+
+const ADDRESS: Address = Address::Internal(InternalAddress::PoS);
+
+const DKG_STORAGE_KEY: &str = "dkg";
+const TRANSCRIPT_STORAGE_KEY: &str = "transcript";
+const ENCRYPTION_KEY_STORAGE_KEY: &str = "encryption_key";
+const SHARE_RANGES_STORAGE_KEY: &str = "share_ranges";
+const DECRYPTION_SHARES_STORAGE_KEY: &str = "decryption_shares";
+const QUEUED_AT_STORAGE_KEY: &str = "queued_at";
+
+/// Get the key corresponding to #PoS/dkg
+fn dkg_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&DKG_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key is under the DKG's prefix
+pub fn is_dkg_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..]
+            if addr == &ADDRESS && prefix == DKG_STORAGE_KEY)
+}
+
+/// Get the key of the transcript of the DKG session of the given epoch
+pub fn transcript_key(epoch: Epoch) -> Key {
+    dkg_prefix()
+        .push(&TRANSCRIPT_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&epoch.0))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the encryption key of the given epoch
+pub fn encryption_key_key(epoch: Epoch) -> Key {
+    dkg_prefix()
+        .push(&ENCRYPTION_KEY_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&epoch.0))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the validators' private key shares of the given epoch
+pub fn share_ranges_key(epoch: Epoch) -> Key {
+    dkg_prefix()
+        .push(&SHARE_RANGES_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&epoch.0))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the decryption shares of the wrapper tx with the given
+/// hash commitment
+pub fn decryption_shares_key(tx_hash: &Hash) -> Key {
+    dkg_prefix()
+        .push(&DECRYPTION_SHARES_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&tx_hash.to_string()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the height of the block that added the wrapper tx with the
+/// given hash commitment to the tx queue
+pub fn queued_at_key(tx_hash: &Hash) -> Key {
+    dkg_prefix()
+        .push(&QUEUED_AT_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&tx_hash.to_string()))
+        .expect("Cannot obtain a storage key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dkg_keys() {
+        let keys = [
+            transcript_key(Epoch(1)),
+            encryption_key_key(Epoch(1)),
+            share_ranges_key(Epoch(1)),
+            decryption_shares_key(&Hash::default()),
+            queued_at_key(&Hash::default()),
+        ];
+        for key in &keys {
+            assert!(is_dkg_key(key));
+        }
+        assert_ne!(encryption_key_key(Epoch(1)), encryption_key_key(Epoch(2)));
+        assert!(!is_dkg_key(&Key::from(ADDRESS.to_db_key())));
+    }
+}
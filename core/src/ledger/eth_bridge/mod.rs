@@ -0,0 +1,9 @@
+//! Ethereum bridge library code
+
+use crate::types::address::{Address, InternalAddress};
+
+/// Ethereum bridge storage
+pub mod storage;
+
+/// The Ethereum bridge internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::EthBridge);
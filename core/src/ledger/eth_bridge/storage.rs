@@ -0,0 +1,204 @@
+//! Storage layout of the Ethereum bridge.
+//!
+//! All the keys live under the bridge's internal address:
+//! - `#EthBridge/queue/<nonce>`: transfers to Ethereum pending to be relayed
+//! - `#EthBridge/erc20/<asset>/balance/<owner>`: balances of the wrapped ERC20
+//!   tokens, which are multitokens of the bridge address with the ERC20 address
+//!   as the sub-prefix
+//! - `#EthBridge/native_erc20`: the ERC20 address of the wrapped native token
+//!   on Ethereum
+//! - `#EthBridge/nonces/{to_namada,to_ethereum}`: the next nonce of the
+//!   transfers in each direction
+//! - `#EthBridge/events/to_namada/<nonce>`: the transfers to Namada attested by
+//!   the validators
+//! - `#EthBridge/events/tallies/<hash>`: the validators' votes on an observed
+//!   Ethereum event, keyed by the event's hash
+//!
+//! The native tokens sent to Ethereum are escrowed in the bridge's balance of
+//! the native token (see [`escrow_key`]).
+
+use super::ADDRESS;
+use crate::types::address::Address;
+use crate::types::ethereum_events::EthAddress;
+use crate::types::hash::Hash;
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::token;
+
+const QUEUE_STORAGE_KEY: &str = "queue";
+const WRAPPED_ERC20_STORAGE_KEY: &str = "erc20";
+const NATIVE_ERC20_STORAGE_KEY: &str = "native_erc20";
+const NONCES_STORAGE_KEY: &str = "nonces";
+const TO_NAMADA_STORAGE_KEY: &str = "to_namada";
+const TO_ETHEREUM_STORAGE_KEY: &str = "to_ethereum";
+const EVENTS_STORAGE_KEY: &str = "events";
+const TALLIES_STORAGE_KEY: &str = "tallies";
+
+/// Check if the given key is under the Ethereum bridge address
+pub fn is_eth_bridge_key(key: &Key) -> bool {
+    matches!(key.segments.first(), Some(DbKeySeg::AddressSeg(addr)) if addr == &ADDRESS)
+}
+
+/// Get the key corresponding to @EthBridge/queue
+pub fn queue_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&QUEUE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of a pending transfer to Ethereum with the given nonce
+pub fn pending_transfer_key(nonce: u64) -> Key {
+    queue_key()
+        .push(&nonce)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key is under the queue of the pending transfers to
+/// Ethereum
+pub fn is_queue_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..]
+            if addr == &ADDRESS && prefix == QUEUE_STORAGE_KEY)
+}
+
+/// Get the multitoken sub-prefix of the wrapped ERC20 token, to be used with
+/// the bridge address as the token address
+pub fn wrapped_erc20_sub_prefix(asset: &EthAddress) -> Key {
+    Key::from(WRAPPED_ERC20_STORAGE_KEY.to_owned().to_db_key())
+        .push(asset)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the owner's balance of the wrapped ERC20 token
+pub fn wrapped_erc20_balance_key(asset: &EthAddress, owner: &Address) -> Key {
+    let prefix = token::multitoken_balance_prefix(
+        &ADDRESS,
+        &wrapped_erc20_sub_prefix(asset),
+    );
+    token::multitoken_balance_key(&prefix, owner)
+}
+
+/// Check if the given key is a balance key of a wrapped ERC20 token. If it is,
+/// returns the ERC20 address and the owner.
+pub fn is_wrapped_erc20_balance_key(
+    key: &Key,
+) -> Option<(EthAddress, &Address)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(asset),
+            DbKeySeg::StringSeg(balance),
+            DbKeySeg::AddressSeg(owner),
+        ] if addr == &ADDRESS
+            && prefix == WRAPPED_ERC20_STORAGE_KEY
+            && balance == token::BALANCE_STORAGE_KEY =>
+        {
+            let asset = EthAddress::parse(asset.clone()).ok()?;
+            Some((asset, owner))
+        }
+        _ => None,
+    }
+}
+
+/// Get the key of the ERC20 address of the wrapped native token on Ethereum
+pub fn native_erc20_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&NATIVE_ERC20_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the bridge's balance of the native token, holding the
+/// tokens escrowed while they are on Ethereum
+pub fn escrow_key(native_token: &Address) -> Key {
+    token::balance_key(native_token, &ADDRESS)
+}
+
+/// Get the key of the nonce of the next transfers to Namada to be applied
+pub fn transfers_to_namada_nonce_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&NONCES_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&TO_NAMADA_STORAGE_KEY.to_owned()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the nonce of the next transfer to Ethereum
+pub fn transfers_to_ethereum_nonce_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&NONCES_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&TO_ETHEREUM_STORAGE_KEY.to_owned()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key prefix of the attested events
+pub fn events_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&EVENTS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the transfers to Namada with the given nonce, once they
+/// have been attested by the validators
+pub fn attested_transfers_to_namada_key(nonce: u64) -> Key {
+    events_prefix()
+        .push(&TO_NAMADA_STORAGE_KEY.to_owned())
+        .and_then(|key| key.push(&nonce))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key prefix of the tallies of the votes on the Ethereum events
+pub fn event_tallies_prefix() -> Key {
+    events_prefix()
+        .push(&TALLIES_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the tally of the votes on the Ethereum event with the given
+/// hash
+pub fn event_tally_key(event_hash: &Hash) -> Key {
+    event_tallies_prefix()
+        .push(&event_hash.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key is a key of the attested events
+pub fn is_events_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), ..]
+            if addr == &ADDRESS && prefix == EVENTS_STORAGE_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::established_address_1;
+    use crate::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+
+    #[test]
+    fn test_wrapped_erc20_balance_key() {
+        let owner = established_address_1();
+        let key = wrapped_erc20_balance_key(&DAI_ERC20_ETH_ADDRESS, &owner);
+        assert_eq!(
+            is_wrapped_erc20_balance_key(&key),
+            Some((DAI_ERC20_ETH_ADDRESS, &owner))
+        );
+        // The wrapped tokens are multitokens of the bridge
+        let (sub_prefix, multitoken_owner) =
+            token::is_multitoken_balance_key(&ADDRESS, &key).unwrap();
+        assert_eq!(
+            sub_prefix,
+            wrapped_erc20_sub_prefix(&DAI_ERC20_ETH_ADDRESS)
+        );
+        assert_eq!(multitoken_owner, &owner);
+
+        assert!(is_wrapped_erc20_balance_key(&queue_key()).is_none());
+    }
+
+    #[test]
+    fn test_pending_transfer_key() {
+        let key = pending_transfer_key(42);
+        assert!(is_queue_key(&key));
+        assert!(is_queue_key(&queue_key()));
+        assert!(is_eth_bridge_key(&key));
+        assert!(!is_queue_key(&native_erc20_key()));
+    }
+}
@@ -38,6 +38,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct BlockGasMeter {
     block_gas: u64,
     transaction_gas: u64,
+    /// The gas limit declared by the wrapper of the current transaction, if
+    /// any. It cannot raise the protocol's transaction gas limit.
+    transaction_gas_limit: Option<u64>,
 }
 
 /// Gas metering in a validity predicate
@@ -68,12 +71,23 @@ impl BlockGasMeter {
             .checked_add(gas)
             .ok_or(Error::GasOverflow)?;
 
-        if self.transaction_gas > TRANSACTION_GAS_LIMIT {
+        let limit = self
+            .transaction_gas_limit
+            .map_or(TRANSACTION_GAS_LIMIT, |limit| {
+                limit.min(TRANSACTION_GAS_LIMIT)
+            });
+        if self.transaction_gas > limit {
             return Err(Error::TransactionGasExceededError);
         }
         Ok(())
     }
 
+    /// Set the gas limit of the current transaction, declared by its wrapper.
+    /// The limit is reset when the transaction is finalized.
+    pub fn set_transaction_gas_limit(&mut self, limit: u64) {
+        self.transaction_gas_limit = Some(limit);
+    }
+
     /// Add the base transaction fee and the fee per transaction byte that's
     /// charged the moment we try to apply the transaction.
     pub fn add_base_transaction_fee(&mut self, bytes_len: usize) -> Result<()> {
@@ -98,6 +112,7 @@ impl BlockGasMeter {
 
         let transaction_gas = self.transaction_gas;
         self.transaction_gas = 0;
+        self.transaction_gas_limit = None;
         if self.block_gas > BLOCK_GAS_LIMIT {
             return Err(Error::BlockGasExceeded);
         }
@@ -107,6 +122,7 @@ impl BlockGasMeter {
     /// Reset the gas meter.
     pub fn reset(&mut self) {
         self.transaction_gas = 0;
+        self.transaction_gas_limit = None;
         self.block_gas = 0;
     }
 
@@ -283,6 +299,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tx_gas_limit_from_wrapper() {
+        let mut meter = BlockGasMeter::default();
+        meter.set_transaction_gas_limit(1_000);
+        meter.add(1_000).expect("within the wrapper's gas limit");
+        assert_matches!(
+            meter.add(1).expect_err("unexpectedly succeeded"),
+            Error::TransactionGasExceededError
+        );
+
+        // The limit only applies to the current transaction
+        meter
+            .finalize_transaction()
+            .expect("over the block gas limit");
+        meter.add(1_001).expect("over the tx gas limit");
+    }
+
     #[test]
     fn test_block_gas_limit() {
         let mut meter = BlockGasMeter::default();
@@ -1,10 +1,15 @@
 use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 
 use super::storage as gov_storage;
+use crate::ledger::parameters::{self, EpochDuration};
 use crate::ledger::storage::types::encode;
 use crate::ledger::storage::{self, Storage};
+use crate::ledger::storage_api::{self, OptionExt, StorageRead};
+use crate::types::governance::{DependentParameters, FundsDestination};
 use crate::types::token::Amount;
 
 #[derive(
@@ -32,6 +37,22 @@ pub struct GovParams {
     pub max_proposal_content_size: u64,
     /// Minimum epochs between end and grace epochs
     pub min_proposal_grace_epochs: u64,
+    /// Minimum fraction of the total voting power that has to vote for a
+    /// tally to be valid
+    pub quorum: Decimal,
+    /// Minimum fraction of yay votes over the non-abstaining votes for a
+    /// proposal to pass
+    pub pass_threshold: Decimal,
+    /// Fraction of veto votes over all the votes above which a proposal is
+    /// vetoed
+    pub veto_threshold: Decimal,
+    /// Where the funds of a proposal that didn't pass, but wasn't vetoed, go
+    pub rejected_funds: FundsDestination,
+    /// Where the funds of a vetoed proposal go
+    pub vetoed_funds: FundsDestination,
+    /// Where the funds of a proposal go when it couldn't be tallied or when
+    /// it passed, but its execution failed
+    pub failed_funds: FundsDestination,
 }
 
 impl Display for GovParams {
@@ -40,13 +61,21 @@ impl Display for GovParams {
             f,
             "Min. proposal fund: {}\nMax. proposal code size: {}\nMin. \
              proposal period: {}\nMax. proposal period: {}\nMax. proposal \
-             content size: {}\nMin. proposal grace epochs: {}",
+             content size: {}\nMin. proposal grace epochs: {}\nQuorum: \
+             {}\nPass threshold: {}\nVeto threshold: {}\nRejected funds: \
+             {}\nVetoed funds: {}\nFailed funds: {}",
             self.min_proposal_fund,
             self.max_proposal_code_size,
             self.min_proposal_period,
             self.max_proposal_period,
             self.max_proposal_content_size,
-            self.min_proposal_grace_epochs
+            self.min_proposal_grace_epochs,
+            self.quorum,
+            self.pass_threshold,
+            self.veto_threshold,
+            self.rejected_funds,
+            self.vetoed_funds,
+            self.failed_funds
         )
     }
 }
@@ -60,6 +89,12 @@ impl Default for GovParams {
             max_proposal_period: 27,
             max_proposal_content_size: 10_000,
             min_proposal_grace_epochs: 6,
+            quorum: dec!(0.334),
+            pass_threshold: dec!(0.6667),
+            veto_threshold: dec!(0.334),
+            rejected_funds: FundsDestination::Author,
+            vetoed_funds: FundsDestination::Burn,
+            failed_funds: FundsDestination::Treasury,
         }
     }
 }
@@ -78,6 +113,12 @@ impl GovParams {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            rejected_funds,
+            vetoed_funds,
+            failed_funds,
         } = self;
 
         let min_proposal_fund_key = gov_storage::get_min_proposal_fund_key();
@@ -122,9 +163,67 @@ impl GovParams {
             )
             .expect("Should be able to write to storage");
 
+        let quorum_key = gov_storage::get_quorum_key();
+        storage
+            .write(&quorum_key, encode(quorum))
+            .expect("Should be able to write to storage");
+
+        let pass_threshold_key = gov_storage::get_pass_threshold_key();
+        storage
+            .write(&pass_threshold_key, encode(pass_threshold))
+            .expect("Should be able to write to storage");
+
+        let veto_threshold_key = gov_storage::get_veto_threshold_key();
+        storage
+            .write(&veto_threshold_key, encode(veto_threshold))
+            .expect("Should be able to write to storage");
+
+        let rejected_funds_key = gov_storage::get_rejected_funds_key();
+        storage
+            .write(&rejected_funds_key, encode(rejected_funds))
+            .expect("Should be able to write to storage");
+
+        let vetoed_funds_key = gov_storage::get_vetoed_funds_key();
+        storage
+            .write(&vetoed_funds_key, encode(vetoed_funds))
+            .expect("Should be able to write to storage");
+
+        let failed_funds_key = gov_storage::get_failed_funds_key();
+        storage
+            .write(&failed_funds_key, encode(failed_funds))
+            .expect("Should be able to write to storage");
+
         let counter_key = gov_storage::get_counter_key();
         storage
             .write(&counter_key, encode(&u64::MIN))
             .expect("Should be able to write to storage");
     }
 }
+
+/// Read the stored values of the parameters that a parameter change proposal
+/// must keep consistent with each other
+pub fn read_dependent_parameters<S>(
+    storage: &S,
+) -> storage_api::Result<DependentParameters>
+where
+    S: for<'iter> StorageRead<'iter>,
+{
+    let epoch_duration: EpochDuration = storage
+        .read(&parameters::storage::get_epoch_duration_storage_key())?
+        .ok_or_err_msg("Missing the epoch duration parameter")?;
+    let epochs_per_year: u64 = storage
+        .read(&parameters::storage::get_epochs_per_year_key())?
+        .ok_or_err_msg("Missing the epochs per year parameter")?;
+    let min_proposal_period: u64 = storage
+        .read(&gov_storage::get_min_proposal_period_key())?
+        .ok_or_err_msg("Missing the min. proposal period parameter")?;
+    let max_proposal_period: u64 = storage
+        .read(&gov_storage::get_max_proposal_period_key())?
+        .ok_or_err_msg("Missing the max. proposal period parameter")?;
+    Ok(DependentParameters {
+        min_epoch_duration: epoch_duration.min_duration.0,
+        epochs_per_year,
+        min_proposal_period,
+        max_proposal_period,
+    })
+}
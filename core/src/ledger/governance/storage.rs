@@ -11,6 +11,7 @@ const PROPOSAL_END_EPOCH: &str = "end_epoch";
 const PROPOSAL_GRACE_EPOCH: &str = "grace_epoch";
 const PROPOSAL_FUNDS: &str = "funds";
 const PROPOSAL_CODE: &str = "proposal_code";
+const PROPOSAL_TYPE: &str = "proposal_type";
 const PROPOSAL_COMMITTING_EPOCH: &str = "epoch";
 
 const MIN_PROPOSAL_FUND_KEY: &str = "min_fund";
@@ -19,8 +20,15 @@ const MIN_PROPOSAL_PERIOD_KEY: &str = "min_period";
 const MAX_PROPOSAL_PERIOD_KEY: &str = "max_period";
 const MAX_PROPOSAL_CONTENT_SIZE_KEY: &str = "max_content";
 const MIN_GRACE_EPOCH_KEY: &str = "min_grace_epoch";
+const QUORUM_KEY: &str = "quorum";
+const PASS_THRESHOLD_KEY: &str = "pass_threshold";
+const VETO_THRESHOLD_KEY: &str = "veto_threshold";
+const REJECTED_FUNDS_KEY: &str = "rejected_funds";
+const VETOED_FUNDS_KEY: &str = "vetoed_funds";
+const FAILED_FUNDS_KEY: &str = "failed_funds";
 const COUNTER_KEY: &str = "counter";
 const PENDING_PROPOSAL: &str = "pending";
+const PGF_PREFIX: &str = "pgf";
 
 /// Check if key is inside governance address space
 pub fn is_governance_key(key: &Key) -> bool {
@@ -83,6 +91,24 @@ pub fn is_proposal_code_key(key: &Key) -> bool {
     }
 }
 
+/// Check if key is proposal type key
+pub fn is_proposal_type_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(proposal_type),
+        ] if addr == &ADDRESS
+            && prefix == PROPOSAL_PREFIX
+            && proposal_type == PROPOSAL_TYPE =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
 /// Check if key is grace epoch key
 pub fn is_grace_epoch_key(key: &Key) -> bool {
     match &key.segments[..] {
@@ -245,6 +271,54 @@ pub fn is_min_grace_epoch_key(key: &Key) -> bool {
                     && min_grace_epoch_param == MIN_GRACE_EPOCH_KEY)
 }
 
+/// Check if key is a quorum param key
+pub fn is_quorum_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(quorum_param),
+         ] if addr == &ADDRESS && quorum_param == QUORUM_KEY)
+}
+
+/// Check if key is a pass threshold param key
+pub fn is_pass_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(pass_threshold_param),
+         ] if addr == &ADDRESS && pass_threshold_param == PASS_THRESHOLD_KEY)
+}
+
+/// Check if key is a veto threshold param key
+pub fn is_veto_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(veto_threshold_param),
+         ] if addr == &ADDRESS && veto_threshold_param == VETO_THRESHOLD_KEY)
+}
+
+/// Check if key is a rejected funds destination param key
+pub fn is_rejected_funds_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(rejected_funds_param),
+         ] if addr == &ADDRESS && rejected_funds_param == REJECTED_FUNDS_KEY)
+}
+
+/// Check if key is a vetoed funds destination param key
+pub fn is_vetoed_funds_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(vetoed_funds_param),
+         ] if addr == &ADDRESS && vetoed_funds_param == VETOED_FUNDS_KEY)
+}
+
+/// Check if key is a failed funds destination param key
+pub fn is_failed_funds_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+             DbKeySeg::AddressSeg(addr),
+             DbKeySeg::StringSeg(failed_funds_param),
+         ] if addr == &ADDRESS && failed_funds_param == FAILED_FUNDS_KEY)
+}
+
 /// Check if key is parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
     is_min_proposal_fund_key(key)
@@ -253,6 +327,12 @@ pub fn is_parameter_key(key: &Key) -> bool {
         || is_min_proposal_period_key(key)
         || is_max_proposal_period_key(key)
         || is_min_grace_epoch_key(key)
+        || is_quorum_key(key)
+        || is_pass_threshold_key(key)
+        || is_veto_threshold_key(key)
+        || is_rejected_funds_key(key)
+        || is_vetoed_funds_key(key)
+        || is_failed_funds_key(key)
 }
 
 /// Check if key is start epoch or end epoch key
@@ -309,6 +389,48 @@ pub fn get_min_proposal_grace_epoch_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get quorum key
+pub fn get_quorum_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&QUORUM_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get pass threshold key
+pub fn get_pass_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PASS_THRESHOLD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get veto threshold key
+pub fn get_veto_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VETO_THRESHOLD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get rejected funds destination key
+pub fn get_rejected_funds_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&REJECTED_FUNDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get vetoed funds destination key
+pub fn get_vetoed_funds_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VETOED_FUNDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get failed funds destination key
+pub fn get_failed_funds_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&FAILED_FUNDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get key of proposal ids counter
 pub fn get_counter_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -379,6 +501,29 @@ pub fn get_proposal_code_key(id: u64) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get proposal type key
+pub fn get_proposal_type_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_TYPE.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the recurring public goods funding payments
+pub fn get_pgf_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PGF_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the recurring payments of a public goods funding proposal
+pub fn get_pgf_payments_key(id: u64) -> Key {
+    get_pgf_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get the proposal committing key prefix
 pub fn get_commiting_proposals_prefix(epoch: u64) -> Key {
     proposal_prefix()
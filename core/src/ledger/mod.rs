@@ -1,5 +1,7 @@
 //! The ledger modules
 
+pub mod dkg;
+pub mod eth_bridge;
 pub mod gas;
 pub mod governance;
 #[cfg(any(feature = "abciplus", feature = "abcipp"))]
@@ -8,5 +10,6 @@ pub mod parameters;
 pub mod slash_fund;
 pub mod storage;
 pub mod storage_api;
+pub mod treasury;
 pub mod tx_env;
 pub mod vp_env;
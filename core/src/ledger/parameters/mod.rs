@@ -249,7 +249,7 @@ where
 /// gas cost.
 pub fn update_epochs_per_year_parameter<DB, H>(
     storage: &mut Storage<DB, H>,
-    value: &EpochDuration,
+    value: &u64,
 ) -> std::result::Result<u64, WriteError>
 where
     DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
@@ -263,7 +263,7 @@ where
 /// cost.
 pub fn update_pos_gain_p_parameter<DB, H>(
     storage: &mut Storage<DB, H>,
-    value: &EpochDuration,
+    value: &Decimal,
 ) -> std::result::Result<u64, WriteError>
 where
     DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
@@ -277,7 +277,7 @@ where
 /// cost.
 pub fn update_pos_gain_d_parameter<DB, H>(
     storage: &mut Storage<DB, H>,
-    value: &EpochDuration,
+    value: &Decimal,
 ) -> std::result::Result<u64, WriteError>
 where
     DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
@@ -291,7 +291,7 @@ where
 /// gas cost.
 pub fn update_staked_ratio_parameter<DB, H>(
     storage: &mut Storage<DB, H>,
-    value: &EpochDuration,
+    value: &Decimal,
 ) -> std::result::Result<u64, WriteError>
 where
     DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
@@ -301,11 +301,11 @@ where
     update(storage, value, key)
 }
 
-/// Update the PoS inflation rate parameter in storage. Returns the parameters
-/// and gas cost.
+/// Update the PoS inflation amount parameter in storage. Returns the
+/// parameters and gas cost.
 pub fn update_pos_inflation_amount_parameter<DB, H>(
     storage: &mut Storage<DB, H>,
-    value: &EpochDuration,
+    value: &u64,
 ) -> std::result::Result<u64, WriteError>
 where
     DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
@@ -12,6 +12,7 @@ const POS_GAIN_P_KEY: &str = "pos_gain_p";
 const POS_GAIN_D_KEY: &str = "pos_gain_d";
 const STAKED_RATIO_KEY: &str = "staked_ratio_key";
 const POS_INFLATION_AMOUNT_KEY: &str = "pos_inflation_amount_key";
+const BLOCK_METADATA_HASH_KEY: &str = "block_metadata_hash";
 
 /// Returns if the key is a parameter key.
 pub fn is_parameter_key(key: &Key) -> bool {
@@ -205,3 +206,15 @@ pub fn get_pos_inflation_amount_key() -> Key {
         ],
     }
 }
+
+/// Storage key of the hash of the last committed block's metadata. It's not a
+/// parameter, but it's kept under the parameters' address to be protected by
+/// their VP.
+pub fn get_block_metadata_hash_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(BLOCK_METADATA_HASH_KEY.to_string()),
+        ],
+    }
+}
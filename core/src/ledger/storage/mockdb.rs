@@ -41,6 +41,7 @@ pub struct MockDBWriteBatch;
 impl DB for MockDB {
     /// There is no cache for MockDB
     type Cache = ();
+    type Checkpoint = MockDB;
     type WriteBatch = MockDBWriteBatch;
 
     fn open(_db_path: impl AsRef<Path>, _cache: Option<&Self::Cache>) -> Self {
@@ -393,6 +394,38 @@ impl DB for MockDB {
         })
     }
 
+    fn prune(
+        &mut self,
+        before: BlockHeight,
+        keep_every: Option<u64>,
+    ) -> Result<()> {
+        let is_pruned = |height: &str| match height.parse::<u64>() {
+            Ok(height) => {
+                let kept = keep_every
+                    .filter(|every| *every != 0)
+                    .map_or(false, |every| height % every == 0);
+                height < before.0 && !kept
+            }
+            Err(_) => false,
+        };
+        // There are no diffs in the MockDB
+        self.0.borrow_mut().retain(|key, _| {
+            let segments: Vec<&str> =
+                key.split(KEY_SEGMENT_SEPARATOR).collect();
+            let pruned = match segments.as_slice() {
+                ["results", height] => is_pruned(height),
+                [height, "tree", _, "store"] => is_pruned(height),
+                _ => false,
+            };
+            !pruned
+        });
+        Ok(())
+    }
+
+    fn checkpoint(&self, _path: impl AsRef<Path>) -> Result<Self::Checkpoint> {
+        Ok(Self(RefCell::new(self.0.borrow().clone())))
+    }
+
     fn batch() -> Self::WriteBatch {
         MockDBWriteBatch
     }
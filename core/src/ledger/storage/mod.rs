@@ -42,6 +42,7 @@ use crate::types::address::{
     masp, Address, EstablishedAddressGen, InternalAddress,
 };
 use crate::types::chain::{ChainId, CHAIN_ID_LENGTH};
+use crate::types::hash::Hash;
 // TODO
 #[cfg(feature = "ferveo-tpke")]
 use crate::types::internal::TxQueue;
@@ -204,12 +205,45 @@ pub struct BlockStateWrite<'a> {
     pub tx_queue: &'a TxQueue,
 }
 
+impl BlockStateWrite<'_> {
+    /// The hash of the block's metadata that is not part of the account
+    /// subspaces. If enabled at genesis, it's written in the merkle tree on
+    /// every commit, so that the block's merkle root commits to the metadata
+    /// too.
+    pub fn metadata_hash(&self) -> Hash {
+        let mut bytes = vec![];
+        self.serialize_metadata(&mut bytes)
+            .expect("Serializing into a vector shouldn't fail");
+        Hash::sha256(bytes)
+    }
+
+    fn serialize_metadata(
+        &self,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        self.hash.serialize(writer)?;
+        self.height.serialize(writer)?;
+        self.epoch.serialize(writer)?;
+        self.pred_epochs.serialize(writer)?;
+        self.next_epoch_min_start_height.serialize(writer)?;
+        self.next_epoch_min_start_time.serialize(writer)?;
+        self.address_gen.serialize(writer)?;
+        self.results.serialize(writer)?;
+        #[cfg(feature = "ferveo-tpke")]
+        self.tx_queue.serialize(writer)?;
+        Ok(())
+    }
+}
+
 /// A database backend.
 pub trait DB: std::fmt::Debug {
     /// A DB's cache
     type Cache;
     /// A handle for batch writes
     type WriteBatch: DBWriteBatch;
+    /// A read-only copy of the DB's state, which can be read from another
+    /// thread while the DB keeps being written
+    type Checkpoint: for<'iter> DBIter<'iter> + Send + 'static;
 
     /// Open the database from provided path
     fn open(
@@ -268,6 +302,23 @@ pub trait DB: std::fmt::Debug {
         key: &Key,
     ) -> Result<i64>;
 
+    /// Prune the diffs of the account subspaces, the merkle tree stores and
+    /// the results of the block heights preceding the given height, except
+    /// for the multiples of `keep_every`, if any. The DB may continue pruning
+    /// the heights in the background after this returns.
+    fn prune(
+        &mut self,
+        before: BlockHeight,
+        keep_every: Option<u64>,
+    ) -> Result<()>;
+
+    /// Create a checkpoint of the DB's current state. A persistent DB writes
+    /// the checkpoint in the given directory, which must not exist yet.
+    fn checkpoint(
+        &self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self::Checkpoint>;
+
     /// Start write batch.
     fn batch() -> Self::WriteBatch;
 
@@ -434,6 +485,18 @@ where
 
     /// Persist the current block's state to the database
     pub fn commit(&mut self) -> Result<()> {
+        // Commit to the block's metadata in the merkle tree, so that it can be
+        // verified against the block's merkle root, e.g. when the state is
+        // restored from a state sync snapshot. This changes the app hash, so
+        // it's only done by the chains that enabled it at genesis.
+        let metadata_hash_key =
+            parameters::storage::get_block_metadata_hash_key();
+        if self.has_key(&metadata_hash_key)?.0 {
+            let metadata_hash = self.block_state_write().metadata_hash();
+            self.write(&metadata_hash_key, metadata_hash.0)?;
+        }
+        // The state is built again, as its merkle tree stores now include the
+        // metadata hash
         let state = BlockStateWrite {
             merkle_tree_stores: self.block.tree.stores(),
             header: self.header.as_ref(),
@@ -455,6 +518,35 @@ where
         Ok(())
     }
 
+    /// Commit to the metadata of every block in the merkle tree from now on
+    /// (see [`BlockStateWrite::metadata_hash`]). This is a consensus-breaking
+    /// change of the app hash, so it may only be enabled at genesis.
+    pub fn enable_block_metadata_commitment(&mut self) -> Result<()> {
+        self.write(
+            &parameters::storage::get_block_metadata_hash_key(),
+            Hash::default().0,
+        )?;
+        Ok(())
+    }
+
+    /// The current block's state to be written into the database
+    fn block_state_write(&self) -> BlockStateWrite<'_> {
+        BlockStateWrite {
+            merkle_tree_stores: self.block.tree.stores(),
+            header: self.header.as_ref(),
+            hash: &self.block.hash,
+            height: self.block.height,
+            epoch: self.block.epoch,
+            results: &self.block.results,
+            pred_epochs: &self.block.pred_epochs,
+            next_epoch_min_start_height: self.next_epoch_min_start_height,
+            next_epoch_min_start_time: self.next_epoch_min_start_time,
+            address_gen: &self.address_gen,
+            #[cfg(feature = "ferveo-tpke")]
+            tx_queue: &self.tx_queue,
+        }
+    }
+
     /// Find the root hash of the merkle tree
     pub fn merkle_root(&self) -> MerkleRoot {
         self.block.tree.root()
@@ -730,13 +822,15 @@ where
         }
     }
 
-    /// Initialize a new epoch when the current epoch is finished. Returns
-    /// `true` on a new epoch.
+    /// Initialize a new epoch when the current epoch is finished. The
+    /// `on_new_epoch` hook is called once the new epoch has begun, before the
+    /// MASP conversions are updated. Returns `true` on a new epoch.
     #[cfg(feature = "wasm-runtime")]
     pub fn update_epoch(
         &mut self,
         height: BlockHeight,
         time: DateTimeUtc,
+        on_new_epoch: impl FnOnce(&mut Self),
     ) -> Result<bool> {
         let (parameters, _gas) =
             parameters::read(self).expect("Couldn't read protocol parameters");
@@ -760,6 +854,7 @@ where
                 .pred_epochs
                 .new_epoch(height, evidence_max_age_num_blocks);
             tracing::info!("Began a new epoch {}", self.block.epoch);
+            on_new_epoch(self);
             self.update_allowed_conversions()?;
         }
         self.update_epoch_in_merkle_tree()?;
@@ -1301,7 +1396,7 @@ mod tests {
             assert_eq!(epoch_before, storage.block.epoch);
 
             // Try to apply the epoch update
-            storage.update_epoch(block_height, block_time).unwrap();
+            storage.update_epoch(block_height, block_time, |_| {}).unwrap();
 
             // Test for 1.
             if block_height.0 - start_height.0
@@ -1356,16 +1451,23 @@ mod tests {
 
             // No update should happen before both epoch duration conditions are
             // satisfied
-            storage.update_epoch(height_before_update, time_before_update).unwrap();
+            storage.update_epoch(height_before_update, time_before_update, |_| {}).unwrap();
             assert_eq!(storage.block.epoch, epoch_before);
-            storage.update_epoch(height_of_update, time_before_update).unwrap();
+            storage.update_epoch(height_of_update, time_before_update, |_| {}).unwrap();
             assert_eq!(storage.block.epoch, epoch_before);
-            storage.update_epoch(height_before_update, time_of_update).unwrap();
+            let mut hook_called = false;
+            storage.update_epoch(height_before_update, time_of_update, |_| hook_called = true).unwrap();
             assert_eq!(storage.block.epoch, epoch_before);
+            assert!(!hook_called);
 
-            // Update should happen at this or after this height and time
-            storage.update_epoch(height_of_update, time_of_update).unwrap();
+            // Update should happen at this or after this height and time and
+            // the new epoch hook should be called
+            storage.update_epoch(height_of_update, time_of_update, |storage| {
+                assert_eq!(storage.block.epoch, epoch_before.next());
+                hook_called = true;
+            }).unwrap();
             assert_eq!(storage.block.epoch, epoch_before.next());
+            assert!(hook_called);
             // The next epoch's minimum duration should change
             assert_eq!(storage.next_epoch_min_start_height,
                 height_of_update + parameters.epoch_duration.min_num_of_blocks);
@@ -2,6 +2,7 @@
 
 use super::*;
 use crate::types::address::Address;
+use crate::types::key::multisig::MultisigKeys;
 use crate::types::key::*;
 
 /// Get the public key associated with the given address. Returns `Ok(None)` if
@@ -14,6 +15,19 @@ where
     storage.read(&key)
 }
 
+/// Get the multisignature keys associated with the given address. Returns
+/// `Ok(None)` if the address is not a multisignature account.
+pub fn get_multisig<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<MultisigKeys>>
+where
+    S: for<'iter> StorageRead<'iter>,
+{
+    let key = multisig_key(owner);
+    storage.read(&key)
+}
+
 /// Reveal a PK of an implicit account - the PK is written into the storage
 /// of the address derived from the PK.
 pub fn reveal_pk<S>(storage: &mut S, pk: &common::PublicKey) -> Result<()>
@@ -0,0 +1,8 @@
+//! Governance treasury library code
+
+use crate::types::address::{Address, InternalAddress};
+
+/// Internal governance treasury address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Treasury);
+
+pub mod storage;
@@ -0,0 +1,8 @@
+//! Governance treasury storage
+
+use crate::types::storage::{DbKeySeg, Key};
+
+/// Check if a key is a treasury key
+pub fn is_treasury_key(key: &Key) -> bool {
+    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &super::ADDRESS)
+}
@@ -3,7 +3,7 @@
 pub mod generated;
 mod types;
 
-pub use types::{Dkg, Error, Signed, SignedTxData, Tx};
+pub use types::{Dkg, Error, Signed, SignedTxData, SigningTx, Tx, TxSignature};
 
 #[cfg(test)]
 mod tests {
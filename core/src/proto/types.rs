@@ -50,12 +50,31 @@ pub struct SignedTxData {
     pub data: Option<Vec<u8>>,
     /// The signature is produced on the tx data concatenated with the tx code
     /// and the timestamp.
-    pub sig: common::Signature,
+    pub sig: TxSignature,
+}
+
+/// The signature(s) attached to the data of a transaction.
+#[derive(
+    Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub enum TxSignature {
+    /// A signature by the public key of an account
+    Single(common::Signature),
+    /// Signatures by some of the public keys of a multisignature account,
+    /// each with the index of its key in the account's
+    /// [`multisig::MultisigKeys`]
+    Multi(Vec<(u8, common::Signature)>),
 }
 
 /// A generic signed data wrapper for Borsh encode-able data.
 #[derive(
-    Clone, Debug, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+    Clone,
+    Debug,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
 )]
 pub struct Signed<T: BorshSerialize + BorshDeserialize> {
     /// Arbitrary data to be signed
@@ -139,7 +158,8 @@ pub struct SigningTx {
 }
 
 impl SigningTx {
-    pub fn hash(&self) -> [u8; 32] {
+    /// The encoded bytes whose hash is signed
+    pub fn to_bytes(&self) -> Vec<u8> {
         let timestamp = Some(self.timestamp.into());
         let mut bytes = vec![];
         types::Tx {
@@ -149,13 +169,44 @@ impl SigningTx {
         }
         .encode(&mut bytes)
         .expect("encoding a transaction failed");
-        hash_tx(&bytes).0
+        bytes
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        hash_tx(&self.to_bytes()).0
     }
 
     /// Sign a transaction using [`SignedTxData`].
     pub fn sign(self, keypair: &common::SecretKey) -> Self {
         let to_sign = self.hash();
         let sig = common::SigScheme::sign(keypair, to_sign);
+        self.attach_signature(TxSignature::Single(sig))
+    }
+
+    /// Sign a transaction of a multisignature account using
+    /// [`SignedTxData`]. Each keypair is given with the index of its public
+    /// key in the account's [`multisig::MultisigKeys`].
+    pub fn sign_multi(self, keypairs: &[(u8, common::SecretKey)]) -> Self {
+        let sigs = keypairs
+            .iter()
+            .map(|(index, keypair)| (*index, self.partial_sign(keypair)))
+            .collect();
+        self.attach_signature(TxSignature::Multi(sigs))
+    }
+
+    /// Produce the signature of a single key over the transaction, without
+    /// attaching it. Used to collect the signatures of the keys of a
+    /// multisignature account.
+    pub fn partial_sign(
+        &self,
+        keypair: &common::SecretKey,
+    ) -> common::Signature {
+        common::SigScheme::sign(keypair, self.hash())
+    }
+
+    /// Wrap the transaction data together with the given signature(s) into
+    /// [`SignedTxData`].
+    pub fn attach_signature(self, sig: TxSignature) -> Self {
         let signed = SignedTxData {
             data: self.data,
             sig,
@@ -371,6 +422,16 @@ impl Tx {
             .expect("code hashes to unexpected value")
     }
 
+    /// Sign a transaction of a multisignature account using
+    /// [`SignedTxData`].
+    pub fn sign_multi(self, keypairs: &[(u8, common::SecretKey)]) -> Self {
+        let code = self.code.clone();
+        SigningTx::from(self)
+            .sign_multi(keypairs)
+            .expand(code)
+            .expect("code hashes to unexpected value")
+    }
+
     /// Verify that the transaction has been signed by the secret key
     /// counterpart of the given public key.
     pub fn verify_sig(
@@ -69,6 +69,8 @@ mod internal {
         "ibc::IBC Mint Address                        ";
     pub const ETH_BRIDGE: &str =
         "ano::ETH Bridge Address                      ";
+    pub const TREASURY: &str =
+        "ano::Governance Treasury                     ";
 }
 
 /// Fixed-length address strings prefix for established addresses.
@@ -198,6 +200,7 @@ impl Address {
                     InternalAddress::EthBridge => {
                         internal::ETH_BRIDGE.to_string()
                     }
+                    InternalAddress::Treasury => internal::TREASURY.to_string(),
                 };
                 debug_assert_eq!(string.len(), FIXED_LEN_STRING_BYTES);
                 string
@@ -251,6 +254,9 @@ impl Address {
                 internal::ETH_BRIDGE => {
                     Ok(Address::Internal(InternalAddress::EthBridge))
                 }
+                internal::TREASURY => {
+                    Ok(Address::Internal(InternalAddress::Treasury))
+                }
                 _ => Err(Error::new(
                     ErrorKind::InvalidData,
                     "Invalid internal address",
@@ -466,6 +472,9 @@ pub enum InternalAddress {
     SlashFund,
     /// Bridge to Ethereum
     EthBridge,
+    /// Governance treasury funding treasury spending and public goods
+    /// funding proposals
+    Treasury,
 }
 
 impl InternalAddress {
@@ -500,6 +509,7 @@ impl Display for InternalAddress {
                 Self::IbcBurn => "IbcBurn".to_string(),
                 Self::IbcMint => "IbcMint".to_string(),
                 Self::EthBridge => "EthBridge".to_string(),
+                Self::Treasury => "Treasury".to_string(),
             }
         )
     }
@@ -771,8 +781,9 @@ pub mod testing {
             InternalAddress::IbcEscrow => {}
             InternalAddress::IbcBurn => {}
             InternalAddress::IbcMint => {}
-            InternalAddress::EthBridge => {} /* Add new addresses in the
-                                              * `prop_oneof` below. */
+            InternalAddress::EthBridge => {}
+            InternalAddress::Treasury => {} /* Add new addresses in the
+                                             * `prop_oneof` below. */
         };
         prop_oneof![
             Just(InternalAddress::PoS),
@@ -787,6 +798,7 @@ pub mod testing {
             Just(InternalAddress::Governance),
             Just(InternalAddress::SlashFund),
             Just(InternalAddress::EthBridge),
+            Just(InternalAddress::Treasury),
         ]
     }
 
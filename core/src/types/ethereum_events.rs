@@ -0,0 +1,243 @@
+//! Types representing the events emitted by the Ethereum bridge smart
+//! contracts and the transfers between Ethereum and Namada.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use data_encoding::HEXLOWER;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::address::Address;
+use crate::types::hash::Hash;
+use crate::types::storage::{self, DbKeySeg, KeySeg};
+use crate::types::token::Amount;
+
+/// The length of an Ethereum address in bytes
+pub const ETH_ADDRESS_LENGTH: usize = 20;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum EthAddressError {
+    #[error("Ethereum address must start with \"0x\", got: {0}")]
+    MissingPrefix(String),
+    #[error("Invalid hex encoding of an Ethereum address: {0}")]
+    Hex(data_encoding::DecodeError),
+    #[error("Ethereum address must be 20 bytes long, got {0} bytes")]
+    Length(usize),
+}
+
+/// An Ethereum address (e.g. of an ERC20 contract or of an account)
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct EthAddress(pub [u8; ETH_ADDRESS_LENGTH]);
+
+impl Display for EthAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+impl FromStr for EthAddress {
+    type Err = EthAddressError;
+
+    /// Parse a "0x"-prefixed hex string. Both lower and upper case hex digits
+    /// are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix("0x")
+            .ok_or_else(|| EthAddressError::MissingPrefix(s.to_owned()))?;
+        let bytes = HEXLOWER
+            .decode(hex.to_ascii_lowercase().as_bytes())
+            .map_err(EthAddressError::Hex)?;
+        let bytes: [u8; ETH_ADDRESS_LENGTH] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| EthAddressError::Length(bytes.len()))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<String> for EthAddress {
+    type Error = EthAddressError;
+
+    fn try_from(string: String) -> Result<Self, Self::Error> {
+        string.parse()
+    }
+}
+
+impl From<EthAddress> for String {
+    fn from(address: EthAddress) -> Self {
+        address.to_string()
+    }
+}
+
+impl KeySeg for EthAddress {
+    fn parse(string: String) -> storage::Result<Self> {
+        string.parse().map_err(|e: EthAddressError| {
+            storage::Error::ParseKeySeg(e.to_string())
+        })
+    }
+
+    fn raw(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_db_key(&self) -> DbKeySeg {
+        DbKeySeg::StringSeg(self.raw())
+    }
+}
+
+/// A transfer of tokens from Ethereum to a Namada account
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferToNamada {
+    /// The amount of tokens transferred
+    pub amount: Amount,
+    /// The address of the ERC20 contract of the transferred asset
+    pub asset: EthAddress,
+    /// The Namada account receiving the tokens
+    pub receiver: Address,
+}
+
+/// A transfer of tokens from a Namada account to Ethereum
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransferToEthereum {
+    /// The amount of tokens transferred
+    pub amount: Amount,
+    /// The address of the ERC20 contract of the transferred asset
+    pub asset: EthAddress,
+    /// The Namada account sending the tokens
+    pub sender: Address,
+    /// The Ethereum account receiving the tokens
+    pub receiver: EthAddress,
+}
+
+/// An event emitted by the Ethereum bridge smart contracts that validators
+/// observed and attest to
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum EthereumEvent {
+    /// A batch of tokens locked in the bridge contract on Ethereum to be
+    /// released on Namada
+    TransfersToNamada {
+        /// Sequence number of the event, incremented by the bridge contract
+        /// with every batch
+        nonce: u64,
+        /// The transfers in the batch
+        transfers: Vec<TransferToNamada>,
+    },
+}
+
+impl EthereumEvent {
+    /// The hash of the Borsh encoded event, identifying it in the attestations
+    pub fn hash(&self) -> Hash {
+        let bytes = self
+            .try_to_vec()
+            .expect("Encoding an Ethereum event shouldn't fail");
+        Hash::sha256(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eth_address_roundtrip() {
+        let raw = "0x6b175474e89094c44da98b954eedeac495271d0f";
+        let address: EthAddress = raw.parse().unwrap();
+        assert_eq!(address.to_string(), raw);
+
+        let upper = "0x6B175474E89094C44DA98B954EEDEAC495271D0F";
+        assert_eq!(upper.parse::<EthAddress>().unwrap(), address);
+
+        let key_seg = address.raw();
+        assert_eq!(EthAddress::parse(key_seg).unwrap(), address);
+
+        assert!(matches!(
+            "6b175474e89094c44da98b954eedeac495271d0f".parse::<EthAddress>(),
+            Err(EthAddressError::MissingPrefix(_))
+        ));
+        assert!(matches!(
+            "0x6b17".parse::<EthAddress>(),
+            Err(EthAddressError::Length(2))
+        ));
+
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"{}\"", raw));
+        assert_eq!(serde_json::from_str::<EthAddress>(&json).unwrap(), address);
+    }
+}
+
+/// Testing helpers
+#[cfg(any(test, feature = "testing"))]
+pub mod testing {
+    use super::*;
+
+    /// The ERC20 contract of DAI on Ethereum mainnet
+    pub const DAI_ERC20_ETH_ADDRESS: EthAddress = EthAddress([
+        0x6b, 0x17, 0x54, 0x74, 0xe8, 0x90, 0x94, 0xc4, 0x4d, 0xa9, 0x8b, 0x95,
+        0x4e, 0xed, 0xea, 0xc4, 0x95, 0x27, 0x1d, 0x0f,
+    ]);
+
+    /// An arbitrary ERC20 contract standing for the wrapped native token
+    pub const WNAM_ERC20_ETH_ADDRESS: EthAddress = EthAddress([
+        0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+        0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    ]);
+
+    /// An arbitrary Ethereum account
+    pub const ETH_RECEIVER: EthAddress = EthAddress([0xee; 20]);
+}
@@ -14,7 +14,7 @@ use crate::types::hash::Hash;
 use crate::types::key::common::{self, Signature};
 use crate::types::key::SigScheme;
 use crate::types::storage::Epoch;
-use crate::types::token::SCALE;
+use crate::types::token::{self, SCALE};
 
 /// Type alias for vote power
 pub type VotePower = u128;
@@ -35,15 +35,16 @@ pub enum ProposalVote {
     Yay,
     /// No
     Nay,
+    /// Counts towards the quorum without supporting either outcome
+    Abstain,
+    /// No, and the proposal funds should be burned
+    NoWithVeto,
 }
 
 impl ProposalVote {
     /// Check if a vote is yay
     pub fn is_yay(&self) -> bool {
-        match self {
-            ProposalVote::Yay => true,
-            ProposalVote::Nay => false,
-        }
+        matches!(self, ProposalVote::Yay)
     }
 }
 
@@ -52,6 +53,8 @@ impl Display for ProposalVote {
         match self {
             ProposalVote::Yay => write!(f, "yay"),
             ProposalVote::Nay => write!(f, "nay"),
+            ProposalVote::Abstain => write!(f, "abstain"),
+            ProposalVote::NoWithVeto => write!(f, "veto"),
         }
     }
 }
@@ -59,7 +62,7 @@ impl Display for ProposalVote {
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum ProposalVoteParseError {
-    #[error("Invalid vote. Vote shall be yay or nay.")]
+    #[error("Invalid vote. Vote shall be yay, nay, abstain or veto.")]
     InvalidVote,
 }
 
@@ -71,22 +74,30 @@ impl FromStr for ProposalVote {
             Ok(ProposalVote::Yay)
         } else if s.eq("nay") {
             Ok(ProposalVote::Nay)
+        } else if s.eq("abstain") {
+            Ok(ProposalVote::Abstain)
+        } else if s.eq("veto") {
+            Ok(ProposalVote::NoWithVeto)
         } else {
             Err(ProposalVoteParseError::InvalidVote)
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 /// The result of a proposal
 pub enum TallyResult {
     /// Proposal was accepted
     Passed,
     /// Proposal was rejected
     Rejected,
+    /// Proposal was rejected because the veto threshold was reached
+    Vetoed,
     /// A critical error in tally computation
     Failed,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 /// The result with votes of a proposal
 pub struct ProposalResult {
     /// The result of a proposal
@@ -95,25 +106,37 @@ pub struct ProposalResult {
     pub total_voting_power: VotePower,
     /// The total voting power from yay votes
     pub total_yay_power: VotePower,
-    /// The total voting power from nay votes (unused at the moment)
+    /// The total voting power from nay votes
     pub total_nay_power: VotePower,
+    /// The total voting power from abstain votes
+    pub total_abstain_power: VotePower,
+    /// The total voting power from veto votes
+    pub total_veto_power: VotePower,
 }
 
 impl Display for ProposalResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let percentage = Decimal::checked_div(
-            self.total_yay_power.into(),
-            self.total_voting_power.into(),
-        )
-        .unwrap_or_default();
+        let percentage = |power: VotePower| {
+            Decimal::checked_div(power.into(), self.total_voting_power.into())
+                .and_then(|ratio| ratio.checked_mul(100.into()))
+                .unwrap_or_default()
+        };
+        let tokens = |power: VotePower| power / SCALE as u128;
 
         write!(
             f,
-            "{} with {} yay votes over {} ({:.2}%)",
+            "{} with {} voting power in total\n  Yay: {} ({:.2}%)\n  Nay: {} \
+             ({:.2}%)\n  Abstain: {} ({:.2}%)\n  Veto: {} ({:.2}%)",
             self.result,
-            self.total_yay_power / SCALE as u128,
-            self.total_voting_power / SCALE as u128,
-            percentage.checked_mul(100.into()).unwrap_or_default()
+            tokens(self.total_voting_power),
+            tokens(self.total_yay_power),
+            percentage(self.total_yay_power),
+            tokens(self.total_nay_power),
+            percentage(self.total_nay_power),
+            tokens(self.total_abstain_power),
+            percentage(self.total_abstain_power),
+            tokens(self.total_veto_power),
+            percentage(self.total_veto_power),
         )
     }
 }
@@ -123,11 +146,406 @@ impl Display for TallyResult {
         match self {
             TallyResult::Passed => write!(f, "passed"),
             TallyResult::Rejected => write!(f, "rejected"),
+            TallyResult::Vetoed => write!(f, "vetoed"),
             TallyResult::Failed => write!(f, "failed"),
         }
     }
 }
 
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Default,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// The type of a proposal along with its declared payload, which is executed
+/// by the protocol once the proposal is accepted
+pub enum ProposalType {
+    /// Executes the optional proposal code
+    #[default]
+    Default,
+    /// Changes protocol, proof-of-stake or governance parameters
+    ParameterChange(Vec<ParameterChange>),
+    /// Transfers from the governance treasury, once
+    TreasurySpend(Vec<TreasuryTransfer>),
+    /// Transfers from the governance treasury, every epoch from the grace
+    /// epoch until the payment's end epoch
+    PublicGoodsFunding(Vec<PgfPayment>),
+    /// Transfers from the slash fund, once
+    SlashFundSpend(Vec<TreasuryTransfer>),
+}
+
+impl ProposalType {
+    /// Check that the declared payload is well-formed. Proposals with a
+    /// payload must declare at least one change or payment.
+    pub fn is_valid(&self, grace_epoch: Epoch) -> bool {
+        match self {
+            ProposalType::Default => true,
+            ProposalType::ParameterChange(changes) => {
+                !changes.is_empty()
+                    && changes.iter().all(ParameterChange::is_valid)
+            }
+            ProposalType::TreasurySpend(transfers)
+            | ProposalType::SlashFundSpend(transfers) => {
+                !transfers.is_empty()
+                    && transfers.iter().all(|transfer| {
+                        transfer.amount != token::Amount::default()
+                    })
+            }
+            ProposalType::PublicGoodsFunding(payments) => {
+                !payments.is_empty()
+                    && payments.iter().all(|payment| {
+                        payment.amount != token::Amount::default()
+                            && payment.end_epoch >= grace_epoch
+                    })
+            }
+        }
+    }
+}
+
+impl Display for ProposalType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalType::Default => write!(f, "default"),
+            ProposalType::ParameterChange(_) => write!(f, "parameter change"),
+            ProposalType::TreasurySpend(_) => write!(f, "treasury spend"),
+            ProposalType::PublicGoodsFunding(_) => {
+                write!(f, "public goods funding")
+            }
+            ProposalType::SlashFundSpend(_) => write!(f, "slash fund spend"),
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A parameter change declared by a proposal
+pub enum ParameterChange {
+    /// A protocol parameter from `ledger::parameters`
+    Protocol(ProtocolParameter),
+    /// A proof-of-stake parameter
+    Pos(PosParameter),
+    /// A governance parameter
+    Governance(GovParameter),
+}
+
+impl ParameterChange {
+    /// Check that the new value of the parameter is in its valid range
+    pub fn is_valid(&self) -> bool {
+        let is_rate =
+            |rate: &Decimal| *rate >= Decimal::ZERO && *rate <= Decimal::ONE;
+        match self {
+            ParameterChange::Protocol(param) => match param {
+                ProtocolParameter::MinNumOfBlocks(value)
+                | ProtocolParameter::MinEpochDuration(value)
+                | ProtocolParameter::MaxExpectedTimePerBlock(value)
+                | ProtocolParameter::EpochsPerYear(value) => *value > 0,
+                ProtocolParameter::VpWhitelist(_)
+                | ProtocolParameter::TxWhitelist(_) => true,
+                ProtocolParameter::PosGainP(value)
+                | ProtocolParameter::PosGainD(value) => *value >= Decimal::ZERO,
+            },
+            ParameterChange::Pos(param) => match param {
+                PosParameter::MaxValidatorSlots(value) => *value > 0,
+                PosParameter::TmVotesPerToken(value) => *value > Decimal::ZERO,
+                PosParameter::BlockProposerReward(value)
+                | PosParameter::BlockVoteReward(value)
+                | PosParameter::MaxInflationRate(value)
+                | PosParameter::TargetStakedRatio(value)
+                | PosParameter::DuplicateVoteMinSlashRate(value)
+                | PosParameter::LightClientAttackMinSlashRate(value)
+                | PosParameter::LivenessMaxMissedRate(value)
+                | PosParameter::LivenessSlashRate(value) => is_rate(value),
+            },
+            ParameterChange::Governance(param) => match param {
+                GovParameter::MinProposalFund(value)
+                | GovParameter::MaxProposalCodeSize(value)
+                | GovParameter::MinProposalPeriod(value)
+                | GovParameter::MaxProposalPeriod(value)
+                | GovParameter::MaxProposalContentSize(value)
+                | GovParameter::MinProposalGraceEpochs(value) => *value > 0,
+                GovParameter::Quorum(value)
+                | GovParameter::PassThreshold(value)
+                | GovParameter::VetoThreshold(value) => is_rate(value),
+                GovParameter::RejectedFunds(_)
+                | GovParameter::VetoedFunds(_)
+                | GovParameter::FailedFunds(_) => true,
+            },
+        }
+    }
+}
+
+/// The number of seconds in a year of 365 days
+const SECONDS_PER_YEAR: u64 = 60 * 60 * 24 * 365;
+
+/// The stored values of the parameters whose valid range depends on other
+/// parameters. A parameter change proposal must keep them consistent with
+/// each other. The grace epochs of proposals aren't bounded by any other
+/// parameter, so they aren't included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependentParameters {
+    /// Minimum duration of an epoch in seconds
+    pub min_epoch_duration: u64,
+    /// Expected number of epochs per year
+    pub epochs_per_year: u64,
+    /// Minimum proposal voting period in epochs
+    pub min_proposal_period: u64,
+    /// Maximum proposal voting period in epochs
+    pub max_proposal_period: u64,
+}
+
+impl DependentParameters {
+    /// Check that the changes of a proposal, applied to the stored values of
+    /// the parameters, keep them consistent with each other. Only the
+    /// constraints involving a changed parameter are checked. Returns the
+    /// violated constraints.
+    pub fn validate_changes(&self, changes: &[ParameterChange]) -> Vec<String> {
+        let mut params = self.clone();
+        let mut epochs_changed = false;
+        let mut periods_changed = false;
+        for change in changes {
+            match change {
+                ParameterChange::Protocol(
+                    ProtocolParameter::MinEpochDuration(value),
+                ) => {
+                    params.min_epoch_duration = *value;
+                    epochs_changed = true;
+                }
+                ParameterChange::Protocol(
+                    ProtocolParameter::EpochsPerYear(value),
+                ) => {
+                    params.epochs_per_year = *value;
+                    epochs_changed = true;
+                }
+                ParameterChange::Governance(
+                    GovParameter::MinProposalPeriod(value),
+                ) => {
+                    params.min_proposal_period = *value;
+                    periods_changed = true;
+                }
+                ParameterChange::Governance(
+                    GovParameter::MaxProposalPeriod(value),
+                ) => {
+                    params.max_proposal_period = *value;
+                    periods_changed = true;
+                }
+                _ => {}
+            }
+        }
+
+        let mut errors = vec![];
+        // Epochs can't be shorter than their min. duration, so there can't
+        // be more of them in a year than fit in it
+        if epochs_changed
+            && params
+                .epochs_per_year
+                .checked_mul(params.min_epoch_duration)
+                .map_or(true, |duration| duration > SECONDS_PER_YEAR)
+        {
+            errors.push(format!(
+                "{} epochs per year of at least {} seconds don't fit in a year",
+                params.epochs_per_year, params.min_epoch_duration
+            ));
+        }
+        if periods_changed
+            && params.min_proposal_period > params.max_proposal_period
+        {
+            errors.push(format!(
+                "The min. proposal period {} is greater than the max. \
+                 proposal period {}",
+                params.min_proposal_period, params.max_proposal_period
+            ));
+        }
+        errors
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A changeable protocol parameter with its new value
+pub enum ProtocolParameter {
+    /// Minimum number of blocks in an epoch
+    MinNumOfBlocks(u64),
+    /// Minimum duration of an epoch in seconds
+    MinEpochDuration(u64),
+    /// Maximum expected time per block in seconds
+    MaxExpectedTimePerBlock(u64),
+    /// Whitelisted validity predicate hashes
+    VpWhitelist(Vec<String>),
+    /// Whitelisted tx hashes
+    TxWhitelist(Vec<String>),
+    /// Expected number of epochs per year
+    EpochsPerYear(u64),
+    /// PoS gain p
+    PosGainP(Decimal),
+    /// PoS gain d
+    PosGainD(Decimal),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A changeable proof-of-stake parameter with its new value. The lengths of
+/// the pipeline, unbonding, slashing and liveness windows cannot be changed,
+/// because the epoched data in storage depends on them.
+pub enum PosParameter {
+    /// Maximum number of active validators
+    MaxValidatorSlots(u64),
+    /// Number of Tendermint votes per staked token
+    TmVotesPerToken(Decimal),
+    /// Reward for proposing a block
+    BlockProposerReward(Decimal),
+    /// Reward for voting on a block
+    BlockVoteReward(Decimal),
+    /// Maximum inflation rate per annum
+    MaxInflationRate(Decimal),
+    /// Target ratio of staked tokens
+    TargetStakedRatio(Decimal),
+    /// Minimum slash rate for duplicate votes
+    DuplicateVoteMinSlashRate(Decimal),
+    /// Minimum slash rate for light client attacks
+    LightClientAttackMinSlashRate(Decimal),
+    /// Maximum rate of missed blocks in the liveness window
+    LivenessMaxMissedRate(Decimal),
+    /// Slash rate for liveness faults
+    LivenessSlashRate(Decimal),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A changeable governance parameter with its new value
+pub enum GovParameter {
+    /// Minimum amount of locked funds
+    MinProposalFund(u64),
+    /// Maximum kibibyte length for proposal code
+    MaxProposalCodeSize(u64),
+    /// Minimum proposal voting period in epochs
+    MinProposalPeriod(u64),
+    /// Maximum proposal voting period in epochs
+    MaxProposalPeriod(u64),
+    /// Maximum number of characters for proposal content
+    MaxProposalContentSize(u64),
+    /// Minimum epochs between end and grace epochs
+    MinProposalGraceEpochs(u64),
+    /// Minimum fraction of the total voting power that has to vote
+    Quorum(Decimal),
+    /// Minimum fraction of yay votes over the non-abstaining votes
+    PassThreshold(Decimal),
+    /// Fraction of veto votes above which a proposal is vetoed
+    VetoThreshold(Decimal),
+    /// Where the funds of rejected proposals go
+    RejectedFunds(FundsDestination),
+    /// Where the funds of vetoed proposals go
+    VetoedFunds(FundsDestination),
+    /// Where the funds of failed proposals go
+    FailedFunds(FundsDestination),
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+/// Where the funds locked by a proposal are moved once it's tallied
+pub enum FundsDestination {
+    /// Refunded to the proposal author
+    Author,
+    /// Burned
+    Burn,
+    /// Moved to the slash fund
+    SlashFund,
+    /// Moved to the governance treasury
+    Treasury,
+}
+
+impl Display for FundsDestination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FundsDestination::Author => write!(f, "author"),
+            FundsDestination::Burn => write!(f, "burn"),
+            FundsDestination::SlashFund => write!(f, "slash fund"),
+            FundsDestination::Treasury => write!(f, "treasury"),
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A transfer from the governance treasury or the slash fund
+pub struct TreasuryTransfer {
+    /// The recipient of the transfer
+    pub target: Address,
+    /// The amount of native tokens to transfer
+    pub amount: token::Amount,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A recurring payment from the governance treasury
+pub struct PgfPayment {
+    /// The recipient of the payment
+    pub target: Address,
+    /// The amount of native tokens paid every epoch
+    pub amount: token::Amount,
+    /// The last epoch in which the payment is made
+    pub end_epoch: Epoch,
+}
+
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
 )]
@@ -147,6 +565,9 @@ pub struct Proposal {
     pub grace_epoch: Epoch,
     /// The code containing the storage changes
     pub proposal_code_path: Option<String>,
+    /// The proposal type and its declared payload
+    #[serde(default)]
+    pub proposal_type: ProposalType,
 }
 
 impl Display for Proposal {
@@ -155,6 +576,93 @@ impl Display for Proposal {
     }
 }
 
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+/// The status of a proposal with respect to its voting period
+pub enum ProposalStatus {
+    /// The voting period hasn't started yet
+    Pending,
+    /// The voting period is in progress
+    OnGoing,
+    /// The voting period has ended
+    Done,
+}
+
+impl Display for ProposalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalStatus::Pending => write!(f, "pending"),
+            ProposalStatus::OnGoing => write!(f, "on-going"),
+            ProposalStatus::Done => write!(f, "done"),
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum ProposalStatusParseError {
+    #[error("Invalid status. Status shall be pending, on-going or done.")]
+    InvalidStatus,
+}
+
+impl FromStr for ProposalStatus {
+    type Err = ProposalStatusParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ProposalStatus::Pending),
+            "on-going" => Ok(ProposalStatus::OnGoing),
+            "done" => Ok(ProposalStatus::Done),
+            _ => Err(ProposalStatusParseError::InvalidStatus),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+/// A proposal as stored on chain
+pub struct StorageProposal {
+    /// The proposal id
+    pub id: u64,
+    /// The proposal content
+    pub content: BTreeMap<String, String>,
+    /// The proposal author address
+    pub author: Address,
+    /// The epoch from which voting is allowed
+    pub voting_start_epoch: Epoch,
+    /// The epoch from which voting is stopped
+    pub voting_end_epoch: Epoch,
+    /// The epoch from which this changes are executed
+    pub grace_epoch: Epoch,
+    /// The proposal type and its declared payload
+    pub proposal_type: ProposalType,
+}
+
+impl StorageProposal {
+    /// Get the status of the proposal at the given epoch
+    pub fn status(&self, current_epoch: Epoch) -> ProposalStatus {
+        if current_epoch < self.voting_start_epoch {
+            ProposalStatus::Pending
+        } else if current_epoch <= self.voting_end_epoch {
+            ProposalStatus::OnGoing
+        } else {
+            ProposalStatus::Done
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+/// A vote on a proposal as stored on chain
+pub struct StorageProposalVote {
+    /// The address that voted
+    pub voter: Address,
+    /// The validator whose stake the vote applies to. It's the voter itself
+    /// for validators and the validator of the bond for delegators.
+    pub delegation: Address,
+    /// The vote
+    pub vote: ProposalVote,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum ProposalError {
@@ -307,3 +815,117 @@ impl OfflineVote {
         .is_ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn test_typed_proposal_from_json() {
+        let proposal: Proposal = serde_json::from_value(serde_json::json!({
+            "content": {"title": "Lower the quorum"},
+            "author": crate::types::address::testing::established_address_1(),
+            "voting_start_epoch": 3,
+            "voting_end_epoch": 6,
+            "grace_epoch": 12,
+            "proposal_type": {
+                "ParameterChange": [{"Governance": {"Quorum": "0.25"}}]
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            proposal.proposal_type,
+            ProposalType::ParameterChange(vec![ParameterChange::Governance(
+                GovParameter::Quorum(dec!(0.25))
+            )])
+        );
+        assert!(proposal.proposal_type.is_valid(proposal.grace_epoch));
+    }
+
+    #[test]
+    fn test_proposal_type_validity() {
+        let grace_epoch = Epoch(12);
+        assert!(ProposalType::Default.is_valid(grace_epoch));
+        assert!(!ProposalType::ParameterChange(vec![]).is_valid(grace_epoch));
+        assert!(!ProposalType::ParameterChange(vec![ParameterChange::Pos(
+            PosParameter::LivenessSlashRate(dec!(1.5))
+        )])
+        .is_valid(grace_epoch));
+
+        let target = crate::types::address::testing::established_address_1();
+        let payment = |amount: u64, end_epoch: u64| PgfPayment {
+            target: target.clone(),
+            amount: token::Amount::whole(amount),
+            end_epoch: Epoch(end_epoch),
+        };
+        assert!(ProposalType::PublicGoodsFunding(vec![payment(10, 20)])
+            .is_valid(grace_epoch));
+        assert!(!ProposalType::PublicGoodsFunding(vec![payment(0, 20)])
+            .is_valid(grace_epoch));
+        assert!(!ProposalType::PublicGoodsFunding(vec![payment(10, 11)])
+            .is_valid(grace_epoch));
+        assert!(!ProposalType::SlashFundSpend(vec![]).is_valid(grace_epoch));
+    }
+
+    #[test]
+    fn test_dependent_parameters_validation() {
+        let params = DependentParameters {
+            min_epoch_duration: 60,
+            epochs_per_year: 525_600,
+            min_proposal_period: 3,
+            max_proposal_period: 27,
+        };
+        let gov = |param| ParameterChange::Governance(param);
+        let protocol = |param| ParameterChange::Protocol(param);
+
+        // Valid alone, but not with the stored max. period
+        let changes = vec![gov(GovParameter::MinProposalPeriod(30))];
+        assert!(changes.iter().all(ParameterChange::is_valid));
+        assert_eq!(params.validate_changes(&changes).len(), 1);
+        // Valid when the max. period is raised by the same proposal
+        let changes = vec![
+            gov(GovParameter::MinProposalPeriod(30)),
+            gov(GovParameter::MaxProposalPeriod(60)),
+        ];
+        assert!(params.validate_changes(&changes).is_empty());
+        let changes = vec![gov(GovParameter::MaxProposalPeriod(2))];
+        assert_eq!(params.validate_changes(&changes).len(), 1);
+
+        // Longer epochs require fewer epochs per year
+        let changes = vec![protocol(ProtocolParameter::MinEpochDuration(120))];
+        assert_eq!(params.validate_changes(&changes).len(), 1);
+        let changes = vec![
+            protocol(ProtocolParameter::MinEpochDuration(120)),
+            protocol(ProtocolParameter::EpochsPerYear(262_800)),
+        ];
+        assert!(params.validate_changes(&changes).is_empty());
+        let changes =
+            vec![protocol(ProtocolParameter::EpochsPerYear(u64::MAX))];
+        assert_eq!(params.validate_changes(&changes).len(), 1);
+
+        // Unrelated changes aren't checked against inconsistent stored values
+        let inconsistent = DependentParameters {
+            min_epoch_duration: 600,
+            ..params
+        };
+        let changes = vec![gov(GovParameter::MaxProposalPeriod(30))];
+        assert!(inconsistent.validate_changes(&changes).is_empty());
+    }
+
+    #[test]
+    fn test_funds_destination_from_json() {
+        let change: ParameterChange = serde_json::from_value(
+            serde_json::json!({"Governance": {"RejectedFunds": "slash_fund"}}),
+        )
+        .unwrap();
+        assert_eq!(
+            change,
+            ParameterChange::Governance(GovParameter::RejectedFunds(
+                FundsDestination::SlashFund
+            ))
+        );
+        assert!(change.is_valid());
+    }
+}
@@ -4,6 +4,7 @@ pub mod common;
 /// Elliptic curve keys for the DKG
 pub mod dkg_session_keys;
 pub mod ed25519;
+pub mod multisig;
 pub mod secp256k1;
 
 use std::fmt::{Debug, Display};
@@ -24,6 +25,7 @@ use crate::types::address;
 
 const PK_STORAGE_KEY: &str = "public_key";
 const PROTOCOL_PK_STORAGE_KEY: &str = "protocol_public_key";
+const MULTISIG_STORAGE_KEY: &str = "multisig";
 
 /// Obtain a storage key for user's public key.
 pub fn pk_key(owner: &Address) -> storage::Key {
@@ -44,6 +46,27 @@ pub fn is_pk_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Obtain a storage key for the public keys and threshold of a multisignature
+/// account.
+pub fn multisig_key(owner: &Address) -> storage::Key {
+    Key::from(owner.to_db_key())
+        .push(&MULTISIG_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is the keys of a multisignature account. If
+/// it is, returns the owner.
+pub fn is_multisig_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == MULTISIG_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
 /// Obtain a storage key for user's protocol public key.
 pub fn protocol_pk_key(owner: &Address) -> storage::Key {
     Key::from(owner.to_db_key())
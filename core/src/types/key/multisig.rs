@@ -0,0 +1,131 @@
+//! Threshold multisignature accounts
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::common;
+
+/// The public keys of a multisignature account along with the number of
+/// signatures required to authorize its transactions. The keys are ordered
+/// and signatures refer to them by their index.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct MultisigKeys {
+    /// The ordered set of public keys
+    pub public_keys: Vec<common::PublicKey>,
+    /// The minimum number of valid signatures
+    pub threshold: u8,
+}
+
+impl MultisigKeys {
+    /// Check that the keys are unique, that there are no more of them than
+    /// can be indexed by a signature and that the threshold can be met.
+    pub fn is_valid(&self) -> bool {
+        let unique_keys: BTreeSet<_> = self.public_keys.iter().collect();
+        unique_keys.len() == self.public_keys.len()
+            && self.public_keys.len() <= usize::from(u8::MAX) + 1
+            && self.threshold > 0
+            && usize::from(self.threshold) <= self.public_keys.len()
+    }
+
+    /// Get the public key at the given index
+    pub fn get(&self, index: u8) -> Option<&common::PublicKey> {
+        self.public_keys.get(usize::from(index))
+    }
+
+    /// Check that at least `threshold` of the given signatures are valid, each
+    /// for a different key. Every signature is given with the index of the
+    /// key it was made with and checked with `verify`. Signatures with an
+    /// unknown index are ignored.
+    pub fn verify_threshold<E>(
+        &self,
+        sigs: &[(u8, common::Signature)],
+        mut verify: impl FnMut(
+            &common::PublicKey,
+            &common::Signature,
+        ) -> Result<bool, E>,
+    ) -> Result<bool, E> {
+        // Don't bother verifying if there aren't enough distinct indices
+        let indices: BTreeSet<_> =
+            sigs.iter().map(|(index, _)| index).collect();
+        if indices.len() < usize::from(self.threshold) {
+            return Ok(false);
+        }
+        let mut signed = BTreeSet::new();
+        for (index, sig) in sigs {
+            if signed.contains(index) {
+                continue;
+            }
+            if let Some(pk) = self.get(*index) {
+                if verify(pk, sig)? {
+                    signed.insert(*index);
+                }
+            }
+            if signed.len() >= usize::from(self.threshold) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::key::testing::{keypair_1, keypair_2};
+    use crate::types::key::{RefTo, SigScheme, VerifySigError};
+
+    #[test]
+    fn test_verify_threshold() {
+        let sk_1 = keypair_1();
+        let sk_2 = keypair_2();
+        let keys = MultisigKeys {
+            public_keys: vec![sk_1.ref_to(), sk_2.ref_to()],
+            threshold: 2,
+        };
+        assert!(keys.is_valid());
+
+        let data = b"tx hash";
+        let sig_1 = common::SigScheme::sign(&sk_1, data);
+        let sig_2 = common::SigScheme::sign(&sk_2, data);
+        let verify = |pk: &common::PublicKey, sig: &common::Signature| {
+            Ok::<_, VerifySigError>(
+                common::SigScheme::verify_signature_raw(pk, data, sig).is_ok(),
+            )
+        };
+
+        let both = [(0, sig_1.clone()), (1, sig_2.clone())];
+        assert!(keys.verify_threshold(&both, verify).unwrap());
+        // The same key cannot be counted twice
+        let twice = [(0, sig_1.clone()), (0, sig_1.clone())];
+        assert!(!keys.verify_threshold(&twice, verify).unwrap());
+        // A signature must match the key at its index
+        let swapped = [(1, sig_1), (0, sig_2)];
+        assert!(!keys.verify_threshold(&swapped, verify).unwrap());
+    }
+
+    #[test]
+    fn test_multisig_keys_validity() {
+        let pk_1 = keypair_1().ref_to();
+        let pk_2 = keypair_2().ref_to();
+        let keys = |public_keys, threshold| MultisigKeys {
+            public_keys,
+            threshold,
+        };
+        assert!(keys(vec![pk_1.clone(), pk_2.clone()], 1).is_valid());
+        assert!(!keys(vec![pk_1.clone(), pk_2.clone()], 0).is_valid());
+        assert!(!keys(vec![pk_1.clone(), pk_2], 3).is_valid());
+        assert!(!keys(vec![pk_1.clone(), pk_1], 1).is_valid());
+    }
+}
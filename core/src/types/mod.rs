@@ -2,6 +2,7 @@
 
 pub mod address;
 pub mod chain;
+pub mod ethereum_events;
 pub mod governance;
 pub mod hash;
 pub mod ibc;
@@ -13,3 +14,4 @@ pub mod time;
 pub mod token;
 pub mod transaction;
 pub mod validity_predicate;
+pub mod vote_extensions;
@@ -5,7 +5,9 @@
 pub mod encrypted_tx {
     use std::io::{Error, ErrorKind, Write};
 
-    use ark_ec::PairingEngine;
+    use ark_ec::{PairingEngine, ProjectiveCurve};
+    use ark_ff::{Field, One, PrimeField, UniformRand};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
     use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use borsh::{BorshDeserialize, BorshSerialize};
     use serde::{Deserialize, Serialize};
@@ -14,6 +16,12 @@ pub mod encrypted_tx {
     use crate::types::transaction::{AffineCurve, EllipticCurve};
     /// The first group in our elliptic curve bilinear pairing
     pub type G1 = <EllipticCurve as PairingEngine>::G1Affine;
+    /// The second group in our elliptic curve bilinear pairing
+    pub type G2 = <EllipticCurve as PairingEngine>::G2Affine;
+    /// The scalar field of our elliptic curve
+    pub type Fr = <EllipticCurve as PairingEngine>::Fr;
+    /// The target group of our elliptic curve bilinear pairing
+    pub type Gt = <EllipticCurve as PairingEngine>::Fqk;
     /// An encryption key for txs
     #[derive(Debug, Clone, PartialEq)]
     pub struct EncryptionKey(pub G1);
@@ -43,6 +51,219 @@ pub mod encrypted_tx {
         }
     }
 
+    /// A validator's share of the decryption of a tx, computed with one of
+    /// its shares of the private key from the DKG. Enough shares can be
+    /// combined to decrypt the tx without anyone learning the private key.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DecryptionShare(pub Gt);
+
+    impl borsh::ser::BorshSerialize for DecryptionShare {
+        fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            let mut buf = Vec::<u8>::new();
+            CanonicalSerialize::serialize(&self.0, &mut buf)
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+            BorshSerialize::serialize(&buf, writer)
+        }
+    }
+
+    impl borsh::de::BorshDeserialize for DecryptionShare {
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            let share: Vec<u8> = BorshDeserialize::deserialize(buf)?;
+            Ok(DecryptionShare(
+                CanonicalDeserialize::deserialize(&*share)
+                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))?,
+            ))
+        }
+    }
+
+    impl borsh::BorshSchema for DecryptionShare {
+        fn add_definitions_recursively(
+            definitions: &mut std::collections::HashMap<
+                borsh::schema::Declaration,
+                borsh::schema::Definition,
+            >,
+        ) {
+            // Encoded as `Vec<u8>`
+            let elements = "u8".into();
+            let definition = borsh::schema::Definition::Sequence { elements };
+            definitions.insert(Self::declaration(), definition);
+        }
+
+        fn declaration() -> borsh::schema::Declaration {
+            "DecryptionShare".into()
+        }
+    }
+
+    /// A validator's decryption shares of a tx, which can be checked against
+    /// the public shares of the DKG key without revealing the private ones.
+    /// With a random blinding factor `b`, the blinding key is the generator
+    /// of `G2` times `b`, the private key shares are multiplied by `b` and
+    /// the checksum is the tx's nonce times `1/b`, so that the pairing of the
+    /// checksum with a blinded key share is the decryption share.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct BlindedDecryptionShares {
+        /// The generator of `G2` times the blinding factor
+        pub blinding_key: G2,
+        /// The private key shares times the blinding factor, in the order
+        /// of their indices
+        pub blinded_key_shares: Vec<G2>,
+        /// The tx's nonce times the inverse of the blinding factor
+        pub checksum: G1,
+    }
+
+    impl BlindedDecryptionShares {
+        /// Check the shares against the public key shares at the same
+        /// indices, which are the generator of `G1` times the evaluations of
+        /// the DKG's secret polynomial, and get the decryption shares of the
+        /// tx. Returns `None` if any of the shares is invalid.
+        pub fn verify(
+            &self,
+            tx: &EncryptedTx,
+            public_key_shares: &[G1],
+        ) -> Option<Vec<DecryptionShare>> {
+            if self.blinded_key_shares.len() != public_key_shares.len() {
+                return None;
+            }
+            let g1 = G1::prime_subgroup_generator();
+            let g2 = G2::prime_subgroup_generator();
+            // The checksum must be the nonce divided by the blinding factor
+            if EllipticCurve::pairing(self.checksum, self.blinding_key)
+                != EllipticCurve::pairing(tx.0.nonce, g2)
+            {
+                return None;
+            }
+            self.blinded_key_shares
+                .iter()
+                .zip(public_key_shares)
+                .map(|(blinded_key_share, public_key_share)| {
+                    // The blinded key share must be the private key share
+                    // matching the public one times the blinding factor
+                    let valid =
+                        EllipticCurve::pairing(
+                            *public_key_share,
+                            self.blinding_key,
+                        ) == EllipticCurve::pairing(g1, *blinded_key_share);
+                    valid.then(|| {
+                        DecryptionShare(EllipticCurve::pairing(
+                            self.checksum,
+                            *blinded_key_share,
+                        ))
+                    })
+                })
+                .collect()
+        }
+    }
+
+    /// Serialize a curve point to bytes
+    fn point_to_bytes(
+        point: &impl CanonicalSerialize,
+    ) -> std::io::Result<Vec<u8>> {
+        let mut buf = Vec::<u8>::new();
+        point
+            .serialize(&mut buf)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        Ok(buf)
+    }
+
+    /// Deserialize a curve point from bytes
+    fn point_from_bytes<T: CanonicalDeserialize>(
+        bytes: &[u8],
+    ) -> std::io::Result<T> {
+        CanonicalDeserialize::deserialize(bytes)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+
+    impl borsh::ser::BorshSerialize for BlindedDecryptionShares {
+        fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            let blinded_key_shares = self
+                .blinded_key_shares
+                .iter()
+                .map(point_to_bytes)
+                .collect::<std::io::Result<Vec<_>>>()?;
+            BorshSerialize::serialize(
+                &(
+                    point_to_bytes(&self.blinding_key)?,
+                    blinded_key_shares,
+                    point_to_bytes(&self.checksum)?,
+                ),
+                writer,
+            )
+        }
+    }
+
+    impl borsh::de::BorshDeserialize for BlindedDecryptionShares {
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            type Encoded = (Vec<u8>, Vec<Vec<u8>>, Vec<u8>);
+            let (blinding_key, blinded_key_shares, checksum): Encoded =
+                BorshDeserialize::deserialize(buf)?;
+            Ok(BlindedDecryptionShares {
+                blinding_key: point_from_bytes(&blinding_key)?,
+                blinded_key_shares: blinded_key_shares
+                    .iter()
+                    .map(|share| point_from_bytes(share))
+                    .collect::<std::io::Result<_>>()?,
+                checksum: point_from_bytes(&checksum)?,
+            })
+        }
+    }
+
+    impl borsh::BorshSchema for BlindedDecryptionShares {
+        fn add_definitions_recursively(
+            definitions: &mut std::collections::HashMap<
+                borsh::schema::Declaration,
+                borsh::schema::Definition,
+            >,
+        ) {
+            // Encoded as `(Vec<u8>, Vec<Vec<u8>>, Vec<u8>)`
+            let elements = "u8".into();
+            let definition = borsh::schema::Definition::Sequence { elements };
+            definitions.insert("Vec<u8>".into(), definition);
+            let elements = "Vec<u8>".into();
+            let definition = borsh::schema::Definition::Sequence { elements };
+            definitions.insert("Vec<Vec<u8>>".into(), definition);
+            let elements =
+                vec!["Vec<u8>".into(), "Vec<Vec<u8>>".into(), "Vec<u8>".into()];
+            let definition = borsh::schema::Definition::Tuple { elements };
+            definitions.insert(Self::declaration(), definition);
+        }
+
+        fn declaration() -> borsh::schema::Declaration {
+            "BlindedDecryptionShares".into()
+        }
+    }
+
+    /// The point at which the DKG's secret polynomial is evaluated for the
+    /// private key share with the given index, out of `total_weight` shares
+    pub fn share_evaluation_point(total_weight: u32, index: u32) -> Fr {
+        Radix2EvaluationDomain::<Fr>::new(total_weight as usize)
+            .expect("The number of key shares must fit in the FFT domain")
+            .element(index as usize)
+    }
+
+    /// Combine the decryption shares of a tx, given with the evaluation
+    /// points of the private key shares they were computed with, by Lagrange
+    /// interpolation in the exponent. The result is the secret shared by
+    /// the sender of the tx and the holder of the private key.
+    pub fn combine_decryption_shares(shares: &[(Fr, DecryptionShare)]) -> Gt {
+        shares.iter().enumerate().fold(
+            Gt::one(),
+            |secret, (i, (x_i, share))| {
+                let lagrange_coeff = shares
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .fold(Fr::one(), |coeff, (_, (x_j, _))| {
+                        coeff
+                            * x_j
+                            * (*x_j - x_i).inverse().expect(
+                                "The evaluation points must be distinct",
+                            )
+                    });
+                secret * share.0.pow(lagrange_coeff.into_repr())
+            },
+        )
+    }
+
     /// We use a specific choice of two groups and bilinear pairing
     /// We use a wrapper type to add traits
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -64,6 +285,47 @@ pub mod encrypted_tx {
         ) -> Vec<u8> {
             tpke::decrypt(&self.0, privkey)
         }
+
+        /// Compute a decryption share of this tx with a private key share
+        pub fn decryption_share(&self, key_share: &G2) -> DecryptionShare {
+            DecryptionShare(EllipticCurve::pairing(self.0.nonce, *key_share))
+        }
+
+        /// Compute the decryption shares of this tx with a validator's
+        /// private key shares, blinded by a random factor
+        pub fn blinded_decryption_shares(
+            &self,
+            key_shares: &[G2],
+        ) -> BlindedDecryptionShares {
+            let mut rng = rand::thread_rng();
+            let (factor, inverse) = loop {
+                let factor = Fr::rand(&mut rng);
+                if let Some(inverse) = factor.inverse() {
+                    break (factor, inverse);
+                }
+            };
+            BlindedDecryptionShares {
+                blinding_key: G2::prime_subgroup_generator()
+                    .mul(factor)
+                    .into_affine(),
+                blinded_key_shares: key_shares
+                    .iter()
+                    .map(|key_share| key_share.mul(factor).into_affine())
+                    .collect(),
+                checksum: self.0.nonce.mul(inverse).into_affine(),
+            }
+        }
+
+        /// Decrypt a message with the decryption shares computed with the
+        /// private key shares at the given evaluation points. There must be
+        /// more shares than the degree of the DKG's secret polynomial.
+        pub fn decrypt_with_shares(
+            &self,
+            shares: &[(Fr, DecryptionShare)],
+        ) -> Vec<u8> {
+            let secret = combine_decryption_shares(shares);
+            tpke::decrypt_with_shared_secret(&self.0, &secret)
+        }
     }
 
     impl borsh::ser::BorshSerialize for EncryptedTx {
@@ -155,7 +417,8 @@ pub mod encrypted_tx {
 
     #[cfg(test)]
     mod test_encrypted_tx {
-        use ark_ec::AffineCurve;
+        use ark_ec::{AffineCurve, ProjectiveCurve};
+        use ark_ff::UniformRand;
 
         use super::*;
 
@@ -215,6 +478,120 @@ pub mod encrypted_tx {
             // check that decryption works as expected
             assert_eq!(decrypted, "Super secret stuff".as_bytes());
         }
+
+        /// Test that a threshold of decryption shares decrypts a payload
+        /// encrypted with the public key of the shared private key.
+        #[test]
+        fn test_decrypt_with_shares() {
+            let mut rng = rand::thread_rng();
+            let total_weight = 4;
+            // A secret polynomial of degree 2, whose value at 0 is the
+            // private key
+            let coeffs: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+            let eval = |x: Fr| {
+                coeffs
+                    .iter()
+                    .rev()
+                    .fold(Fr::default(), |acc, c| acc * x + c)
+            };
+            let pubkey = EncryptionKey(
+                G1::prime_subgroup_generator().mul(coeffs[0]).into_affine(),
+            );
+            let key_shares: Vec<(Fr, G2)> = (0..total_weight)
+                .map(|index| {
+                    let x = share_evaluation_point(total_weight, index);
+                    (
+                        x,
+                        G2::prime_subgroup_generator()
+                            .mul(eval(x))
+                            .into_affine(),
+                    )
+                })
+                .collect();
+
+            let encrypted =
+                EncryptedTx::encrypt("Super secret stuff".as_bytes(), pubkey);
+            let shares: Vec<(Fr, DecryptionShare)> = key_shares
+                .iter()
+                .map(|(x, key_share)| {
+                    (*x, encrypted.decryption_share(key_share))
+                })
+                .collect();
+
+            // Any 3 of the shares are enough, and so are all of them
+            assert_eq!(
+                encrypted.decrypt_with_shares(&shares[1..]),
+                "Super secret stuff".as_bytes()
+            );
+            assert_eq!(
+                encrypted.decrypt_with_shares(&shares),
+                "Super secret stuff".as_bytes()
+            );
+
+            // The shares survive a Borsh roundtrip
+            let share = &shares[0].1;
+            let bytes = share.try_to_vec().expect("Test failed");
+            assert_eq!(
+                &DecryptionShare::try_from_slice(&bytes).expect("Test failed"),
+                share
+            );
+        }
+
+        /// Test that blinded decryption shares are verified against the
+        /// public key shares and give the same decryption shares as the
+        /// private key shares
+        #[test]
+        fn test_verify_blinded_decryption_shares() {
+            let mut rng = rand::thread_rng();
+            let secrets: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+            let public_key_shares: Vec<G1> = secrets
+                .iter()
+                .map(|s| G1::prime_subgroup_generator().mul(*s).into_affine())
+                .collect();
+            let key_shares: Vec<G2> = secrets
+                .iter()
+                .map(|s| G2::prime_subgroup_generator().mul(*s).into_affine())
+                .collect();
+            let pubkey = EncryptionKey(public_key_shares[0]);
+            let encrypted =
+                EncryptedTx::encrypt("Super secret stuff".as_bytes(), pubkey);
+
+            let blinded = encrypted.blinded_decryption_shares(&key_shares);
+            let shares = blinded
+                .verify(&encrypted, &public_key_shares)
+                .expect("Test failed");
+            let expected: Vec<DecryptionShare> = key_shares
+                .iter()
+                .map(|key_share| encrypted.decryption_share(key_share))
+                .collect();
+            assert_eq!(shares, expected);
+
+            // A share made with another key share is rejected
+            let mut invalid = blinded.clone();
+            invalid.blinded_key_shares.swap(0, 1);
+            assert!(invalid.verify(&encrypted, &public_key_shares).is_none());
+            // A checksum of another tx is rejected
+            let other = EncryptedTx::encrypt(
+                "Other stuff".as_bytes(),
+                EncryptionKey(public_key_shares[1]),
+            );
+            let mut invalid = blinded.clone();
+            invalid.checksum =
+                other.blinded_decryption_shares(&key_shares).checksum;
+            assert!(invalid.verify(&encrypted, &public_key_shares).is_none());
+            // Missing shares are rejected
+            assert!(blinded
+                .verify(&encrypted, &public_key_shares[1..])
+                .is_none());
+
+            // The shares survive a Borsh roundtrip
+            let bytes = blinded.try_to_vec().expect("Test failed");
+            assert_eq!(
+                BlindedDecryptionShares::try_from_slice(&bytes)
+                    .expect("Test failed"),
+                blinded
+            );
+        }
     }
 }
 
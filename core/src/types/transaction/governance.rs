@@ -2,7 +2,9 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::types::address::Address;
-use crate::types::governance::{Proposal, ProposalError, ProposalVote};
+use crate::types::governance::{
+    Proposal, ProposalError, ProposalType, ProposalVote,
+};
 use crate::types::storage::Epoch;
 
 /// A tx data type to hold proposal data
@@ -30,6 +32,8 @@ pub struct InitProposalData {
     pub grace_epoch: Epoch,
     /// The code containing the storage changes
     pub proposal_code: Option<Vec<u8>>,
+    /// The proposal type and its declared payload
+    pub proposal_type: ProposalType,
 }
 
 /// A tx data type to hold vote proposal data
@@ -57,6 +61,12 @@ impl TryFrom<Proposal> for InitProposalData {
     type Error = ProposalError;
 
     fn try_from(proposal: Proposal) -> Result<Self, Self::Error> {
+        // Only default proposals can execute code
+        if proposal.proposal_type != ProposalType::Default
+            && proposal.proposal_code_path.is_some()
+        {
+            return Err(Self::Error::InvalidProposalData);
+        }
         let proposal_code = if let Some(path) = proposal.proposal_code_path {
             match std::fs::read(path) {
                 Ok(bytes) => Some(bytes),
@@ -74,6 +84,7 @@ impl TryFrom<Proposal> for InitProposalData {
             voting_end_epoch: proposal.voting_end_epoch,
             grace_epoch: proposal.grace_epoch,
             proposal_code,
+            proposal_type: proposal.proposal_type,
         })
     }
 }
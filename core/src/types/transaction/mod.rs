@@ -159,10 +159,14 @@ pub struct UpdateVp {
     Deserialize,
 )]
 pub struct InitAccount {
-    /// Public key to be written into the account's storage. This can be used
-    /// for signature verification of transactions for the newly created
-    /// account.
-    pub public_key: common::PublicKey,
+    /// Public keys to be written into the account's storage. These can be
+    /// used for signature verification of transactions for the newly created
+    /// account. A single key is stored as the account's public key, while
+    /// multiple keys make it a multisignature account.
+    pub public_keys: Vec<common::PublicKey>,
+    /// The number of signatures required to authorize a transaction of a
+    /// multisignature account. Ignored when there's a single public key.
+    pub threshold: u8,
     /// The VP code
     pub vp_code: Vec<u8>,
 }
@@ -194,6 +198,8 @@ pub struct InitValidator {
     /// The maximum change allowed per epoch to the commission rate. This is
     /// immutable once set here.
     pub max_commission_rate_change: Decimal,
+    /// Validator's metadata
+    pub metadata: pos::ValidatorMetaData,
     /// The VP code for validator account
     pub validator_vp_code: Vec<u8>,
 }
@@ -209,7 +215,7 @@ pub mod tx_types {
     use thiserror;
 
     use super::*;
-    use crate::proto::{SignedTxData, Tx};
+    use crate::proto::{SignedTxData, Tx, TxSignature};
     use crate::types::transaction::protocol::ProtocolTx;
 
     /// Errors relating to decrypting a wrapper tx and its
@@ -307,12 +313,12 @@ pub mod tx_types {
             {
                 // verify signature and extract signed data
                 TxType::Wrapper(wrapper) => {
-                    wrapper.validate_sig(signed_hash, sig)?;
+                    wrapper.validate_sig(signed_hash, single_sig(sig)?)?;
                     Ok(TxType::Wrapper(wrapper))
                 }
                 // verify signature and extract signed data
                 TxType::Protocol(protocol) => {
-                    protocol.validate_sig(signed_hash, sig)?;
+                    protocol.validate_sig(signed_hash, single_sig(sig)?)?;
                     Ok(TxType::Protocol(protocol))
                 }
                 // we extract the signed data, but don't check the signature
@@ -337,6 +343,17 @@ pub mod tx_types {
         }
     }
 
+    /// Wrapper and protocol txs are signed by a single key, they cannot carry
+    /// a multisignature
+    fn single_sig(sig: &TxSignature) -> Result<&common::Signature, TxError> {
+        match sig {
+            TxSignature::Single(sig) => Ok(sig),
+            TxSignature::Multi(_) => Err(TxError::SigError(
+                "Expected a single signature, found a multisignature".into(),
+            )),
+        }
+    }
+
     #[cfg(test)]
     mod test_process_tx {
         use super::*;
@@ -516,7 +533,9 @@ pub mod tx_types {
                     .try_to_vec()
                     .expect("Test failed"),
             ),
-            sig: common::Signature::try_from_sig(&ed_sig).unwrap(),
+            sig: TxSignature::Single(
+                common::Signature::try_from_sig(&ed_sig).unwrap(),
+            ),
         };
         // create the tx with signed decrypted data
         let tx =
@@ -5,6 +5,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use crate::types::address::Address;
+use crate::types::key::common;
 use crate::types::token;
 
 /// A bond is a validator's self-bond or a delegation from non-validator to a
@@ -55,6 +56,31 @@ pub struct Withdraw {
     pub source: Option<Address>,
 }
 
+/// A redelegation of delegated tokens from one validator to another, without
+/// unbonding them.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct Redelegation {
+    /// Source validator address
+    pub src_validator: Address,
+    /// Destination validator address
+    pub dest_validator: Address,
+    /// Owner of the redelegated bond
+    pub owner: Address,
+    /// The amount of tokens to redelegate
+    pub amount: token::Amount,
+}
+
 /// A change to the validator commission rate.
 #[derive(
     Debug,
@@ -74,3 +100,85 @@ pub struct CommissionChange {
     /// The new commission rate
     pub new_rate: Decimal,
 }
+
+/// A change to the validator consensus key.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ConsensusKeyChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new consensus key
+    pub consensus_key: common::PublicKey,
+}
+
+/// Validator's metadata published on chain, e.g. for display in explorers and
+/// wallets.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ValidatorMetaData {
+    /// Validator's name
+    pub name: Option<String>,
+    /// Validator's description
+    pub description: Option<String>,
+    /// Validator's website
+    pub website: Option<String>,
+    /// Validator's contact, e.g. an email address
+    pub contact: Option<String>,
+}
+
+impl ValidatorMetaData {
+    /// The maximum length in bytes of a metadata field
+    pub const MAX_FIELD_LEN: usize = 512;
+
+    /// Check that none of the set fields is empty or longer than
+    /// [`ValidatorMetaData::MAX_FIELD_LEN`].
+    pub fn is_valid(&self) -> bool {
+        [&self.name, &self.description, &self.website, &self.contact]
+            .into_iter()
+            .flatten()
+            .all(|field| {
+                !field.is_empty() && field.len() <= Self::MAX_FIELD_LEN
+            })
+    }
+}
+
+/// A change to the validator metadata.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct MetaDataChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new metadata
+    pub metadata: ValidatorMetaData,
+}
@@ -33,8 +33,11 @@ mod protocol_txs {
 
     use super::*;
     use crate::proto::Tx;
+    use crate::types::hash::Hash;
     use crate::types::key::*;
+    use crate::types::transaction::encrypted::BlindedDecryptionShares;
     use crate::types::transaction::{EllipticCurve, TxError, TxType};
+    use crate::types::vote_extensions::ethereum_events::VextDigest;
 
     const TX_NEW_DKG_KP_WASM: &str = "tx_update_dkg_session_keypair.wasm";
 
@@ -68,6 +71,10 @@ mod protocol_txs {
     #[derive(Clone, Debug)]
     pub struct DkgMessage(pub Message<EllipticCurve>);
 
+    /// The decryption shares of a tx computed by a validator with each of
+    /// its private key shares, blinded so that anyone can verify them
+    pub type TxDecryptionShares = BlindedDecryptionShares;
+
     #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, BorshSchema)]
     #[allow(clippy::large_enum_variant)]
     /// Types of protocol messages to be sent
@@ -76,9 +83,12 @@ mod protocol_txs {
         DKG(DkgMessage),
         /// Tx requesting a new DKG session keypair
         NewDkgKeypair(Tx),
-        /// Aggregation of Ethereum state changes
-        /// voted on by validators in last block
-        EthereumStateUpdate(Tx),
+        /// A validator's decryption shares of the wrapper txs in the tx
+        /// queue, with the hash commitment of each tx
+        DecryptionShares(Vec<(Hash, TxDecryptionShares)>),
+        /// Ethereum events observed by the validators, aggregated from the
+        /// vote extensions of the last block
+        EthereumEvents(VextDigest),
     }
 
     impl ProtocolTxType {
@@ -16,7 +16,9 @@ pub mod wrapper_tx {
     use crate::types::key::*;
     use crate::types::storage::Epoch;
     use crate::types::token::Amount;
-    use crate::types::transaction::encrypted::EncryptedTx;
+    use crate::types::transaction::encrypted::{
+        DecryptionShare, EncryptedTx, Fr,
+    };
     use crate::types::transaction::{
         hash_tx, EncryptionKey, Hash, TxError, TxType,
     };
@@ -190,11 +192,32 @@ pub mod wrapper_tx {
             gas_limit: GasLimit,
             tx: Tx,
             encryption_key: EncryptionKey,
+        ) -> WrapperTx {
+            Self::new_with_pk(
+                fee,
+                keypair.ref_to(),
+                epoch,
+                gas_limit,
+                tx,
+                encryption_key,
+            )
+        }
+
+        /// Create a new wrapper tx like [`WrapperTx::new`], but from the
+        /// public key of the fee payer, for when its secret key is not at
+        /// hand, e.g. because it's held by a hardware wallet
+        pub fn new_with_pk(
+            fee: Fee,
+            pk: common::PublicKey,
+            epoch: Epoch,
+            gas_limit: GasLimit,
+            tx: Tx,
+            encryption_key: EncryptionKey,
         ) -> WrapperTx {
             let inner_tx = EncryptedTx::encrypt(&tx.to_bytes(), encryption_key);
             Self {
                 fee,
-                pk: keypair.ref_to(),
+                pk,
                 epoch,
                 gas_limit,
                 inner_tx,
@@ -226,6 +249,26 @@ pub mod wrapper_tx {
         ) -> Result<Tx, WrapperTxErr> {
             // decrypt the inner tx
             let decrypted = self.inner_tx.decrypt(privkey);
+            self.check_decrypted(decrypted)
+        }
+
+        /// Decrypt the wrapped transaction with the validators' decryption
+        /// shares, given with the evaluation points of their private key
+        /// shares. Fails in the same cases as [`WrapperTx::decrypt`].
+        pub fn decrypt_with_shares(
+            &self,
+            shares: &[(Fr, DecryptionShare)],
+        ) -> Result<Tx, WrapperTxErr> {
+            let decrypted = self.inner_tx.decrypt_with_shares(shares);
+            self.check_decrypted(decrypted)
+        }
+
+        /// Check the decrypted bytes against the hash commitment and decode
+        /// them as a tx
+        fn check_decrypted(
+            &self,
+            decrypted: Vec<u8>,
+        ) -> Result<Tx, WrapperTxErr> {
             // check that the hash equals commitment
             if hash_tx(&decrypted) != self.tx_hash {
                 Err(WrapperTxErr::DecryptedHash)
@@ -244,7 +287,13 @@ pub mod wrapper_tx {
             if self.pk != keypair.ref_to() {
                 return Err(WrapperTxErr::InvalidKeyPair);
             }
-            Ok(Tx::new(
+            Ok(self.to_unsigned_tx().sign(keypair))
+        }
+
+        /// Convert to a normal Tx type without signing it. The fee payer has
+        /// to sign it before it's submitted.
+        pub fn to_unsigned_tx(&self) -> Tx {
+            Tx::new(
                 vec![],
                 Some(
                     TxType::Wrapper(self.clone())
@@ -252,7 +301,6 @@ pub mod wrapper_tx {
                         .expect("Could not serialize WrapperTx"),
                 ),
             )
-            .sign(keypair))
         }
 
         /// Validate the signature of a wrapper tx
@@ -336,7 +384,7 @@ pub mod wrapper_tx {
     #[cfg(test)]
     mod test_wrapper_tx {
         use super::*;
-        use crate::proto::SignedTxData;
+        use crate::proto::{SignedTxData, TxSignature};
         use crate::types::address::nam;
 
         fn gen_keypair() -> common::SecretKey {
@@ -472,8 +520,11 @@ pub mod wrapper_tx {
             tx.data = Some(signed_tx_data.try_to_vec().expect("Test failed"));
 
             // check that the signature is not valid
-            tx.verify_sig(&keypair.ref_to(), &signed_tx_data.sig)
-                .expect_err("Test failed");
+            let sig = match &signed_tx_data.sig {
+                TxSignature::Single(sig) => sig,
+                TxSignature::Multi(_) => panic!("Test failed"),
+            };
+            tx.verify_sig(&keypair.ref_to(), sig).expect_err("Test failed");
             // check that the try from method also fails
             let err = crate::types::transaction::process_tx(tx)
                 .expect_err("Test failed");
@@ -0,0 +1,124 @@
+//! Vote extensions carrying the Ethereum events observed by the validators.
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::proto::Signed;
+use crate::types::address::Address;
+use crate::types::ethereum_events::EthereumEvent;
+use crate::types::key::common;
+use crate::types::storage::BlockHeight;
+
+/// A validator's vote extension with the Ethereum events it has observed,
+/// which it signs with its protocol key.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct Vext {
+    /// The address of the signing validator
+    pub validator_addr: Address,
+    /// The height of the block voted on
+    pub block_height: BlockHeight,
+    /// The observed Ethereum events, which may be empty
+    pub ethereum_events: Vec<EthereumEvent>,
+}
+
+/// A vote extension signed with the validator's protocol key
+pub type SignedVext = Signed<Vext>;
+
+impl Vext {
+    /// A vote extension without any Ethereum events
+    pub fn empty(block_height: BlockHeight, validator_addr: Address) -> Self {
+        Self {
+            validator_addr,
+            block_height,
+            ethereum_events: vec![],
+        }
+    }
+
+    /// Sign the vote extension with the validator's protocol key
+    pub fn sign(self, protocol_key: &common::SecretKey) -> SignedVext {
+        Signed::new(protocol_key, self)
+    }
+}
+
+/// The vote extensions from the commit of the last block, included by the
+/// block proposer in a protocol tx.
+#[derive(
+    Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct VextDigest {
+    /// The signed vote extensions, at most one per validator
+    pub vexts: Vec<SignedVext>,
+}
+
+impl VextDigest {
+    /// The addresses of the validators whose vote extensions are included.
+    /// Returns `None` if there is more than one vote extension from the same
+    /// validator.
+    pub fn validators(&self) -> Option<BTreeSet<&Address>> {
+        let mut validators = BTreeSet::new();
+        for vext in &self.vexts {
+            if !validators.insert(&vext.data.validator_addr) {
+                return None;
+            }
+        }
+        Some(validators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+    use crate::types::ethereum_events::TransferToNamada;
+    use crate::types::key::testing::keypair_1;
+    use crate::types::key::RefTo;
+
+    #[test]
+    fn test_vext_digest() {
+        let keypair = keypair_1();
+        let event = EthereumEvent::TransfersToNamada {
+            nonce: 0,
+            transfers: vec![TransferToNamada {
+                amount: 100.into(),
+                asset: DAI_ERC20_ETH_ADDRESS,
+                receiver: established_address_2(),
+            }],
+        };
+        let vext = Vext {
+            ethereum_events: vec![event],
+            ..Vext::empty(BlockHeight(1), established_address_1())
+        }
+        .sign(&keypair);
+        assert!(vext.verify(&keypair.ref_to()).is_ok());
+
+        let digest = VextDigest {
+            vexts: vec![
+                vext.clone(),
+                Vext::empty(BlockHeight(1), established_address_2())
+                    .sign(&keypair),
+            ],
+        };
+        assert_eq!(digest.validators().map(|vals| vals.len()), Some(2));
+
+        // Only a single vote extension per validator is allowed
+        let digest = VextDigest {
+            vexts: vec![vext.clone(), vext],
+        };
+        assert!(digest.validators().is_none());
+    }
+}
@@ -0,0 +1,4 @@
+//! Data attached by the validators to their pre-commit votes with ABCI++ vote
+//! extensions and aggregated by the block proposers into protocol txs.
+
+pub mod ethereum_events;
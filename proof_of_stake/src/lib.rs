@@ -15,12 +15,13 @@
 pub mod btree_set;
 pub mod epoched;
 pub mod parameters;
+pub mod rewards;
 pub mod storage;
 pub mod types;
 pub mod validation;
 
 use core::fmt::Debug;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::num::TryFromIntError;
 
@@ -29,16 +30,22 @@ use epoched::{
 };
 use namada_core::ledger::storage_api;
 use namada_core::types::address::{self, Address, InternalAddress};
-use namada_core::types::key::common;
+use namada_core::types::key::{self, common};
 use namada_core::types::storage::Epoch;
 use namada_core::types::token;
 pub use parameters::PosParams;
+use rewards::{
+    split_validator_rewards, PosRewardsCalculator, RewardsError,
+    ValidatorRewards,
+};
 use rust_decimal::Decimal;
 use thiserror::Error;
 use types::{
-    ActiveValidator, Bonds, CommissionRates, GenesisValidator, Slash,
-    SlashType, Slashes, TotalDeltas, Unbond, Unbonds, ValidatorConsensusKeys,
-    ValidatorDeltas, ValidatorSet, ValidatorSetUpdate, ValidatorSets,
+    cubic_slash_rate, ActiveValidator, Bonds, CommissionRates,
+    GenesisValidator, MissedBlocks, QueuedSlash, Redelegation, Redelegations,
+    RewardsAccumulator, Slash, SlashQueue, SlashType, Slashes, TotalDeltas,
+    Unbond, Unbonds, ValidatorConsensusKeys, ValidatorDeltas,
+    ValidatorMetaData, ValidatorSet, ValidatorSetUpdate, ValidatorSets,
     ValidatorState, ValidatorStates,
 };
 
@@ -69,6 +76,11 @@ pub trait PosReadOnly {
 
     /// Read PoS parameters.
     fn read_pos_params(&self) -> Result<PosParams, storage_api::Error>;
+    /// Read PoS raw hash of validator's consensus key.
+    fn read_validator_address_raw_hash(
+        &self,
+        raw_hash: impl AsRef<str>,
+    ) -> Result<Option<Address>, storage_api::Error>;
     /// Read PoS validator's consensus key (used for signing block votes).
     fn read_validator_consensus_key(
         &self,
@@ -102,6 +114,11 @@ pub trait PosReadOnly {
         &self,
         key: &Address,
     ) -> Result<Option<Decimal>, storage_api::Error>;
+    /// Read PoS validator's metadata
+    fn read_validator_metadata(
+        &self,
+        key: &Address,
+    ) -> Result<Option<ValidatorMetaData>, storage_api::Error>;
     /// Read PoS bond (validator self-bond or a delegation).
     fn read_bond(
         &self,
@@ -117,6 +134,13 @@ pub trait PosReadOnly {
     fn read_validator_set(&self) -> Result<ValidatorSets, storage_api::Error>;
     /// Read PoS total deltas for all validators (active and inactive)
     fn read_total_deltas(&self) -> Result<TotalDeltas, storage_api::Error>;
+    /// Read PoS slashes waiting to be processed
+    fn read_slash_queue(&self) -> Result<SlashQueue, storage_api::Error>;
+    /// Read PoS redelegations into a bond.
+    fn read_redelegations(
+        &self,
+        key: &BondId,
+    ) -> Result<Redelegations, storage_api::Error>;
 
     /// Check if the given address is a validator by checking that it has some
     /// state.
@@ -251,6 +275,12 @@ pub trait PosActions: PosReadOnly {
         key: &Address,
         value: Decimal,
     ) -> Result<(), storage_api::Error>;
+    /// Write PoS validator's metadata
+    fn write_validator_metadata(
+        &mut self,
+        key: &Address,
+        value: ValidatorMetaData,
+    ) -> Result<(), storage_api::Error>;
     /// Write PoS validator's total deltas of their bonds (validator self-bonds
     /// and delegations).
     fn write_validator_deltas(
@@ -272,6 +302,12 @@ pub trait PosActions: PosReadOnly {
         key: &BondId,
         value: Unbonds,
     ) -> Result<(), storage_api::Error>;
+    /// Write PoS redelegations into a bond.
+    fn write_redelegations(
+        &mut self,
+        key: &BondId,
+        value: Redelegations,
+    ) -> Result<(), storage_api::Error>;
     /// Write PoS validator set (active and inactive).
     fn write_validator_set(
         &mut self,
@@ -306,7 +342,13 @@ pub trait PosActions: PosReadOnly {
         current_epoch: Epoch,
         commission_rate: Decimal,
         max_commission_rate_change: Decimal,
+        metadata: ValidatorMetaData,
     ) -> Result<(), storage_api::Error> {
+        if !metadata.is_valid() {
+            return Err(
+                BecomeValidatorError::InvalidMetaData(address.clone()).into()
+            );
+        }
         let params = self.read_pos_params()?;
         let mut validator_set = self.read_validator_set()?;
         if self.is_validator(address)? {
@@ -340,6 +382,7 @@ pub trait PosActions: PosReadOnly {
             address,
             max_commission_rate_change,
         )?;
+        self.write_validator_metadata(address, metadata)?;
 
         let commission_rates =
             Epoched::init(commission_rate, current_epoch, &params);
@@ -369,6 +412,17 @@ pub trait PosActions: PosReadOnly {
         }
         let params = self.read_pos_params()?;
         let validator_state = self.read_validator_state(validator)?;
+        if let Some(ValidatorState::Jailed) =
+            validator_state.as_ref().and_then(|state| {
+                state.get_at_offset(
+                    current_epoch,
+                    DynEpochOffset::PipelineLen,
+                    &params,
+                )
+            })
+        {
+            return Err(BondError::JailedValidator(validator.clone()).into());
+        }
         let source = source.unwrap_or(validator);
         let bond_id = BondId {
             source: source.clone(),
@@ -490,6 +544,12 @@ pub trait PosActions: PosReadOnly {
 
         let unbond = self.read_unbond(&bond_id)?;
         let slashes = self.read_validator_slashes(&bond_id.validator)?;
+        let queued_slashes: Slashes = self
+            .read_slash_queue()?
+            .into_iter()
+            .filter(|queued| queued.validator == bond_id.validator)
+            .map(|queued| queued.slash)
+            .collect();
 
         let WithdrawData {
             unbond,
@@ -500,6 +560,7 @@ pub trait PosActions: PosReadOnly {
             &bond_id,
             unbond,
             slashes,
+            queued_slashes,
             current_epoch,
         )?;
 
@@ -529,6 +590,148 @@ pub trait PosActions: PosReadOnly {
         Ok(slashed)
     }
 
+    /// Redelegate tokens delegated by the `owner` from the `src_validator` to
+    /// the `dest_validator` without unbonding them. The tokens are moved to
+    /// the destination validator's stake at the pipeline offset and remain
+    /// slashable for the source validator's infractions committed before
+    /// that. A bond cannot be redelegated while it has incoming redelegations
+    /// that are still slashable.
+    fn redelegate_tokens(
+        &mut self,
+        owner: &Address,
+        src_validator: &Address,
+        dest_validator: &Address,
+        amount: token::Amount,
+        current_epoch: Epoch,
+    ) -> Result<(), storage_api::Error> {
+        if src_validator == dest_validator {
+            return Err(RedelegationError::SameValidator(
+                src_validator.clone(),
+            )
+            .into());
+        }
+        if self.is_validator(owner)? {
+            return Err(RedelegationError::OwnerMustNotBeAValidator(
+                owner.clone(),
+            )
+            .into());
+        }
+        let params = self.read_pos_params()?;
+        let dest_state = self.read_validator_state(dest_validator)?;
+        if let Some(ValidatorState::Jailed) =
+            dest_state.as_ref().and_then(|state| {
+                state.get_at_offset(
+                    current_epoch,
+                    DynEpochOffset::PipelineLen,
+                    &params,
+                )
+            })
+        {
+            return Err(
+                BondError::JailedValidator(dest_validator.clone()).into()
+            );
+        }
+
+        let src_bond_id = BondId {
+            source: owner.clone(),
+            validator: src_validator.clone(),
+        };
+        let mut src_bond = self.read_bond(&src_bond_id)?.ok_or_else(|| {
+            RedelegationError::NoBondFound(src_bond_id.clone())
+        })?;
+        // Redelegations cannot be chained while the tokens redelegated into
+        // the source bond may still be slashed, otherwise they would escape
+        // the slashes of their original validator
+        let has_slashable_redelegation = self
+            .read_redelegations(&src_bond_id)?
+            .iter()
+            .any(|redelegation| {
+                redelegation.is_slashable(&params, current_epoch)
+            });
+        if has_slashable_redelegation {
+            return Err(RedelegationError::SlashableIncomingRedelegation(
+                src_bond_id,
+            )
+            .into());
+        }
+        let mut src_deltas =
+            self.read_validator_deltas(src_validator)?.ok_or_else(|| {
+                UnbondError::ValidatorHasNoBonds(src_validator.clone())
+            })?;
+        let slashes = self.read_validator_slashes(src_validator)?;
+        let mut total_deltas = self.read_total_deltas()?;
+        let mut validator_set = self.read_validator_set()?;
+
+        let RedelegationData { deltas, amount } = redelegate_tokens(
+            &params,
+            &src_bond_id,
+            &mut src_bond,
+            amount,
+            slashes,
+            &mut src_deltas,
+            &mut total_deltas,
+            &mut validator_set,
+            current_epoch,
+        )?;
+
+        let dest_bond_id = BondId {
+            source: owner.clone(),
+            validator: dest_validator.clone(),
+        };
+        let dest_bond = self.read_bond(&dest_bond_id)?;
+        let dest_deltas = self.read_validator_deltas(dest_validator)?;
+        let BondData {
+            bond: dest_bond,
+            validator_deltas: dest_deltas,
+        } = bond_tokens(
+            &params,
+            dest_state,
+            &dest_bond_id,
+            dest_bond,
+            amount,
+            dest_deltas,
+            &mut total_deltas,
+            &mut validator_set,
+            current_epoch,
+        )?;
+
+        let total_bonds = src_bond.get_at_offset(
+            current_epoch,
+            DynEpochOffset::PipelineLen,
+            &params,
+        );
+        match total_bonds {
+            Some(total_bonds) if total_bonds.sum() != 0.into() => {
+                self.write_bond(&src_bond_id, src_bond)?;
+            }
+            _ => {
+                // If the bond is left empty, delete it
+                self.delete_bond(&src_bond_id)?
+            }
+        }
+        self.write_bond(&dest_bond_id, dest_bond)?;
+        self.write_validator_deltas(src_validator, src_deltas)?;
+        self.write_validator_deltas(dest_validator, dest_deltas)?;
+        self.write_total_deltas(total_deltas)?;
+        self.write_validator_set(validator_set)?;
+
+        // Record the redelegation so that the tokens can be slashed for the
+        // source validator's infractions and forget the ones that can no
+        // longer be slashed
+        let mut redelegations = self.read_redelegations(&dest_bond_id)?;
+        redelegations.retain(|redelegation| {
+            redelegation.is_slashable(&params, current_epoch)
+        });
+        redelegations.push(Redelegation {
+            src_validator: src_validator.clone(),
+            epoch: current_epoch,
+            deltas,
+        });
+        self.write_redelegations(&dest_bond_id, redelegations)?;
+
+        Ok(())
+    }
+
     /// Change the commission rate of a validator
     fn change_validator_commission_rate(
         &mut self,
@@ -604,6 +807,118 @@ pub trait PosActions: PosReadOnly {
 
         Ok(())
     }
+
+    /// Change the consensus key of a validator. The new key takes effect at
+    /// the pipeline offset. The raw hash of the previous key is kept, so that
+    /// evidence of misbehaviour signed with it can still be attributed to the
+    /// validator.
+    fn change_consensus_key(
+        &mut self,
+        validator: &Address,
+        consensus_key: &common::PublicKey,
+        current_epoch: Epoch,
+    ) -> Result<(), storage_api::Error> {
+        if !matches!(consensus_key, common::PublicKey::Ed25519(_)) {
+            return Err(ConsensusKeyChangeError::InvalidKeyScheme(
+                validator.clone(),
+            )
+            .into());
+        }
+        let mut consensus_keys = self
+            .read_validator_consensus_key(validator)?
+            .ok_or_else(|| {
+                ConsensusKeyChangeError::NotAValidator(validator.clone())
+            })?;
+        let raw_hash = key::tm_consensus_key_raw_hash(consensus_key);
+        if self.read_validator_address_raw_hash(&raw_hash)?.is_some() {
+            return Err(ConsensusKeyChangeError::KeyAlreadyUsed(
+                validator.clone(),
+            )
+            .into());
+        }
+        let params = self.read_pos_params()?;
+        consensus_keys.set(consensus_key.clone(), current_epoch, &params);
+        self.write_validator_consensus_key(validator, consensus_keys)?;
+        self.write_validator_address_raw_hash(validator, consensus_key)
+    }
+
+    /// Change the metadata of a validator. The given metadata replaces the
+    /// existing record as a whole.
+    fn change_validator_metadata(
+        &mut self,
+        validator: &Address,
+        metadata: ValidatorMetaData,
+    ) -> Result<(), storage_api::Error> {
+        if !self.is_validator(validator)? {
+            return Err(
+                MetaDataChangeError::NotAValidator(validator.clone()).into()
+            );
+        }
+        if !metadata.is_valid() {
+            return Err(MetaDataChangeError::InvalidMetaData(
+                validator.clone(),
+            )
+            .into());
+        }
+        self.write_validator_metadata(validator, metadata)
+    }
+
+    /// Unjail a validator that has been jailed for missing too many blocks.
+    /// The validator becomes a `Candidate` again and re-enters the validator
+    /// set at the pipeline offset.
+    fn unjail_validator(
+        &mut self,
+        validator: &Address,
+        current_epoch: Epoch,
+    ) -> Result<(), storage_api::Error> {
+        let params = self.read_pos_params()?;
+        let mut state = self
+            .read_validator_state(validator)?
+            .ok_or_else(|| UnjailError::NotAValidator(validator.clone()))?;
+        // The validator must have already been jailed in the current epoch
+        // and must not have been unjailed yet
+        let is_jailed =
+            matches!(state.get(current_epoch), Some(ValidatorState::Jailed))
+                && matches!(
+                    state.get_at_offset(
+                        current_epoch,
+                        DynEpochOffset::PipelineLen,
+                        &params
+                    ),
+                    Some(ValidatorState::Jailed)
+                );
+        if !is_jailed {
+            return Err(UnjailError::NotJailed(validator.clone()).into());
+        }
+        let validator_deltas = self.read_validator_deltas(validator)?;
+        let mut validator_set = self.read_validator_set()?;
+
+        state.set(ValidatorState::Candidate, current_epoch, &params);
+        validator_set.update_from_offset(
+            |validator_set, epoch| {
+                let bonded_stake = validator_deltas
+                    .as_ref()
+                    .and_then(|deltas| deltas.get(epoch))
+                    .unwrap_or_default();
+                insert_validator(
+                    &params,
+                    validator_set,
+                    WeightedValidator {
+                        bonded_stake: u64::try_from(bonded_stake)
+                            .unwrap_or_default(),
+                        address: validator.clone(),
+                    },
+                );
+            },
+            current_epoch,
+            DynEpochOffset::PipelineLen,
+            &params,
+        );
+        self.write_validator_state(validator, state)?;
+        self.write_validator_set(validator_set)?;
+
+        Ok(())
+    }
 }
 
 /// PoS system base trait for system initialization on genesis block, updating
@@ -647,6 +962,26 @@ pub trait PosBase {
     fn read_validator_set(&self) -> ValidatorSets;
     /// Read PoS total deltas of all validators (active and inactive).
     fn read_total_deltas(&self) -> TotalDeltas;
+    /// Read all PoS bonds (validator self-bonds and delegations).
+    fn read_bonds(&self) -> Vec<(BondId, Bonds)>;
+    /// Read PoS bond (validator self-bond or a delegation).
+    fn read_bond(&self, key: &BondId) -> Option<Bonds>;
+    /// Read all PoS redelegations keyed by the bonds they were redelegated
+    /// into.
+    fn read_redelegations(&self) -> Vec<(BondId, Redelegations)>;
+    /// Read the address of the proposer of the last committed block.
+    fn read_last_block_proposer(&self) -> Option<Address>;
+    /// Read the block rewards accumulated in the current epoch.
+    fn read_rewards_accumulator(&self) -> RewardsAccumulator;
+    /// Read PoS validator's missed blocks in its liveness window
+    fn read_validator_missed_blocks(
+        &self,
+        key: &Address,
+    ) -> Option<MissedBlocks>;
+    /// Read PoS slashes waiting to be processed
+    fn read_slash_queue(&self) -> SlashQueue;
+    /// Read PoS recently processed slashes
+    fn read_processed_slashes(&self) -> SlashQueue;
 
     /// Write PoS parameters.
     fn write_pos_params(&mut self, params: &PosParams);
@@ -687,12 +1022,30 @@ pub trait PosBase {
     fn write_validator_slash(&mut self, validator: &Address, value: Slash);
     /// Write PoS bond (validator self-bond or a delegation).
     fn write_bond(&mut self, key: &BondId, value: &Bonds);
+    /// Write PoS redelegations into a bond.
+    fn write_redelegations(&mut self, key: &BondId, value: &Redelegations);
     /// Write PoS validator set (active and inactive).
     fn write_validator_set(&mut self, value: &ValidatorSets);
     /// Write total deltas in PoS for all validators (active and inactive)
     fn write_total_deltas(&mut self, value: &TotalDeltas);
+    /// Write the address of the proposer of the last committed block.
+    fn write_last_block_proposer(&mut self, address: &Address);
+    /// Write the block rewards accumulated in the current epoch.
+    fn write_rewards_accumulator(&mut self, value: &RewardsAccumulator);
+    /// Write PoS validator's missed blocks in its liveness window
+    fn write_validator_missed_blocks(
+        &mut self,
+        key: &Address,
+        value: &MissedBlocks,
+    );
+    /// Delete PoS validator's missed blocks in its liveness window
+    fn delete_validator_missed_blocks(&mut self, key: &Address);
+    /// Write PoS slashes waiting to be processed
+    fn write_slash_queue(&mut self, value: &SlashQueue);
+    /// Write PoS recently processed slashes
+    fn write_processed_slashes(&mut self, value: &SlashQueue);
     /// Credit tokens to the `target` account. This should only be used at
-    /// genesis.
+    /// genesis and for minting of the PoS rewards.
     fn credit_tokens(
         &mut self,
         token: &Address,
@@ -789,6 +1142,28 @@ pub trait PosBase {
         let prev_validators =
             previous_epoch.and_then(|epoch| validators.get(epoch));
 
+        let current_consensus_key = |address: &Address| -> common::PublicKey {
+            self.read_validator_consensus_key(address)
+                .unwrap()
+                .get(current_epoch)
+                .unwrap()
+                .clone()
+        };
+        // The consensus key that Tendermint knows the validator by, if it's
+        // been set before the current epoch
+        let prev_consensus_key =
+            |address: &Address| -> Option<common::PublicKey> {
+                let prev_epoch = previous_epoch?;
+                self.read_validator_consensus_key(address)?
+                    .get(prev_epoch)
+                    .cloned()
+            };
+        let is_key_rotated = |address: &Address| -> bool {
+            prev_consensus_key(address)
+                .map(|prev_key| prev_key != current_consensus_key(address))
+                .unwrap_or_default()
+        };
+
         // If the validator has never been active before and it doesn't have
         // more than 0 voting power, we should not tell Tendermint to
         // update it until it does. Tendermint uses 0 voting power as a
@@ -806,7 +1181,9 @@ pub trait PosBase {
                 if let (Some(prev_epoch), Some(prev_validators)) =
                     (previous_epoch, prev_validators)
                 {
-                    if prev_validators.active.contains(validator) {
+                    if prev_validators.active.contains(validator)
+                        && !is_key_rotated(&validator.address)
+                    {
                         println!(
                             "skipping validator update, still the same {}",
                             validator.address
@@ -832,14 +1209,8 @@ pub trait PosBase {
                         }
                     }
                 }
-                let consensus_key = self
-                    .read_validator_consensus_key(&validator.address)
-                    .unwrap()
-                    .get(current_epoch)
-                    .unwrap()
-                    .clone();
                 Some(ValidatorSetUpdate::Active(ActiveValidator {
-                    consensus_key,
+                    consensus_key: current_consensus_key(&validator.address),
                     bonded_stake: validator.bonded_stake,
                 }))
             },
@@ -870,37 +1241,511 @@ pub trait PosBase {
                         }
                     }
                 }
-                let consensus_key = self
-                    .read_validator_consensus_key(&validator.address)
-                    .unwrap()
-                    .get(current_epoch)
-                    .unwrap()
-                    .clone();
+                let consensus_key = prev_consensus_key(&validator.address)
+                    .unwrap_or_else(|| {
+                        current_consensus_key(&validator.address)
+                    });
                 Some(ValidatorSetUpdate::Deactivated(consensus_key))
             },
         );
-        active_validators.chain(inactive_validators).for_each(f)
+        // Validators that were active in the previous epoch, but are not in
+        // the current validator set anymore, have been jailed and must be
+        // removed from Tendermint's validator set
+        let jailed_validators = prev_validators
+            .iter()
+            .flat_map(|prev_validators| prev_validators.active.iter())
+            .filter(|validator: &&WeightedValidator| {
+                !cur_validators
+                    .active
+                    .iter()
+                    .chain(cur_validators.inactive.iter())
+                    .any(|cur| cur.address == validator.address)
+            })
+            .map(|validator: &WeightedValidator| {
+                let consensus_key = prev_consensus_key(&validator.address)
+                    .unwrap_or_else(|| {
+                        current_consensus_key(&validator.address)
+                    });
+                ValidatorSetUpdate::Deactivated(consensus_key)
+            });
+        // Validators that are active in both epochs, but whose consensus key
+        // has been rotated, must have their previous key removed from
+        // Tendermint's validator set. The new key is added with the active
+        // validators above.
+        let rotated_keys = prev_validators
+            .iter()
+            .flat_map(|prev_validators| prev_validators.active.iter())
+            .filter(|validator: &&WeightedValidator| {
+                cur_validators
+                    .active
+                    .iter()
+                    .any(|cur| cur.address == validator.address)
+            })
+            .filter_map(|validator: &WeightedValidator| {
+                let prev_key = prev_consensus_key(&validator.address)?;
+                (prev_key != current_consensus_key(&validator.address))
+                    .then_some(ValidatorSetUpdate::Deactivated(prev_key))
+            });
+        active_validators
+            .chain(inactive_validators)
+            .chain(jailed_validators)
+            .chain(rotated_keys)
+            .for_each(f)
     }
 
-    /// Apply a slash to a byzantine validator for the given evidence.
-    fn slash(
+    /// Accumulate the block rewards earned by the active validators of the
+    /// given `epoch` for a block proposed by the `proposer` and signed by the
+    /// `signers`. This should be called with the proposer and the signers of
+    /// the last committed block, in the epoch in which that block was
+    /// committed.
+    fn log_block_rewards(
+        &mut self,
+        epoch: Epoch,
+        proposer: &Address,
+        signers: &HashSet<Address>,
+    ) -> Result<(), RewardsError> {
+        let params = self.read_pos_params();
+        let validator_set = self.read_validator_set();
+        let active_validators = match validator_set.get(epoch) {
+            Some(validator_set) => &validator_set.active,
+            None => return Ok(()),
+        };
+
+        // Find the total stake of the active validator set and the stake of
+        // the validators who signed the block
+        let mut total_stake: u64 = 0;
+        let mut signing_stake: u64 = 0;
+        for validator in active_validators.iter() {
+            total_stake += validator.bonded_stake;
+            if signers.contains(&validator.address) {
+                signing_stake += validator.bonded_stake;
+            }
+        }
+        let coeffs = PosRewardsCalculator {
+            proposer_reward: params.block_proposer_reward,
+            signer_reward: params.block_vote_reward,
+            signing_stake,
+            total_stake,
+        }
+        .get_reward_coeffs()?;
+
+        // Add the fraction of the block rewards earned by each validator to
+        // its accumulated rewards
+        let total_stake = Decimal::from(total_stake);
+        let signing_stake = Decimal::from(signing_stake);
+        let mut accumulator = self.read_rewards_accumulator();
+        for WeightedValidator {
+            bonded_stake,
+            address,
+        } in active_validators.iter()
+        {
+            let stake = Decimal::from(*bonded_stake);
+            let mut rewards_frac =
+                coeffs.active_val_coeff * stake / total_stake;
+            if address == proposer {
+                rewards_frac += coeffs.proposer_coeff;
+            }
+            if signers.contains(address) {
+                rewards_frac += coeffs.signer_coeff * stake / signing_stake;
+            }
+            *accumulator.validators.entry(address.clone()).or_default() +=
+                rewards_frac;
+        }
+        accumulator.num_blocks += 1;
+        self.write_rewards_accumulator(&accumulator);
+        Ok(())
+    }
+
+    /// Distribute the given `rewards` for the `rewarded_epoch` between the
+    /// validators according to the block rewards they've accumulated in it.
+    /// Each validator's rewards are split between its commission, credited to
+    /// its self-bond, and its bonds proportionally to their amounts in the
+    /// `rewarded_epoch`. The rewards are credited to bonds at the pipeline
+    /// offset from the `current_epoch` and the distributed tokens are minted
+    /// to the PoS account. The validators that are jailed or inactive before
+    /// the pipeline offset don't receive any rewards. The accumulated rewards
+    /// are reset first, so that they never carry over to the next epoch. All
+    /// the credits are then computed before anything is written, so on error
+    /// the rewards of the epoch are dropped and the rest of the storage is
+    /// left unchanged. Returns the amount of distributed tokens.
+    fn distribute_rewards(
+        &mut self,
+        current_epoch: Epoch,
+        rewarded_epoch: Epoch,
+        rewards: token::Amount,
+    ) -> Result<token::Amount, RewardsError> {
+        let params = self.read_pos_params();
+        let accumulator = self.read_rewards_accumulator();
+        self.write_rewards_accumulator(&RewardsAccumulator::default());
+        if accumulator.num_blocks == 0 || rewards == token::Amount::default() {
+            return Ok(token::Amount::default());
+        }
+        let num_blocks = Decimal::from(accumulator.num_blocks);
+
+        // Group the bonds of the rewarded validators by their validator
+        let mut validators_bonds: HashMap<Address, HashMap<BondId, Bonds>> =
+            HashMap::default();
+        for (bond_id, bonds) in self.read_bonds() {
+            if accumulator.validators.contains_key(&bond_id.validator) {
+                validators_bonds
+                    .entry(bond_id.validator.clone())
+                    .or_default()
+                    .insert(bond_id, bonds);
+            }
+        }
+
+        let mut validator_set = self.read_validator_set();
+        let mut total_deltas = self.read_total_deltas();
+        let mut total_distributed = token::Amount::default();
+        let mut credited_bonds: Vec<(BondId, Bonds)> = vec![];
+        let mut credited_deltas: Vec<(Address, ValidatorDeltas)> = vec![];
+        for (validator, rewards_frac) in accumulator.validators {
+            let validator_rewards = token::Amount::from(decimal_mult_u64(
+                rewards_frac / num_blocks,
+                rewards.into(),
+            ));
+            if validator_rewards == token::Amount::default() {
+                continue;
+            }
+            // Jailed and inactive validators cannot receive new bonds
+            let validator_state = self.read_validator_state(&validator);
+            let is_rewardable =
+                validator_state.as_ref().map_or(false, |state| {
+                    current_epoch
+                        .iter_range(OffsetPipelineLen::value(&params) + 1)
+                        .all(|epoch| {
+                            !matches!(
+                                state.get(epoch),
+                                Some(ValidatorState::Jailed)
+                                    | Some(ValidatorState::Inactive)
+                            )
+                        })
+                });
+            if !is_rewardable {
+                continue;
+            }
+            let commission_rate = self
+                .read_validator_commission_rate(&validator)
+                .get(rewarded_epoch)
+                .copied()
+                .unwrap_or_default();
+            let mut bonds =
+                validators_bonds.remove(&validator).unwrap_or_default();
+            let bond_amounts: Vec<(BondId, token::Amount)> = bonds
+                .iter()
+                .map(|(bond_id, bond)| {
+                    let amount = bond
+                        .get(rewarded_epoch)
+                        .map(|bond| bond.sum())
+                        .unwrap_or_default();
+                    (bond_id.clone(), amount)
+                })
+                .collect();
+            let ValidatorRewards {
+                commission,
+                bonds: bonds_rewards,
+            } = split_validator_rewards(
+                validator_rewards,
+                commission_rate,
+                &bond_amounts,
+            );
+
+            // The commission is credited to the validator's self-bond
+            let mut credits: BTreeMap<BondId, token::Amount> =
+                bonds_rewards.into_iter().collect();
+            let self_bond_id = BondId {
+                source: validator.clone(),
+                validator: validator.clone(),
+            };
+            *credits.entry(self_bond_id).or_default() += commission;
+
+            let mut validator_deltas = self.read_validator_deltas(&validator);
+            for (bond_id, amount) in credits {
+                if amount == token::Amount::default() {
+                    continue;
+                }
+                let BondData {
+                    bond,
+                    validator_deltas: deltas,
+                } = bond_tokens(
+                    &params,
+                    validator_state.clone(),
+                    &bond_id,
+                    bonds.remove(&bond_id),
+                    amount,
+                    validator_deltas,
+                    &mut total_deltas,
+                    &mut validator_set,
+                    current_epoch,
+                )?;
+                validator_deltas = Some(deltas);
+                credited_bonds.push((bond_id, bond));
+                total_distributed += amount;
+            }
+            if let Some(deltas) = validator_deltas {
+                credited_deltas.push((validator, deltas));
+            }
+        }
+
+        // Write the credits only once they've all been computed
+        for (bond_id, bond) in credited_bonds {
+            self.write_bond(&bond_id, &bond);
+        }
+        for (validator, deltas) in credited_deltas {
+            self.write_validator_deltas(&validator, &deltas);
+        }
+        self.write_validator_set(&validator_set);
+        self.write_total_deltas(&total_deltas);
+
+        // Mint the distributed rewards to the PoS account
+        let staking_token = self.staking_token_address();
+        self.credit_tokens(
+            &staking_token,
+            &Self::POS_ADDRESS,
+            total_distributed,
+        );
+        Ok(total_distributed)
+    }
+
+    /// Record the active validators of the `current_epoch` that didn't sign
+    /// the block at the given `height` as having missed it. Any validator
+    /// that has missed more blocks within its liveness window than allowed
+    /// by the PoS parameters is slashed for liveness and jailed. Returns the
+    /// addresses of the jailed validators.
+    fn record_missed_blocks(
+        &mut self,
+        current_epoch: Epoch,
+        height: u64,
+        non_signers: &HashSet<Address>,
+    ) -> Result<Vec<Address>, SlashError> {
+        let params = self.read_pos_params();
+        let max_missed = decimal_mult_u64(
+            params.liveness_max_missed_rate,
+            params.liveness_window_len,
+        );
+        let mut jailed = vec![];
+        for validator in non_signers {
+            // Skip validators that are already being jailed
+            let is_jailed = self
+                .read_validator_state(validator)
+                .and_then(|state| {
+                    state
+                        .get_at_offset(
+                            current_epoch,
+                            DynEpochOffset::PipelineLen,
+                            &params,
+                        )
+                        .copied()
+                })
+                .map_or(false, |state| state == ValidatorState::Jailed);
+            if is_jailed {
+                continue;
+            }
+            // Start a new window once the previous one has elapsed
+            let mut missed_blocks = self
+                .read_validator_missed_blocks(validator)
+                .filter(|missed| {
+                    height < missed.window_start + params.liveness_window_len
+                })
+                .unwrap_or(MissedBlocks {
+                    window_start: height,
+                    count: 0,
+                });
+            missed_blocks.count += 1;
+            if missed_blocks.count > max_missed {
+                self.delete_validator_missed_blocks(validator);
+                jailed.push(validator.clone());
+            } else {
+                self.write_validator_missed_blocks(validator, &missed_blocks);
+            }
+        }
+
+        // Liveness faults aren't correlated, so they're slashed right away at
+        // a fixed rate
+        for validator in &jailed {
+            let slash = Slash {
+                epoch: current_epoch,
+                block_height: height,
+                r#type: SlashType::Liveness,
+                rate: SlashType::Liveness.get_slash_rate(&params),
+            };
+            let stake = self
+                .read_validator_deltas(validator)
+                .and_then(|deltas| deltas.get(current_epoch))
+                .unwrap_or_default();
+            let stake = u64::try_from(stake).map_err(|_err| {
+                SlashError::NegativeStake(stake, validator.clone())
+            })?;
+            self.apply_slash(&params, current_epoch, validator, slash, stake)?;
+            self.jail_validator(&params, current_epoch, validator);
+        }
+        Ok(jailed)
+    }
+
+    /// Jail a validator at the pipeline offset from the `current_epoch`. The
+    /// validator is removed from the validator set and if it leaves an empty
+    /// slot in the active set, the inactive validator with the most stake is
+    /// promoted to it.
+    fn jail_validator(
         &mut self,
         params: &PosParams,
         current_epoch: Epoch,
+        validator: &Address,
+    ) {
+        if let Some(mut state) = self.read_validator_state(validator) {
+            state.set(ValidatorState::Jailed, current_epoch, params);
+            self.write_validator_state(validator, &state);
+        }
+        let mut validator_set = self.read_validator_set();
+        validator_set.update_from_offset(
+            |validator_set, _epoch| remove_validator(validator_set, validator),
+            current_epoch,
+            DynEpochOffset::PipelineLen,
+            params,
+        );
+        self.write_validator_set(&validator_set);
+    }
+
+    /// Queue a slash of a byzantine validator for the given evidence. The
+    /// slash is applied by [`PosBase::process_slashes`] once the cubic
+    /// slashing window around the evidence epoch has passed.
+    fn slash(
+        &mut self,
+        params: &PosParams,
         evidence_epoch: Epoch,
         evidence_block_height: impl Into<u64>,
         slash_type: SlashType,
         validator: &Address,
     ) -> Result<(), SlashError> {
-        let evidence_epoch = evidence_epoch;
         let rate = slash_type.get_slash_rate(params);
-        let validator_slash = Slash {
+        let slash = Slash {
             epoch: evidence_epoch,
             r#type: slash_type,
             rate,
             block_height: evidence_block_height.into(),
         };
 
+        let validator_stake = self
+            .read_validator_deltas(validator)
+            .ok_or_else(|| {
+                SlashError::ValidatorHasNoTotalDeltas(validator.clone())
+            })?
+            .get(evidence_epoch)
+            .unwrap_or_default();
+        let validator_stake =
+            u64::try_from(validator_stake).map_err(|_err| {
+                SlashError::NegativeStake(validator_stake, validator.clone())
+            })?;
+        let total_stake = self
+            .read_total_deltas()
+            .get(evidence_epoch)
+            .unwrap_or_default();
+        let total_stake = u64::try_from(total_stake)
+            .map_err(|_err| SlashError::InvalidSlashChange(total_stake))?;
+
+        let mut queue = self.read_slash_queue();
+        queue.push(QueuedSlash {
+            validator: validator.clone(),
+            slash,
+            validator_stake,
+            total_stake,
+        });
+        self.write_slash_queue(&queue);
+        Ok(())
+    }
+
+    /// Process the queued slashes whose cubic slashing window has passed
+    /// before the `current_epoch`. The rate of each slash is computed from
+    /// the stake of all the validators slashed for infractions within the
+    /// window around its epoch. Returns the applied slashes.
+    fn process_slashes(
+        &mut self,
+        current_epoch: Epoch,
+    ) -> Result<Vec<QueuedSlash>, SlashError> {
+        let params = self.read_pos_params();
+        let window = params.cubic_slashing_window_length;
+        let (ready, pending): (SlashQueue, SlashQueue) = self
+            .read_slash_queue()
+            .into_iter()
+            .partition(|queued| queued.slash.epoch + window < current_epoch);
+        if ready.is_empty() {
+            return Ok(vec![]);
+        }
+        // Forget the processed slashes that can no longer fall into the window
+        // of any evidence that's still accepted
+        let mut processed: SlashQueue = self
+            .read_processed_slashes()
+            .into_iter()
+            .filter(|queued| {
+                queued.slash.epoch + window + params.unbonding_len
+                    >= current_epoch
+            })
+            .collect();
+
+        let rates: Vec<Decimal> = ready
+            .iter()
+            .map(|queued| {
+                let epoch = queued.slash.epoch;
+                let window_start = epoch.sub_or_default(Epoch(window));
+                let window_end = epoch + window;
+                // Count each validator's stake only once, even if it
+                // committed more infractions within the window
+                let mut slashed_stakes: HashMap<&Address, u64> =
+                    HashMap::default();
+                for other in pending.iter().chain(&ready).chain(&processed) {
+                    if other.slash.epoch >= window_start
+                        && other.slash.epoch <= window_end
+                    {
+                        let stake =
+                            slashed_stakes.entry(&other.validator).or_default();
+                        *stake = (*stake).max(other.validator_stake);
+                    }
+                }
+                let slashed_stake: u64 = slashed_stakes.values().sum();
+                let slashed_stake_frac = if queued.total_stake == 0 {
+                    Decimal::ONE
+                } else {
+                    (Decimal::from(slashed_stake)
+                        / Decimal::from(queued.total_stake))
+                    .min(Decimal::ONE)
+                };
+                cubic_slash_rate(
+                    queued.slash.r#type.get_slash_rate(&params),
+                    slashed_stake_frac,
+                )
+            })
+            .collect();
+
+        let mut applied = vec![];
+        for (mut queued, rate) in ready.into_iter().zip(rates) {
+            queued.slash.rate = rate;
+            self.apply_slash(
+                &params,
+                current_epoch,
+                &queued.validator,
+                queued.slash.clone(),
+                queued.validator_stake,
+            )?;
+            processed.push(queued.clone());
+            applied.push(queued);
+        }
+        self.write_slash_queue(&pending);
+        self.write_processed_slashes(&processed);
+        Ok(applied)
+    }
+
+    /// Apply a slash to a byzantine validator. The slash is applied to the
+    /// validator's current stake at the pipeline offset, but to no more than
+    /// its `infraction_stake` as the tokens bonded after the infraction are
+    /// not slashable.
+    fn apply_slash(
+        &mut self,
+        params: &PosParams,
+        current_epoch: Epoch,
+        validator: &Address,
+        validator_slash: Slash,
+        infraction_stake: u64,
+    ) -> Result<(), SlashError> {
         let mut deltas =
             self.read_validator_deltas(validator).ok_or_else(|| {
                 SlashError::ValidatorHasNoTotalDeltas(validator.clone())
@@ -913,6 +1758,7 @@ pub trait PosBase {
             current_epoch,
             validator,
             &validator_slash,
+            infraction_stake,
             &mut deltas,
             &mut validator_set,
             &mut total_deltas,
@@ -923,7 +1769,7 @@ pub trait PosBase {
         let slashed_amount = token::Amount::from(slashed_amount);
 
         self.write_validator_deltas(validator, &deltas);
-        self.write_validator_slash(validator, validator_slash);
+        self.write_validator_slash(validator, validator_slash.clone());
         self.write_validator_set(&validator_set);
         self.write_total_deltas(&total_deltas);
 
@@ -936,6 +1782,122 @@ pub trait PosBase {
             &Self::POS_ADDRESS,
             &Self::POS_SLASH_POOL_ADDRESS,
         );
+
+        self.slash_redelegations(
+            params,
+            current_epoch,
+            validator,
+            &validator_slash,
+        )
+    }
+
+    /// Slash the tokens that have been redelegated away from a byzantine
+    /// validator after the infraction. The slash is applied to the bonds into
+    /// which they were redelegated at the pipeline offset, but not to the
+    /// tokens that have already been unbonded from them.
+    fn slash_redelegations(
+        &mut self,
+        params: &PosParams,
+        current_epoch: Epoch,
+        src_validator: &Address,
+        src_slash: &Slash,
+    ) -> Result<(), SlashError> {
+        let update_offset = DynEpochOffset::PipelineLen;
+        let mut total_slashed = token::Amount::default();
+        for (bond_id, mut redelegations) in self.read_redelegations() {
+            let mut slashed = token::Amount::default();
+            for redelegation in
+                redelegations.iter_mut().filter(|redelegation| {
+                    &redelegation.src_validator == src_validator
+                })
+            {
+                for ((epoch_start, epoch_end), delta) in
+                    redelegation.deltas.iter_mut()
+                {
+                    if src_slash.epoch >= *epoch_start
+                        && src_slash.epoch <= *epoch_end
+                    {
+                        let raw_delta: u64 = (*delta).into();
+                        let slashed_delta = token::Amount::from(
+                            decimal_mult_u64(src_slash.rate, raw_delta),
+                        );
+                        *delta -= slashed_delta;
+                        slashed += slashed_delta;
+                    }
+                }
+            }
+            if slashed == token::Amount::default() {
+                continue;
+            }
+            self.write_redelegations(&bond_id, &redelegations);
+
+            let mut bond = match self.read_bond(&bond_id) {
+                Some(bond) => bond,
+                None => continue,
+            };
+            let bonded = bond
+                .get_at_offset(current_epoch, update_offset, params)
+                .unwrap_or_default()
+                .sum();
+            let slashed = if slashed > bonded { bonded } else { slashed };
+            bond.add_at_offset(
+                Bond {
+                    pos_deltas: Default::default(),
+                    neg_deltas: slashed,
+                },
+                current_epoch,
+                update_offset,
+                params,
+            );
+            self.write_bond(&bond_id, &bond);
+
+            let mut deltas = self
+                .read_validator_deltas(&bond_id.validator)
+                .ok_or_else(|| {
+                    SlashError::ValidatorHasNoTotalDeltas(
+                        bond_id.validator.clone(),
+                    )
+                })?;
+            let mut validator_set = self.read_validator_set();
+            let mut total_deltas = self.read_total_deltas();
+            // Update validator set. This has to be done before we update the
+            // `validator_deltas`, because we need to look-up the validator
+            // with its voting power before the change.
+            let token_change = -token::Change::from(slashed);
+            update_validator_set(
+                params,
+                &bond_id.validator,
+                token_change,
+                update_offset,
+                &mut validator_set,
+                Some(&deltas),
+                current_epoch,
+            );
+            deltas.add_at_offset(
+                token_change,
+                current_epoch,
+                update_offset,
+                params,
+            );
+            total_deltas.add_at_offset(
+                token_change,
+                current_epoch,
+                update_offset,
+                params,
+            );
+            self.write_validator_deltas(&bond_id.validator, &deltas);
+            self.write_validator_set(&validator_set);
+            self.write_total_deltas(&total_deltas);
+            total_slashed += slashed;
+        }
+
+        // Transfer the slashed tokens to the PoS slash pool
+        self.transfer(
+            &self.staking_token_address(),
+            total_slashed,
+            &Self::POS_ADDRESS,
+            &Self::POS_SLASH_POOL_ADDRESS,
+        );
         Ok(())
     }
 }
@@ -952,6 +1914,8 @@ pub enum GenesisError {
 pub enum BecomeValidatorError {
     #[error("The given address {0} is already a validator")]
     AlreadyValidator(Address),
+    #[error("Invalid metadata for validator {0}")]
+    InvalidMetaData(Address),
 }
 
 #[allow(missing_docs)]
@@ -966,6 +1930,8 @@ pub enum BondError {
     SourceMustNotBeAValidator(Address),
     #[error("The given validator address {0} is inactive")]
     InactiveValidator(Address),
+    #[error("The given validator address {0} is jailed")]
+    JailedValidator(Address),
     #[error("Voting power overflow: {0}")]
     VotingPowerOverflow(TryFromIntError),
 }
@@ -987,6 +1953,30 @@ pub enum UnbondError {
     VotingPowerOverflow(TryFromIntError),
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum RedelegationError {
+    #[error("No bond could be found for {0}")]
+    NoBondFound(BondId),
+    #[error(
+        "Trying to redelegate more tokens ({0}) than the amount bonded ({1})"
+    )]
+    AmountGreaterThanBond(token::Amount, token::Amount),
+    #[error("The source and destination validator {0} must be different")]
+    SameValidator(Address),
+    #[error(
+        "The given owner address {0} is a validator address. Validators may \
+         not redelegate their self-bonds."
+    )]
+    OwnerMustNotBeAValidator(Address),
+    #[error(
+        "The bond {0} has tokens redelegated into it that may still be \
+         slashed for the infractions of their source validator. They cannot \
+         be redelegated again until then."
+    )]
+    SlashableIncomingRedelegation(BondId),
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum WithdrawError {
@@ -994,6 +1984,11 @@ pub enum WithdrawError {
     NoUnbondFound(BondId),
     #[error("No unbond may be withdrawn yet for {0}")]
     NoWithdrawableUnbond(BondId),
+    #[error(
+        "The unbond for {0} cannot be withdrawn until the queued slashes of \
+         its validator are processed"
+    )]
+    QueuedSlash(BondId),
 }
 
 #[allow(missing_docs)]
@@ -1011,6 +2006,15 @@ pub enum SlashError {
     NegativeStake(i128, Address),
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum UnjailError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("The validator {0} is not jailed")]
+    NotJailed(Address),
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum CommissionRateChangeError {
@@ -1030,6 +2034,26 @@ pub enum CommissionRateChangeError {
     CannotRead(Address),
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ConsensusKeyChangeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("The consensus key of validator {0} must be an ed25519 key")]
+    InvalidKeyScheme(Address),
+    #[error("The new consensus key of validator {0} has already been used")]
+    KeyAlreadyUsed(Address),
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum MetaDataChangeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("Invalid metadata for validator {0}")]
+    InvalidMetaData(Address),
+}
+
 struct GenesisData<Validators>
 where
     Validators: Iterator<Item = Result<GenesisValidatorData, GenesisError>>,
@@ -1158,6 +2182,7 @@ fn slash(
     current_epoch: Epoch,
     validator: &Address,
     slash: &Slash,
+    infraction_stake: u64,
     validator_deltas: &mut ValidatorDeltas,
     validator_set: &mut ValidatorSets,
     total_deltas: &mut TotalDeltas,
@@ -1171,8 +2196,9 @@ fn slash(
         ));
     }
     let raw_current_stake: i128 = current_stake;
+    let slashable_stake = raw_current_stake.min(infraction_stake.into());
     let slashed_amount: token::Change =
-        decimal_mult_i128(slash.rate, raw_current_stake);
+        decimal_mult_i128(slash.rate, slashable_stake);
     let token_change = -slashed_amount;
 
     // Apply slash at pipeline offset
@@ -1412,53 +2438,75 @@ fn unbond_tokens(
     };
 
     let update_offset = DynEpochOffset::UnbondingLen;
-    let mut to_unbond = amount;
-    let to_unbond = &mut to_unbond;
-    let mut slashed_amount = token::Amount::default();
-    // Decrement the bond deltas starting from the rightmost value (a bond in a
-    // future-most epoch) until whole amount is decremented
+    let unbonded =
+        remove_from_bond(params, bond, amount, update_offset, current_epoch);
+    // Calculate how much the unbonded deltas would be after slashing
+    let slashed_amount = unbonded.iter().fold(
+        token::Amount::default(),
+        |acc, ((epoch_start, _epoch_end), delta)| {
+            acc + apply_slashes(*delta, *epoch_start, &slashes)
+        },
+    );
+    unbond.add(Unbond { deltas: unbonded }, current_epoch, params);
+
+    // Update validator set. This has to be done before we update the
+    // `validator_deltas`, because we need to look-up the validator with
+    // its voting power before the change.
+    let token_change = -token::Change::from(slashed_amount);
+    update_validator_set(
+        params,
+        &bond_id.validator,
+        token_change,
+        update_offset,
+        validator_set,
+        Some(validator_deltas),
+        current_epoch,
+    );
+
+    // Update validator's deltas
+    validator_deltas.add(token_change, current_epoch, params);
+
+    // Update the total deltas of all validators.
+    // TODO: provide some error handling that was maybe here before?
+    total_deltas.add(token_change, current_epoch, params);
+
+    Ok(UnbondData { unbond })
+}
+
+/// Remove the `amount` of tokens from a bond at the `update_offset`. The bond
+/// deltas are decremented starting from the rightmost value (a bond in a
+/// future-most epoch) until the whole amount is decremented. Returns the
+/// removed deltas keyed by a pair of the epoch of the bond and the last epoch
+/// in which the removed tokens are still bonded.
+fn remove_from_bond(
+    params: &PosParams,
+    bond: &mut Bonds,
+    amount: token::Amount,
+    update_offset: DynEpochOffset,
+    current_epoch: Epoch,
+) -> HashMap<(Epoch, Epoch), token::Amount> {
+    let bond_end = current_epoch + update_offset.value(params) - 1;
+    let mut removed: HashMap<(Epoch, Epoch), token::Amount> =
+        HashMap::default();
+    let mut to_remove = amount;
+    let to_remove = &mut to_remove;
     bond.rev_while(
         |bonds, _epoch| {
             for (epoch_start, bond_delta) in bonds.pos_deltas.iter() {
-                if *to_unbond == 0.into() {
+                if *to_remove == 0.into() {
                     return true;
                 }
-                let mut unbonded = HashMap::default();
-                let unbond_end =
-                    current_epoch + update_offset.value(params) - 1;
-                // We need to accumulate the slashed delta for multiple
-                // slashes applicable to a bond, where
-                // each slash should be calculated from
-                // the delta reduced by the previous slash.
-                let applied_delta = if *to_unbond > *bond_delta {
-                    unbonded.insert((*epoch_start, unbond_end), *bond_delta);
-                    *to_unbond -= *bond_delta;
+                let removed_delta = if *to_remove > *bond_delta {
                     *bond_delta
                 } else {
-                    unbonded.insert((*epoch_start, unbond_end), *to_unbond);
-                    let applied_delta = *to_unbond;
-                    *to_unbond = 0.into();
-                    applied_delta
+                    *to_remove
                 };
-                // Calculate how much the bond delta would be after slashing
-                let mut slashed_bond_delta = applied_delta;
-                for slash in &slashes {
-                    if slash.epoch >= *epoch_start {
-                        let raw_delta: u64 = slashed_bond_delta.into();
-                        let raw_slashed_delta =
-                            decimal_mult_u64(slash.rate, raw_delta);
-                        let slashed_delta =
-                            token::Amount::from(raw_slashed_delta);
-                        slashed_bond_delta -= slashed_delta;
-                    }
-                }
-                slashed_amount += slashed_bond_delta;
-
-                // For each decremented bond value write a new unbond
-                unbond.add(Unbond { deltas: unbonded }, current_epoch, params);
+                *to_remove -= removed_delta;
+                *removed.entry((*epoch_start, bond_end)).or_default() +=
+                    removed_delta;
             }
-            // Stop the update once all the tokens are unbonded
-            *to_unbond != 0.into()
+            // Stop the update once all the tokens are removed
+            *to_remove != 0.into()
         },
         current_epoch,
         params,
@@ -1473,11 +2521,81 @@ fn unbond_tokens(
         update_offset,
         params,
     );
+    removed
+}
+
+/// Calculate how much a bond delta would be after applying the slashes for
+/// the infractions committed at or after its `epoch_start`. We need to
+/// accumulate the slashed delta for multiple slashes applicable to a bond,
+/// where each slash should be calculated from the delta reduced by the
+/// previous slash.
+fn apply_slashes(
+    delta: token::Amount,
+    epoch_start: Epoch,
+    slashes: &[Slash],
+) -> token::Amount {
+    let mut slashed_delta = delta;
+    for slash in slashes {
+        if slash.epoch >= epoch_start {
+            let raw_delta: u64 = slashed_delta.into();
+            let raw_slashed = decimal_mult_u64(slash.rate, raw_delta);
+            slashed_delta -= token::Amount::from(raw_slashed);
+        }
+    }
+    slashed_delta
+}
+
+struct RedelegationData {
+    /// The redelegated bond deltas with the source validator's slashes
+    /// applied
+    deltas: HashMap<(Epoch, Epoch), token::Amount>,
+    /// The sum of the redelegated deltas
+    amount: token::Amount,
+}
+
+/// Remove the redelegated tokens from a bond to the source validator. The
+/// tokens stop contributing to the source validator's stake at the pipeline
+/// offset.
+#[allow(clippy::too_many_arguments)]
+fn redelegate_tokens(
+    params: &PosParams,
+    bond_id: &BondId,
+    bond: &mut Bonds,
+    amount: token::Amount,
+    slashes: Slashes,
+    validator_deltas: &mut ValidatorDeltas,
+    total_deltas: &mut TotalDeltas,
+    validator_set: &mut ValidatorSets,
+    current_epoch: Epoch,
+) -> Result<RedelegationData, RedelegationError> {
+    let redelegatable_amount = bond
+        .get_at_offset(current_epoch, DynEpochOffset::PipelineLen, params)
+        .unwrap_or_default()
+        .sum();
+    if amount > redelegatable_amount {
+        return Err(RedelegationError::AmountGreaterThanBond(
+            amount,
+            redelegatable_amount,
+        ));
+    }
+
+    let update_offset = DynEpochOffset::PipelineLen;
+    let deltas: HashMap<(Epoch, Epoch), token::Amount> =
+        remove_from_bond(params, bond, amount, update_offset, current_epoch)
+            .into_iter()
+            .map(|((epoch_start, epoch_end), delta)| {
+                let delta = apply_slashes(delta, epoch_start, &slashes);
+                ((epoch_start, epoch_end), delta)
+            })
+            .collect();
+    let amount = deltas
+        .values()
+        .fold(token::Amount::default(), |acc, delta| acc + *delta);
 
     // Update validator set. This has to be done before we update the
     // `validator_deltas`, because we need to look-up the validator with
     // its voting power before the change.
-    let token_change = -token::Change::from(slashed_amount);
+    let token_change = -token::Change::from(amount);
     update_validator_set(
         params,
         &bond_id.validator,
@@ -1487,15 +2605,20 @@ fn unbond_tokens(
         Some(validator_deltas),
         current_epoch,
     );
+    validator_deltas.add_at_offset(
+        token_change,
+        current_epoch,
+        update_offset,
+        params,
+    );
+    total_deltas.add_at_offset(
+        token_change,
+        current_epoch,
+        update_offset,
+        params,
+    );
 
-    // Update validator's deltas
-    validator_deltas.add(token_change, current_epoch, params);
-
-    // Update the total deltas of all validators.
-    // TODO: provide some error handling that was maybe here before?
-    total_deltas.add(token_change, current_epoch, params);
-
-    Ok(UnbondData { unbond })
+    Ok(RedelegationData { deltas, amount })
 }
 
 /// Update validator set when a validator's receives a new bond and when its
@@ -1552,10 +2675,7 @@ fn update_validator_set(
                         validator_set.inactive.remove(&validator_pre);
                         validator_set.inactive.insert(validator_post);
                     }
-                } else {
-                    debug_assert!(
-                        validator_set.active.contains(&validator_pre)
-                    );
+                } else if validator_set.active.contains(&validator_pre) {
                     let max_inactive_validator =
                         validator_set.inactive.last_shim();
                     let max_bonded_stake = max_inactive_validator
@@ -1584,18 +2704,66 @@ fn update_validator_set(
     )
 }
 
+/// Remove a validator from the validator set. If it was active, the inactive
+/// validator with the most stake takes its slot.
+fn remove_validator(validator_set: &mut ValidatorSet, address: &Address) {
+    let find = |set: &BTreeSet<WeightedValidator>| {
+        set.iter()
+            .find(|validator| &validator.address == address)
+            .cloned()
+    };
+    if let Some(validator) = find(&validator_set.active) {
+        validator_set.active.remove(&validator);
+        if let Some(activate_max) = validator_set.inactive.pop_last_shim() {
+            validator_set.active.insert(activate_max);
+        }
+    } else if let Some(validator) = find(&validator_set.inactive) {
+        validator_set.inactive.remove(&validator);
+    }
+}
+
+/// Insert a validator into the validator set. It becomes active if there's a
+/// free slot or if it has more stake than the least staked active validator,
+/// which is then moved to the inactive set.
+fn insert_validator(
+    params: &PosParams,
+    validator_set: &mut ValidatorSet,
+    validator: WeightedValidator,
+) {
+    if validator_set.active.len() < params.max_validator_slots as usize {
+        validator_set.active.insert(validator);
+        return;
+    }
+    let min_bonded_stake = validator_set
+        .active
+        .first_shim()
+        .map(|v| v.bonded_stake)
+        .unwrap_or_default();
+    if validator.bonded_stake > min_bonded_stake {
+        if let Some(deactivate_min) = validator_set.active.pop_first_shim() {
+            validator_set.inactive.insert(deactivate_min);
+        }
+        validator_set.active.insert(validator);
+    } else {
+        validator_set.inactive.insert(validator);
+    }
+}
+
 struct WithdrawData {
     pub unbond: Unbonds,
     pub withdrawn: token::Amount,
     pub slashed: token::Amount,
 }
 
-/// Withdraw tokens from unbonds of self-bonds or delegations.
+/// Withdraw tokens from unbonds of self-bonds or delegations. The withdrawal
+/// fails if any of the `queued_slashes`, whose rates are not known yet,
+/// applies to the withdrawn unbonds.
 fn withdraw_unbonds(
     params: &PosParams,
     bond_id: &BondId,
     unbond: Option<Unbonds>,
     slashes: Vec<Slash>,
+    queued_slashes: Vec<Slash>,
     current_epoch: Epoch,
 ) -> Result<WithdrawData, WithdrawError> {
     let mut unbond =
@@ -1603,6 +2771,18 @@ fn withdraw_unbonds(
     let withdrawable_unbond = unbond
         .get(current_epoch)
         .ok_or_else(|| WithdrawError::NoWithdrawableUnbond(bond_id.clone()))?;
+    let has_queued_slash =
+        withdrawable_unbond
+            .deltas
+            .keys()
+            .any(|(epoch_start, epoch_end)| {
+                queued_slashes.iter().any(|slash| {
+                    slash.epoch >= *epoch_start && slash.epoch <= *epoch_end
+                })
+            });
+    if has_queued_slash {
+        return Err(WithdrawError::QueuedSlash(bond_id.clone()));
+    }
     let mut slashed = token::Amount::default();
     let withdrawn_amount = withdrawable_unbond.deltas.iter().fold(
         token::Amount::default(),
@@ -1654,8 +2834,32 @@ impl From<WithdrawError> for storage_api::Error {
     }
 }
 
+impl From<RedelegationError> for storage_api::Error {
+    fn from(err: RedelegationError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<UnjailError> for storage_api::Error {
+    fn from(err: UnjailError) -> Self {
+        Self::new(err)
+    }
+}
+
 impl From<CommissionRateChangeError> for storage_api::Error {
     fn from(err: CommissionRateChangeError) -> Self {
         Self::new(err)
     }
 }
+
+impl From<ConsensusKeyChangeError> for storage_api::Error {
+    fn from(err: ConsensusKeyChangeError) -> Self {
+        Self::new(err)
+    }
+}
+
+impl From<MetaDataChangeError> for storage_api::Error {
+    fn from(err: MetaDataChangeError) -> Self {
+        Self::new(err)
+    }
+}
@@ -1,6 +1,7 @@
 //! Proof-of-Stake system parameters
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::types::governance::PosParameter;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -24,10 +25,10 @@ pub struct PosParams {
     /// Used in validators' voting power calculation to interface with
     /// tendermint.
     pub tm_votes_per_token: Decimal,
-    /// Amount of tokens rewarded to a validator for proposing a block
+    /// Maximum fraction of the block rewards given to the block proposer
     pub block_proposer_reward: Decimal,
-    /// Amount of tokens rewarded to each validator that voted on a block
-    /// proposal
+    /// Fraction of the block rewards split between the validators that voted
+    /// on a block proposal
     pub block_vote_reward: Decimal,
     /// Maximum staking rewards rate per annum
     pub max_inflation_rate: Decimal,
@@ -39,6 +40,18 @@ pub struct PosParams {
     /// Portion of validator's stake that should be slashed on a light client
     /// attack.
     pub light_client_attack_min_slash_rate: Decimal,
+    /// The number of epochs before and after an infraction epoch within which
+    /// the infractions are considered correlated for cubic slashing
+    pub cubic_slashing_window_length: u64,
+    /// The number of the most recent blocks over which the validators'
+    /// liveness is tracked
+    pub liveness_window_len: u64,
+    /// Maximum fraction of the blocks in the liveness window that a validator
+    /// may miss before it's jailed
+    pub liveness_max_missed_rate: Decimal,
+    /// Portion of validator's stake that should be slashed when it's jailed
+    /// for missing too many blocks.
+    pub liveness_slash_rate: Decimal,
 }
 
 impl Default for PosParams {
@@ -60,6 +73,12 @@ impl Default for PosParams {
             duplicate_vote_min_slash_rate: dec!(0.001),
             // slash 0.1%
             light_client_attack_min_slash_rate: dec!(0.001),
+            cubic_slashing_window_length: 1,
+            liveness_window_len: 10_000,
+            // jail after missing 90% of the blocks in the window
+            liveness_max_missed_rate: dec!(0.9),
+            // slash 0.01%
+            liveness_slash_rate: dec!(0.0001),
         }
     }
 }
@@ -81,6 +100,15 @@ pub enum ValidationError {
          pipeline: {1}"
     )]
     UnbondingLenTooShort(u64, u64),
+    #[error(
+        "Cubic slashing window length must be < unbonding length. Got window: \
+         {0}, unbonding: {1}"
+    )]
+    CubicSlashingWindowTooLong(u64, u64),
+    #[error("Liveness window length must be > 0")]
+    LivenessWindowEmpty,
+    #[error("Liveness max missed rate must be between 0 and 1, got {0}")]
+    LivenessMaxMissedRateOutOfRange(Decimal),
 }
 
 /// The number of fundamental units per whole token of the native staking token
@@ -111,6 +139,15 @@ impl PosParams {
             ))
         }
 
+        // Slashes must be processed before the tokens unbonded in the
+        // infraction epoch can be withdrawn
+        if self.cubic_slashing_window_length >= self.unbonding_len {
+            errors.push(ValidationError::CubicSlashingWindowTooLong(
+                self.cubic_slashing_window_length,
+                self.unbonding_len,
+            ))
+        }
+
         // Check maximum total voting power cannot get larger than what
         // Tendermint allows
         let max_total_voting_power = Decimal::from(self.max_validator_slots)
@@ -136,8 +173,57 @@ impl PosParams {
             ))
         }
 
+        if self.liveness_window_len == 0 {
+            errors.push(ValidationError::LivenessWindowEmpty)
+        }
+        if self.liveness_max_missed_rate.is_sign_negative()
+            || self.liveness_max_missed_rate > dec!(1.0)
+        {
+            errors.push(ValidationError::LivenessMaxMissedRateOutOfRange(
+                self.liveness_max_missed_rate,
+            ))
+        }
+
         errors
     }
+
+    /// Set the new value of a parameter changed by a governance proposal. The
+    /// parameters should be validated after all the changes of a proposal
+    /// are applied.
+    pub fn apply_change(&mut self, change: &PosParameter) {
+        match *change {
+            PosParameter::MaxValidatorSlots(value) => {
+                self.max_validator_slots = value
+            }
+            PosParameter::TmVotesPerToken(value) => {
+                self.tm_votes_per_token = value
+            }
+            PosParameter::BlockProposerReward(value) => {
+                self.block_proposer_reward = value
+            }
+            PosParameter::BlockVoteReward(value) => {
+                self.block_vote_reward = value
+            }
+            PosParameter::MaxInflationRate(value) => {
+                self.max_inflation_rate = value
+            }
+            PosParameter::TargetStakedRatio(value) => {
+                self.target_staked_ratio = value
+            }
+            PosParameter::DuplicateVoteMinSlashRate(value) => {
+                self.duplicate_vote_min_slash_rate = value
+            }
+            PosParameter::LightClientAttackMinSlashRate(value) => {
+                self.light_client_attack_min_slash_rate = value
+            }
+            PosParameter::LivenessMaxMissedRate(value) => {
+                self.liveness_max_missed_rate = value
+            }
+            PosParameter::LivenessSlashRate(value) => {
+                self.liveness_slash_rate = value
+            }
+        }
+    }
 }
 
 #[cfg(test)]
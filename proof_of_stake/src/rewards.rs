@@ -0,0 +1,263 @@
+//! PoS rewards distribution.
+//!
+//! In each block, the proposer and the signers of the previous block are
+//! assigned a fraction of the block rewards. The fractions are accumulated per
+//! validator over the epoch (see [`crate::types::RewardsAccumulator`]) and at
+//! the end of the epoch, the epoch's rewards are split between the validators
+//! according to their accumulated fractions and credited to their bonds.
+
+use namada_core::types::token;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use thiserror::Error;
+
+use crate::types::BondId;
+use crate::BondError;
+
+/// The minimum fraction of the block rewards given to the block proposer,
+/// regardless of the stake that has signed the block.
+const MIN_PROPOSER_REWARD: Decimal = dec!(0.01);
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum RewardsError {
+    #[error(
+        "Insufficient votes, needed at least 2/3 of the total active stake, \
+         got {signing_stake} out of {total_stake}"
+    )]
+    InsufficientVotes {
+        signing_stake: u64,
+        total_stake: u64,
+    },
+    #[error("The active validator set has no stake")]
+    NoActiveStake,
+    #[error("Rewards coefficients are not properly set: {0:?}")]
+    CoeffsNotSet(PosRewards),
+    #[error("Failed to credit rewards to a bond: {0}")]
+    Bond(BondError),
+}
+
+/// Coefficients of the three different ways to earn a fraction of the block
+/// rewards. The coefficients add up to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PosRewards {
+    /// Fraction of the block rewards given to the block proposer
+    pub proposer_coeff: Decimal,
+    /// Fraction of the block rewards split between the signers of the block,
+    /// proportionally to their stake
+    pub signer_coeff: Decimal,
+    /// Fraction of the block rewards split between all the active
+    /// validators, proportionally to their stake
+    pub active_val_coeff: Decimal,
+}
+
+/// Calculator of the block rewards coefficients from the PoS parameters and the
+/// stake that has signed a block.
+#[derive(Debug, Clone, Copy)]
+pub struct PosRewardsCalculator {
+    /// Maximum fraction of the rewards that goes to the block proposer
+    /// (`block_proposer_reward` in [`crate::PosParams`])
+    pub proposer_reward: Decimal,
+    /// Fraction of the rewards that goes to the block signers
+    /// (`block_vote_reward` in [`crate::PosParams`])
+    pub signer_reward: Decimal,
+    /// Total stake of the validators who signed the block
+    pub signing_stake: u64,
+    /// Total stake of the active validator set
+    pub total_stake: u64,
+}
+
+impl PosRewardsCalculator {
+    /// Calculate the block rewards coefficients. The proposer's reward grows
+    /// with the stake that signed the block above the 2/3 threshold.
+    pub fn get_reward_coeffs(&self) -> Result<PosRewards, RewardsError> {
+        if self.total_stake == 0 {
+            return Err(RewardsError::NoActiveStake);
+        }
+        let votes_needed = self.get_min_required_votes();
+        if self.signing_stake < votes_needed {
+            return Err(RewardsError::InsufficientVotes {
+                signing_stake: self.signing_stake,
+                total_stake: self.total_stake,
+            });
+        }
+
+        let signing_stake = Decimal::from(self.signing_stake);
+        let total_stake = Decimal::from(self.total_stake);
+        let votes_needed = Decimal::from(votes_needed);
+
+        let proposer_coeff =
+            self.proposer_reward * (signing_stake - votes_needed) / total_stake
+                + MIN_PROPOSER_REWARD;
+        let signer_coeff = self.signer_reward;
+        let active_val_coeff = dec!(1.0) - proposer_coeff - signer_coeff;
+
+        let coeffs = PosRewards {
+            proposer_coeff,
+            signer_coeff,
+            active_val_coeff,
+        };
+        if active_val_coeff < Decimal::ZERO {
+            return Err(RewardsError::CoeffsNotSet(coeffs));
+        }
+        Ok(coeffs)
+    }
+
+    /// The ceiling of 2/3 of the total stake
+    fn get_min_required_votes(&self) -> u64 {
+        let total_stake = u128::from(self.total_stake);
+        ((2 * total_stake + 2) / 3) as u64
+    }
+}
+
+/// Rewards earned by a validator in an epoch, split into the validator's
+/// commission and the rewards of each of its bonds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorRewards {
+    /// The commission, to be credited to the validator's self-bond
+    pub commission: token::Amount,
+    /// The rewards of each bond (the self-bond and delegations)
+    pub bonds: Vec<(BondId, token::Amount)>,
+}
+
+impl ValidatorRewards {
+    /// The total amount of the split rewards. Because of rounding, it may be
+    /// lower than the amount of rewards that has been split.
+    pub fn total(&self) -> token::Amount {
+        self.bonds
+            .iter()
+            .fold(self.commission, |acc, (_id, amount)| acc + *amount)
+    }
+}
+
+/// Split the `rewards` earned by a validator between its commission, given by
+/// the `commission_rate`, and its `bonds` proportionally to their amounts.
+/// Any remainder from rounding down is not distributed. When there are no
+/// bonded tokens, the whole rewards are given as the commission.
+pub fn split_validator_rewards(
+    rewards: token::Amount,
+    commission_rate: Decimal,
+    bonds: &[(BondId, token::Amount)],
+) -> ValidatorRewards {
+    let total_bonded: u128 = bonds
+        .iter()
+        .map(|(_id, amount)| u128::from(u64::from(*amount)))
+        .sum();
+    if total_bonded == 0 {
+        return ValidatorRewards {
+            commission: rewards,
+            bonds: vec![],
+        };
+    }
+
+    let raw_rewards = u64::from(rewards);
+    let commission = token::Amount::from(crate::types::decimal_mult_u64(
+        commission_rate,
+        raw_rewards,
+    ));
+    let delegators_rewards = u128::from(u64::from(rewards - commission));
+    let bonds = bonds
+        .iter()
+        .filter_map(|(bond_id, amount)| {
+            let raw_amount = u128::from(u64::from(*amount));
+            let bond_rewards = delegators_rewards * raw_amount / total_bonded;
+            // The product is bounded by `delegators_rewards`, which is a `u64`
+            let bond_rewards = bond_rewards as u64;
+            (bond_rewards != 0)
+                .then(|| (bond_id.clone(), token::Amount::from(bond_rewards)))
+        })
+        .collect();
+    ValidatorRewards { commission, bonds }
+}
+
+impl From<BondError> for RewardsError {
+    fn from(err: BondError) -> Self {
+        Self::Bond(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_reward_coeffs() {
+        let calculator = PosRewardsCalculator {
+            proposer_reward: dec!(0.125),
+            signer_reward: dec!(0.1),
+            signing_stake: 100,
+            total_stake: 100,
+        };
+        let coeffs = calculator.get_reward_coeffs().unwrap();
+        // 0.125 * (100 - 67) / 100 + 0.01
+        assert_eq!(coeffs.proposer_coeff, dec!(0.05125));
+        assert_eq!(coeffs.signer_coeff, dec!(0.1));
+        assert_eq!(
+            coeffs.proposer_coeff
+                + coeffs.signer_coeff
+                + coeffs.active_val_coeff,
+            dec!(1.0)
+        );
+
+        // With exactly 2/3 of the stake signing, the proposer only gets the
+        // minimum reward
+        let calculator = PosRewardsCalculator {
+            signing_stake: 67,
+            ..calculator
+        };
+        let coeffs = calculator.get_reward_coeffs().unwrap();
+        assert_eq!(coeffs.proposer_coeff, MIN_PROPOSER_REWARD);
+
+        let calculator = PosRewardsCalculator {
+            signing_stake: 66,
+            ..calculator
+        };
+        assert!(matches!(
+            calculator.get_reward_coeffs(),
+            Err(RewardsError::InsufficientVotes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_split_validator_rewards() {
+        let validator = established_address_1();
+        let self_bond = BondId {
+            source: validator.clone(),
+            validator: validator.clone(),
+        };
+        let delegation_1 = BondId {
+            source: established_address_2(),
+            validator: validator.clone(),
+        };
+        let delegation_2 = BondId {
+            source: established_address_3(),
+            validator,
+        };
+        let bonds = [
+            (self_bond.clone(), token::Amount::from(500)),
+            (delegation_1.clone(), token::Amount::from(300)),
+            (delegation_2.clone(), token::Amount::from(200)),
+        ];
+
+        let rewards = split_validator_rewards(1_000.into(), dec!(0.1), &bonds);
+        assert_eq!(rewards.commission, token::Amount::from(100));
+        assert_eq!(
+            rewards.bonds,
+            vec![
+                (self_bond, token::Amount::from(450)),
+                (delegation_1, token::Amount::from(270)),
+                (delegation_2, token::Amount::from(180)),
+            ]
+        );
+        assert_eq!(rewards.total(), token::Amount::from(1_000));
+
+        // Without any bonded tokens, everything goes to the commission
+        let rewards = split_validator_rewards(1_000.into(), dec!(0.1), &[]);
+        assert_eq!(rewards.commission, token::Amount::from(1_000));
+        assert!(rewards.bonds.is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 
 use namada_core::ledger::storage::types::{decode, encode};
 use namada_core::ledger::storage::{self, Storage, StorageHasher};
+use namada_core::ledger::storage_api;
 use namada_core::types::address::Address;
 use namada_core::types::storage::{DbKeySeg, Key, KeySeg};
 use namada_core::types::{key, token};
@@ -19,13 +20,20 @@ const VALIDATOR_CONSENSUS_KEY_STORAGE_KEY: &str = "consensus_key";
 const VALIDATOR_STATE_STORAGE_KEY: &str = "state";
 const VALIDATOR_DELTAS_STORAGE_KEY: &str = "validator_deltas";
 const VALIDATOR_COMMISSION_RATE_STORAGE_KEY: &str = "commission_rate";
+const VALIDATOR_METADATA_STORAGE_KEY: &str = "metadata";
 const VALIDATOR_MAX_COMMISSION_CHANGE_STORAGE_KEY: &str =
     "max_commission_rate_change";
 const SLASHES_PREFIX: &str = "slash";
 const BOND_STORAGE_KEY: &str = "bond";
 const UNBOND_STORAGE_KEY: &str = "unbond";
+const REDELEGATION_STORAGE_KEY: &str = "redelegation";
 const VALIDATOR_SET_STORAGE_KEY: &str = "validator_set";
 const TOTAL_DELTAS_STORAGE_KEY: &str = "total_deltas";
+const LAST_BLOCK_PROPOSER_STORAGE_KEY: &str = "last_block_proposer";
+const REWARDS_ACCUMULATOR_STORAGE_KEY: &str = "rewards_accumulator";
+const MISSED_BLOCKS_STORAGE_KEY: &str = "missed_blocks";
+const SLASH_QUEUE_STORAGE_KEY: &str = "slash_queue";
+const PROCESSED_SLASHES_STORAGE_KEY: &str = "processed_slashes";
 
 /// Is the given key a PoS storage key?
 pub fn is_pos_key(key: &Key) -> bool {
@@ -81,6 +89,31 @@ pub fn is_validator_address_raw_hash_key(key: &Key) -> Option<&str> {
     }
 }
 
+/// Storage key for the blocks missed by a validator in its liveness window.
+pub fn validator_missed_blocks_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&MISSED_BLOCKS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the blocks missed by a validator in its liveness window?
+pub fn is_validator_missed_blocks_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(validator),
+            DbKeySeg::StringSeg(key),
+        ] if addr == &ADDRESS
+            && prefix == VALIDATOR_STORAGE_PREFIX
+            && key == MISSED_BLOCKS_STORAGE_KEY =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator's consensus key.
 pub fn validator_consensus_key_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -158,6 +191,31 @@ pub fn is_validator_max_commission_rate_change_key(
     }
 }
 
+/// Storage key for validator's metadata.
+pub fn validator_metadata_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_METADATA_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for validator's metadata?
+pub fn is_validator_metadata_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(validator),
+            DbKeySeg::StringSeg(key),
+        ] if addr == &ADDRESS
+            && prefix == VALIDATOR_STORAGE_PREFIX
+            && key == VALIDATOR_METADATA_STORAGE_KEY =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator's consensus key.
 pub fn validator_state_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -308,6 +366,41 @@ pub fn is_unbond_key(key: &Key) -> Option<BondId> {
     }
 }
 
+/// Storage key prefix for all redelegations.
+pub fn redelegations_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&REDELEGATION_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the redelegations into a bond with the given ID (owner and
+/// destination validator).
+pub fn redelegation_key(bond_id: &BondId) -> Key {
+    redelegations_prefix()
+        .push(&bond_id.source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&bond_id.validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for redelegations?
+pub fn is_redelegation_key(key: &Key) -> Option<BondId> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(source),
+            DbKeySeg::AddressSeg(validator),
+        ] if addr == &ADDRESS && prefix == REDELEGATION_STORAGE_KEY => {
+            Some(BondId {
+                source: source.clone(),
+                validator: validator.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator set (active and inactive).
 pub fn validator_set_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -334,6 +427,64 @@ pub fn is_total_deltas_key(key: &Key) -> bool {
                     if addr == &ADDRESS && key == TOTAL_DELTAS_STORAGE_KEY)
 }
 
+/// Storage key for the address of the proposer of the last committed block.
+pub fn last_block_proposer_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&LAST_BLOCK_PROPOSER_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the address of the proposer of the last committed
+/// block?
+pub fn is_last_block_proposer_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+                [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+                    if addr == &ADDRESS && key == LAST_BLOCK_PROPOSER_STORAGE_KEY)
+}
+
+/// Storage key for the block rewards accumulated in the current epoch.
+pub fn rewards_accumulator_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&REWARDS_ACCUMULATOR_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the block rewards accumulated in the current epoch?
+pub fn is_rewards_accumulator_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+                [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+                    if addr == &ADDRESS && key == REWARDS_ACCUMULATOR_STORAGE_KEY)
+}
+
+
+/// Storage key for the slashes waiting to be processed.
+pub fn slash_queue_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&SLASH_QUEUE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the slashes waiting to be processed?
+pub fn is_slash_queue_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+                [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+                    if addr == &ADDRESS && key == SLASH_QUEUE_STORAGE_KEY)
+}
+
+/// Storage key for the recently processed slashes.
+pub fn processed_slashes_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PROCESSED_SLASHES_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the recently processed slashes?
+pub fn is_processed_slashes_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+                [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+                    if addr == &ADDRESS && key == PROCESSED_SLASHES_STORAGE_KEY)
+}
+
 /// Get validator address from bond key
 pub fn get_validator_address_from_bond(key: &Key) -> Option<Address> {
     match key.get_at(3) {
@@ -437,6 +588,69 @@ where
         decode(value.unwrap()).unwrap()
     }
 
+    fn read_bonds(&self) -> Vec<(BondId, Bonds)> {
+        storage_api::iter_prefix(self, &bonds_prefix())
+            .unwrap()
+            .filter_map(|result| {
+                let (key, bonds) = result.unwrap();
+                is_bond_key(&key).map(|bond_id| (bond_id, bonds))
+            })
+            .collect()
+    }
+
+    fn read_bond(&self, key: &BondId) -> Option<Bonds> {
+        let (value, _gas) = self.read(&bond_key(key)).unwrap();
+        value.map(|value| decode(value).unwrap())
+    }
+
+    fn read_redelegations(&self) -> Vec<(BondId, Redelegations)> {
+        storage_api::iter_prefix(self, &redelegations_prefix())
+            .unwrap()
+            .filter_map(|result| {
+                let (key, redelegations) = result.unwrap();
+                is_redelegation_key(&key)
+                    .map(|bond_id| (bond_id, redelegations))
+            })
+            .collect()
+    }
+
+    fn read_last_block_proposer(
+        &self,
+    ) -> Option<namada_core::types::address::Address> {
+        let (value, _gas) = self.read(&last_block_proposer_key()).unwrap();
+        value.map(|value| decode(value).unwrap())
+    }
+
+    fn read_rewards_accumulator(&self) -> RewardsAccumulator {
+        let (value, _gas) = self.read(&rewards_accumulator_key()).unwrap();
+        value
+            .map(|value| decode(value).unwrap())
+            .unwrap_or_default()
+    }
+
+    fn read_validator_missed_blocks(
+        &self,
+        key: &namada_core::types::address::Address,
+    ) -> Option<MissedBlocks> {
+        let (value, _gas) =
+            self.read(&validator_missed_blocks_key(key)).unwrap();
+        value.map(|value| decode(value).unwrap())
+    }
+
+    fn read_slash_queue(&self) -> SlashQueue {
+        let (value, _gas) = self.read(&slash_queue_key()).unwrap();
+        value
+            .map(|value| decode(value).unwrap())
+            .unwrap_or_default()
+    }
+
+    fn read_processed_slashes(&self) -> SlashQueue {
+        let (value, _gas) = self.read(&processed_slashes_key()).unwrap();
+        value
+            .map(|value| decode(value).unwrap())
+            .unwrap_or_default()
+    }
+
     fn write_pos_params(&mut self, params: &PosParams) {
         self.write(&params_key(), encode(params)).unwrap();
     }
@@ -514,6 +728,10 @@ where
         self.write(&bond_key(key), encode(value)).unwrap();
     }
 
+    fn write_redelegations(&mut self, key: &BondId, value: &Redelegations) {
+        self.write(&redelegation_key(key), encode(value)).unwrap();
+    }
+
     fn write_validator_set(&mut self, value: &ValidatorSets) {
         self.write(&validator_set_key(), encode(value)).unwrap();
     }
@@ -522,6 +740,43 @@ where
         self.write(&total_deltas_key(), encode(value)).unwrap();
     }
 
+    fn write_last_block_proposer(
+        &mut self,
+        address: &namada_core::types::address::Address,
+    ) {
+        self.write(&last_block_proposer_key(), encode(address))
+            .unwrap();
+    }
+
+    fn write_rewards_accumulator(&mut self, value: &RewardsAccumulator) {
+        self.write(&rewards_accumulator_key(), encode(value))
+            .unwrap();
+    }
+
+    fn write_validator_missed_blocks(
+        &mut self,
+        key: &namada_core::types::address::Address,
+        value: &MissedBlocks,
+    ) {
+        self.write(&validator_missed_blocks_key(key), encode(value))
+            .unwrap();
+    }
+
+    fn delete_validator_missed_blocks(
+        &mut self,
+        key: &namada_core::types::address::Address,
+    ) {
+        self.delete(&validator_missed_blocks_key(key)).unwrap();
+    }
+
+    fn write_slash_queue(&mut self, value: &SlashQueue) {
+        self.write(&slash_queue_key(), encode(value)).unwrap();
+    }
+
+    fn write_processed_slashes(&mut self, value: &SlashQueue) {
+        self.write(&processed_slashes_key(), encode(value)).unwrap();
+    }
+
     fn credit_tokens(
         &mut self,
         token: &namada_core::types::address::Address,
@@ -620,6 +875,15 @@ macro_rules! impl_pos_read_only {
                 Ok(namada_core::ledger::storage::types::decode(value).unwrap())
             }
 
+            fn read_validator_address_raw_hash(
+                &self,
+                raw_hash: impl AsRef<str>,
+            ) -> namada_core::ledger::storage_api::Result<Option<namada_core::types::address::Address>> {
+                let value =
+                    namada_core::ledger::storage_api::StorageRead::read_bytes(self, &$crate::storage::validator_address_raw_hash_key(raw_hash))?;
+                Ok(value.map(|value| namada_core::ledger::storage::types::decode(value).unwrap()))
+            }
+
             fn read_validator_consensus_key(
                 &self,
                 key: &namada_core::types::address::Address,
@@ -647,6 +911,15 @@ macro_rules! impl_pos_read_only {
                 Ok(value.map(|value| namada_core::ledger::storage::types::decode(value).unwrap()))
             }
 
+            fn read_validator_metadata(
+                &self,
+                key: &namada_core::types::address::Address,
+            ) -> namada_core::ledger::storage_api::Result<Option<$crate::types::ValidatorMetaData>> {
+                let value =
+                    namada_core::ledger::storage_api::StorageRead::read_bytes(self, &$crate::storage::validator_metadata_key(key))?;
+                Ok(value.map(|value| namada_core::ledger::storage::types::decode(value).unwrap()))
+            }
+
             fn read_validator_state(
                 &self,
                 key: &namada_core::types::address::Address,
@@ -690,6 +963,16 @@ macro_rules! impl_pos_read_only {
                 Ok(value.map(|value| namada_core::ledger::storage::types::decode(value).unwrap()))
             }
 
+            fn read_redelegations(
+                &self,
+                key: &BondId,
+            ) -> namada_core::ledger::storage_api::Result<$crate::types::Redelegations> {
+                let value = namada_core::ledger::storage_api::StorageRead::read_bytes(self, &$crate::storage::redelegation_key(key))?;
+                Ok(value
+                    .map(|value| namada_core::ledger::storage::types::decode(value).unwrap())
+                    .unwrap_or_default())
+            }
+
             fn read_validator_set(
                 &self,
             ) -> namada_core::ledger::storage_api::Result<ValidatorSets> {
@@ -705,6 +988,16 @@ macro_rules! impl_pos_read_only {
                     namada_core::ledger::storage_api::StorageRead::read_bytes(self, &total_deltas_key())?.unwrap();
                 Ok(namada_core::ledger::storage::types::decode(value).unwrap())
             }
+
+            fn read_slash_queue(
+                &self,
+            ) -> namada_core::ledger::storage_api::Result<$crate::types::SlashQueue> {
+                let value =
+                    namada_core::ledger::storage_api::StorageRead::read_bytes(self, &$crate::storage::slash_queue_key())?;
+                Ok(value
+                    .map(|value| namada_core::ledger::storage::types::decode(value).unwrap())
+                    .unwrap_or_default())
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 //! Proof of Stake data types
 
 use core::fmt::Debug;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::hash::Hash;
@@ -12,6 +12,7 @@ use namada_core::types::address::Address;
 use namada_core::types::key::common;
 use namada_core::types::storage::Epoch;
 use namada_core::types::token;
+pub use namada_core::types::transaction::pos::ValidatorMetaData;
 use rust_decimal::prelude::{Decimal, ToPrimitive};
 
 use crate::epoched::{
@@ -171,7 +172,10 @@ pub enum ValidatorState {
     /// A `Candidate` validator may participate in the consensus. It is either
     /// in the active or inactive validator set.
     Candidate,
-    // TODO consider adding `Jailed`
+    /// A `Jailed` validator has been removed from the validator set for
+    /// missing too many blocks and may not participate in the consensus until
+    /// it's unjailed.
+    Jailed,
 }
 
 /// A bond is either a validator's self-bond or a delegation from a regular
@@ -206,6 +210,27 @@ pub struct Unbond {
     pub deltas: HashMap<(Epoch, Epoch), token::Amount>,
 }
 
+/// Tokens of a bond that have been redelegated to it from another validator.
+/// They remain slashable for the infractions of the source validator committed
+/// while they were still bonded to it.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub struct Redelegation {
+    /// The validator from which the tokens were redelegated
+    pub src_validator: Address,
+    /// The epoch in which the tokens were redelegated
+    pub epoch: Epoch,
+    /// A key is a pair of the epoch of the bond to the source validator from
+    /// which the tokens were redelegated and the last epoch in which they
+    /// were bonded to it. The amounts have the source validator's slashes
+    /// already applied.
+    pub deltas: HashMap<(Epoch, Epoch), token::Amount>,
+}
+
+/// Redelegations into a bond.
+pub type Redelegations = Vec<Redelegation>;
+
 /// A slash applied to validator, to punish byzantine behavior by removing
 /// their staked tokens at and before the epoch of the slash.
 #[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
@@ -224,6 +249,61 @@ pub struct Slash {
 /// their staked tokens at and before the epoch of the slash.
 pub type Slashes = Vec<Slash>;
 
+/// A slash of a validator's infraction together with the stakes needed to
+/// compute its rate from the infractions that occurred around the same epoch.
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct QueuedSlash {
+    /// The slashed validator
+    pub validator: Address,
+    /// The slash. Until it's processed, its rate is the minimum slash rate of
+    /// its type.
+    pub slash: Slash,
+    /// The validator's stake in the epoch of the infraction
+    pub validator_stake: u64,
+    /// The total stake of all the validators in the epoch of the infraction
+    pub total_stake: u64,
+}
+
+/// Slashes of infractions waiting to be processed at the end of their cubic
+/// slashing window, or already processed ones that may still fall into the
+/// window of another infraction.
+pub type SlashQueue = Vec<QueuedSlash>;
+
+/// Fractions of the block rewards accumulated by the validators over the
+/// blocks of an epoch. At the end of the epoch, the epoch's rewards are
+/// distributed according to these fractions and the accumulator is reset.
+#[derive(
+    Debug, Clone, Default, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub struct RewardsAccumulator {
+    /// The number of blocks for which the rewards have been accumulated
+    pub num_blocks: u64,
+    /// The sum of the fractions of the block rewards earned by each validator.
+    /// The fractions of a single block add up to 1.
+    pub validators: BTreeMap<Address, Decimal>,
+}
+
+/// The number of blocks missed by a validator within its current liveness
+/// window, i.e. the blocks whose last commit wasn't signed by it. A window
+/// starts at the first block missed after the previous window has elapsed. It's
+/// only stored for the validators that missed some blocks.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+)]
+pub struct MissedBlocks {
+    /// The height of the first block of the liveness window
+    pub window_start: u64,
+    /// The number of blocks missed since the start of the window
+    pub count: u64,
+}
+
 /// A type of slashsable event.
 #[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub enum SlashType {
@@ -231,6 +311,8 @@ pub enum SlashType {
     DuplicateVote,
     /// Light client attack.
     LightClientAttack,
+    /// Missing too many blocks within the liveness window.
+    Liveness,
 }
 
 impl Display for BondId {
@@ -317,6 +399,24 @@ impl Add for Unbond {
     }
 }
 
+impl Redelegation {
+    /// Check if the redelegated tokens may still be slashed for an infraction
+    /// of the source validator, i.e. if the evidence of an infraction from
+    /// the last epoch in which they were bonded to it may still be accepted
+    /// or its slash is yet to be processed.
+    pub fn is_slashable(
+        &self,
+        params: &PosParams,
+        current_epoch: Epoch,
+    ) -> bool {
+        self.epoch
+            + params.pipeline_len
+            + params.unbonding_len
+            + params.cubic_slashing_window_length
+            > current_epoch
+    }
+}
+
 impl SlashType {
     /// Get the slash rate applicable to the given slash type from the PoS
     /// parameters.
@@ -326,6 +426,7 @@ impl SlashType {
             SlashType::LightClientAttack => {
                 params.light_client_attack_min_slash_rate
             }
+            SlashType::Liveness => params.liveness_slash_rate,
         }
     }
 }
@@ -335,10 +436,24 @@ impl Display for SlashType {
         match self {
             SlashType::DuplicateVote => write!(f, "Duplicate vote"),
             SlashType::LightClientAttack => write!(f, "Light client attack"),
+            SlashType::Liveness => write!(f, "Liveness"),
         }
     }
 }
 
+/// Calculate the rate of a slash from the fraction of the total stake held by
+/// all the validators slashed for infractions within the cubic slashing
+/// window. The slashed amount is cubic in the fraction (the rate is quadratic
+/// and applied to the stake), up to the whole stake, and the rate is floored
+/// at the minimum slash rate of the slash type.
+pub fn cubic_slash_rate(
+    min_rate: Decimal,
+    slashed_stake_frac: Decimal,
+) -> Decimal {
+    let rate = Decimal::from(9) * slashed_stake_frac * slashed_stake_frac;
+    rate.min(Decimal::ONE).max(min_rate)
+}
+
 /// Multiply a value of type Decimal with one of type u64 and then return the
 /// truncated u64
 pub fn decimal_mult_u64(dec: Decimal, int: u64) -> u64 {
@@ -378,4 +493,39 @@ pub mod tests {
     pub fn arb_epoch(range: Range<u64>) -> impl Strategy<Value = Epoch> {
         range.prop_map(Epoch)
     }
+
+    proptest! {
+        /// Check that the cubic slash rate is within the minimum rate and 1
+        /// and that it doesn't decrease with more slashed stake.
+        #[test]
+        fn test_cubic_slash_rate(
+            min_rate in crate::parameters::testing::arb_rate(),
+            frac in crate::parameters::testing::arb_rate(),
+            more_frac in crate::parameters::testing::arb_rate(),
+        ) {
+            let rate = cubic_slash_rate(min_rate, frac);
+            assert!(rate >= min_rate);
+            assert!(rate <= Decimal::ONE);
+            let more_frac = (frac + more_frac).min(Decimal::ONE);
+            assert!(cubic_slash_rate(min_rate, more_frac) >= rate);
+        }
+    }
+
+    #[test]
+    fn test_cubic_slash_rate_bounds() {
+        let min_rate = Decimal::new(1, 3);
+        // A single validator with a small fraction of stake gets the minimum
+        assert_eq!(cubic_slash_rate(min_rate, Decimal::new(1, 3)), min_rate);
+        // 10% of stake gives 9 * 0.1^2 = 0.09
+        assert_eq!(
+            cubic_slash_rate(min_rate, Decimal::new(1, 1)),
+            Decimal::new(9, 2)
+        );
+        // Slashing a third of the stake or more takes the whole stake
+        assert_eq!(
+            cubic_slash_rate(min_rate, Decimal::new(5, 1)),
+            Decimal::ONE
+        );
+        assert_eq!(cubic_slash_rate(min_rate, Decimal::ONE), Decimal::ONE);
+    }
 }
@@ -1,7 +1,7 @@
 //! Validation of updated PoS data
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -17,10 +17,12 @@ use crate::btree_set::BTreeSetShims;
 use crate::epoched::DynEpochOffset;
 use crate::parameters::PosParams;
 use crate::types::{
-    decimal_mult_i128, decimal_mult_u64, BondId, Bonds, CommissionRates, Slash,
-    Slashes, TotalDeltas, Unbonds, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorSets, ValidatorState, ValidatorStates, WeightedValidator,
+    decimal_mult_i128, decimal_mult_u64, BondId, Bonds, CommissionRates,
+    Redelegations, Slash, Slashes, TotalDeltas, Unbonds,
+    ValidatorConsensusKeys, ValidatorDeltas, ValidatorMetaData, ValidatorSets,
+    ValidatorState, ValidatorStates, WeightedValidator,
 };
+use crate::{apply_slashes, remove_from_bond};
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -128,10 +130,39 @@ pub enum Error {
     InvalidAddressRawHash(String, String),
     #[error("Invalid address raw hash update")]
     InvalidRawHashUpdate,
+    #[error(
+        "The address raw hash of the new consensus key of validator {0} is \
+         missing or invalid"
+    )]
+    InvalidRotatedConsensusKeyRawHash(Address),
     #[error("Invalid new validator {0}, some fields are missing: {1:?}.")]
     InvalidNewValidator(Address, NewValidator),
     #[error("New validator {0} has not been added to the validator set.")]
     NewValidatorMissingInValidatorSet(Address),
+    #[error("Unjailed validator {0} has not been added to the validator set.")]
+    UnjailedValidatorMissingInValidatorSet(Address),
+    #[error(
+        "Invalid redelegation into bond ID {0}. It must be redelegated from \
+         another validator in the current epoch."
+    )]
+    InvalidRedelegation(BondId),
+    #[error("Bond ID {0} has not been updated for its redelegation.")]
+    RedelegationMissingBondUpdate(BondId),
+    #[error(
+        "A redelegation into bond ID {0} has been removed while it may still \
+         be slashed."
+    )]
+    SlashableRedelegationRemoved(BondId),
+    #[error(
+        "The tokens redelegated from or into bond ID {0} don't match the \
+         change of the bond."
+    )]
+    InvalidRedelegatedAmount(BondId),
+    #[error(
+        "Bond ID {0} has been redelegated while the tokens redelegated into \
+         it may still be slashed."
+    )]
+    SlashableRedelegationRedelegated(BondId),
     #[error("Validator set has not been updated for new validators.")]
     MissingValidatorSetUpdate,
     #[error(
@@ -144,6 +175,10 @@ pub enum Error {
          validator {0}, got {1}."
     )]
     InvalidMaxCommissionRateChange(Address, Decimal),
+    #[error("Invalid metadata for validator {0}.")]
+    InvalidValidatorMetaData(Address),
+    #[error("Metadata of validator {0} cannot be removed.")]
+    ValidatorMetaDataRemoved(Address),
 }
 
 /// An update of PoS data.
@@ -159,6 +194,15 @@ pub enum DataUpdate {
         data: Data<Bonds>,
         /// List of slashes applied to the bond's validator
         slashes: Slashes,
+        /// Redelegations into the bond prior to the update
+        redelegations: Redelegations,
+    },
+    /// Redelegations update
+    Redelegation {
+        /// ID of the bond into which the tokens were redelegated
+        id: BondId,
+        /// Redelegations prior and posterior state
+        data: Data<Redelegations>,
     },
     /// Unbond update
     Unbond {
@@ -202,6 +246,8 @@ pub enum ValidatorUpdate {
     CommissionRate(Data<CommissionRates>, Option<Decimal>),
     /// Maximum commission rate change update
     MaxCommissionRateChange(Data<Decimal>),
+    /// Metadata update
+    MetaData(Data<ValidatorMetaData>),
 }
 
 /// Data update with prior and posterior state.
@@ -271,9 +317,46 @@ pub fn validate(
         validator_set_post,
         total_deltas_by_epoch: _,
         bonded_stake_by_epoch,
-        new_validators,
+        mut new_validators,
+        unjailed_validators,
+        redelegated_bonds,
+        redelegated_into_bonds,
+        changed_bonds,
+        removed_bond_deltas,
+        rotated_consensus_keys,
     } = Validate::accumulate_changes(changes, params, &constants, &mut errors);
 
+    // Check that the address raw hash of a rotated consensus key is written.
+    // The raw hash is accumulated with new validators' fields, so it has to be
+    // taken out before they're checked.
+    for (address, consensus_key) in rotated_consensus_keys {
+        let expected_raw_hash = consensus_key.tm_raw_hash();
+        match new_validators.remove(&address) {
+            Some(NewValidator {
+                has_address_raw_hash: Some(raw_hash),
+                ..
+            }) if raw_hash == expected_raw_hash => {}
+            _ => errors.push(Error::InvalidRotatedConsensusKeyRawHash(address)),
+        }
+    }
+
+    // Check that the bonds from which tokens are redelegated are updated and
+    // that the redelegated tokens are the ones removed from them
+    for (bond_id, deltas) in redelegated_bonds {
+        if !changed_bonds.contains_key(&bond_id) {
+            errors.push(Error::RedelegationMissingBondUpdate(bond_id))
+        } else if removed_bond_deltas.get(&bond_id) != Some(&deltas) {
+            errors.push(Error::InvalidRedelegatedAmount(bond_id))
+        }
+    }
+    // Check that the redelegated tokens are added to the bonds into which
+    // they're redelegated
+    for (bond_id, amount) in redelegated_into_bonds {
+        if changed_bonds.get(&bond_id) != Some(&token::Change::from(amount)) {
+            errors.push(Error::InvalidRedelegatedAmount(bond_id))
+        }
+    }
+
     // Check total deltas against bonds
     for (validator, total_delta) in total_deltas.iter() {
         let bond_delta = bond_delta.get(validator).copied().unwrap_or_default();
@@ -355,11 +438,13 @@ pub fn validate(
                                 None => {
                                     // Others must be have the same voting power
                                     // as in pre (active or inactive), or be a
-                                    // newly added validator
+                                    // newly added or unjailed validator
                                     if !pre.active.contains(validator)
                                         && !pre.inactive.contains(validator)
                                         && !new_validators
                                             .contains_key(&validator.address)
+                                        && !unjailed_validators
+                                            .contains(&validator.address)
                                     {
                                         let mut is_valid = false;
 
@@ -424,11 +509,13 @@ pub fn validate(
                                 None => {
                                     // Others must be have the same voting power
                                     // as in pre (active or inactive), or be a
-                                    // newly added validator
+                                    // newly added or unjailed validator
                                     if !pre.active.contains(validator)
                                         && !pre.inactive.contains(validator)
                                         && !new_validators
                                             .contains_key(&validator.address)
+                                        && !unjailed_validators
+                                            .contains(&validator.address)
                                     {
                                         let mut is_valid = false;
 
@@ -585,6 +672,31 @@ pub fn validate(
         }
     }
 
+    // Check unjailed validators are back in the validator set
+    if !unjailed_validators.is_empty() {
+        match &validator_set_post {
+            None => errors.push(Error::MissingValidatorSetUpdate),
+            Some(sets) => {
+                let validator_sets = sets.get(pipeline_epoch);
+                for address in unjailed_validators {
+                    let is_in_set = validator_sets.map_or(false, |set| {
+                        set.active
+                            .iter()
+                            .chain(set.inactive.iter())
+                            .any(|validator| validator.address == address)
+                    });
+                    if !is_in_set {
+                        errors.push(
+                            Error::UnjailedValidatorMissingInValidatorSet(
+                                address,
+                            ),
+                        )
+                    }
+                }
+            }
+        }
+    }
+
     // Sum the bond totals
     let bond_delta = bond_delta
         .values()
@@ -627,6 +739,24 @@ struct Accumulator {
     validator_set_pre: Option<ValidatorSets>,
     validator_set_post: Option<ValidatorSets>,
     new_validators: HashMap<Address, NewValidator>,
+    /// Jailed validators that are being unjailed
+    unjailed_validators: HashSet<Address>,
+    /// Bonds from which tokens are being redelegated with the redelegated
+    /// deltas
+    redelegated_bonds: HashMap<BondId, HashMap<(Epoch, Epoch), token::Amount>>,
+    /// Bonds into which tokens are being redelegated with the sum of the
+    /// redelegated tokens
+    redelegated_into_bonds: HashMap<BondId, token::Amount>,
+    /// Bonds that are being updated with the change of their tokens
+    changed_bonds: HashMap<BondId, token::Change>,
+    /// Bonds from which tokens are being redelegated with the deltas removed
+    /// from them at the pipeline offset, with their validator's slashes
+    /// applied
+    removed_bond_deltas:
+        HashMap<BondId, HashMap<(Epoch, Epoch), token::Amount>>,
+    /// Existing validators whose consensus key is being changed with the new
+    /// key at pipeline epoch
+    rotated_consensus_keys: HashMap<Address, common::PublicKey>,
 }
 
 /// An empty local type to re-use trait bounds for the functions associated with
@@ -641,7 +771,7 @@ struct Validate {
 impl Validate {
     fn accumulate_changes(
         changes: Vec<DataUpdate>,
-        _params: &PosParams,
+        params: &PosParams,
         constants: &Constants,
         errors: &mut Vec<Error>,
     ) -> Accumulator {
@@ -660,8 +790,31 @@ impl Validate {
             validator_set_pre,
             validator_set_post,
             new_validators,
+            unjailed_validators,
+            redelegated_bonds,
+            redelegated_into_bonds,
+            changed_bonds,
+            removed_bond_deltas,
+            rotated_consensus_keys,
         } = &mut accumulator;
 
+        // Redelegations have to be checked before bonds, because the bonds
+        // from which tokens are redelegated may be decremented at the
+        // pipeline offset
+        for change in &changes {
+            if let Redelegation { id, data } = change {
+                Self::redelegation(
+                    params,
+                    constants,
+                    errors,
+                    redelegated_bonds,
+                    redelegated_into_bonds,
+                    id,
+                    data,
+                )
+            }
+        }
+
         for change in changes {
             match change {
                 Validator { address, update } => match update {
@@ -669,6 +822,7 @@ impl Validate {
                         constants,
                         errors,
                         new_validators,
+                        unjailed_validators,
                         address,
                         data,
                     ),
@@ -676,6 +830,7 @@ impl Validate {
                         constants,
                         errors,
                         new_validators,
+                        rotated_consensus_keys,
                         address,
                         data,
                     ),
@@ -706,11 +861,44 @@ impl Validate {
                             data,
                         )
                     }
+                    MetaData(data) => {
+                        Self::validator_metadata(errors, address, data)
+                    }
                 },
                 Balance(data) => Self::balance(errors, balance_delta, data),
-                Bond { id, data, slashes } => {
-                    Self::bond(constants, errors, bond_delta, id, data, slashes)
+                Bond {
+                    id,
+                    data,
+                    slashes,
+                    redelegations,
+                } => {
+                    let is_redelegated = redelegated_bonds.contains_key(&id);
+                    // Tokens redelegated into a bond cannot be redelegated
+                    // again while they may still be slashed
+                    if is_redelegated
+                        && redelegations.iter().any(|redelegation| {
+                            redelegation
+                                .is_slashable(params, constants.current_epoch)
+                        })
+                    {
+                        errors.push(Error::SlashableRedelegationRedelegated(
+                            id.clone(),
+                        ))
+                    }
+                    Self::bond(
+                        params,
+                        constants,
+                        errors,
+                        bond_delta,
+                        changed_bonds,
+                        removed_bond_deltas,
+                        id,
+                        data,
+                        slashes,
+                        is_redelegated,
+                    )
                 }
+                Redelegation { .. } => {}
                 Unbond { id, data, slashes } => Self::unbond(
                     constants,
                     errors,
@@ -750,6 +938,7 @@ impl Validate {
         constants: &Constants,
         errors: &mut Vec<Error>,
         new_validators: &mut HashMap<Address, NewValidator>,
+        unjailed_validators: &mut HashSet<Address>,
         address: Address,
         data: Data<ValidatorStates>,
     ) {
@@ -809,6 +998,15 @@ impl Validate {
                     (Some(Pending), Some(Candidate) | Some(Inactive))
                     | (Some(Candidate), Some(Inactive))
                     | (Some(Inactive), Some(Candidate) | Some(Pending)) => {}
+                    // A validator may only be unjailed once it's been jailed
+                    // in the current epoch. Validators can only be jailed by
+                    // the protocol, never in a transaction.
+                    (Some(Jailed), Some(Candidate))
+                        if pre.get(constants.current_epoch)
+                            == Some(&Jailed) =>
+                    {
+                        unjailed_validators.insert(address);
+                    }
                     _ => errors.push(Error::InvalidNewValidatorState(
                         constants.pipeline_epoch.into(),
                     )),
@@ -825,6 +1023,7 @@ impl Validate {
         constants: &Constants,
         errors: &mut Vec<Error>,
         new_validators: &mut HashMap<Address, NewValidator>,
+        rotated_consensus_keys: &mut HashMap<Address, common::PublicKey>,
         address: Address,
         data: Data<ValidatorConsensusKeys>,
     ) {
@@ -868,6 +1067,22 @@ impl Validate {
                         ),
                     }
                 }
+                // A new key may be set at pipeline epoch
+                match (
+                    pre.get(constants.pipeline_epoch),
+                    post.get(constants.pipeline_epoch),
+                ) {
+                    (Some(key_pre), Some(key_post)) if key_pre == key_post => {}
+                    (_, Some(key_post)) => {
+                        rotated_consensus_keys
+                            .insert(address, key_post.clone());
+                    }
+                    (_, None) => {
+                        errors.push(Error::InvalidValidatorConsensusKeyUpdate(
+                            constants.pipeline_epoch.into(),
+                        ))
+                    }
+                }
             }
             (Some(_), None) => {
                 errors.push(Error::ValidatorConsensusKeyIsRequired(address))
@@ -1151,6 +1366,21 @@ impl Validate {
         }
     }
 
+    fn validator_metadata(
+        errors: &mut Vec<Error>,
+        address: Address,
+        data: Data<ValidatorMetaData>,
+    ) {
+        match data.post {
+            Some(post) => {
+                if !post.is_valid() {
+                    errors.push(Error::InvalidValidatorMetaData(address))
+                }
+            }
+            None => errors.push(Error::ValidatorMetaDataRemoved(address)),
+        }
+    }
+
     fn balance(
         errors: &mut Vec<Error>,
         balance_delta: &mut token::Change,
@@ -1167,13 +1397,21 @@ impl Validate {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn bond(
+        params: &PosParams,
         constants: &Constants,
         errors: &mut Vec<Error>,
         bond_delta: &mut HashMap<Address, token::Change>,
+        changed_bonds: &mut HashMap<BondId, token::Change>,
+        removed_bond_deltas: &mut HashMap<
+            BondId,
+            HashMap<(Epoch, Epoch), token::Amount>,
+        >,
         id: BondId,
         data: Data<Bonds>,
         slashes: Vec<Slash>,
+        is_redelegated: bool,
     ) {
         match (data.pre, data.post) {
             // Bond may be updated from newly bonded tokens and unbonding
@@ -1297,7 +1535,13 @@ impl Validate {
                                 }
                             }
                         }
-                        if epoch != constants.unbonding_epoch {
+                        // Tokens may also be redelegated from the bond at
+                        // the pipeline offset
+                        let is_neg_delta_epoch = epoch
+                            == constants.unbonding_epoch
+                            || (is_redelegated
+                                && epoch == constants.pipeline_epoch);
+                        if !is_neg_delta_epoch {
                             match neg_deltas.get(&epoch) {
                                 Some(deltas) => {
                                     if -*deltas
@@ -1353,6 +1597,27 @@ impl Validate {
                             acc + *delta
                         });
 
+                if is_redelegated {
+                    let removed_at_pipeline = |bonds: &Bonds| {
+                        bonds
+                            .get_delta_at_epoch(constants.pipeline_epoch)
+                            .map(|bond| bond.neg_deltas)
+                            .unwrap_or_default()
+                    };
+                    let pre_removed = removed_at_pipeline(&pre);
+                    let post_removed = removed_at_pipeline(&post);
+                    if post_removed > pre_removed {
+                        let deltas = Self::removed_bond_deltas(
+                            params,
+                            constants,
+                            &pre,
+                            post_removed - pre_removed,
+                            &slashes,
+                        );
+                        removed_bond_deltas.insert(id.clone(), deltas);
+                    }
+                }
+                changed_bonds.insert(id.clone(), total);
                 if total != token::Change::default() {
                     let bond_entry =
                         bond_delta.entry(id.validator).or_default();
@@ -1419,6 +1684,7 @@ impl Validate {
                 if total_delta == token::Change::default() {
                     errors.push(Error::EmptyBond(id.clone()))
                 }
+                changed_bonds.insert(id.clone(), total_delta);
                 let bond_entry = bond_delta.entry(id.validator).or_default();
                 *bond_entry += total_delta;
             }
@@ -1447,6 +1713,22 @@ impl Validate {
                         total_delta += token::Change::from(bond.neg_deltas)
                     }
                 }
+                // All the tokens may have been redelegated
+                if is_redelegated {
+                    let amount = pre
+                        .get_at_offset(
+                            constants.current_epoch,
+                            DynEpochOffset::PipelineLen,
+                            params,
+                        )
+                        .unwrap_or_default()
+                        .sum();
+                    let deltas = Self::removed_bond_deltas(
+                        params, constants, &pre, amount, &slashes,
+                    );
+                    removed_bond_deltas.insert(id.clone(), deltas);
+                }
+                changed_bonds.insert(id.clone(), total_delta);
                 let bond_entry = bond_delta.entry(id.validator).or_default();
                 *bond_entry += total_delta;
             }
@@ -1454,6 +1736,85 @@ impl Validate {
         }
     }
 
+    /// Replay the removal of the given amount of redelegated tokens from a
+    /// bond to find the deltas that have been redelegated, with the slashes of
+    /// the bond's validator applied.
+    fn removed_bond_deltas(
+        params: &PosParams,
+        constants: &Constants,
+        pre: &Bonds,
+        amount: token::Amount,
+        slashes: &[Slash],
+    ) -> HashMap<(Epoch, Epoch), token::Amount> {
+        let mut bond = pre.clone();
+        remove_from_bond(
+            params,
+            &mut bond,
+            amount,
+            DynEpochOffset::PipelineLen,
+            constants.current_epoch,
+        )
+        .into_iter()
+        .map(|((epoch_start, epoch_end), delta)| {
+            let delta = apply_slashes(delta, epoch_start, slashes);
+            ((epoch_start, epoch_end), delta)
+        })
+        .collect()
+    }
+
+    fn redelegation(
+        params: &PosParams,
+        constants: &Constants,
+        errors: &mut Vec<Error>,
+        redelegated_bonds: &mut HashMap<
+            BondId,
+            HashMap<(Epoch, Epoch), token::Amount>,
+        >,
+        redelegated_into_bonds: &mut HashMap<BondId, token::Amount>,
+        id: &BondId,
+        data: &Data<Redelegations>,
+    ) {
+        let pre = data.pre.clone().unwrap_or_default();
+        let post = data.post.clone().unwrap_or_default();
+        // Redelegations may only be removed once they can no longer be
+        // slashed
+        for redelegation in &pre {
+            if !post.contains(redelegation)
+                && redelegation.is_slashable(params, constants.current_epoch)
+            {
+                errors.push(Error::SlashableRedelegationRemoved(id.clone()))
+            }
+        }
+        // New redelegations must be redelegated from another validator in the
+        // current epoch and the tokens must leave the source validator at the
+        // pipeline offset
+        for redelegation in &post {
+            if pre.contains(redelegation) {
+                continue;
+            }
+            let is_valid = redelegation.epoch == constants.current_epoch
+                && redelegation.src_validator != id.validator
+                && redelegation.deltas.keys().all(|(_start, end)| {
+                    *end + 1_u64 == constants.pipeline_epoch
+                });
+            if !is_valid {
+                errors.push(Error::InvalidRedelegation(id.clone()))
+            }
+            let src_deltas = redelegated_bonds
+                .entry(BondId {
+                    source: id.source.clone(),
+                    validator: redelegation.src_validator.clone(),
+                })
+                .or_default();
+            let dest_amount =
+                redelegated_into_bonds.entry(id.clone()).or_default();
+            for (epochs, delta) in &redelegation.deltas {
+                *src_deltas.entry(*epochs).or_default() += *delta;
+                *dest_amount += *delta;
+            }
+        }
+    }
+
     fn unbond(
         constants: &Constants,
         errors: &mut Vec<Error>,
@@ -1697,3 +2058,140 @@ impl Validate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+        established_address_4,
+    };
+
+    use super::*;
+    use crate::types::{Bond, Redelegation};
+
+    /// Setup the changes of a redelegation of the given `amount` from a bond
+    /// with the given incoming `redelegations`, recording the
+    /// `recorded_amount` as redelegated.
+    fn redelegation_changes(
+        params: &PosParams,
+        amount: token::Amount,
+        recorded_amount: token::Amount,
+        redelegations: Redelegations,
+    ) -> Vec<DataUpdate> {
+        let owner = established_address_1();
+        let src_id = BondId {
+            source: owner.clone(),
+            validator: established_address_2(),
+        };
+        let dest_id = BondId {
+            source: owner,
+            validator: established_address_3(),
+        };
+        let current_epoch = Epoch::default();
+        let src_pre = Bonds::init_at_genesis(
+            Bond {
+                pos_deltas: HashMap::from([(current_epoch, amount)]),
+                neg_deltas: token::Amount::default(),
+            },
+            current_epoch,
+        );
+        let mut src_post = src_pre.clone();
+        let deltas = remove_from_bond(
+            params,
+            &mut src_post,
+            amount,
+            DynEpochOffset::PipelineLen,
+            current_epoch,
+        )
+        .into_keys()
+        .map(|epochs| (epochs, recorded_amount))
+        .collect();
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        let dest_post = Bonds::init_at_offset(
+            Bond {
+                pos_deltas: HashMap::from([(pipeline_epoch, amount)]),
+                neg_deltas: token::Amount::default(),
+            },
+            current_epoch,
+            DynEpochOffset::PipelineLen,
+            params,
+        );
+        let redelegation = Redelegation {
+            src_validator: src_id.validator.clone(),
+            epoch: current_epoch,
+            deltas,
+        };
+        vec![
+            DataUpdate::Bond {
+                id: src_id,
+                data: Data {
+                    pre: Some(src_pre),
+                    post: Some(src_post),
+                },
+                slashes: vec![],
+                redelegations,
+            },
+            DataUpdate::Bond {
+                id: dest_id.clone(),
+                data: Data {
+                    pre: None,
+                    post: Some(dest_post),
+                },
+                slashes: vec![],
+                redelegations: vec![],
+            },
+            DataUpdate::Redelegation {
+                id: dest_id,
+                data: Data {
+                    pre: None,
+                    post: Some(vec![redelegation]),
+                },
+            },
+        ]
+    }
+
+    /// Test that the redelegated tokens must match the tokens removed from
+    /// the source bond and added to the destination bond and that a bond
+    /// with slashable incoming redelegations cannot be redelegated.
+    #[test]
+    fn test_redelegation_validation() {
+        let params = PosParams::default();
+        let amount = token::Amount::from(1_000);
+        let has_error =
+            |errors: &[Error], f: fn(&Error) -> bool| errors.iter().any(f);
+        let is_invalid_amount =
+            |err: &Error| matches!(err, Error::InvalidRedelegatedAmount(_));
+        let is_chained = |err: &Error| {
+            matches!(err, Error::SlashableRedelegationRedelegated(_))
+        };
+
+        let changes = redelegation_changes(&params, amount, amount, vec![]);
+        let errors = validate(&params, changes, Epoch::default());
+        assert!(!has_error(&errors, is_invalid_amount));
+        assert!(!has_error(&errors, is_chained));
+
+        // More tokens recorded as redelegated than moved between the bonds
+        let changes = redelegation_changes(
+            &params,
+            amount,
+            amount + token::Amount::from(1),
+            vec![],
+        );
+        let errors = validate(&params, changes, Epoch::default());
+        assert!(has_error(&errors, is_invalid_amount));
+
+        // Tokens redelegated into the source bond that may still be slashed
+        let incoming = Redelegation {
+            src_validator: established_address_4(),
+            epoch: Epoch::default(),
+            deltas: HashMap::from([(
+                (Epoch::default(), Epoch::default()),
+                amount,
+            )]),
+        };
+        let changes =
+            redelegation_changes(&params, amount, amount, vec![incoming]);
+        let errors = validate(&params, changes, Epoch::default());
+        assert!(has_error(&errors, is_chained));
+    }
+}
@@ -1,17 +1,34 @@
 //! Validity predicate for the Ethereum bridge
+//!
+//! The bridge's keys may only be changed by:
+//! - applying the transfers to Namada that have been attested by the
+//!   validators, which mints the wrapped ERC20 tokens or releases the escrowed
+//!   native tokens
+//! - queueing transfers to Ethereum, which burns the wrapped ERC20 tokens or
+//!   escrows the native tokens
+//! - transfers of the wrapped ERC20 tokens between Namada accounts, which must
+//!   preserve the supply of every wrapped token
+//!
+//! The attested events and the ERC20 address of the native token can only be
+//! written by the protocol.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::ledger::native_vp::{Ctx, NativeVp};
+pub use namada_core::ledger::eth_bridge::ADDRESS;
+
+use super::storage;
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::ledger::storage as ledger_storage;
 use crate::ledger::storage::StorageHasher;
+use crate::ledger::storage_api::StorageRead;
 use crate::types::address::{Address, InternalAddress};
+use crate::types::ethereum_events::{
+    EthAddress, TransferToEthereum, TransferToNamada,
+};
 use crate::types::storage::Key;
+use crate::types::token;
 use crate::vm::WasmCacheAccess;
 
-/// Internal address for the Ethereum bridge VP
-pub const ADDRESS: Address = Address::Internal(InternalAddress::EthBridge);
-
 /// Validity predicate for the Ethereum bridge
 pub struct EthBridge<'ctx, DB, H, CA>
 where
@@ -26,8 +43,175 @@ where
 #[allow(missing_docs)]
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Internal error")]
-    Internal,
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Ethereum bridge VP functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Changes of token balances, keyed by the balance storage keys
+type BalanceChanges = BTreeMap<Key, token::Change>;
+
+impl<'a, DB, H, CA> EthBridge<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Read the nonce at the given key before and after the tx.
+    fn read_nonces(&self, key: &Key) -> Result<(u64, u64)> {
+        let pre = self.ctx.pre().read(key)?.unwrap_or_default();
+        let post = self.ctx.post().read(key)?.unwrap_or_default();
+        Ok((pre, post))
+    }
+
+    /// Read the change of the balance at the given key made by the tx.
+    fn read_balance_change(&self, key: &Key) -> Result<token::Change> {
+        let pre: token::Amount = self.ctx.pre().read(key)?.unwrap_or_default();
+        let post: token::Amount =
+            self.ctx.post().read(key)?.unwrap_or_default();
+        Ok(post.change() - pre.change())
+    }
+
+    /// Add the balance changes of the attested transfers to Namada applied by
+    /// the tx. Returns `false` if any of the applied transfers is missing an
+    /// attestation.
+    fn add_transfers_to_namada(
+        &self,
+        native_token: &Address,
+        native_erc20: Option<&EthAddress>,
+        changes: &mut BalanceChanges,
+    ) -> Result<bool> {
+        let (pre_nonce, post_nonce) =
+            self.read_nonces(&storage::transfers_to_namada_nonce_key())?;
+        if post_nonce < pre_nonce {
+            tracing::debug!(
+                pre_nonce,
+                post_nonce,
+                "The nonce of the transfers to Namada cannot decrease"
+            );
+            return Ok(false);
+        }
+        for nonce in pre_nonce..post_nonce {
+            let transfers: Option<Vec<TransferToNamada>> = self
+                .ctx
+                .pre()
+                .read(&storage::attested_transfers_to_namada_key(nonce))?;
+            let transfers = match transfers {
+                Some(transfers) => transfers,
+                None => {
+                    tracing::debug!(
+                        nonce,
+                        "The transfers to Namada haven't been attested"
+                    );
+                    return Ok(false);
+                }
+            };
+            for TransferToNamada {
+                amount,
+                asset,
+                receiver,
+            } in transfers
+            {
+                if Some(&asset) == native_erc20 {
+                    add_change(
+                        changes,
+                        token::balance_key(native_token, &receiver),
+                        amount.change(),
+                    );
+                    add_change(
+                        changes,
+                        storage::escrow_key(native_token),
+                        -amount.change(),
+                    );
+                } else {
+                    add_change(
+                        changes,
+                        storage::wrapped_erc20_balance_key(&asset, &receiver),
+                        amount.change(),
+                    );
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Add the balance changes of the transfers to Ethereum queued by the tx.
+    /// Returns `false` if the queue hasn't been extended with exactly one new
+    /// transfer for every new nonce.
+    fn add_transfers_to_ethereum(
+        &self,
+        keys_changed: &BTreeSet<Key>,
+        native_token: &Address,
+        native_erc20: Option<&EthAddress>,
+        changes: &mut BalanceChanges,
+    ) -> Result<bool> {
+        let (pre_nonce, post_nonce) =
+            self.read_nonces(&storage::transfers_to_ethereum_nonce_key())?;
+        if post_nonce < pre_nonce {
+            tracing::debug!(
+                pre_nonce,
+                post_nonce,
+                "The nonce of the transfers to Ethereum cannot decrease"
+            );
+            return Ok(false);
+        }
+        let new_transfer_keys: BTreeSet<Key> = (pre_nonce..post_nonce)
+            .map(storage::pending_transfer_key)
+            .collect();
+        let changed_queue_keys: BTreeSet<&Key> = keys_changed
+            .iter()
+            .filter(|key| storage::is_queue_key(key))
+            .collect();
+        if changed_queue_keys
+            != new_transfer_keys.iter().collect::<BTreeSet<_>>()
+        {
+            tracing::debug!(
+                "Only the new transfers to Ethereum can be added to the queue"
+            );
+            return Ok(false);
+        }
+        for key in &new_transfer_keys {
+            if self.ctx.pre().has_key(key)? {
+                tracing::debug!(%key, "A pending transfer cannot be replaced");
+                return Ok(false);
+            }
+            let transfer: Option<TransferToEthereum> =
+                self.ctx.post().read(key)?;
+            let TransferToEthereum {
+                amount,
+                asset,
+                sender,
+                receiver: _,
+            } = match transfer {
+                Some(transfer) => transfer,
+                None => {
+                    tracing::debug!(%key, "Missing a pending transfer");
+                    return Ok(false);
+                }
+            };
+            if Some(&asset) == native_erc20 {
+                add_change(
+                    changes,
+                    token::balance_key(native_token, &sender),
+                    -amount.change(),
+                );
+                add_change(
+                    changes,
+                    storage::escrow_key(native_token),
+                    amount.change(),
+                );
+            } else {
+                add_change(
+                    changes,
+                    storage::wrapped_erc20_balance_key(&asset, &sender),
+                    -amount.change(),
+                );
+            }
+        }
+        Ok(true)
+    }
 }
 
 impl<'a, DB, H, CA> NativeVp for EthBridge<'a, DB, H, CA>
@@ -42,16 +226,477 @@ where
 
     fn validate_tx(
         &self,
-        _tx_data: &[u8],
-        _keys_changed: &BTreeSet<Key>,
-        _verifiers: &BTreeSet<Address>,
-    ) -> Result<bool, Self::Error> {
+        tx_data: &[u8],
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
         tracing::debug!(
-            tx_data_len = _tx_data.len(),
-            keys_changed_len = _keys_changed.len(),
-            verifiers_len = _verifiers.len(),
+            tx_data_len = tx_data.len(),
+            keys_changed_len = keys_changed.len(),
+            verifiers_len = verifiers.len(),
             "Validity predicate triggered",
         );
-        Ok(false)
+        let native_token = self.ctx.pre().get_native_token()?;
+        let escrow_key = storage::escrow_key(&native_token);
+        let nonce_keys = [
+            storage::transfers_to_namada_nonce_key(),
+            storage::transfers_to_ethereum_nonce_key(),
+        ];
+
+        // Any other key of the bridge is written only by the protocol
+        let forbidden_key = keys_changed.iter().find(|key| {
+            storage::is_eth_bridge_key(key)
+                && storage::is_wrapped_erc20_balance_key(key).is_none()
+                && !storage::is_queue_key(key)
+                && !nonce_keys.contains(key)
+        });
+        if let Some(key) = forbidden_key {
+            tracing::debug!(%key, "The key cannot be changed by a tx");
+            return Ok(false);
+        }
+
+        let native_erc20: Option<EthAddress> =
+            self.ctx.pre().read(&storage::native_erc20_key())?;
+        let mut expected_changes = BalanceChanges::default();
+        if !self.add_transfers_to_namada(
+            &native_token,
+            native_erc20.as_ref(),
+            &mut expected_changes,
+        )? || !self.add_transfers_to_ethereum(
+            keys_changed,
+            &native_token,
+            native_erc20.as_ref(),
+            &mut expected_changes,
+        )? {
+            return Ok(false);
+        }
+
+        // The balances affected by the bridge transfers must change exactly
+        // as expected
+        for (key, expected) in &expected_changes {
+            let change = self.read_balance_change(key)?;
+            if change != *expected {
+                tracing::debug!(
+                    %key,
+                    ?change,
+                    ?expected,
+                    "Unexpected balance change"
+                );
+                return Ok(false);
+            }
+        }
+        if !expected_changes.contains_key(&escrow_key)
+            && keys_changed.contains(&escrow_key)
+            && self.read_balance_change(&escrow_key)? != 0
+        {
+            tracing::debug!(
+                "The escrow can only change with the bridge transfers"
+            );
+            return Ok(false);
+        }
+
+        // The remaining changes of the wrapped tokens are transfers between
+        // Namada accounts, authorized by their VPs, which must not change the
+        // supply
+        let mut supply_changes: BTreeMap<EthAddress, token::Change> =
+            BTreeMap::new();
+        for key in keys_changed {
+            if expected_changes.contains_key(key) {
+                continue;
+            }
+            if let Some((asset, _owner)) =
+                storage::is_wrapped_erc20_balance_key(key)
+            {
+                let change = self.read_balance_change(key)?;
+                *supply_changes.entry(asset).or_default() += change;
+            }
+        }
+        if let Some((asset, change)) =
+            supply_changes.iter().find(|(_asset, change)| **change != 0)
+        {
+            tracing::debug!(
+                %asset,
+                ?change,
+                "The supply of a wrapped token can only change with the \
+                 bridge transfers"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+/// Add a change to the balance at the given key.
+fn add_change(changes: &mut BalanceChanges, key: Key, change: token::Change) {
+    *changes.entry(key).or_default() += change;
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage::write_log::WriteLog;
+    use crate::proto::Tx;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::types::ethereum_events::testing::{
+        DAI_ERC20_ETH_ADDRESS, ETH_RECEIVER, WNAM_ERC20_ETH_ADDRESS,
+    };
+    use crate::types::ethereum_events::EthereumEvent;
+    use crate::types::storage::TxIndex;
+    use crate::vm::wasm;
+
+    /// Stands for the Ethereum bridge contracts and the validators attesting
+    /// to their events, writing the attested events directly to storage
+    #[derive(Default)]
+    struct FakeEventSource {
+        next_nonce: u64,
+    }
+
+    impl FakeEventSource {
+        /// Emit and attest the next batch of transfers to Namada
+        fn attest_transfers(
+            &mut self,
+            storage: &mut TestStorage,
+            transfers: Vec<TransferToNamada>,
+        ) -> EthereumEvent {
+            let nonce = self.next_nonce;
+            self.next_nonce += 1;
+            let key = storage::attested_transfers_to_namada_key(nonce);
+            storage
+                .write(&key, transfers.try_to_vec().unwrap())
+                .unwrap();
+            EthereumEvent::TransfersToNamada { nonce, transfers }
+        }
+    }
+
+    fn setup() -> (TestStorage, WriteLog) {
+        let mut storage = TestStorage::default();
+        storage
+            .write(
+                &storage::native_erc20_key(),
+                WNAM_ERC20_ETH_ADDRESS.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        (storage, WriteLog::default())
+    }
+
+    fn write_pre<T: BorshSerialize>(
+        storage: &mut TestStorage,
+        key: &Key,
+        value: T,
+    ) {
+        storage.write(key, value.try_to_vec().unwrap()).unwrap();
+    }
+
+    fn write_post<T: BorshSerialize>(
+        write_log: &mut WriteLog,
+        key: &Key,
+        value: T,
+    ) {
+        write_log.write(key, value.try_to_vec().unwrap()).unwrap();
+    }
+
+    fn validate(storage: &TestStorage, write_log: &WriteLog) -> bool {
+        let tx_index = TxIndex::default();
+        let tx = Tx::new(vec![], None);
+        let gas_meter = VpGasMeter::new(0);
+        let (vp_wasm_cache, _vp_cache_dir) =
+            wasm::compilation_cache::common::testing::cache();
+        let (_verifiers, keys_changed) =
+            write_log.verifiers_and_changed_keys(&BTreeSet::new());
+        let verifiers = BTreeSet::new();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            storage,
+            write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        EthBridge { ctx }
+            .validate_tx(&[], &keys_changed, &verifiers)
+            .unwrap()
+    }
+
+    /// Apply the attested transfers to Namada like a tx would.
+    fn apply_transfers(
+        storage: &TestStorage,
+        write_log: &mut WriteLog,
+        event: &EthereumEvent,
+    ) {
+        let EthereumEvent::TransfersToNamada { nonce, transfers } = event;
+        let native_token = storage.native_token.clone();
+        let escrow_key = storage::escrow_key(&native_token);
+        let pre_escrow = read_amount(storage, &escrow_key);
+        let mut escrow = pre_escrow;
+        for transfer in transfers {
+            let key = if transfer.asset == WNAM_ERC20_ETH_ADDRESS {
+                escrow.spend(&transfer.amount);
+                token::balance_key(&native_token, &transfer.receiver)
+            } else {
+                storage::wrapped_erc20_balance_key(
+                    &transfer.asset,
+                    &transfer.receiver,
+                )
+            };
+            write_post(write_log, &key, transfer.amount);
+        }
+        if escrow != pre_escrow {
+            write_post(write_log, &escrow_key, escrow);
+        }
+        write_post(
+            write_log,
+            &storage::transfers_to_namada_nonce_key(),
+            nonce + 1,
+        );
+    }
+
+    fn read_amount(storage: &TestStorage, key: &Key) -> token::Amount {
+        storage
+            .read(key)
+            .unwrap()
+            .0
+            .map_or_else(token::Amount::default, |bytes| {
+                token::Amount::try_from_slice(&bytes).unwrap()
+            })
+    }
+
+    #[test]
+    fn test_attested_transfers_to_namada() {
+        let (mut storage, mut write_log) = setup();
+        let mut events = FakeEventSource::default();
+        let escrow_key = storage::escrow_key(&storage.native_token);
+        write_pre(&mut storage, &escrow_key, token::Amount::whole(100));
+
+        let event = events.attest_transfers(
+            &mut storage,
+            vec![
+                TransferToNamada {
+                    amount: token::Amount::whole(10),
+                    asset: DAI_ERC20_ETH_ADDRESS,
+                    receiver: established_address_1(),
+                },
+                TransferToNamada {
+                    amount: token::Amount::whole(20),
+                    asset: WNAM_ERC20_ETH_ADDRESS,
+                    receiver: established_address_2(),
+                },
+            ],
+        );
+        apply_transfers(&storage, &mut write_log, &event);
+        assert!(validate(&storage, &write_log));
+
+        // Releasing more than the transferred native tokens
+        write_post(&mut write_log, &escrow_key, token::Amount::whole(70));
+        assert!(!validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_unattested_transfers_to_namada() {
+        let (mut storage, mut write_log) = setup();
+        let mut events = FakeEventSource::default();
+        let transfers = vec![TransferToNamada {
+            amount: token::Amount::whole(10),
+            asset: DAI_ERC20_ETH_ADDRESS,
+            receiver: established_address_1(),
+        }];
+
+        // Minting without any attested event
+        let event = EthereumEvent::TransfersToNamada {
+            nonce: 0,
+            transfers: transfers.clone(),
+        };
+        apply_transfers(&storage, &mut write_log, &event);
+        assert!(!validate(&storage, &write_log));
+        write_log.drop_tx();
+
+        // Minting without bumping the nonce
+        events.attest_transfers(&mut storage, transfers);
+        let key = storage::wrapped_erc20_balance_key(
+            &DAI_ERC20_ETH_ADDRESS,
+            &established_address_1(),
+        );
+        write_post(&mut write_log, &key, token::Amount::whole(10));
+        assert!(!validate(&storage, &write_log));
+
+        // Minting more than attested
+        write_post(
+            &mut write_log,
+            &storage::transfers_to_namada_nonce_key(),
+            1_u64,
+        );
+        write_post(&mut write_log, &key, token::Amount::whole(11));
+        assert!(!validate(&storage, &write_log));
+
+        write_post(&mut write_log, &key, token::Amount::whole(10));
+        assert!(validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_transfers_to_namada_wrong_receiver() {
+        let (mut storage, mut write_log) = setup();
+        let mut events = FakeEventSource::default();
+        events.attest_transfers(
+            &mut storage,
+            vec![TransferToNamada {
+                amount: token::Amount::whole(10),
+                asset: DAI_ERC20_ETH_ADDRESS,
+                receiver: established_address_1(),
+            }],
+        );
+        let key = storage::wrapped_erc20_balance_key(
+            &DAI_ERC20_ETH_ADDRESS,
+            &established_address_2(),
+        );
+        write_post(&mut write_log, &key, token::Amount::whole(10));
+        write_post(
+            &mut write_log,
+            &storage::transfers_to_namada_nonce_key(),
+            1_u64,
+        );
+        assert!(!validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_transfer_wrapped_to_ethereum() {
+        let (mut storage, mut write_log) = setup();
+        let sender = established_address_1();
+        let balance_key =
+            storage::wrapped_erc20_balance_key(&DAI_ERC20_ETH_ADDRESS, &sender);
+        write_pre(&mut storage, &balance_key, token::Amount::whole(10));
+
+        let transfer = TransferToEthereum {
+            amount: token::Amount::whole(4),
+            asset: DAI_ERC20_ETH_ADDRESS,
+            sender,
+            receiver: ETH_RECEIVER,
+        };
+        write_post(&mut write_log, &balance_key, token::Amount::whole(6));
+        // Burning without queueing the transfer
+        assert!(!validate(&storage, &write_log));
+
+        write_post(
+            &mut write_log,
+            &storage::pending_transfer_key(0),
+            transfer.clone(),
+        );
+        // Queueing without bumping the nonce
+        assert!(!validate(&storage, &write_log));
+
+        write_post(
+            &mut write_log,
+            &storage::transfers_to_ethereum_nonce_key(),
+            1_u64,
+        );
+        assert!(validate(&storage, &write_log));
+
+        // Burning less than the transferred amount
+        write_post(&mut write_log, &balance_key, token::Amount::whole(7));
+        assert!(!validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_transfer_native_to_ethereum() {
+        let (mut storage, mut write_log) = setup();
+        let sender = established_address_1();
+        let native_token = storage.native_token.clone();
+        let balance_key = token::balance_key(&native_token, &sender);
+        let escrow_key = storage::escrow_key(&native_token);
+        write_pre(&mut storage, &balance_key, token::Amount::whole(10));
+
+        let transfer = TransferToEthereum {
+            amount: token::Amount::whole(4),
+            asset: WNAM_ERC20_ETH_ADDRESS,
+            sender,
+            receiver: ETH_RECEIVER,
+        };
+        write_post(&mut write_log, &balance_key, token::Amount::whole(6));
+        write_post(&mut write_log, &storage::pending_transfer_key(0), transfer);
+        write_post(
+            &mut write_log,
+            &storage::transfers_to_ethereum_nonce_key(),
+            1_u64,
+        );
+        // The tokens must be escrowed
+        assert!(!validate(&storage, &write_log));
+
+        write_post(&mut write_log, &escrow_key, token::Amount::whole(4));
+        assert!(validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_transfer_wrapped_between_accounts() {
+        let (mut storage, mut write_log) = setup();
+        let source_key = storage::wrapped_erc20_balance_key(
+            &DAI_ERC20_ETH_ADDRESS,
+            &established_address_1(),
+        );
+        let target_key = storage::wrapped_erc20_balance_key(
+            &DAI_ERC20_ETH_ADDRESS,
+            &established_address_2(),
+        );
+        write_pre(&mut storage, &source_key, token::Amount::whole(10));
+
+        write_post(&mut write_log, &source_key, token::Amount::whole(7));
+        write_post(&mut write_log, &target_key, token::Amount::whole(3));
+        assert!(validate(&storage, &write_log));
+
+        // Minting without any attested event
+        write_post(&mut write_log, &target_key, token::Amount::whole(4));
+        assert!(!validate(&storage, &write_log));
+    }
+
+    #[test]
+    fn test_protocol_keys() {
+        let (mut storage, mut write_log) = setup();
+
+        // Writing an attestation
+        let key = storage::attested_transfers_to_namada_key(0);
+        write_post(&mut write_log, &key, Vec::<TransferToNamada>::new());
+        assert!(!validate(&storage, &write_log));
+        write_log.drop_tx();
+
+        // Changing the native ERC20 address
+        write_post(
+            &mut write_log,
+            &storage::native_erc20_key(),
+            DAI_ERC20_ETH_ADDRESS,
+        );
+        assert!(!validate(&storage, &write_log));
+        write_log.drop_tx();
+
+        // Releasing the escrow without an attested event
+        let escrow_key = storage::escrow_key(&storage.native_token);
+        write_pre(&mut storage, &escrow_key, token::Amount::whole(100));
+        write_post(&mut write_log, &escrow_key, token::Amount::whole(90));
+        assert!(!validate(&storage, &write_log));
+        write_log.drop_tx();
+
+        // Removing a pending transfer
+        write_pre(
+            &mut storage,
+            &storage::pending_transfer_key(0),
+            TransferToEthereum {
+                amount: token::Amount::whole(1),
+                asset: DAI_ERC20_ETH_ADDRESS,
+                sender: established_address_1(),
+                receiver: ETH_RECEIVER,
+            },
+        );
+        write_log.delete(&storage::pending_transfer_key(0)).unwrap();
+        assert!(!validate(&storage, &write_log));
+        write_log.drop_tx();
+
+        // Writing arbitrary data to the queue
+        write_post(&mut write_log, &storage::queue_key(), "queue".to_owned());
+        assert!(!validate(&storage, &write_log));
     }
 }
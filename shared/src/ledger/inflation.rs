@@ -0,0 +1,247 @@
+//! Inflation of the staking token. The amount of tokens minted for the PoS
+//! rewards in each epoch is adjusted by a proportional-derivative (PD)
+//! controller that steers the ratio of the staked tokens towards its target.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::ledger::pos::namada_proof_of_stake::PosBase;
+use crate::ledger::storage::{self as ledger_storage, Storage, StorageHasher};
+use crate::ledger::{parameters, storage_api};
+use crate::types::token;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to read the protocol parameters: {0}")]
+    ReadParameters(parameters::ReadError),
+    #[error("Failed to write the protocol parameters: {0}")]
+    WriteParameters(parameters::WriteError),
+    #[error("Failed to read the staking token supply: {0}")]
+    TotalSupply(storage_api::Error),
+}
+
+/// Result of inflation functions
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The inputs of the PD controller for a single epoch.
+#[derive(Debug, Clone, Copy)]
+pub struct RewardsController {
+    /// Amount of the staked tokens
+    pub locked_tokens: u64,
+    /// Total supply of the staking token
+    pub total_tokens: u64,
+    /// The target ratio of the staked tokens to the total supply
+    pub locked_ratio_target: Decimal,
+    /// The ratio of the staked tokens in the last epoch
+    pub locked_ratio_last: Decimal,
+    /// Maximum annual inflation rate
+    pub max_reward_rate: Decimal,
+    /// The inflation amount minted in the last epoch
+    pub last_inflation_amount: u64,
+    /// Nominal proportional gain
+    pub p_gain_nom: Decimal,
+    /// Nominal derivative gain
+    pub d_gain_nom: Decimal,
+    /// Expected number of epochs per year
+    pub epochs_per_year: u64,
+}
+
+/// The values updated by the PD controller, to be persisted for the next
+/// epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValsToUpdate {
+    /// The current ratio of the staked tokens
+    pub locked_ratio: Decimal,
+    /// The inflation amount for the current epoch
+    pub inflation: u64,
+}
+
+impl RewardsController {
+    /// Calculate the inflation amount for the current epoch. The nominal gains
+    /// are scaled by the maximum inflation per epoch and the result is kept
+    /// between zero and this maximum.
+    pub fn run(&self) -> ValsToUpdate {
+        let locked_ratio = if self.total_tokens == 0 {
+            Decimal::ZERO
+        } else {
+            Decimal::from(self.locked_tokens) / Decimal::from(self.total_tokens)
+        };
+        let max_inflation = if self.epochs_per_year == 0 {
+            Decimal::ZERO
+        } else {
+            Decimal::from(self.total_tokens) * self.max_reward_rate
+                / Decimal::from(self.epochs_per_year)
+        };
+        let p_gain = self.p_gain_nom * max_inflation;
+        let d_gain = self.d_gain_nom * max_inflation;
+
+        let error = self.locked_ratio_target - locked_ratio;
+        let last_error = self.locked_ratio_target - self.locked_ratio_last;
+        let delta_error = error - last_error;
+        let control = p_gain * error + d_gain * delta_error;
+
+        let inflation = (Decimal::from(self.last_inflation_amount) + control)
+            .max(Decimal::ZERO)
+            .min(max_inflation);
+        ValsToUpdate {
+            locked_ratio,
+            // The inflation is bounded by the maximum inflation, which is
+            // lower than the total supply
+            inflation: inflation.floor().to_u64().unwrap_or_default(),
+        }
+    }
+}
+
+/// Run the PD controller for the current epoch with the inputs read from
+/// storage and persist the new staked ratio and inflation amount in the
+/// protocol parameters. This should be called from the new epoch hook of
+/// `Storage::update_epoch`, once the new epoch has begun. Returns the amount of
+/// tokens to be minted for the PoS rewards of the last epoch.
+pub fn update_pos_inflation<DB, H>(
+    storage: &mut Storage<DB, H>,
+) -> Result<token::Amount>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (params, _gas) =
+        parameters::read(storage).map_err(Error::ReadParameters)?;
+    let pos_params = storage.read_pos_params();
+    let epoch = storage.block.epoch;
+
+    let locked_tokens = storage
+        .read_total_deltas()
+        .get(epoch)
+        .map(token::Amount::from_change)
+        .unwrap_or_default();
+    let staking_token = storage.staking_token_address();
+    let total_tokens = read_total_supply(storage, &staking_token)?;
+
+    let ValsToUpdate {
+        locked_ratio,
+        inflation,
+    } = RewardsController {
+        locked_tokens: locked_tokens.into(),
+        total_tokens: total_tokens.into(),
+        locked_ratio_target: pos_params.target_staked_ratio,
+        locked_ratio_last: params.staked_ratio,
+        max_reward_rate: pos_params.max_inflation_rate,
+        last_inflation_amount: params.pos_inflation_amount,
+        p_gain_nom: params.pos_gain_p,
+        d_gain_nom: params.pos_gain_d,
+        epochs_per_year: params.epochs_per_year,
+    }
+    .run();
+
+    parameters::update_staked_ratio_parameter(storage, &locked_ratio)
+        .map_err(Error::WriteParameters)?;
+    parameters::update_pos_inflation_amount_parameter(storage, &inflation)
+        .map_err(Error::WriteParameters)?;
+    Ok(token::Amount::from(inflation))
+}
+
+/// Read the total supply of the given token as the sum of all the balances.
+fn read_total_supply<DB, H>(
+    storage: &Storage<DB, H>,
+    token: &crate::types::address::Address,
+) -> Result<token::Amount>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let balances = storage_api::iter_prefix::<token::Amount>(
+        storage,
+        &token::balance_prefix(token),
+    )
+    .map_err(Error::TotalSupply)?;
+    balances.try_fold(token::Amount::default(), |total, balance| {
+        let (_key, balance) = balance.map_err(Error::TotalSupply)?;
+        Ok(total + balance)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn controller() -> RewardsController {
+        RewardsController {
+            locked_tokens: 500_000,
+            total_tokens: 1_000_000,
+            locked_ratio_target: dec!(0.6667),
+            locked_ratio_last: dec!(0.5),
+            max_reward_rate: dec!(0.1),
+            last_inflation_amount: 0,
+            p_gain_nom: dec!(0.1),
+            d_gain_nom: dec!(0.1),
+            epochs_per_year: 100,
+        }
+    }
+
+    #[test]
+    fn test_inflation_below_target() {
+        // max inflation = 1_000_000 * 0.1 / 100 = 1_000
+        // control = 0.1 * 1_000 * (0.6667 - 0.5) = 16.67
+        let ValsToUpdate {
+            locked_ratio,
+            inflation,
+        } = controller().run();
+        assert_eq!(locked_ratio, dec!(0.5));
+        assert_eq!(inflation, 16);
+
+        // The inflation keeps growing while the ratio is under the target
+        let ValsToUpdate { inflation, .. } = RewardsController {
+            last_inflation_amount: inflation,
+            ..controller()
+        }
+        .run();
+        assert_eq!(inflation, 32);
+
+        // A falling staked ratio speeds up the growth
+        let ValsToUpdate { inflation, .. } = RewardsController {
+            locked_tokens: 400_000,
+            ..controller()
+        }
+        .run();
+        // control = 100 * 0.2667 + 100 * 0.1 = 36.67
+        assert_eq!(inflation, 36);
+    }
+
+    #[test]
+    fn test_inflation_bounds() {
+        // Above the target, the inflation cannot drop below zero
+        let ValsToUpdate { inflation, .. } = RewardsController {
+            locked_tokens: 900_000,
+            locked_ratio_last: dec!(0.9),
+            ..controller()
+        }
+        .run();
+        assert_eq!(inflation, 0);
+
+        // The inflation is capped by the max inflation rate
+        let ValsToUpdate { inflation, .. } = RewardsController {
+            last_inflation_amount: 999,
+            locked_tokens: 0,
+            ..controller()
+        }
+        .run();
+        assert_eq!(inflation, 1_000);
+
+        // Without any tokens, nothing is minted
+        let ValsToUpdate {
+            locked_ratio,
+            inflation,
+        } = RewardsController {
+            locked_tokens: 0,
+            total_tokens: 0,
+            ..controller()
+        }
+        .run();
+        assert_eq!(locked_ratio, Decimal::ZERO);
+        assert_eq!(inflation, 0);
+    }
+}
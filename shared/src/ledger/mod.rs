@@ -3,8 +3,10 @@
 pub mod eth_bridge;
 pub mod events;
 pub mod ibc;
+pub mod inflation;
 pub mod masp;
 pub mod native_vp;
+pub mod offline_governance;
 pub mod pos;
 #[cfg(all(feature = "wasm-runtime", feature = "ferveo-tpke"))]
 pub mod protocol;
@@ -13,5 +15,5 @@ pub mod storage;
 pub mod vp_host_fns;
 
 pub use namada_core::ledger::{
-    gas, governance, parameters, storage_api, tx_env, vp_env,
+    dkg, gas, governance, parameters, storage_api, tx_env, vp_env,
 };
@@ -4,6 +4,7 @@ pub mod utils;
 
 use std::collections::BTreeSet;
 
+use namada_core::ledger::governance::parameters::read_dependent_parameters;
 use namada_core::ledger::governance::storage as gov_storage;
 use namada_core::ledger::storage;
 use namada_core::ledger::vp_env::VpEnv;
@@ -12,9 +13,11 @@ use utils::is_valid_validator_voting_period;
 
 use crate::ledger::native_vp;
 use crate::ledger::native_vp::{Ctx, NativeVp};
+use crate::ledger::pos::namada_proof_of_stake::PosReadOnly;
 use crate::ledger::pos::{self, BondId, Bonds};
 use crate::ledger::storage_api::StorageRead;
 use crate::types::address::{Address, InternalAddress};
+use crate::types::governance::{ParameterChange, ProposalType};
 use crate::types::storage::{Epoch, Key};
 use crate::types::token;
 use crate::vm::WasmCacheAccess;
@@ -77,6 +80,9 @@ where
                 (KeyType::PROPOSAL_CODE, Some(proposal_id)) => {
                     self.is_valid_proposal_code(proposal_id)
                 }
+                (KeyType::PROPOSAL_TYPE, Some(proposal_id)) => {
+                    self.is_valid_proposal_type(proposal_id)
+                }
                 (KeyType::GRACE_EPOCH, Some(proposal_id)) => {
                     self.is_valid_grace_epoch(proposal_id)
                 }
@@ -272,6 +278,93 @@ where
         }
     }
 
+    /// Validate a proposal type key
+    pub fn is_valid_proposal_type(&self, proposal_id: u64) -> Result<bool> {
+        let proposal_type_key = gov_storage::get_proposal_type_key(proposal_id);
+        let code_key = gov_storage::get_proposal_code_key(proposal_id);
+        let grace_epoch_key = gov_storage::get_grace_epoch_key(proposal_id);
+
+        let has_pre_proposal_type = self.ctx.has_key_pre(&proposal_type_key)?;
+        if has_pre_proposal_type {
+            return Ok(false);
+        }
+
+        let proposal_type: Option<ProposalType> =
+            self.ctx.post().read(&proposal_type_key)?;
+        let grace_epoch: Option<Epoch> =
+            self.ctx.post().read(&grace_epoch_key)?;
+
+        match (proposal_type, grace_epoch) {
+            (Some(proposal_type), Some(grace_epoch)) => {
+                // Only default proposals can execute code
+                let has_post_code = self.ctx.has_key_post(&code_key)?;
+                Ok(proposal_type.is_valid(grace_epoch)
+                    && (proposal_type == ProposalType::Default
+                        || !has_post_code)
+                    && self.is_valid_pos_params_change(&proposal_type)?
+                    && self.is_valid_dependent_params_change(&proposal_type)?)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Validate the PoS parameters with all the changes of a parameter change
+    /// proposal applied. They're validated again when the proposal is
+    /// executed, as the parameters may change in the meantime.
+    fn is_valid_pos_params_change(
+        &self,
+        proposal_type: &ProposalType,
+    ) -> Result<bool> {
+        let changes = match proposal_type {
+            ProposalType::ParameterChange(changes) => changes,
+            _ => return Ok(true),
+        };
+        let mut params = self.ctx.pre().read_pos_params()?;
+        let mut params_changed = false;
+        for change in changes {
+            if let ParameterChange::Pos(param) = change {
+                params.apply_change(param);
+                params_changed = true;
+            }
+        }
+        if !params_changed {
+            return Ok(true);
+        }
+        let errors = params.validate();
+        if !errors.is_empty() {
+            tracing::info!(
+                "The proposed parameter changes would make the PoS parameters \
+                 invalid: {:?}",
+                errors
+            );
+        }
+        Ok(errors.is_empty())
+    }
+
+    /// Validate that the changes of a parameter change proposal are
+    /// consistent with the other stored parameters. They're validated again
+    /// when the proposal is executed, as the parameters may change in the
+    /// meantime.
+    fn is_valid_dependent_params_change(
+        &self,
+        proposal_type: &ProposalType,
+    ) -> Result<bool> {
+        let changes = match proposal_type {
+            ProposalType::ParameterChange(changes) => changes,
+            _ => return Ok(true),
+        };
+        let params = read_dependent_parameters(&self.ctx.pre())?;
+        let errors = params.validate_changes(changes);
+        if !errors.is_empty() {
+            tracing::info!(
+                "The proposed parameter changes are inconsistent with the \
+                 other parameters: {:?}",
+                errors
+            );
+        }
+        Ok(errors.is_empty())
+    }
+
     /// Validate a grace_epoch key
     pub fn is_valid_grace_epoch(&self, proposal_id: u64) -> Result<bool> {
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
@@ -606,6 +699,8 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     PROPOSAL_CODE,
     #[allow(non_camel_case_types)]
+    PROPOSAL_TYPE,
+    #[allow(non_camel_case_types)]
     PROPOSAL_COMMIT,
     #[allow(non_camel_case_types)]
     GRACE_EPOCH,
@@ -635,6 +730,8 @@ impl KeyType {
             KeyType::CONTENT
         } else if gov_storage::is_proposal_code_key(key) {
             KeyType::PROPOSAL_CODE
+        } else if gov_storage::is_proposal_type_key(key) {
+            KeyType::PROPOSAL_TYPE
         } else if gov_storage::is_grace_epoch_key(key) {
             KeyType::GRACE_EPOCH
         } else if gov_storage::is_start_epoch_key(key) {
@@ -5,26 +5,75 @@ use std::str::FromStr;
 
 use borsh::BorshDeserialize;
 use namada_proof_of_stake::PosReadOnly;
+use rust_decimal::Decimal;
 use thiserror::Error;
 
+use crate::ledger::governance::parameters::GovParams;
 use crate::ledger::governance::storage as gov_storage;
 use crate::ledger::pos::BondId;
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
-use crate::ledger::storage_api;
+use crate::ledger::storage_api::{self, OptionExt};
 use crate::types::address::Address;
-use crate::types::governance::{ProposalVote, TallyResult, VotePower};
+use crate::types::governance::{
+    ProposalResult, ProposalVote, TallyResult, VotePower,
+};
 use crate::types::storage::{Epoch, Key};
 use crate::types::token;
 
 /// Proposal structure holding votes information necessary to compute the
 /// outcome
 pub struct Votes {
-    /// Map from validators who votes yay to their total stake amount
-    pub yay_validators: HashMap<Address, VotePower>,
-    /// Map from delegation who votes yay to their bond amount
-    pub yay_delegators: HashMap<Address, HashMap<Address, VotePower>>,
-    /// Map from delegation who votes nay to their bond amount
-    pub nay_delegators: HashMap<Address, HashMap<Address, VotePower>>,
+    /// Map from validators who voted to their total stake amount and vote
+    pub validators: HashMap<Address, (VotePower, ProposalVote)>,
+    /// Map from delegators who voted to their bond amount and vote for each
+    /// of the validators they delegate to
+    pub delegators:
+        HashMap<Address, HashMap<Address, (VotePower, ProposalVote)>>,
+}
+
+/// The governance parameters that decide the outcome of a tally
+#[derive(Debug, Clone, Copy)]
+pub struct TallyThresholds {
+    /// Minimum fraction of the total voting power that has to vote
+    pub quorum: Decimal,
+    /// Minimum fraction of yay votes over the non-abstaining votes
+    pub pass_threshold: Decimal,
+    /// Fraction of veto votes over all the votes above which a proposal is
+    /// vetoed
+    pub veto_threshold: Decimal,
+}
+
+impl TallyThresholds {
+    /// Read the tally thresholds from storage
+    pub fn read<S>(storage: &S) -> storage_api::Result<Self>
+    where
+        S: for<'iter> storage_api::StorageRead<'iter>,
+    {
+        let quorum = storage
+            .read(&gov_storage::get_quorum_key())?
+            .ok_or_err_msg("Missing governance quorum parameter")?;
+        let pass_threshold = storage
+            .read(&gov_storage::get_pass_threshold_key())?
+            .ok_or_err_msg("Missing governance pass threshold parameter")?;
+        let veto_threshold = storage
+            .read(&gov_storage::get_veto_threshold_key())?
+            .ok_or_err_msg("Missing governance veto threshold parameter")?;
+        Ok(Self {
+            quorum,
+            pass_threshold,
+            veto_threshold,
+        })
+    }
+}
+
+impl From<&GovParams> for TallyThresholds {
+    fn from(params: &GovParams) -> Self {
+        Self {
+            quorum: params.quorum,
+            pass_threshold: params.pass_threshold,
+            veto_threshold: params.veto_threshold,
+        }
+    }
 }
 
 /// Proposal errors
@@ -74,48 +123,96 @@ impl ProposalEvent {
     }
 }
 
-/// Return a proposal result - accepted only when the result is `Ok(true)`.
+/// Compute the result of a proposal at the given epoch, reading the tally
+/// thresholds from storage
 pub fn compute_tally<D, H>(
     storage: &Storage<D, H>,
     epoch: Epoch,
     votes: Votes,
-) -> storage_api::Result<bool>
+) -> storage_api::Result<ProposalResult>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
     H: StorageHasher + Sync + 'static,
 {
     let total_stake: VotePower = storage.total_stake(epoch)?.into();
+    let thresholds = TallyThresholds::read(storage)?;
 
-    let Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    } = votes;
+    Ok(tally_votes(total_stake, &votes, thresholds))
+}
 
-    let mut total_yay_staked_tokens = VotePower::from(0_u64);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_staked_tokens += amount;
-    }
+/// Tally the votes of a proposal. A delegator's vote overrides the vote of
+/// their validator for the delegated amount. The proposal is rejected if the
+/// quorum isn't reached, vetoed if the veto votes exceed the veto threshold
+/// and otherwise passes if the yay votes over the non-abstaining votes reach
+/// the pass threshold.
+pub fn tally_votes(
+    total_voting_power: VotePower,
+    votes: &Votes,
+    thresholds: TallyThresholds,
+) -> ProposalResult {
+    let mut result = ProposalResult {
+        result: TallyResult::Rejected,
+        total_voting_power,
+        total_yay_power: 0,
+        total_nay_power: 0,
+        total_abstain_power: 0,
+        total_veto_power: 0,
+    };
 
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (_, vote_map) in yay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if !yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens += vote_power;
-            }
-        }
+    for (vote_power, vote) in votes.validators.values() {
+        *vote_power_mut(&mut result, vote) += vote_power;
     }
-
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (_, vote_map) in nay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens -= vote_power;
+    for vote_map in votes.delegators.values() {
+        for (validator_address, (vote_power, vote)) in vote_map.iter() {
+            if let Some((_, validator_vote)) =
+                votes.validators.get(validator_address)
+            {
+                let validator_power =
+                    vote_power_mut(&mut result, validator_vote);
+                *validator_power = validator_power.saturating_sub(*vote_power);
             }
+            *vote_power_mut(&mut result, vote) += vote_power;
         }
     }
 
-    Ok(3 * total_yay_staked_tokens >= 2 * total_stake)
+    let total_votes = result.total_yay_power
+        + result.total_nay_power
+        + result.total_abstain_power
+        + result.total_veto_power;
+    let ratio = |power: VotePower, total: VotePower| {
+        Decimal::checked_div(power.into(), total.into()).unwrap_or_default()
+    };
+
+    result.result = if total_votes == 0
+        || ratio(total_votes, total_voting_power) < thresholds.quorum
+    {
+        TallyResult::Rejected
+    } else if ratio(result.total_veto_power, total_votes)
+        > thresholds.veto_threshold
+    {
+        TallyResult::Vetoed
+    } else if ratio(
+        result.total_yay_power,
+        total_votes - result.total_abstain_power,
+    ) >= thresholds.pass_threshold
+    {
+        TallyResult::Passed
+    } else {
+        TallyResult::Rejected
+    };
+    result
+}
+
+fn vote_power_mut<'a>(
+    result: &'a mut ProposalResult,
+    vote: &ProposalVote,
+) -> &'a mut VotePower {
+    match vote {
+        ProposalVote::Yay => &mut result.total_yay_power,
+        ProposalVote::Nay => &mut result.total_nay_power,
+        ProposalVote::Abstain => &mut result.total_abstain_power,
+        ProposalVote::NoWithVeto => &mut result.total_veto_power,
+    }
 }
 
 /// Prepare Votes structure to compute proposal tally
@@ -134,11 +231,11 @@ where
         gov_storage::get_proposal_vote_prefix_key(proposal_id);
     let (vote_iter, _) = storage.iter_prefix(&vote_prefix_key);
 
-    let mut yay_validators = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
+    let mut validator_votes = HashMap::new();
+    let mut delegator_votes: HashMap<
+        Address,
+        HashMap<Address, (VotePower, ProposalVote)>,
+    > = HashMap::new();
 
     for (key, vote_bytes, _) in vote_iter {
         let vote_key = Key::from_str(key.as_str()).ok();
@@ -148,13 +245,13 @@ where
                 let voter_address = gov_storage::get_voter_address(&key);
                 match voter_address {
                     Some(voter_address) => {
-                        if vote.is_yay() && validators.contains(voter_address) {
+                        if validators.contains(voter_address) {
                             let amount: VotePower = storage
                                 .validator_stake(voter_address, epoch)?
                                 .into();
-                            yay_validators
-                                .insert(voter_address.clone(), amount);
-                        } else if !validators.contains(voter_address) {
+                            validator_votes
+                                .insert(voter_address.clone(), (amount, vote));
+                        } else {
                             let validator_address =
                                 gov_storage::get_vote_delegation_address(&key);
                             match validator_address {
@@ -166,23 +263,13 @@ where
                                     let amount =
                                         storage.bond_amount(&bond_id, epoch)?;
                                     if amount != token::Amount::default() {
-                                        if vote.is_yay() {
-                                            let entry = yay_delegators
-                                                .entry(voter_address.to_owned())
-                                                .or_default();
-                                            entry.insert(
-                                                validator.to_owned(),
-                                                VotePower::from(amount),
-                                            );
-                                        } else {
-                                            let entry = nay_delegators
-                                                .entry(voter_address.to_owned())
-                                                .or_default();
-                                            entry.insert(
-                                                validator.to_owned(),
-                                                VotePower::from(amount),
-                                            );
-                                        }
+                                        let entry = delegator_votes
+                                            .entry(voter_address.to_owned())
+                                            .or_default();
+                                        entry.insert(
+                                            validator.to_owned(),
+                                            (VotePower::from(amount), vote),
+                                        );
                                     }
                                 }
                                 None => continue,
@@ -197,9 +284,8 @@ where
     }
 
     Ok(Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
+        validators: validator_votes,
+        delegators: delegator_votes,
     })
 }
 
@@ -232,3 +318,72 @@ where
         None => Ok(false),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    const THRESHOLDS: TallyThresholds = TallyThresholds {
+        quorum: dec!(0.334),
+        pass_threshold: dec!(0.6667),
+        veto_threshold: dec!(0.334),
+    };
+
+    #[test]
+    fn test_tally_delegator_overrides_validator_vote() {
+        let validator = established_address_1();
+        let delegator = established_address_2();
+        let votes = Votes {
+            validators: HashMap::from([(
+                validator.clone(),
+                (100, ProposalVote::Yay),
+            )]),
+            delegators: HashMap::from([(
+                delegator,
+                HashMap::from([(validator, (40, ProposalVote::Abstain))]),
+            )]),
+        };
+
+        let result = tally_votes(100, &votes, THRESHOLDS);
+        assert_eq!(result.total_yay_power, 60);
+        assert_eq!(result.total_abstain_power, 40);
+        // Abstaining power doesn't count towards the pass threshold
+        assert!(matches!(result.result, TallyResult::Passed));
+    }
+
+    #[test]
+    fn test_tally_quorum_and_veto() {
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+        let validator_3 = established_address_3();
+
+        // Not enough voting power voted
+        let votes = Votes {
+            validators: HashMap::from([(
+                validator_1.clone(),
+                (30, ProposalVote::Yay),
+            )]),
+            delegators: HashMap::new(),
+        };
+        let result = tally_votes(100, &votes, THRESHOLDS);
+        assert!(matches!(result.result, TallyResult::Rejected));
+
+        // The veto votes exceed the veto threshold
+        let votes = Votes {
+            validators: HashMap::from([
+                (validator_1, (30, ProposalVote::Yay)),
+                (validator_2, (30, ProposalVote::Nay)),
+                (validator_3, (40, ProposalVote::NoWithVeto)),
+            ]),
+            delegators: HashMap::new(),
+        };
+        let result = tally_votes(100, &votes, THRESHOLDS);
+        assert_eq!(result.total_veto_power, 40);
+        assert!(matches!(result.result, TallyResult::Vetoed));
+    }
+}
@@ -4,6 +4,7 @@
 pub mod governance;
 pub mod parameters;
 pub mod slash_fund;
+pub mod treasury;
 
 use std::cell::RefCell;
 use std::collections::BTreeSet;
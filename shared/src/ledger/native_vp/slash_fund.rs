@@ -60,11 +60,14 @@ where
                     if addr.ne(&slash_fund::ADDRESS) {
                         return true;
                     }
-                    governance::utils::is_proposal_accepted(
-                        self.ctx.storage,
-                        tx_data,
-                    )
-                    .unwrap_or(false)
+                    // Funds can be sent to the slash fund, but only an
+                    // executed proposal can spend them
+                    self.is_deposit(key).unwrap_or(false)
+                        || governance::utils::is_proposal_accepted(
+                            self.ctx.storage,
+                            tx_data,
+                        )
+                        .unwrap_or(false)
                 }
                 KeyType::UNKNOWN_SLASH_FUND => false,
                 KeyType::UNKNOWN => true,
@@ -74,6 +77,21 @@ where
     }
 }
 
+impl<'a, DB, H, CA> SlashFundVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the change of the slash fund balance is a deposit
+    fn is_deposit(&self, key: &Key) -> Result<bool> {
+        let pre: token::Amount = self.ctx.pre().read(key)?.unwrap_or_default();
+        let post: token::Amount =
+            self.ctx.post().read(key)?.unwrap_or_default();
+        Ok(post >= pre)
+    }
+}
+
 #[allow(clippy::upper_case_acronyms)]
 enum KeyType {
     #[allow(clippy::upper_case_acronyms)]
@@ -0,0 +1,118 @@
+//! Governance treasury VP
+
+use std::collections::BTreeSet;
+
+use namada_core::ledger::treasury;
+/// Treasury storage
+pub use namada_core::ledger::treasury::storage;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, governance, Ctx, NativeVp};
+use crate::ledger::storage::{self as ledger_storage, StorageHasher};
+use crate::ledger::storage_api::StorageRead;
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::Key;
+use crate::types::token;
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// Treasury functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Governance treasury VP
+pub struct TreasuryVp<'a, DB, H, CA>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for TreasuryVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = InternalAddress::Treasury;
+
+    fn validate_tx(
+        &self,
+        tx_data: &[u8],
+        keys_changed: &BTreeSet<Key>,
+        _verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let result = keys_changed.iter().all(|key| {
+            let key_type: KeyType = get_key_type(key);
+            match key_type {
+                KeyType::BALANCE(addr) => {
+                    if addr.ne(&treasury::ADDRESS) {
+                        return true;
+                    }
+                    // Anyone can fund the treasury with any token, but
+                    // spending from it requires an accepted proposal
+                    self.is_deposit(key).unwrap_or(false)
+                        || governance::utils::is_proposal_accepted(
+                            self.ctx.storage,
+                            tx_data,
+                        )
+                        .unwrap_or(false)
+                }
+                KeyType::UNKNOWN_TREASURY => false,
+                KeyType::UNKNOWN => true,
+            }
+        });
+        Ok(result)
+    }
+}
+
+impl<'a, DB, H, CA> TreasuryVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Check that the change of the treasury balance is a deposit
+    fn is_deposit(&self, key: &Key) -> Result<bool> {
+        let pre: token::Amount = self.ctx.pre().read(key)?.unwrap_or_default();
+        let post: token::Amount =
+            self.ctx.post().read(key)?.unwrap_or_default();
+        Ok(post >= pre)
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+enum KeyType {
+    #[allow(clippy::upper_case_acronyms)]
+    BALANCE(Address),
+    #[allow(clippy::upper_case_acronyms)]
+    #[allow(non_camel_case_types)]
+    UNKNOWN_TREASURY,
+    #[allow(clippy::upper_case_acronyms)]
+    UNKNOWN,
+}
+
+/// Get the type of a changed key. The balances of every token, including
+/// the multitoken ones, are treated the same.
+fn get_key_type(value: &Key) -> KeyType {
+    if storage::is_treasury_key(value) {
+        return KeyType::UNKNOWN_TREASURY;
+    }
+    let owner = token::is_any_token_balance_key(value).or_else(|| {
+        token::is_any_multitoken_balance_key(value).map(|(_, owner)| owner)
+    });
+    match owner {
+        Some(owner) => KeyType::BALANCE(owner.clone()),
+        None => KeyType::UNKNOWN,
+    }
+}
@@ -0,0 +1,476 @@
+//! Tallying of offline (signaling) governance proposals.
+//!
+//! An offline proposal and the votes cast on it are kept in a directory as
+//! JSON files: the proposal in a file named [`PROPOSAL_FILE_NAME`] and every
+//! vote in a file whose name starts with [`VOTE_FILE_PREFIX`]. Only the
+//! stake of the voters at the proposal's `tally_epoch` and their revealed
+//! public keys are taken from the chain, captured in a [`StakeSnapshot`].
+//! Given the same directory and snapshot, [`tally`] always produces the same
+//! [`TallyReport`], which can then be signed by whoever ran the tally.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use thiserror::Error;
+
+use crate::ledger::native_vp::governance::utils::{
+    tally_votes, TallyThresholds, Votes,
+};
+use crate::types::address::Address;
+use crate::types::governance::{
+    OfflineProposal, OfflineVote, ProposalResult, ProposalVote, VotePower,
+};
+use crate::types::hash::Hash;
+use crate::types::key::common::{self, Signature};
+use crate::types::key::SigScheme;
+use crate::types::storage::Epoch;
+
+/// The name of the file holding the offline proposal
+pub const PROPOSAL_FILE_NAME: &str = "proposal";
+/// The prefix of the names of the files holding the offline votes
+pub const VOTE_FILE_PREFIX: &str = "proposal-vote-";
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Can't read {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("JSON was not well-formatted in {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+    #[error(
+        "The folder must contain the offline proposal in a file named \
+         \"{PROPOSAL_FILE_NAME}\""
+    )]
+    MissingProposal,
+    #[error("No public key revealed for the proposal signer {0}")]
+    MissingProposalKey(Address),
+    #[error("Bad proposal signature")]
+    InvalidProposalSignature,
+    #[error(
+        "The stake snapshot is for epoch {snapshot}, but the proposal is \
+         tallied at epoch {tally}"
+    )]
+    EpochMismatch { snapshot: Epoch, tally: Epoch },
+}
+
+/// Offline governance functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An offline proposal together with the votes cast on it
+#[derive(Debug, Clone)]
+pub struct OfflineVotingData {
+    /// The proposal
+    pub proposal: OfflineProposal,
+    /// The votes, in the order of the names of the files they were read from
+    pub votes: Vec<OfflineVote>,
+}
+
+impl OfflineVotingData {
+    /// Load the proposal and the votes from the given directory. Files that
+    /// are neither the proposal nor a vote are ignored.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let entries =
+            fs::read_dir(dir).map_err(|e| Error::Read(dir.to_owned(), e))?;
+
+        let mut is_proposal_present = false;
+        let mut vote_files = BTreeSet::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::Read(dir.to_owned(), e))?;
+            let file_type = entry
+                .file_type()
+                .map_err(|e| Error::Read(entry.path(), e))?;
+            if !file_type.is_file() {
+                continue;
+            }
+            let file_name = entry.file_name();
+            if file_name == PROPOSAL_FILE_NAME {
+                is_proposal_present = true;
+            } else if file_name.to_string_lossy().starts_with(VOTE_FILE_PREFIX)
+            {
+                vote_files.insert(entry.path());
+            }
+        }
+        if !is_proposal_present {
+            return Err(Error::MissingProposal);
+        }
+
+        let path = dir.join(PROPOSAL_FILE_NAME);
+        let proposal = serde_json::from_reader(open(&path)?)
+            .map_err(|e| Error::Parse(path, e))?;
+        let votes = vote_files
+            .into_iter()
+            .map(|path| {
+                serde_json::from_reader(open(&path)?)
+                    .map_err(|e| Error::Parse(path, e))
+            })
+            .collect::<Result<Vec<OfflineVote>>>()?;
+        Ok(Self { proposal, votes })
+    }
+
+    /// The addresses whose public key and stake are needed to tally the
+    /// proposal, i.e. the proposal signer and all the voters.
+    pub fn addresses(&self) -> BTreeSet<Address> {
+        self.votes
+            .iter()
+            .map(|vote| vote.address.clone())
+            .chain([self.proposal.address.clone()])
+            .collect()
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).map_err(|e| Error::Read(path.to_owned(), e))
+}
+
+/// The on-chain data an offline proposal is tallied against
+#[derive(Debug, Clone, Default)]
+pub struct StakeSnapshot {
+    /// The epoch at which the stake was taken
+    pub epoch: Epoch,
+    /// The total voting power at the epoch
+    pub total_stake: VotePower,
+    /// The revealed public keys of the addresses taking part in the vote
+    pub public_keys: HashMap<Address, common::PublicKey>,
+    /// The stake of the validators taking part in the vote
+    pub validators: HashMap<Address, VotePower>,
+    /// The bond amounts of the delegators taking part in the vote, for each
+    /// of the validators they delegate to
+    pub delegations: HashMap<Address, HashMap<Address, VotePower>>,
+}
+
+/// The reason an offline vote wasn't counted
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum VoteRejection {
+    /// The vote was cast on another proposal
+    WrongProposal,
+    /// The voter hasn't revealed its public key
+    MissingPublicKey,
+    /// The vote signature doesn't match the voter's public key
+    InvalidSignature,
+    /// The voter cast more than one different vote
+    ConflictingVotes,
+    /// The voter had no stake at the tally epoch
+    NoStake,
+}
+
+/// The outcome of an offline proposal tally
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TallyReport {
+    /// The hash of the tallied proposal
+    pub proposal_hash: Hash,
+    /// The epoch at which the stake was taken
+    pub tally_epoch: Epoch,
+    /// The result of the tally
+    pub result: ProposalResult,
+    /// The counted votes, ordered by voter address
+    pub counted: Vec<(Address, ProposalVote)>,
+    /// The votes that weren't counted, ordered by voter address
+    pub rejected: Vec<(Address, VoteRejection)>,
+}
+
+impl TallyReport {
+    /// Compute the hash of the report
+    pub fn compute_hash(&self) -> Hash {
+        let report_serialized = self
+            .try_to_vec()
+            .expect("Conversion to bytes shouldn't fail.");
+        Hash::sha256(report_serialized)
+    }
+
+    /// Sign the report
+    pub fn sign(
+        self,
+        address: Address,
+        signing_key: &common::SecretKey,
+    ) -> SignedTallyReport {
+        let signature =
+            common::SigScheme::sign(signing_key, self.compute_hash());
+        SignedTallyReport {
+            report: self,
+            signature,
+            address,
+        }
+    }
+}
+
+/// A tally report signed by whoever computed it
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct SignedTallyReport {
+    /// The report
+    pub report: TallyReport,
+    /// The signature over the report hash
+    pub signature: Signature,
+    /// The address corresponding to the signature pk
+    pub address: Address,
+}
+
+impl SignedTallyReport {
+    /// Check whether the signature is valid or not
+    pub fn check_signature(&self, public_key: &common::PublicKey) -> bool {
+        common::SigScheme::verify_signature(
+            public_key,
+            &self.report.compute_hash(),
+            &self.signature,
+        )
+        .is_ok()
+    }
+}
+
+/// Tally an offline proposal against the given stake snapshot.
+///
+/// A vote is only counted if it's cast on the proposal, signed with the
+/// voter's revealed public key and the voter had some stake at the tally
+/// epoch. Identical votes from the same voter are counted once, while a
+/// voter who cast different votes is not counted at all. As for on-chain
+/// proposals, a delegator's vote overrides the vote of its validator for the
+/// delegated stake.
+pub fn tally(
+    data: &OfflineVotingData,
+    snapshot: &StakeSnapshot,
+    thresholds: TallyThresholds,
+) -> Result<TallyReport> {
+    let proposal = &data.proposal;
+    if snapshot.epoch != proposal.tally_epoch {
+        return Err(Error::EpochMismatch {
+            snapshot: snapshot.epoch,
+            tally: proposal.tally_epoch,
+        });
+    }
+    let proposal_key = snapshot
+        .public_keys
+        .get(&proposal.address)
+        .ok_or_else(|| Error::MissingProposalKey(proposal.address.clone()))?;
+    if !proposal.check_signature(proposal_key) {
+        return Err(Error::InvalidProposalSignature);
+    }
+    let proposal_hash = proposal.compute_hash();
+
+    let mut rejected: BTreeSet<(Address, VoteRejection)> = BTreeSet::new();
+    let mut valid_votes: BTreeMap<Address, Vec<ProposalVote>> = BTreeMap::new();
+    for vote in &data.votes {
+        let rejection = match snapshot.public_keys.get(&vote.address) {
+            _ if vote.proposal_hash != proposal_hash => {
+                Some(VoteRejection::WrongProposal)
+            }
+            None => Some(VoteRejection::MissingPublicKey),
+            Some(public_key) if !vote.check_signature(public_key) => {
+                Some(VoteRejection::InvalidSignature)
+            }
+            Some(_) => None,
+        };
+        match rejection {
+            Some(rejection) => {
+                rejected.insert((vote.address.clone(), rejection));
+            }
+            None => {
+                let voter_votes =
+                    valid_votes.entry(vote.address.clone()).or_default();
+                if !voter_votes.contains(&vote.vote) {
+                    voter_votes.push(vote.vote.clone());
+                }
+            }
+        }
+    }
+
+    let mut counted = Vec::new();
+    let mut votes = Votes {
+        validators: HashMap::new(),
+        delegators: HashMap::new(),
+    };
+    for (voter, mut voter_votes) in valid_votes {
+        if voter_votes.len() > 1 {
+            rejected.insert((voter, VoteRejection::ConflictingVotes));
+            continue;
+        }
+        let vote = voter_votes.pop().expect("A voter has at least one vote");
+        if let Some(stake) = snapshot.validators.get(&voter) {
+            votes
+                .validators
+                .insert(voter.clone(), (*stake, vote.clone()));
+        } else if let Some(delegations) = snapshot
+            .delegations
+            .get(&voter)
+            .filter(|delegations| !delegations.is_empty())
+        {
+            let delegations = delegations
+                .iter()
+                .map(|(validator, amount)| {
+                    (validator.clone(), (*amount, vote.clone()))
+                })
+                .collect();
+            votes.delegators.insert(voter.clone(), delegations);
+        } else {
+            rejected.insert((voter, VoteRejection::NoStake));
+            continue;
+        }
+        counted.push((voter, vote));
+    }
+
+    let result = tally_votes(snapshot.total_stake, &votes, thresholds);
+    Ok(TallyReport {
+        proposal_hash,
+        tally_epoch: proposal.tally_epoch,
+        result,
+        counted,
+        rejected: rejected.into_iter().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+    use crate::types::governance::{Proposal, TallyResult};
+    use crate::types::key::testing::{keypair_1, keypair_2};
+    use crate::types::key::RefTo;
+
+    const THRESHOLDS: TallyThresholds = TallyThresholds {
+        quorum: dec!(0.334),
+        pass_threshold: dec!(0.6667),
+        veto_threshold: dec!(0.334),
+    };
+
+    fn offline_proposal(
+        author: Address,
+        key: &common::SecretKey,
+    ) -> OfflineProposal {
+        let proposal = Proposal {
+            id: None,
+            content: BTreeMap::from([(
+                "title".to_owned(),
+                "Signal support".to_owned(),
+            )]),
+            author: author.clone(),
+            voting_start_epoch: Epoch(0),
+            voting_end_epoch: Epoch(3),
+            grace_epoch: Epoch(6),
+            proposal_code_path: None,
+            proposal_type: Default::default(),
+        };
+        OfflineProposal::new(proposal, author, key)
+    }
+
+    #[test]
+    fn test_offline_tally() {
+        let validator = established_address_1();
+        let delegator = established_address_2();
+        let stranger = established_address_3();
+        let validator_key = keypair_1();
+        let delegator_key = keypair_2();
+
+        let proposal = offline_proposal(validator.clone(), &validator_key);
+        let vote = |vote, address: &Address, key| {
+            OfflineVote::new(&proposal, vote, address.clone(), key)
+        };
+        let votes = vec![
+            vote(ProposalVote::Yay, &validator, &validator_key),
+            // A duplicate vote is only counted once
+            vote(ProposalVote::Yay, &validator, &validator_key),
+            // The delegator's vote overrides its validator's
+            vote(ProposalVote::Nay, &delegator, &delegator_key),
+            // Signed with someone else's key
+            vote(ProposalVote::Nay, &stranger, &delegator_key),
+        ];
+        let data = OfflineVotingData { proposal, votes };
+
+        let snapshot = StakeSnapshot {
+            epoch: Epoch(6),
+            total_stake: 100,
+            public_keys: HashMap::from([
+                (validator.clone(), validator_key.ref_to()),
+                (delegator.clone(), delegator_key.ref_to()),
+                (stranger.clone(), validator_key.ref_to()),
+            ]),
+            validators: HashMap::from([(validator.clone(), 100)]),
+            delegations: HashMap::from([(
+                delegator.clone(),
+                HashMap::from([(validator.clone(), 20)]),
+            )]),
+        };
+
+        let report = tally(&data, &snapshot, THRESHOLDS).unwrap();
+        assert_eq!(report.result.total_yay_power, 80);
+        assert_eq!(report.result.total_nay_power, 20);
+        assert!(matches!(report.result.result, TallyResult::Passed));
+        assert_eq!(
+            report.rejected,
+            vec![(stranger, VoteRejection::InvalidSignature)]
+        );
+        assert_eq!(report.counted.len(), 2);
+
+        // The tally is reproducible regardless of the order of the votes
+        let mut reversed = data.clone();
+        reversed.votes.reverse();
+        let other_report = tally(&reversed, &snapshot, THRESHOLDS).unwrap();
+        assert_eq!(report.compute_hash(), other_report.compute_hash());
+
+        let signed = report.sign(delegator, &delegator_key);
+        assert!(signed.check_signature(&delegator_key.ref_to()));
+        assert!(!signed.check_signature(&validator_key.ref_to()));
+    }
+
+    #[test]
+    fn test_offline_tally_rejections() {
+        let validator = established_address_1();
+        let voter = established_address_2();
+        let validator_key = keypair_1();
+        let voter_key = keypair_2();
+
+        let proposal = offline_proposal(validator.clone(), &validator_key);
+        let votes = vec![
+            OfflineVote::new(
+                &proposal,
+                ProposalVote::Yay,
+                voter.clone(),
+                &voter_key,
+            ),
+            OfflineVote::new(
+                &proposal,
+                ProposalVote::NoWithVeto,
+                voter.clone(),
+                &voter_key,
+            ),
+        ];
+        let data = OfflineVotingData { proposal, votes };
+        let mut snapshot = StakeSnapshot {
+            epoch: Epoch(6),
+            total_stake: 100,
+            public_keys: HashMap::from([
+                (validator.clone(), validator_key.ref_to()),
+                (voter.clone(), voter_key.ref_to()),
+            ]),
+            validators: HashMap::from([(validator, 80), (voter.clone(), 20)]),
+            delegations: HashMap::new(),
+        };
+
+        let report = tally(&data, &snapshot, THRESHOLDS).unwrap();
+        assert!(report.counted.is_empty());
+        assert_eq!(
+            report.rejected,
+            vec![(voter, VoteRejection::ConflictingVotes)]
+        );
+        assert!(matches!(report.result.result, TallyResult::Rejected));
+
+        snapshot.epoch = Epoch(5);
+        assert!(matches!(
+            tally(&data, &snapshot, THRESHOLDS),
+            Err(Error::EpochMismatch { .. })
+        ));
+    }
+}
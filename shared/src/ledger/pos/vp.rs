@@ -14,14 +14,14 @@ use rust_decimal::Decimal;
 use thiserror::Error;
 
 use super::{
-    bond_key, is_bond_key, is_params_key, is_total_deltas_key, is_unbond_key,
-    is_validator_deltas_key, is_validator_set_key, params_key,
-    total_deltas_key, unbond_key, validator_commission_rate_key,
-    validator_consensus_key_key, validator_deltas_key,
-    validator_max_commission_rate_change_key, validator_set_key,
-    validator_slashes_key, validator_state_key, BondId, Bonds, CommissionRates,
-    TotalDeltas, Unbonds, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorSets,
+    bond_key, is_bond_key, is_params_key, is_redelegation_key,
+    is_total_deltas_key, is_unbond_key, is_validator_deltas_key,
+    is_validator_set_key, params_key, redelegation_key, total_deltas_key,
+    unbond_key, validator_commission_rate_key, validator_consensus_key_key,
+    validator_deltas_key, validator_max_commission_rate_change_key,
+    validator_set_key, validator_slashes_key, validator_state_key, BondId,
+    Bonds, CommissionRates, Redelegations, TotalDeltas, Unbonds,
+    ValidatorConsensusKeys, ValidatorDeltas, ValidatorMetaData, ValidatorSets,
 };
 use crate::ledger::native_vp::{
     self, governance, Ctx, CtxPostStorageRead, CtxPreStorageRead, NativeVp,
@@ -29,7 +29,8 @@ use crate::ledger::native_vp::{
 use crate::ledger::pos::{
     is_validator_address_raw_hash_key, is_validator_commission_rate_key,
     is_validator_consensus_key_key,
-    is_validator_max_commission_rate_change_key, is_validator_state_key,
+    is_validator_max_commission_rate_change_key, is_validator_metadata_key,
+    is_validator_state_key,
 };
 use crate::ledger::storage::{self as ledger_storage, StorageHasher};
 use crate::ledger::storage_api::StorageRead;
@@ -213,10 +214,20 @@ where
                     .read_bytes(&validator_slashes_key(&bond_id.validator))?
                     .and_then(|bytes| Slashes::try_from_slice(&bytes[..]).ok())
                     .unwrap_or_default();
+                // And the redelegations into the bond
+                let redelegations = self
+                    .ctx
+                    .pre()
+                    .read_bytes(&redelegation_key(&bond_id))?
+                    .and_then(|bytes| {
+                        Redelegations::try_from_slice(&bytes[..]).ok()
+                    })
+                    .unwrap_or_default();
                 changes.push(Bond {
                     id: bond_id.clone(),
                     data: Data { pre, post },
                     slashes,
+                    redelegations,
                 });
             } else if let Some(unbond_id) = is_unbond_key(key) {
                 let pre =
@@ -239,6 +250,17 @@ where
                     data: Data { pre, post },
                     slashes,
                 });
+            } else if let Some(bond_id) = is_redelegation_key(key) {
+                let pre = self.ctx.pre().read_bytes(key)?.and_then(|bytes| {
+                    Redelegations::try_from_slice(&bytes[..]).ok()
+                });
+                let post = self.ctx.post().read_bytes(key)?.and_then(|bytes| {
+                    Redelegations::try_from_slice(&bytes[..]).ok()
+                });
+                changes.push(Redelegation {
+                    id: bond_id,
+                    data: Data { pre, post },
+                });
             } else if is_total_deltas_key(key) {
                 let pre = self.ctx.pre().read_bytes(key)?.and_then(|bytes| {
                     super::TotalDeltas::try_from_slice(&bytes[..]).ok()
@@ -281,6 +303,17 @@ where
                     address: address.clone(),
                     update: MaxCommissionRateChange(Data { pre, post }),
                 });
+            } else if let Some(address) = is_validator_metadata_key(key) {
+                let pre = self.ctx.pre().read_bytes(key)?.and_then(|bytes| {
+                    ValidatorMetaData::try_from_slice(&bytes[..]).ok()
+                });
+                let post = self.ctx.post().read_bytes(key)?.and_then(|bytes| {
+                    ValidatorMetaData::try_from_slice(&bytes[..]).ok()
+                });
+                changes.push(Validator {
+                    address: address.clone(),
+                    update: MetaData(Data { pre, post }),
+                });
             } else if key.segments.get(0) == Some(&addr.to_db_key()) {
                 // Unknown changes to this address space are disallowed
                 tracing::info!("PoS unrecognized key change {} rejected", key);
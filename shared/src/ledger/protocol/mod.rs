@@ -11,6 +11,7 @@ use crate::ledger::ibc::vp::{Ibc, IbcToken};
 use crate::ledger::native_vp::governance::GovernanceVp;
 use crate::ledger::native_vp::parameters::{self, ParametersVp};
 use crate::ledger::native_vp::slash_fund::SlashFundVp;
+use crate::ledger::native_vp::treasury::TreasuryVp;
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pos::{self, PosVP};
 use crate::ledger::storage::write_log::WriteLog;
@@ -54,6 +55,8 @@ pub enum Error {
     GovernanceNativeVpError(crate::ledger::native_vp::governance::Error),
     #[error("SlashFund native VP error: {0}")]
     SlashFundNativeVpError(crate::ledger::native_vp::slash_fund::Error),
+    #[error("Treasury native VP error: {0}")]
+    TreasuryNativeVpError(crate::ledger::native_vp::treasury::Error),
     #[error("Ethereum bridge native VP error: {0}")]
     EthBridgeNativeVpError(crate::ledger::eth_bridge::vp::Error),
     #[error("Access to an internal address {0} is forbidden")]
@@ -370,6 +373,14 @@ where
                             gas_meter = bridge.ctx.gas_meter.into_inner();
                             result
                         }
+                        InternalAddress::Treasury => {
+                            let treasury = TreasuryVp { ctx };
+                            let result = treasury
+                                .validate_tx(tx_data, &keys_changed, &verifiers)
+                                .map_err(Error::TreasuryNativeVpError);
+                            gas_meter = treasury.ctx.gas_meter.into_inner();
+                            result
+                        }
                     };
 
                     accepted
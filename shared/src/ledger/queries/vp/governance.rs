@@ -0,0 +1,482 @@
+use std::collections::BTreeMap;
+
+use crate::ledger::governance::storage as gov_storage;
+use crate::ledger::native_vp::governance::utils::{
+    compute_tally, get_proposal_votes,
+};
+use crate::ledger::queries::types::RequestCtx;
+use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
+use crate::ledger::storage_api::{self, OptionExt, StorageRead};
+use crate::types::address::Address;
+use crate::types::governance::{
+    ProposalResult, ProposalStatus, ProposalVote, StorageProposal,
+    StorageProposalVote,
+};
+
+// Governance validity predicate queries
+router! {GOV,
+    ( "proposal" / [id: u64] ) -> Option<StorageProposal> = proposal,
+
+    ( "proposals" / [status: opt ProposalStatus] / [offset: opt u64]
+      / [limit: opt u64] )
+    -> Vec<StorageProposal> = proposals,
+
+    ( "votes" / [id: u64] / [offset: opt u64] / [limit: opt u64] )
+    -> Vec<StorageProposalVote> = votes,
+
+    ( "vote" / [id: u64] / [voter: Address] )
+    -> Vec<StorageProposalVote> = voter_votes,
+
+    ( "tally" / [id: u64] ) -> Option<ProposalResult> = tally,
+}
+
+/// The max number of proposals returned by a single `proposals` query. Use
+/// the `offset` to get the next page.
+pub const MAX_PROPOSALS_PER_PAGE: u64 = 100;
+
+// Handlers that implement the functions via `trait StorageRead`:
+
+/// Find a proposal by its id. Returns `None` if no such proposal has been
+/// submitted.
+fn proposal<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+) -> storage_api::Result<Option<StorageProposal>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_proposal(ctx.storage, id)
+}
+
+/// Get the submitted proposals in the order of their ids, optionally
+/// filtered by their status at the current epoch. The optional `offset` skips
+/// that many proposals and `limit` caps the number of returned proposals, up
+/// to [`MAX_PROPOSALS_PER_PAGE`].
+fn proposals<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    status: Option<ProposalStatus>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> storage_api::Result<Vec<StorageProposal>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let counter_key = gov_storage::get_counter_key();
+    let counter: u64 =
+        StorageRead::read(ctx.storage, &counter_key)?.unwrap_or_default();
+    let current_epoch = ctx.storage.last_epoch;
+    let limit = limit
+        .unwrap_or(MAX_PROPOSALS_PER_PAGE)
+        .min(MAX_PROPOSALS_PER_PAGE);
+
+    (0..counter)
+        .filter_map(|id| read_proposal(ctx.storage, id).transpose())
+        .filter(|proposal| match (status, proposal) {
+            (Some(status), Ok(proposal)) => {
+                proposal.status(current_epoch) == status
+            }
+            _ => true,
+        })
+        .skip(offset.unwrap_or_default() as usize)
+        .take(limit as usize)
+        .collect()
+}
+
+/// Get the votes cast on a proposal in storage key order. The optional
+/// `offset` skips that many votes and `limit` caps the number of returned
+/// votes.
+fn votes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> storage_api::Result<Vec<StorageProposalVote>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let votes =
+        iter_votes(ctx.storage, id)?.skip(offset.unwrap_or_default() as usize);
+    match limit {
+        Some(limit) => votes.take(limit as usize).collect(),
+        None => votes.collect(),
+    }
+}
+
+/// Get the votes cast on a proposal by the given voter. A delegator has one
+/// vote for each of the validators it delegates to.
+fn voter_votes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+    voter: Address,
+) -> storage_api::Result<Vec<StorageProposalVote>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    iter_votes(ctx.storage, id)?
+        .filter(|vote| !matches!(vote, Ok(vote) if vote.voter != voter))
+        .collect()
+}
+
+/// Compute the tally of a proposal the same way it's done at the end of its
+/// voting period. While the voting period is in progress, the result is the
+/// outcome the proposal would have with the votes cast so far. Returns `None`
+/// if the proposal doesn't exist or its voting period hasn't started yet.
+fn tally<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+) -> storage_api::Result<Option<ProposalResult>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let proposal = match read_proposal(ctx.storage, id)? {
+        Some(proposal) => proposal,
+        None => return Ok(None),
+    };
+    let current_epoch = ctx.storage.last_epoch;
+    if current_epoch < proposal.voting_start_epoch {
+        return Ok(None);
+    }
+    let epoch = std::cmp::min(current_epoch, proposal.voting_end_epoch);
+
+    let votes = get_proposal_votes(ctx.storage, epoch, id)?;
+    compute_tally(ctx.storage, epoch, votes).map(Some)
+}
+
+fn read_proposal<D, H>(
+    storage: &Storage<D, H>,
+    id: u64,
+) -> storage_api::Result<Option<StorageProposal>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let author_key = gov_storage::get_author_key(id);
+    let author: Address = match StorageRead::read(storage, &author_key)? {
+        Some(author) => author,
+        None => return Ok(None),
+    };
+
+    let content_key = gov_storage::get_content_key(id);
+    let content: BTreeMap<String, String> =
+        StorageRead::read(storage, &content_key)?.unwrap_or_default();
+
+    let start_epoch_key = gov_storage::get_voting_start_epoch_key(id);
+    let voting_start_epoch = StorageRead::read(storage, &start_epoch_key)?
+        .ok_or_err_msg("Missing proposal voting start epoch")?;
+
+    let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
+    let voting_end_epoch = StorageRead::read(storage, &end_epoch_key)?
+        .ok_or_err_msg("Missing proposal voting end epoch")?;
+
+    let grace_epoch_key = gov_storage::get_grace_epoch_key(id);
+    let grace_epoch = StorageRead::read(storage, &grace_epoch_key)?
+        .ok_or_err_msg("Missing proposal grace epoch")?;
+
+    let proposal_type_key = gov_storage::get_proposal_type_key(id);
+    let proposal_type =
+        StorageRead::read(storage, &proposal_type_key)?.unwrap_or_default();
+
+    Ok(Some(StorageProposal {
+        id,
+        content,
+        author,
+        voting_start_epoch,
+        voting_end_epoch,
+        grace_epoch,
+        proposal_type,
+    }))
+}
+
+/// Iterate the votes cast on a proposal in storage key order, reading them
+/// lazily from storage
+fn iter_votes<'a, D, H>(
+    storage: &'a Storage<D, H>,
+    id: u64,
+) -> storage_api::Result<
+    impl Iterator<Item = storage_api::Result<StorageProposalVote>> + 'a,
+>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let vote_prefix_key = gov_storage::get_proposal_vote_prefix_key(id);
+    let votes =
+        storage_api::iter_prefix::<ProposalVote>(storage, &vote_prefix_key)?;
+    Ok(votes.filter_map(|vote| {
+        let (key, vote) = match vote {
+            Ok(vote) => vote,
+            Err(err) => return Some(Err(err)),
+        };
+        let voter = gov_storage::get_voter_address(&key)?;
+        let delegation = gov_storage::get_vote_delegation_address(&key)?;
+        Some(Ok(StorageProposalVote {
+            voter: voter.clone(),
+            delegation: delegation.clone(),
+            vote,
+        }))
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::ledger::governance::parameters::GovParams;
+    use crate::ledger::pos::types::GenesisValidator;
+    use crate::ledger::pos::{init_genesis_storage, PosParams};
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage_api::StorageWrite;
+    use crate::types::governance::TallyResult;
+    use crate::types::key::{self, RefTo};
+    use crate::types::storage::Epoch;
+    use crate::types::{address, token};
+
+    /// Write a proposal with the given voting period to storage
+    fn write_proposal(
+        storage: &mut TestStorage,
+        id: u64,
+        voting_start_epoch: Epoch,
+        voting_end_epoch: Epoch,
+    ) -> storage_api::Result<()> {
+        let author = address::testing::established_address_1();
+        StorageWrite::write(storage, &gov_storage::get_author_key(id), author)?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_voting_start_epoch_key(id),
+            voting_start_epoch,
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_voting_end_epoch_key(id),
+            voting_end_epoch,
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_grace_epoch_key(id),
+            voting_end_epoch.next(),
+        )?;
+        StorageWrite::write(storage, &gov_storage::get_counter_key(), id + 1)
+    }
+
+    #[tokio::test]
+    async fn test_gov_queries_proposals() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+
+        // There are no proposals yet
+        let proposal = RPC.vp().gov().proposal(&client, &0).await.unwrap();
+        assert!(proposal.is_none());
+        let proposals = RPC
+            .vp()
+            .gov()
+            .proposals(&client, &None, &None, &None)
+            .await
+            .unwrap();
+        assert!(proposals.is_empty());
+
+        // A proposal that is done, one on-going and one pending at epoch 1
+        write_proposal(&mut client.storage, 0, Epoch(0), Epoch(0))?;
+        write_proposal(&mut client.storage, 1, Epoch(1), Epoch(2))?;
+        write_proposal(&mut client.storage, 2, Epoch(2), Epoch(3))?;
+        client.storage.last_epoch = Epoch(1);
+
+        let proposal = RPC.vp().gov().proposal(&client, &1).await.unwrap();
+        let proposal = proposal.expect("The proposal should exist");
+        assert_eq!(proposal.id, 1);
+        assert_eq!(proposal.voting_start_epoch, Epoch(1));
+        assert_eq!(proposal.voting_end_epoch, Epoch(2));
+        assert_eq!(proposal.grace_epoch, Epoch(3));
+
+        let ids = |proposals: Vec<StorageProposal>| -> Vec<u64> {
+            proposals.iter().map(|proposal| proposal.id).collect()
+        };
+        let proposals = RPC
+            .vp()
+            .gov()
+            .proposals(&client, &None, &None, &None)
+            .await
+            .unwrap();
+        assert_eq!(ids(proposals), vec![0, 1, 2]);
+
+        for (status, expected) in [
+            (ProposalStatus::Done, 0),
+            (ProposalStatus::OnGoing, 1),
+            (ProposalStatus::Pending, 2),
+        ] {
+            let proposals = RPC
+                .vp()
+                .gov()
+                .proposals(&client, &Some(status), &None, &None)
+                .await
+                .unwrap();
+            assert_eq!(ids(proposals), vec![expected]);
+        }
+
+        let proposals = RPC
+            .vp()
+            .gov()
+            .proposals(&client, &None, &Some(1), &Some(1))
+            .await
+            .unwrap();
+        assert_eq!(ids(proposals), vec![1]);
+
+        // The limit cannot exceed the max page size
+        for id in 3..MAX_PROPOSALS_PER_PAGE + 1 {
+            write_proposal(&mut client.storage, id, Epoch(0), Epoch(0))?;
+        }
+        let proposals = RPC
+            .vp()
+            .gov()
+            .proposals(&client, &None, &None, &Some(u64::MAX))
+            .await
+            .unwrap();
+        assert_eq!(proposals.len() as u64, MAX_PROPOSALS_PER_PAGE);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gov_queries_votes() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+        write_proposal(&mut client.storage, 0, Epoch(0), Epoch(2))?;
+
+        let validator = address::testing::established_address_2();
+        let delegators = [
+            address::testing::established_address_3(),
+            address::testing::established_address_4(),
+        ];
+        StorageWrite::write(
+            &mut client.storage,
+            &gov_storage::get_vote_proposal_key(
+                0,
+                validator.clone(),
+                validator.clone(),
+            ),
+            ProposalVote::Yay,
+        )?;
+        for delegator in &delegators {
+            StorageWrite::write(
+                &mut client.storage,
+                &gov_storage::get_vote_proposal_key(
+                    0,
+                    delegator.clone(),
+                    validator.clone(),
+                ),
+                ProposalVote::Nay,
+            )?;
+        }
+
+        let votes = RPC
+            .vp()
+            .gov()
+            .votes(&client, &0, &None, &None)
+            .await
+            .unwrap();
+        assert_eq!(votes.len(), 3);
+        assert!(votes.iter().all(|vote| vote.delegation == validator));
+
+        // Get the votes one at a time
+        for (offset, expected) in votes.iter().enumerate() {
+            let page = RPC
+                .vp()
+                .gov()
+                .votes(&client, &0, &Some(offset as u64), &Some(1))
+                .await
+                .unwrap();
+            assert_eq!(page, vec![expected.clone()]);
+        }
+        let page = RPC
+            .vp()
+            .gov()
+            .votes(&client, &0, &Some(3), &None)
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+
+        let voter_votes = RPC
+            .vp()
+            .gov()
+            .voter_votes(&client, &0, &delegators[0])
+            .await
+            .unwrap();
+        assert_eq!(
+            voter_votes,
+            vec![StorageProposalVote {
+                voter: delegators[0].clone(),
+                delegation: validator,
+                vote: ProposalVote::Nay,
+            }]
+        );
+
+        // There are no votes on another proposal
+        let votes = RPC
+            .vp()
+            .gov()
+            .votes(&client, &1, &None, &None)
+            .await
+            .unwrap();
+        assert!(votes.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gov_queries_tally() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+
+        // There's no tally of a proposal that doesn't exist
+        let tally = RPC.vp().gov().tally(&client, &0).await.unwrap();
+        assert!(tally.is_none());
+
+        let validator = address::testing::established_address_2();
+        let genesis_validator = GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::from(100_000_000),
+            consensus_key: key::testing::keypair_1().ref_to(),
+            commission_rate: Decimal::new(5, 2),
+            max_commission_rate_change: Decimal::new(1, 2),
+        };
+        init_genesis_storage(
+            &mut client.storage,
+            &PosParams::default(),
+            [genesis_validator].iter(),
+            Epoch(0),
+        );
+        GovParams::default().init_storage(&mut client.storage);
+
+        // There's no tally before the voting period starts
+        write_proposal(&mut client.storage, 0, Epoch(1), Epoch(2))?;
+        let tally = RPC.vp().gov().tally(&client, &0).await.unwrap();
+        assert!(tally.is_none());
+
+        // The tally of an on-going proposal has the votes cast so far
+        write_proposal(&mut client.storage, 1, Epoch(0), Epoch(2))?;
+        let tally = RPC.vp().gov().tally(&client, &1).await.unwrap();
+        let tally = tally.expect("The tally should exist");
+        assert_eq!(tally.result, TallyResult::Rejected);
+        assert_eq!(tally.total_yay_power, 0);
+
+        StorageWrite::write(
+            &mut client.storage,
+            &gov_storage::get_vote_proposal_key(
+                1,
+                validator.clone(),
+                validator,
+            ),
+            ProposalVote::Yay,
+        )?;
+        let tally = RPC.vp().gov().tally(&client, &1).await.unwrap();
+        let tally = tally.expect("The tally should exist");
+        assert_eq!(tally.result, TallyResult::Passed);
+        assert_eq!(tally.total_yay_power, tally.total_voting_power);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,13 @@
 // Re-export to show in rustdoc!
+pub use governance::Gov;
+use governance::GOV;
 pub use pos::Pos;
 use pos::POS;
+mod governance;
 mod pos;
 
 // Validity predicate queries
 router! {VP,
     ( "pos" ) = (sub POS),
+    ( "gov" ) = (sub GOV),
 }
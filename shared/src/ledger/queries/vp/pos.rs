@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use namada_proof_of_stake::PosReadOnly;
 
-use crate::ledger::pos::{self, BondId};
+use crate::ledger::pos::{self, BondId, ValidatorMetaData};
 use crate::ledger::queries::types::RequestCtx;
 use crate::ledger::storage::{DBIter, StorageHasher, DB};
 use crate::ledger::storage_api;
@@ -20,12 +20,15 @@ router! {POS,
 
         ( "stake" / [validator: Address] / [epoch: opt Epoch] )
         -> token::Amount = validator_stake,
+
+        ( "metadata" / [validator: Address] )
+        -> Option<ValidatorMetaData> = validator_metadata,
     },
 
     ( "total_stake" / [epoch: opt Epoch] )
     -> token::Amount = total_stake,
 
-    ( "delegations" / [owner: Address] )
+    ( "delegations" / [owner: Address] / [epoch: opt Epoch] )
     -> HashSet<Address> = delegations,
 
     ( "bond_amount" / [owner: Address] / [validator: Address] / [epoch: opt Epoch] )
@@ -76,6 +79,18 @@ where
     ctx.storage.validator_stake(&validator, epoch)
 }
 
+/// Get the metadata of a validator, if any has been set.
+fn validator_metadata<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    validator: Address,
+) -> storage_api::Result<Option<ValidatorMetaData>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    ctx.storage.read_validator_metadata(&validator)
+}
+
 /// Get the total stake in PoS system at the given epoch or current when `None`.
 fn total_stake<D, H>(
     ctx: RequestCtx<'_, D, H>,
@@ -112,10 +127,12 @@ where
 }
 
 /// Find all the validator addresses to whom the given `owner` address has
-/// some delegation in any epoch
+/// some delegation in any epoch or, when an `epoch` is given, a non-zero
+/// bond at that epoch
 fn delegations<D, H>(
     ctx: RequestCtx<'_, D, H>,
     owner: Address,
+    epoch: Option<Epoch>,
 ) -> storage_api::Result<HashSet<Address>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -134,6 +151,17 @@ where
                     "Delegation key should contain validator address.",
                 )
             })?;
+        if let Some(epoch) = epoch {
+            let bond_id = BondId {
+                source: owner.clone(),
+                validator: validator_address.clone(),
+            };
+            if ctx.storage.bond_amount(&bond_id, epoch)?
+                == token::Amount::default()
+            {
+                continue;
+            }
+        }
         delegations.insert(validator_address);
     }
     Ok(delegations)
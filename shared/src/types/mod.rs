@@ -4,6 +4,6 @@ pub mod ibc;
 pub mod key;
 
 pub use namada_core::types::{
-    address, chain, governance, hash, internal, masp, storage, time, token,
-    transaction, validity_predicate,
+    address, chain, ethereum_events, governance, hash, internal, masp, storage,
+    time, token, transaction, validity_predicate, vote_extensions,
 };
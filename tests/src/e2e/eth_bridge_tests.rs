@@ -1,4 +1,9 @@
-use crate::e2e::helpers::get_actor_rpc;
+use namada::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+use namada::types::ethereum_events::{EthereumEvent, TransferToNamada};
+use namada::types::token;
+use namada_apps::config::{Config, TendermintMode};
+
+use crate::e2e::helpers::{find_address, get_actor_rpc};
 use crate::e2e::setup;
 use crate::e2e::setup::constants::{
     wasm_abs_path, ALBERT, TX_WRITE_STORAGE_KEY_WASM,
@@ -92,3 +97,58 @@ fn everything() {
         namadac_tx.assert_success();
     }
 }
+
+/// Test that the transfers to Namada fed to the validator by the mock Ethereum
+/// oracle are voted on in its vote extensions and applied once attested. The
+/// vote extensions require the ledger to be built with the `abcipp` feature.
+#[test]
+#[cfg_attr(not(feature = "abcipp"), ignore)]
+fn transfers_to_namada_from_mock_oracle() {
+    const LEDGER_STARTUP_TIMEOUT_SECONDS: u64 = 30;
+    const SOLE_VALIDATOR: Who = Who::Validator(0);
+
+    let test = setup::single_node_net().unwrap();
+
+    // Point the validator to a file with the Ethereum events
+    let events_file = test.test_dir.path().join("ethereum_events.json");
+    let base_dir = test.get_base_dir(&SOLE_VALIDATOR);
+    let mut config = Config::load(
+        &base_dir,
+        &test.net.chain_id,
+        Some(TendermintMode::Validator),
+    );
+    config.ledger.shell.ethereum_mock_events_file = Some(events_file.clone());
+    config.write(&base_dir, &test.net.chain_id, true).unwrap();
+
+    let mut namadan_ledger = run_as!(
+        test,
+        SOLE_VALIDATOR,
+        Bin::Node,
+        &["ledger"],
+        Some(LEDGER_STARTUP_TIMEOUT_SECONDS)
+    )
+    .unwrap();
+    namadan_ledger
+        .exp_string("Starting the mock Ethereum oracle")
+        .unwrap();
+    namadan_ledger.exp_string("Committed block hash").unwrap();
+
+    let receiver = find_address(&test, ALBERT).unwrap();
+    let event = EthereumEvent::TransfersToNamada {
+        nonce: 0,
+        transfers: vec![TransferToNamada {
+            amount: token::Amount::whole(100),
+            asset: DAI_ERC20_ETH_ADDRESS,
+            receiver,
+        }],
+    };
+    let line = serde_json::to_string(&event).unwrap() + "\n";
+    std::fs::write(&events_file, line).unwrap();
+
+    namadan_ledger
+        .exp_string("Received an Ethereum event")
+        .unwrap();
+    namadan_ledger
+        .exp_string("Applied the transfers to Namada")
+        .unwrap();
+}
@@ -28,7 +28,7 @@ mod tests {
         get_dummy_header as tm_dummy_header, Error as IbcError,
     };
     use namada::ledger::tx_env::TxEnv;
-    use namada::proto::{SignedTxData, Tx};
+    use namada::proto::{SignedTxData, Tx, TxSignature};
     use namada::tendermint_proto::Protobuf;
     use namada::types::key::*;
     use namada::types::storage::{self, BlockHash, BlockHeight, Key, KeySeg};
@@ -476,21 +476,16 @@ mod tests {
                     .expect("decoding signed data we just signed")
             });
             assert_eq!(&signed_tx_data.data, data);
-            assert!(
-                vp::CTX
-                    .verify_tx_signature(&pk, &signed_tx_data.sig)
-                    .unwrap()
-            );
+            let sig = match &signed_tx_data.sig {
+                TxSignature::Single(sig) => sig,
+                TxSignature::Multi(_) => panic!("expected a single signature"),
+            };
+            assert!(vp::CTX.verify_tx_signature(&pk, sig).unwrap());
 
             let other_keypair = key::testing::keypair_2();
-            assert!(
-                !vp::CTX
-                    .verify_tx_signature(
-                        &other_keypair.ref_to(),
-                        &signed_tx_data.sig
-                    )
-                    .unwrap()
-            );
+            assert!(!vp::CTX
+                .verify_tx_signature(&other_keypair.ref_to(), sig)
+                .unwrap());
         }
     }
 
@@ -183,6 +183,18 @@ impl TestTxEnv {
             .unwrap();
     }
 
+    /// Set multisignature keys for the address.
+    pub fn write_multisig_keys(
+        &mut self,
+        address: &Address,
+        keys: &key::multisig::MultisigKeys,
+    ) {
+        let storage_key = key::multisig_key(address);
+        self.storage
+            .write(&storage_key, keys.try_to_vec().unwrap())
+            .unwrap();
+    }
+
     /// Apply the tx changes to the write log.
     pub fn execute_tx(&mut self) -> Result<(), Error> {
         let empty_data = vec![];
@@ -0,0 +1,48 @@
+//! Ethereum bridge
+
+use namada_core::ledger::eth_bridge::{storage, ADDRESS as eth_bridge_address};
+use namada_core::types::ethereum_events::{EthAddress, TransferToEthereum};
+
+use super::*;
+use crate::token::transfer;
+
+/// A transaction to queue a transfer of tokens to Ethereum. The wrapped ERC20
+/// tokens are burned, while the native tokens are escrowed by the bridge.
+pub fn transfer_to_ethereum(
+    ctx: &mut Ctx,
+    data: TransferToEthereum,
+) -> TxResult {
+    let native_erc20: Option<EthAddress> =
+        ctx.read(&storage::native_erc20_key())?;
+    if Some(data.asset) == native_erc20 {
+        let native_token = ctx.get_native_token()?;
+        transfer(
+            ctx,
+            &data.sender,
+            &eth_bridge_address,
+            &native_token,
+            None,
+            data.amount,
+            &None,
+            &None,
+        )?;
+    } else {
+        let balance_key =
+            storage::wrapped_erc20_balance_key(&data.asset, &data.sender);
+        let mut balance: token::Amount = ctx
+            .read(&balance_key)?
+            .ok_or_err_msg("The sender has no balance of the wrapped token")?;
+        if balance < data.amount {
+            return Err(Error::new_const(
+                "Insufficient balance of the wrapped token",
+            ));
+        }
+        balance.spend(&data.amount);
+        ctx.write(&balance_key, balance)?;
+    }
+
+    let nonce_key = storage::transfers_to_ethereum_nonce_key();
+    let nonce: u64 = ctx.read(&nonce_key)?.unwrap_or_default();
+    ctx.write(&storage::pending_transfer_key(nonce), data)?;
+    ctx.write(&nonce_key, nonce + 1)
+}
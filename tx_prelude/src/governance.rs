@@ -39,6 +39,9 @@ pub fn init_proposal(ctx: &mut Ctx, data: InitProposalData) -> TxResult {
         ctx.write_bytes(&proposal_code_key, proposal_code)?;
     }
 
+    let proposal_type_key = storage::get_proposal_type_key(proposal_id);
+    ctx.write(&proposal_type_key, data.proposal_type)?;
+
     ctx.write(&counter_key, proposal_id + 1)?;
 
     let min_proposal_funds_key = storage::get_min_proposal_fund_key();
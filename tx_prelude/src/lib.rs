@@ -6,6 +6,7 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
 
+pub mod eth_bridge;
 pub mod governance;
 pub mod ibc;
 pub mod key;
@@ -26,7 +27,7 @@ pub use namada_core::ledger::storage_api::{
     StorageWrite,
 };
 pub use namada_core::ledger::tx_env::TxEnv;
-pub use namada_core::proto::{Signed, SignedTxData};
+pub use namada_core::proto::{Signed, SignedTxData, TxSignature};
 pub use namada_core::types::address::Address;
 use namada_core::types::chain::CHAIN_ID_LENGTH;
 use namada_core::types::internal::HostEnvResult;
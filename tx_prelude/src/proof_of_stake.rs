@@ -5,14 +5,17 @@ use namada_core::types::transaction::InitValidator;
 use namada_core::types::{key, token};
 pub use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::storage::{
-    bond_key, params_key, total_deltas_key, unbond_key,
+    bond_key, params_key, redelegation_key, total_deltas_key, unbond_key,
     validator_address_raw_hash_key, validator_commission_rate_key,
     validator_consensus_key_key, validator_deltas_key,
-    validator_max_commission_rate_change_key, validator_set_key,
-    validator_slashes_key, validator_state_key, BondId, Bonds, TotalDeltas,
-    Unbonds, ValidatorConsensusKeys, ValidatorDeltas, ValidatorSets,
+    validator_max_commission_rate_change_key, validator_metadata_key,
+    validator_set_key, validator_slashes_key, validator_state_key, BondId,
+    Bonds, Redelegations, TotalDeltas, Unbonds, ValidatorConsensusKeys,
+    ValidatorDeltas, ValidatorSets,
+};
+use namada_proof_of_stake::types::{
+    CommissionRates, ValidatorMetaData, ValidatorStates,
 };
-use namada_proof_of_stake::types::{CommissionRates, ValidatorStates};
 pub use namada_proof_of_stake::{
     epoched, parameters, types, PosActions as PosWrite, PosReadOnly as PosRead,
 };
@@ -76,6 +79,26 @@ impl Ctx {
         )
     }
 
+    /// Redelegate tokens delegated by the `owner` from the `src_validator` to
+    /// the `dest_validator` without unbonding them.
+    pub fn redelegate_tokens(
+        &mut self,
+        owner: &Address,
+        src_validator: &Address,
+        dest_validator: &Address,
+        amount: token::Amount,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        namada_proof_of_stake::PosActions::redelegate_tokens(
+            self,
+            owner,
+            src_validator,
+            dest_validator,
+            amount,
+            current_epoch,
+        )
+    }
+
     /// Change validator commission rate.
     pub fn change_validator_commission_rate(
         &mut self,
@@ -91,6 +114,43 @@ impl Ctx {
         )
     }
 
+    /// Change validator consensus key. The new key takes effect at the
+    /// pipeline offset.
+    pub fn change_consensus_key(
+        &mut self,
+        validator: &Address,
+        consensus_key: &common::PublicKey,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        namada_proof_of_stake::PosActions::change_consensus_key(
+            self,
+            validator,
+            consensus_key,
+            current_epoch,
+        )
+    }
+
+    /// Change validator metadata.
+    pub fn change_validator_metadata(
+        &mut self,
+        validator: &Address,
+        metadata: ValidatorMetaData,
+    ) -> TxResult {
+        namada_proof_of_stake::PosActions::change_validator_metadata(
+            self, validator, metadata,
+        )
+    }
+
+    /// Unjail a validator that has been jailed for missing too many blocks.
+    pub fn unjail_validator(&mut self, validator: &Address) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        namada_proof_of_stake::PosActions::unjail_validator(
+            self,
+            validator,
+            current_epoch,
+        )
+    }
+
     /// Attempt to initialize a validator account. On success, returns the
     /// initialized validator account's address.
     pub fn init_validator(
@@ -102,6 +162,7 @@ impl Ctx {
             dkg_key,
             commission_rate,
             max_commission_rate_change,
+            metadata,
             validator_vp_code,
         }: InitValidator,
     ) -> EnvResult<Address> {
@@ -121,6 +182,7 @@ impl Ctx {
             current_epoch,
             commission_rate,
             max_commission_rate_change,
+            metadata,
         )?;
 
         Ok(validator_address)
@@ -180,6 +242,14 @@ impl namada_proof_of_stake::PosActions for Ctx {
         self.write(&validator_max_commission_rate_change_key(key), value)
     }
 
+    fn write_validator_metadata(
+        &mut self,
+        key: &Address,
+        value: ValidatorMetaData,
+    ) -> storage_api::Result<()> {
+        self.write(&validator_metadata_key(key), &value)
+    }
+
     fn write_validator_deltas(
         &mut self,
         key: &Address,
@@ -204,6 +274,14 @@ impl namada_proof_of_stake::PosActions for Ctx {
         self.write(&unbond_key(key), &value)
     }
 
+    fn write_redelegations(
+        &mut self,
+        key: &BondId,
+        value: Redelegations,
+    ) -> storage_api::Result<()> {
+        self.write(&redelegation_key(key), &value)
+    }
+
     fn write_validator_set(
         &mut self,
         value: ValidatorSets,
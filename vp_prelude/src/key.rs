@@ -1,6 +1,8 @@
 //! Cryptographic signature keys
 
+use namada_core::proto::TxSignature;
 use namada_core::types::address::Address;
+use namada_core::types::key::multisig::MultisigKeys;
 pub use namada_core::types::key::*;
 
 use super::*;
@@ -10,3 +12,34 @@ use super::*;
 pub fn get(ctx: &Ctx, owner: &Address) -> EnvResult<Option<common::PublicKey>> {
     storage_api::key::get(&ctx.pre(), owner)
 }
+
+/// Get the multisignature keys associated with the given address from the
+/// state prior to tx execution. Returns `Ok(None)` if not found.
+pub fn get_multisig(
+    ctx: &Ctx,
+    owner: &Address,
+) -> EnvResult<Option<MultisigKeys>> {
+    storage_api::key::get_multisig(&ctx.pre(), owner)
+}
+
+/// Check that the tx is authorized by the given account. A single signature
+/// must be made with the account's public key and a multisignature must
+/// contain enough valid signatures to meet the account's threshold.
+pub fn verify_signatures(
+    ctx: &Ctx,
+    owner: &Address,
+    sig: &TxSignature,
+) -> EnvResult<bool> {
+    match sig {
+        TxSignature::Single(sig) => match get(ctx, owner)? {
+            Some(pk) => ctx.verify_tx_signature(&pk, sig),
+            None => Ok(false),
+        },
+        TxSignature::Multi(sigs) => match get_multisig(ctx, owner)? {
+            Some(keys) => keys.verify_threshold(sigs, |pk, sig| {
+                ctx.verify_tx_signature(pk, sig)
+            }),
+            None => Ok(false),
+        },
+    }
+}
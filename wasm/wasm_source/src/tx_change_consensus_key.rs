@@ -0,0 +1,16 @@
+//! A tx for a validator to change their consensus key.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let transaction::pos::ConsensusKeyChange {
+        validator,
+        consensus_key,
+    } = transaction::pos::ConsensusKeyChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode ConsensusKeyChange")?;
+    ctx.change_consensus_key(&validator, &consensus_key)
+}
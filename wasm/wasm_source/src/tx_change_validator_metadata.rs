@@ -0,0 +1,112 @@
+//! A tx for a validator to change their metadata.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let transaction::pos::MetaDataChange {
+        validator,
+        metadata,
+    } = transaction::pos::MetaDataChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode MetaDataChange")?;
+    ctx.change_validator_metadata(&validator, metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use namada::ledger::pos::{PosParams, PosVP};
+    use namada::proto::Tx;
+    use namada::types::storage::Epoch;
+    use namada_tests::log::test;
+    use namada_tests::native_vp::pos::init_pos;
+    use namada_tests::native_vp::TestNativeVpEnv;
+    use namada_tests::tx::*;
+    use namada_tx_prelude::key::RefTo;
+    use namada_tx_prelude::token;
+    use namada_tx_prelude::transaction::pos::ValidatorMetaData;
+    use namada_vp_prelude::proof_of_stake::GenesisValidator;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    /// Initialize PoS with a single genesis validator and return its address
+    fn init_validator() -> Address {
+        let validator = address::testing::established_address_1();
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::from(1_000_000),
+            consensus_key: key::testing::keypair_1().ref_to(),
+            commission_rate: Decimal::new(5, 2),
+            max_commission_rate_change: Decimal::new(1, 2),
+        }];
+        init_pos(&genesis_validators[..], &PosParams::default(), Epoch(0));
+        validator
+    }
+
+    /// Sign the metadata change for the validator with a test key
+    fn tx_data(validator: &Address, metadata: &ValidatorMetaData) -> Vec<u8> {
+        let change = transaction::pos::MetaDataChange {
+            validator: validator.clone(),
+            metadata: metadata.clone(),
+        };
+        let tx = Tx::new(vec![], Some(change.try_to_vec().unwrap()));
+        tx.sign(&key::testing::keypair_2()).data.unwrap()
+    }
+
+    /// Test that valid metadata is written and accepted by the PoS VP
+    #[test]
+    fn test_tx_change_validator_metadata() -> TxResult {
+        let validator = init_validator();
+        let metadata = ValidatorMetaData {
+            name: Some("validator".to_string()),
+            description: None,
+            website: Some("https://namada.net".to_string()),
+            contact: Some("a".repeat(ValidatorMetaData::MAX_FIELD_LEN)),
+        };
+
+        apply_tx(ctx(), tx_data(&validator, &metadata))?;
+
+        let metadata_post = ctx().read_validator_metadata(&validator)?;
+        assert_eq!(metadata_post, Some(metadata));
+
+        let tx_env = tx_host_env::take();
+        let vp_env = TestNativeVpEnv::from_tx_env(tx_env, address::POS);
+        let result = vp_env.validate_tx(PosVP::new);
+        let result =
+            result.expect("Validation of valid changes must not fail!");
+        assert!(
+            result,
+            "PoS Validity predicate must accept this transaction"
+        );
+
+        Ok(())
+    }
+
+    /// Test that metadata with an empty or too long field is rejected
+    #[test]
+    fn test_tx_change_validator_metadata_invalid() {
+        let validator = init_validator();
+        let too_long = "a".repeat(ValidatorMetaData::MAX_FIELD_LEN + 1);
+        let invalid = [
+            ValidatorMetaData {
+                name: Some(String::new()),
+                ..Default::default()
+            },
+            ValidatorMetaData {
+                description: Some(too_long.clone()),
+                ..Default::default()
+            },
+            ValidatorMetaData {
+                website: Some(too_long),
+                ..Default::default()
+            },
+        ];
+        for metadata in invalid {
+            assert!(apply_tx(ctx(), tx_data(&validator, &metadata)).is_err());
+        }
+        assert_eq!(ctx().read_validator_metadata(&validator).unwrap(), None);
+    }
+}
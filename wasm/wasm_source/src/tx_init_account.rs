@@ -1,5 +1,5 @@
-//! A tx to initialize a new established address with a given public key and
-//! a validity predicate.
+//! A tx to initialize a new established address with a given public key, or a
+//! set of public keys with a signature threshold, and a validity predicate.
 
 use namada_tx_prelude::*;
 
@@ -13,7 +13,24 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
     debug_log!("apply_tx called to init a new established account");
 
     let address = ctx.init_account(&tx_data.vp_code)?;
-    let pk_key = key::pk_key(&address);
-    ctx.write(&pk_key, &tx_data.public_key)?;
+    match &tx_data.public_keys[..] {
+        [public_key] => {
+            let pk_key = key::pk_key(&address);
+            ctx.write(&pk_key, public_key)?;
+        }
+        _ => {
+            let keys = key::multisig::MultisigKeys {
+                public_keys: tx_data.public_keys,
+                threshold: tx_data.threshold,
+            };
+            if !keys.is_valid() {
+                return Err(Error::new_const(
+                    "Invalid multisignature keys or threshold",
+                ));
+            }
+            let multisig_key = key::multisig_key(&address);
+            ctx.write(&multisig_key, keys)?;
+        }
+    }
     Ok(())
 }
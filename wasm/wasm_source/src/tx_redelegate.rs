@@ -0,0 +1,20 @@
+//! A tx for a PoS redelegation that moves delegated tokens from one validator
+//! to another without unbonding them.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let transaction::pos::Redelegation {
+        src_validator,
+        dest_validator,
+        owner,
+        amount,
+    } = transaction::pos::Redelegation::try_from_slice(&data[..])
+        .wrap_err("failed to decode Redelegation")?;
+
+    ctx.redelegate_tokens(&owner, &src_validator, &dest_validator, amount)
+}
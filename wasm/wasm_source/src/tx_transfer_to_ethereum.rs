@@ -0,0 +1,16 @@
+//! A tx to send tokens from Namada to Ethereum.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let transfer =
+        ethereum_events::TransferToEthereum::try_from_slice(&data[..])
+            .wrap_err("failed to decode TransferToEthereum")?;
+    debug_log!("apply_tx called with transfer to Ethereum: {:#?}", transfer);
+
+    eth_bridge::transfer_to_ethereum(ctx, transfer)
+}
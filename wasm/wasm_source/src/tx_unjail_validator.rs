@@ -0,0 +1,14 @@
+//! A tx for a validator to unjail itself after it's been jailed for missing
+//! too many blocks.
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let validator = Address::try_from_slice(&data[..])
+        .wrap_err("failed to decode an Address")?;
+    ctx.unjail_validator(&validator)
+}
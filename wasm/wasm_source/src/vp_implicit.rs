@@ -68,18 +68,10 @@ fn validate_tx(
         Lazy::new(|| SignedTxData::try_from_slice(&tx_data[..]));
 
     let valid_sig = Lazy::new(|| match &*signed_tx_data {
-        Ok(signed_tx_data) => {
-            let pk = key::get(ctx, &addr);
-            match pk {
-                Ok(Some(pk)) => {
-                    matches!(
-                        ctx.verify_tx_signature(&pk, &signed_tx_data.sig),
-                        Ok(true)
-                    )
-                }
-                _ => false,
-            }
-        }
+        Ok(signed_tx_data) => matches!(
+            key::verify_signatures(ctx, &addr, &signed_tx_data.sig),
+            Ok(true)
+        ),
         _ => false,
     });
 
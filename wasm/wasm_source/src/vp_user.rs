@@ -68,18 +68,10 @@ fn validate_tx(
         Lazy::new(|| SignedTxData::try_from_slice(&tx_data[..]));
 
     let valid_sig = Lazy::new(|| match &*signed_tx_data {
-        Ok(signed_tx_data) => {
-            let pk = key::get(ctx, &addr);
-            match pk {
-                Ok(Some(pk)) => {
-                    matches!(
-                        ctx.verify_tx_signature(&pk, &signed_tx_data.sig),
-                        Ok(true)
-                    )
-                }
-                _ => false,
-            }
-        }
+        Ok(signed_tx_data) => matches!(
+            key::verify_signatures(ctx, &addr, &signed_tx_data.sig),
+            Ok(true)
+        ),
         _ => false,
     });
 
@@ -585,6 +577,66 @@ mod tests {
         );
     }
 
+    /// Test that a validity predicate update of a multisignature account is
+    /// accepted only if signed with enough of the account's keys.
+    #[test]
+    fn test_multisig_vp_update() {
+        let vp_owner = address::testing::established_address_1();
+        let keypair_1 = key::testing::keypair_1();
+        let keypair_2 = key::testing::keypair_2();
+        let keys = key::multisig::MultisigKeys {
+            public_keys: vec![keypair_1.ref_to(), keypair_2.ref_to()],
+            threshold: 2,
+        };
+        let vp_code =
+            std::fs::read(VP_ALWAYS_TRUE_WASM).expect("cannot load wasm");
+
+        for (signers, expected) in [
+            (vec![(0, keypair_1.clone())], false),
+            (vec![(0, keypair_1.clone()), (0, keypair_1.clone())], false),
+            (vec![(1, keypair_1.clone()), (0, keypair_2.clone())], false),
+            (vec![(0, keypair_1.clone()), (1, keypair_2.clone())], true),
+        ] {
+            // Initialize a tx environment
+            let mut tx_env = TestTxEnv::default();
+            tx_env.init_parameters(None, None, None);
+
+            // Spawn the accounts to be able to modify their storage
+            tx_env.spawn_accounts([&vp_owner]);
+
+            tx_env.write_multisig_keys(&vp_owner, &keys);
+
+            // Initialize VP environment from a transaction
+            vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+                // Update VP in a transaction
+                tx::ctx()
+                    .update_validity_predicate(address, &vp_code)
+                    .unwrap();
+            });
+
+            let mut vp_env = vp_host_env::take();
+            let tx = vp_env.tx.clone();
+            let signed_tx = tx.sign_multi(&signers);
+            let tx_data: Vec<u8> = signed_tx.data.as_ref().cloned().unwrap();
+            vp_env.tx = signed_tx;
+            let keys_changed: BTreeSet<storage::Key> =
+                vp_env.all_touched_storage_keys();
+            let verifiers: BTreeSet<Address> = BTreeSet::default();
+            vp_host_env::set(vp_env);
+            assert_eq!(
+                validate_tx(
+                    &CTX,
+                    tx_data,
+                    vp_owner.clone(),
+                    keys_changed,
+                    verifiers
+                )
+                .unwrap(),
+                expected
+            );
+        }
+    }
+
     /// Test that a validity predicate update is rejected if not whitelisted
     #[test]
     fn test_signed_vp_update_not_whitelisted_rejected() {
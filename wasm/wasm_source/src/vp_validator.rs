@@ -68,18 +68,10 @@ fn validate_tx(
         Lazy::new(|| SignedTxData::try_from_slice(&tx_data[..]));
 
     let valid_sig = Lazy::new(|| match &*signed_tx_data {
-        Ok(signed_tx_data) => {
-            let pk = key::get(ctx, &addr);
-            match pk {
-                Ok(Some(pk)) => {
-                    matches!(
-                        ctx.verify_tx_signature(&pk, &signed_tx_data.sig),
-                        Ok(true)
-                    )
-                }
-                _ => false,
-            }
-        }
+        Ok(signed_tx_data) => matches!(
+            key::verify_signatures(ctx, &addr, &signed_tx_data.sig),
+            Ok(true)
+        ),
         _ => false,
     });
 
@@ -142,8 +134,30 @@ fn validate_tx(
                     Some(source) => *source != addr || *valid_sig,
                     None => true,
                 };
-                let valid =
-                    valid_bond_or_unbond_change && valid_commission_rate_change;
+                let metadata = proof_of_stake::is_validator_metadata_key(key);
+                // Validator's metadata change must be signed
+                let valid_metadata_change = match metadata {
+                    Some(source) => *source != addr || *valid_sig,
+                    None => true,
+                };
+                let consensus_key =
+                    proof_of_stake::is_validator_consensus_key_key(key);
+                // Validator's consensus key change must be signed
+                let valid_consensus_key_change = match consensus_key {
+                    Some(source) => *source != addr || *valid_sig,
+                    None => true,
+                };
+                let state = proof_of_stake::is_validator_state_key(key);
+                // Validator's state change (e.g. unjailing) must be signed
+                let valid_state_change = match state {
+                    Some(source) => *source != addr || *valid_sig,
+                    None => true,
+                };
+                let valid = valid_bond_or_unbond_change
+                    && valid_commission_rate_change
+                    && valid_metadata_change
+                    && valid_consensus_key_change
+                    && valid_state_change;
                 debug_log!(
                     "PoS key {} {}",
                     key,